@@ -0,0 +1,2 @@
+-- Add draft_spec field to events table for settings drafts defined in the database
+ALTER TABLE events ADD COLUMN draft_spec JSONB;
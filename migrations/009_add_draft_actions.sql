@@ -0,0 +1,19 @@
+-- Record every applied settings draft action so drafts can be reviewed and undone
+CREATE TYPE draft_frontend AS ENUM ('discord', 'racetime', 'web');
+
+CREATE TABLE draft_actions (
+    id SERIAL PRIMARY KEY,
+    race BIGINT NOT NULL REFERENCES races(id) ON DELETE CASCADE,
+    actor BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    team BIGINT REFERENCES teams(id) ON DELETE SET NULL,
+    frontend draft_frontend NOT NULL,
+    action JSONB NOT NULL,
+    state_before JSONB NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    undone_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    undone_at TIMESTAMPTZ
+);
+
+CREATE INDEX draft_actions_race_idx ON draft_actions (race);
+
+ALTER TABLE public.draft_actions OWNER TO mido;
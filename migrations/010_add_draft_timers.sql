@@ -0,0 +1,12 @@
+-- Optional time limit for each step of a settings draft, enforced by the draft timer task
+ALTER TABLE events ADD COLUMN draft_step_time_limit INTERVAL;
+
+CREATE TABLE draft_timers (
+    race BIGINT PRIMARY KEY REFERENCES races(id) ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED,
+    deadline TIMESTAMPTZ NOT NULL,
+    reminded BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+ALTER TABLE public.draft_timers OWNER TO mido;
+
+ALTER TYPE draft_frontend ADD VALUE 'timer';
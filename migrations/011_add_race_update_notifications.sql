@@ -0,0 +1,33 @@
+-- Notify listeners (used for GraphQL subscriptions) whenever a race's schedule, room, seed, draft, or result changes.
+-- Notifications are only delivered once the transaction commits, so subscribers never see changes that get rolled back.
+CREATE FUNCTION public.notify_race_update() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    kinds text[] := ARRAY[]::text[];
+BEGIN
+    IF TG_OP = 'INSERT' OR (OLD.start, OLD.async_start1, OLD.async_start2, OLD.async_start3) IS DISTINCT FROM (NEW.start, NEW.async_start1, NEW.async_start2, NEW.async_start3) THEN
+        kinds := kinds || 'SCHEDULE'::text;
+    END IF;
+    IF TG_OP = 'INSERT' OR (OLD.room, OLD.async_room1, OLD.async_room2, OLD.async_room3) IS DISTINCT FROM (NEW.room, NEW.async_room1, NEW.async_room2, NEW.async_room3) THEN
+        kinds := kinds || 'ROOM'::text;
+    END IF;
+    IF TG_OP = 'INSERT' OR (OLD.web_id, OLD.file_stem, OLD.tfb_uuid, OLD.xkeys_uuid, OLD.hash1, OLD.hash2, OLD.hash3, OLD.hash4, OLD.hash5, OLD.seed_password) IS DISTINCT FROM (NEW.web_id, NEW.file_stem, NEW.tfb_uuid, NEW.xkeys_uuid, NEW.hash1, NEW.hash2, NEW.hash3, NEW.hash4, NEW.hash5, NEW.seed_password) THEN
+        kinds := kinds || 'SEED'::text;
+    END IF;
+    IF TG_OP = 'INSERT' OR OLD.draft_state IS DISTINCT FROM NEW.draft_state THEN
+        kinds := kinds || 'DRAFT'::text;
+    END IF;
+    IF TG_OP = 'INSERT' OR (OLD.end_time, OLD.async_end1, OLD.async_end2, OLD.async_end3) IS DISTINCT FROM (NEW.end_time, NEW.async_end1, NEW.async_end2, NEW.async_end3) THEN
+        kinds := kinds || 'RESULTS'::text;
+    END IF;
+    IF cardinality(kinds) > 0 THEN
+        PERFORM pg_notify('race_updates', json_build_object('race', NEW.id, 'series', NEW.series, 'event', NEW.event, 'kinds', kinds)::text);
+    END IF;
+    RETURN NULL;
+END
+$$;
+
+ALTER FUNCTION public.notify_race_update() OWNER TO mido;
+
+CREATE TRIGGER race_updates AFTER INSERT OR UPDATE ON public.races FOR EACH ROW EXECUTE FUNCTION public.notify_race_update();
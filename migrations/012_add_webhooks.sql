@@ -0,0 +1,37 @@
+-- Outgoing webhooks registered by event organizers, and a log of their deliveries
+CREATE TYPE webhook_event_type AS ENUM ('race_scheduled', 'room_opened', 'seed_rolled', 'race_finished', 'signup_confirmed', 'team_resigned', 'test');
+
+CREATE TABLE webhooks (
+    id BIGINT PRIMARY KEY,
+    series VARCHAR(8) NOT NULL,
+    event VARCHAR(8) NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    event_types webhook_event_type[] NOT NULL,
+    created_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (series, event) REFERENCES events(series, event) ON DELETE CASCADE
+);
+
+CREATE INDEX webhooks_event_idx ON webhooks (series, event);
+
+ALTER TABLE public.webhooks OWNER TO mido;
+
+CREATE TABLE webhook_deliveries (
+    id SERIAL PRIMARY KEY,
+    webhook BIGINT NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event_type webhook_event_type NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    -- NULL once the delivery has succeeded or has been given up on
+    next_attempt_at TIMESTAMPTZ DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    last_status SMALLINT,
+    last_error TEXT
+);
+
+CREATE INDEX webhook_deliveries_webhook_idx ON webhook_deliveries (webhook, created_at);
+CREATE INDEX webhook_deliveries_pending_idx ON webhook_deliveries (next_attempt_at) WHERE next_attempt_at IS NOT NULL;
+
+ALTER TABLE public.webhook_deliveries OWNER TO mido;
@@ -0,0 +1,2 @@
+-- The winner of each game of a best-of-N match, so the bot can tell when a team has clinched the match
+ALTER TABLE races ADD COLUMN winning_team BIGINT REFERENCES teams(id) ON DELETE SET NULL;
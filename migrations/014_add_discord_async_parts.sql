@@ -0,0 +1,15 @@
+-- Progress and results of private async parts that are run in Discord direct messages instead of a racetime.gg room
+CREATE TABLE discord_async_parts (
+    race BIGINT NOT NULL REFERENCES races(id) ON DELETE CASCADE,
+    part SMALLINT NOT NULL CHECK (part BETWEEN 1 AND 3),
+    team BIGINT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    -- set when the Ready button is pressed, the seed is posted once the countdown ends
+    ready_at TIMESTAMPTZ,
+    start TIMESTAMPTZ,
+    finish TIMESTAMPTZ,
+    forfeited BOOLEAN NOT NULL DEFAULT FALSE,
+    vod TEXT,
+    PRIMARY KEY (race, part)
+);
+
+ALTER TABLE public.discord_async_parts OWNER TO mido;
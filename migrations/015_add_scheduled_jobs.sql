@@ -0,0 +1,35 @@
+-- Timed background work such as opening race rooms, prerolling seeds, and importing races, run by the scheduler task
+CREATE TYPE job_kind AS ENUM ('open_room', 'preroll_seed', 'preroll_goal_seeds', 'draft_timer', 'unlock_spoiler_log', 'import_races');
+
+CREATE TABLE scheduled_jobs (
+    id SERIAL PRIMARY KEY,
+    kind job_kind NOT NULL,
+    -- NULL for jobs which aren't about a specific race
+    race BIGINT REFERENCES races(id) ON DELETE CASCADE,
+    run_at TIMESTAMPTZ NOT NULL,
+    -- set while the job is running, reset on startup in case the previous run was interrupted
+    started_at TIMESTAMPTZ,
+    -- failed attempts since the job was last scheduled
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+
+CREATE UNIQUE INDEX scheduled_jobs_race_idx ON scheduled_jobs (kind, race) WHERE race IS NOT NULL;
+CREATE UNIQUE INDEX scheduled_jobs_global_idx ON scheduled_jobs (kind) WHERE race IS NULL;
+CREATE INDEX scheduled_jobs_pending_idx ON scheduled_jobs (run_at) WHERE started_at IS NULL;
+
+ALTER TABLE public.scheduled_jobs OWNER TO mido;
+
+-- Wake the scheduler when a job is added or rescheduled. Like race updates, this is only delivered once the transaction commits.
+CREATE FUNCTION public.notify_scheduled_jobs() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    PERFORM pg_notify('scheduled_jobs', '');
+    RETURN NULL;
+END
+$$;
+
+ALTER FUNCTION public.notify_scheduled_jobs() OWNER TO mido;
+
+CREATE TRIGGER scheduled_jobs AFTER INSERT OR UPDATE OF run_at ON public.scheduled_jobs FOR EACH STATEMENT EXECUTE FUNCTION public.notify_scheduled_jobs();
@@ -0,0 +1,4 @@
+-- The third entrant of a three-entrant race can be any kind of entrant, just like the first two
+ALTER TABLE races ADD COLUMN p3_discord BIGINT;
+ALTER TABLE races ADD COLUMN p3_racetime TEXT;
+ALTER TABLE races ADD COLUMN p3_twitch TEXT;
@@ -0,0 +1,24 @@
+-- Finish time and placement of each entrant, recorded when a race result is reported automatically
+CREATE TABLE race_results (
+    race BIGINT NOT NULL REFERENCES races(id) ON DELETE CASCADE,
+    -- the entrant is identified like the team1/p1_discord/p1 columns of races
+    team BIGINT REFERENCES teams(id) ON DELETE CASCADE,
+    discord_id BIGINT,
+    name TEXT,
+    racetime_id TEXT,
+    twitch_username TEXT,
+    placement SMALLINT NOT NULL CHECK (placement >= 1),
+    -- for Triforce Blitz, the time the last piece was collected
+    finish_time INTERVAL,
+    tfb_pieces SMALLINT,
+    dnf BOOLEAN NOT NULL,
+    -- flags of the room the entrant raced in, which differs between the parts of an async
+    fpa_invoked BOOLEAN NOT NULL DEFAULT FALSE,
+    breaks_used BOOLEAN NOT NULL DEFAULT FALSE,
+    CHECK (num_nonnulls(team, discord_id, name) = 1)
+);
+
+CREATE INDEX race_results_race_idx ON race_results (race);
+CREATE INDEX race_results_team_idx ON race_results (team) WHERE team IS NOT NULL;
+
+ALTER TABLE public.race_results OWNER TO mido;
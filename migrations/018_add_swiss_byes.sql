@@ -0,0 +1,14 @@
+-- Teams which sat out a round of a Swiss phase paired by Hyrule Town Hall, counted as a match win in the standings
+CREATE TABLE swiss_byes (
+    series VARCHAR(8) NOT NULL,
+    event VARCHAR(8) NOT NULL,
+    phase TEXT NOT NULL,
+    round TEXT NOT NULL,
+    team BIGINT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    PRIMARY KEY (team, phase, round),
+    FOREIGN KEY (series, event) REFERENCES events(series, event) ON DELETE CASCADE
+);
+
+CREATE INDEX swiss_byes_event_idx ON swiss_byes (series, event);
+
+ALTER TABLE public.swiss_byes OWNER TO mido;
@@ -0,0 +1,31 @@
+-- Events proposed through the /new-event wizard, created once a site admin approves them
+CREATE TYPE event_proposal_status AS ENUM ('pending', 'approved', 'rejected');
+
+CREATE TABLE event_proposals (
+    id BIGINT PRIMARY KEY,
+    requester BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    series VARCHAR(8) NOT NULL,
+    event VARCHAR(8) NOT NULL,
+    display_name TEXT NOT NULL,
+    start TIMESTAMPTZ,
+    end_time TIMESTAMPTZ,
+    team_config team_config NOT NULL,
+    enter_flow JSONB NOT NULL,
+    discord_guild BIGINT,
+    -- racetime.gg goals are handled in code, so this is only shown to the admin reviewing the proposal
+    racetime_goal TEXT,
+    single_settings JSONB,
+    draft_spec JSONB,
+    notes TEXT,
+    status event_proposal_status NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    CHECK (single_settings IS NULL OR draft_spec IS NULL)
+);
+
+CREATE INDEX event_proposals_requester_idx ON event_proposals (requester);
+CREATE INDEX event_proposals_pending_idx ON event_proposals (created_at) WHERE status = 'pending';
+
+ALTER TYPE public.event_proposal_status OWNER TO mido;
+ALTER TABLE public.event_proposals OWNER TO mido;
@@ -0,0 +1,44 @@
+-- Series metadata and behavior flags, previously hardcoded, so new series can be added without a code change
+CREATE TABLE series (
+    series VARCHAR(8) PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    -- used for calendar entries and overlapping signups when a race has no end time yet
+    default_race_duration INTERVAL NOT NULL DEFAULT '3 hours',
+    -- remind race room entrants to check their emulator settings when the race starts
+    emulator_settings_reminder BOOLEAN NOT NULL DEFAULT FALSE,
+    -- make race rooms invitational when the race starts
+    prevent_late_joins BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+INSERT INTO series (series, display_name, default_race_duration, emulator_settings_reminder, prevent_late_joins) VALUES
+    ('9dos', '9 Days of SAWS', '3 hours 30 minutes', FALSE, FALSE),
+    ('br', 'Copa do Brasil', '3 hours 30 minutes', FALSE, FALSE),
+    ('coop', 'Co-op Tournaments', '3 hours', FALSE, FALSE),
+    ('fr', 'Tournois Francophones', '3 hours 30 minutes', FALSE, FALSE),
+    ('league', 'League', '3 hours 30 minutes', TRUE, FALSE),
+    ('mp', 'Mixed Pools Tournaments', '3 hours', FALSE, FALSE),
+    ('mq', '12 MQ Tournaments', '4 hours', FALSE, FALSE),
+    ('mw', 'Multiworld Tournaments', '4 hours', FALSE, FALSE),
+    ('ohko', 'Battle Royale', '2 hours 30 minutes', FALSE, FALSE),
+    ('pic', 'Pictionary Spoiler Log Races', '4 hours', FALSE, FALSE),
+    ('rsl', 'Random Settings League', '4 hours 30 minutes', FALSE, FALSE),
+    ('s', 'Standard Tournaments', '3 hours 30 minutes', TRUE, FALSE),
+    ('scrubs', 'Scrubs Tournaments', '3 hours', FALSE, FALSE),
+    ('sgl', 'SpeedGaming Live', '3 hours', FALSE, TRUE),
+    ('soh', 'Songs of Hope', '3 hours 30 minutes', FALSE, FALSE),
+    ('tfb', 'Triforce Blitz', '2 hours', FALSE, FALSE),
+    ('wttbb', 'WeTryToBeBetter', '3 hours', FALSE, FALSE),
+    ('xkeys', 'Crosskeys Tournaments', '2 hours 30 minutes', FALSE, FALSE);
+
+-- per-event overrides of the series flags, NULL means the series default applies
+ALTER TABLE events
+ADD COLUMN emulator_settings_reminder BOOLEAN,
+ADD COLUMN prevent_late_joins BOOLEAN;
+
+UPDATE events SET emulator_settings_reminder = FALSE WHERE series = 's' AND event = 'w';
+UPDATE events SET prevent_late_joins = TRUE WHERE series = 's' AND event = '8';
+
+ALTER TABLE events ADD FOREIGN KEY (series) REFERENCES series(series);
+ALTER TABLE event_proposals ADD FOREIGN KEY (series) REFERENCES series(series);
+
+ALTER TABLE public.series OWNER TO mido;
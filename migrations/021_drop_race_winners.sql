@@ -0,0 +1,2 @@
+-- Game winners are derived from race_results instead
+ALTER TABLE races DROP COLUMN winning_team;
@@ -0,0 +1,22 @@
+-- How the standings of each phase are shown, chosen by organizers instead of guessed from the phase name
+CREATE TYPE standings_format AS ENUM ('swiss', 'groups', 'bracket');
+
+-- used for races without a phase and for phases without their own format
+ALTER TABLE events ADD COLUMN standings_format standings_format NOT NULL DEFAULT 'bracket';
+
+CREATE TABLE phase_standings_formats (
+    series VARCHAR(8) NOT NULL,
+    event VARCHAR(8) NOT NULL,
+    phase TEXT NOT NULL,
+    format standings_format NOT NULL,
+    PRIMARY KEY (series, event, phase),
+    FOREIGN KEY (series, event) REFERENCES events(series, event) ON DELETE CASCADE
+);
+
+-- keep showing the standings of existing events the way they were shown before
+INSERT INTO phase_standings_formats (series, event, phase, format) SELECT DISTINCT series, event, phase, CASE
+    WHEN LOWER(phase) LIKE '%swiss%' THEN 'swiss'::standings_format
+    ELSE 'groups'::standings_format
+END FROM races WHERE LOWER(phase) LIKE '%swiss%' OR LOWER(phase) LIKE '%group%';
+
+ALTER TABLE public.phase_standings_formats OWNER TO mido;
@@ -0,0 +1,10 @@
+-- Reminders posted in scheduling threads ahead of races, sent by the race_reminder scheduled job
+ALTER TYPE job_kind ADD VALUE 'race_reminder';
+
+CREATE TABLE race_reminders (
+    race BIGINT PRIMARY KEY REFERENCES races(id) ON DELETE CASCADE,
+    -- the start time the entrants were reminded of, so a rescheduled race is reminded again
+    start TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE public.race_reminders OWNER TO mido;
@@ -0,0 +1,6 @@
+-- The start time of a Discord async part is now set when the Ready button is pressed, so this records whether the seed has actually been posted, letting an interrupted countdown resume after a restart
+ALTER TABLE discord_async_parts ADD COLUMN seed_posted BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE discord_async_parts SET seed_posted = TRUE WHERE start IS NOT NULL;
+-- parts whose countdown was interrupted before this change are resumed as well
+UPDATE discord_async_parts SET start = ready_at + INTERVAL '10 seconds' WHERE ready_at IS NOT NULL AND start IS NULL;
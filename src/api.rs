@@ -1,10 +1,12 @@
 use {
     async_graphql::{
         Context,
-        EmptySubscription,
+        Data,
+        Enum,
         Error,
         Guard,
         ID as GqlId,
+        InputObject,
         InputValueError,
         InputValueResult,
         Object,
@@ -13,9 +15,13 @@ use {
         ScalarType,
         Schema,
         SimpleObject,
+        Subscription,
         Value,
         http::{
             GraphQLPlaygroundConfig,
+            WebSocket as GraphQLWebSocket,
+            WebSocketProtocols,
+            WsMessage,
             playground_source,
         },
     },
@@ -24,17 +30,45 @@ use {
         GraphQLRequest,
         GraphQLResponse,
     },
-    rocket::http::ContentType,
+    futures::{
+        SinkExt as _,
+        stream::Stream,
+    },
+    rocket::http::{
+        ContentType,
+        Header,
+    },
+    rocket_ws::{
+        Channel,
+        Message,
+        WebSocket,
+        frame::CloseFrame,
+    },
+    sqlx::postgres::PgListener,
     crate::{
         auth::Discriminator,
-        event::teams,
+        event::{
+            roles,
+            teams,
+        },
         prelude::*,
     },
 };
 
 macro_rules! db {
     ($db:ident = $ctx:expr; $expr:expr) => {
-        lock!($db = $ctx.data_unchecked::<ArcTransaction>(); $expr)
+        if let Ok(transaction) = $ctx.data::<ArcTransaction>() {
+            lock!($db = transaction; $expr)
+        } else {
+            // subscriptions are long-lived, so each resolver uses its own transaction instead of one for the entire connection
+            let mut transaction = $ctx.data_unchecked::<PgPool>().begin().await?;
+            let result = {
+                let $db = &mut transaction;
+                $expr
+            };
+            transaction.commit().await?;
+            result
+        }
     };
 }
 
@@ -144,6 +178,25 @@ impl Guard for ShowRestreamConsent<'_> {
     }
 }
 
+struct ShowEntrants<'a>(&'a event::Data<'static>);
+
+impl Guard for ShowEntrants<'_> {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let me = &ctx.data::<ApiKey>().map_err(|e| Error {
+            message: format!("This query requires an API key. Provide one using the X-API-Key header."),
+            source: Some(Arc::new(e)),
+            extensions: None,
+        })?.user;
+        db!(db = ctx; {
+            if self.0.organizers(&mut *db).await?.contains(me) || self.0.restreamers(&mut *db).await?.contains(me) {
+                Ok(())
+            } else {
+                Err("Only event organizers and restream coordinators can view the full list of entrants.".into())
+            }
+        })
+    }
+}
+
 struct EditRace(GqlId);
 
 impl Guard for EditRace {
@@ -217,7 +270,7 @@ impl ScalarType for UtcTimestamp {
     }
 }
 
-type MidosHouseSchema = Schema<Query, Mutation, EmptySubscription>;
+type MidosHouseSchema = Schema<Query, Mutation, Subscription>;
 
 pub(crate) struct Query;
 
@@ -234,8 +287,9 @@ enum UserFromDiscordError {
     }
 
     /// Returns a series (group of events) by its URL part.
-    async fn series(&self, name: String) -> Option<Series> {
-        name.parse().ok().map(Series)
+    async fn series(&self, ctx: &Context<'_>, name: String) -> Result<Option<Series>, sqlx::Error> {
+        let Ok(series) = name.parse::<crate::series::Series>() else { return Ok(None) };
+        Ok(db!(db = ctx; series.display_name(&mut *db).await?).map(|_| Series(series)))
     }
 
     /// Returns the Mido's House user connected to the given racetime.gg user ID, if any.
@@ -255,7 +309,233 @@ enum UserFromDiscordError {
 
 pub(crate) struct Mutation;
 
+/// Loads the race with the given ID, applies `edit` to it, and saves it, recording the API key's owner as the last editor.
+async fn edit_race(ctx: &Context<'_>, id: GqlId, edit: impl FnOnce(&mut cal::Race) -> Result<()>) -> Result<Race> {
+    db!(db = ctx; {
+        let mut race = cal::Race::from_id(&mut *db, ctx.data_unchecked(), id.try_into()?).await?;
+        edit(&mut race)?;
+        save_edited_race(ctx, &mut *db, race).await
+    })
+}
+
+/// Saves a race as part of the transaction that changed it, recording the API key's owner as the last editor.
+async fn save_edited_race(ctx: &Context<'_>, transaction: &mut Transaction<'_, Postgres>, mut race: cal::Race) -> Result<Race> {
+    race.last_edited_by = Some(ctx.data_unchecked::<ApiKey>().user.id);
+    race.last_edited_at = Some(Utc::now());
+    race.save(transaction).await?;
+    Ok(Race(race))
+}
+
+/// One entrant's result, as given to the `recordRaceResults` mutation.
+#[derive(InputObject)]
+struct RaceResultInput {
+    /// The 1-based index of the entrant, in the order the teams are listed in `Race.teams`.
+    entrant: u8,
+    /// Counting from 1. Entrants with the same score share a placement.
+    placement: u16,
+    /// The finish time in seconds. For Triforce Blitz, the time the last Triforce piece was collected. Required unless the entrant did not finish.
+    finish_time: Option<f64>,
+    /// The number of Triforce pieces the entrant collected. Omit for events other than Triforce Blitz.
+    triforce_pieces: Option<u8>,
+    /// Whether the entrant did not finish.
+    #[graphql(default)]
+    dnf: bool,
+    /// Whether FPA was called in the race room the entrant raced in.
+    #[graphql(default)]
+    fpa_invoked: bool,
+    /// Whether breaks were used in the race room the entrant raced in.
+    #[graphql(default)]
+    breaks_used: bool,
+}
+
+/// Checks that `part` is a valid async part number for the race, i.e. 1 or 2 for races with 2 entrants and 1, 2, or 3 for races with 3 entrants.
+fn check_async_part(race: &cal::Race, part: u8) -> Result<()> {
+    let num_parts = match race.entrants {
+        Entrants::Two(_) => 2,
+        Entrants::Three(_) => 3,
+        _ => return Err("Only races with 2 or 3 entrants can be asyncs.".into()),
+    };
+    if (1..=num_parts).contains(&part) {
+        Ok(())
+    } else {
+        Err(format!("This race only has {num_parts} async parts.").into())
+    }
+}
+
+/// Same wording as the Discord scheduling commands.
+const ROOM_ALREADY_OPEN: &str = "Sorry, this command can't be used since a race room is already open. Please contact a tournament organizer if necessary.";
+
+fn parse_room_url(room: &str) -> Result<Url> {
+    let room = Url::parse(room).map_err(|e| format!("Failed to parse race room URL: {e}"))?;
+    if room.host_str() == Some(racetime_host()) {
+        Ok(room)
+    } else {
+        Err("Race room must be a racetime.gg URL.".into())
+    }
+}
+
 #[Object] impl Mutation {
+    /// Schedules the race as a live race starting at the given time, or reschedules it if it's already scheduled.
+    /// If the race was previously scheduled as an async, the async start times are discarded.
+    /// A racetime.gg room is opened automatically shortly before the race.
+    /// Like the `/schedule` Discord command, this fails if a race room is already open or the start time doesn't give the event's minimum scheduling notice.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn schedule_race(&self, ctx: &Context<'_>, id: GqlId, start: UtcTimestamp) -> Result<Race> {
+        db!(db = ctx; {
+            let mut race = cal::Race::from_id(&mut *db, ctx.data_unchecked(), id.try_into()?).await?;
+            if race.has_any_room() {
+                return Err(ROOM_ALREADY_OPEN.into())
+            }
+            if let Some(error) = race.event(&mut *db).await?.schedule_notice_error(start.0) {
+                return Err(error.into())
+            }
+            race.schedule.set_live_start(start.0);
+            race.schedule_updated_at = Some(Utc::now());
+            save_edited_race(ctx, &mut *db, race).await
+        })
+    }
+
+    /// Sets the start time of one part of an async race. `part` is the 1-based index of the team playing that part, in the order the teams are listed in `Race.teams`.
+    /// If the race was previously scheduled as a live race, the live start time is discarded.
+    /// Like the `/schedule-async` Discord command, this fails if the race room for this part is already open or the start time doesn't give the event's minimum scheduling notice.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn schedule_race_async_part(&self, ctx: &Context<'_>, id: GqlId, part: u8, start: UtcTimestamp) -> Result<Race> {
+        db!(db = ctx; {
+            let mut race = cal::Race::from_id(&mut *db, ctx.data_unchecked(), id.try_into()?).await?;
+            check_async_part(&race, part)?;
+            let room_open = match &race.schedule {
+                RaceSchedule::Unscheduled => false,
+                RaceSchedule::Live { room, .. } => room.is_some(),
+                RaceSchedule::Async { room1, room2, room3, .. } => match part {
+                    1 => room1.is_some(),
+                    2 => room2.is_some(),
+                    3 => room3.is_some(),
+                    _ => unreachable!("checked by check_async_part"),
+                },
+            };
+            if room_open {
+                return Err(ROOM_ALREADY_OPEN.into())
+            }
+            if let Some(error) = race.event(&mut *db).await?.schedule_notice_error(start.0) {
+                return Err(error.into())
+            }
+            match part {
+                1 => { race.schedule.set_async_start1(start.0); }
+                2 => { race.schedule.set_async_start2(start.0); }
+                3 => { race.schedule.set_async_start3(start.0); }
+                _ => unreachable!("checked by check_async_part"),
+            }
+            race.schedule_updated_at = Some(Utc::now());
+            save_edited_race(ctx, &mut *db, race).await
+        })
+    }
+
+    /// Sets the racetime.gg room for a race whose room wasn't opened by Mido's House, e.g. because it was opened manually.
+    /// For async races, `asyncPart` specifies which part the room is for. For live races, it must be omitted.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn set_race_room(&self, ctx: &Context<'_>, id: GqlId, room: String, async_part: Option<u8>) -> Result<Race> {
+        let room = parse_room_url(&room)?;
+        edit_race(ctx, id, |race| {
+            if let Some(part) = async_part {
+                check_async_part(race, part)?;
+            }
+            match (&mut race.schedule, async_part) {
+                (RaceSchedule::Unscheduled, _) => return Err("The race room can't be added yet because the race isn't scheduled.".into()),
+                (RaceSchedule::Live { room: live_room, .. }, None) => *live_room = Some(room),
+                (RaceSchedule::Live { .. }, Some(_)) => return Err("This race is scheduled as a live race, so asyncPart must be omitted.".into()),
+                (RaceSchedule::Async { .. }, None) => return Err("This race is scheduled as an async, so asyncPart is required.".into()),
+                (RaceSchedule::Async { room1, .. }, Some(1)) => *room1 = Some(room),
+                (RaceSchedule::Async { room2, .. }, Some(2)) => *room2 = Some(room),
+                (RaceSchedule::Async { room3, .. }, Some(3)) => *room3 = Some(room),
+                (RaceSchedule::Async { .. }, Some(_)) => unreachable!("checked by check_async_part"),
+            }
+            Ok(())
+        }).await
+    }
+
+    /// Records the time at which a race (or, for async races, the part specified by `asyncPart`) ended.
+    /// This is only needed for races without racetime.gg rooms, since results are read from the room otherwise.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn set_race_end(&self, ctx: &Context<'_>, id: GqlId, end: UtcTimestamp, async_part: Option<u8>) -> Result<Race> {
+        edit_race(ctx, id, |race| {
+            if let Some(part) = async_part {
+                check_async_part(race, part)?;
+            }
+            match (&mut race.schedule, async_part) {
+                (RaceSchedule::Unscheduled, _) => return Err("The race can't have ended because it isn't scheduled.".into()),
+                (RaceSchedule::Live { end: live_end, .. }, None) => *live_end = Some(end.0),
+                (RaceSchedule::Live { .. }, Some(_)) => return Err("This race is scheduled as a live race, so asyncPart must be omitted.".into()),
+                (RaceSchedule::Async { .. }, None) => return Err("This race is scheduled as an async, so asyncPart is required.".into()),
+                (RaceSchedule::Async { end1, .. }, Some(1)) => *end1 = Some(end.0),
+                (RaceSchedule::Async { end2, .. }, Some(2)) => *end2 = Some(end.0),
+                (RaceSchedule::Async { end3, .. }, Some(3)) => *end3 = Some(end.0),
+                (RaceSchedule::Async { .. }, Some(_)) => unreachable!("checked by check_async_part"),
+            }
+            Ok(())
+        }).await
+    }
+
+    /// Adds a link to a VoD of the race from the point of view of the given player, e.g. for races which weren't restreamed.
+    /// `player` is a Mido's House user ID.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn add_race_player_video(&self, ctx: &Context<'_>, id: GqlId, player: GqlId, video_url: String) -> Result<Race> {
+        let video_url = Url::parse(&video_url).map_err(|e| format!("Failed to parse URL: {e}"))?;
+        let player = Id::<Users>::try_from(&player)?;
+        db!(db = ctx; {
+            if user::User::from_id(&mut **db, player).await?.is_none() {
+                return Err("There is no Mido's House user with this ID.".into())
+            }
+            let race = cal::Race::from_id(&mut *db, ctx.data_unchecked(), id.try_into()?).await?;
+            sqlx::query!("INSERT INTO race_player_videos (race, player, video) VALUES ($1, $2, $3)", race.id as _, player as _, video_url.to_string()).execute(&mut **db).await?;
+            save_edited_race(ctx, &mut *db, race).await
+        })
+    }
+
+    /// Records the results of a race with 2 or 3 entrants, replacing any results that were reported automatically.
+    /// This is needed for races whose results aren't reported automatically, e.g. because FPA was called or the top finishers were within the retime window.
+    /// Each entrant must be listed exactly once. The winner is the entrant placed first who did not forfeit. If this decides a best-of-N match, the match's remaining games are ignored.
+    /// Requires permission to edit races and an API key with the `write` scope.
+    #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
+    async fn record_race_results(&self, ctx: &Context<'_>, id: GqlId, results: Vec<RaceResultInput>) -> Result<Race> {
+        db!(db = ctx; {
+            let race = cal::Race::from_id(&mut *db, ctx.data_unchecked(), id.try_into()?).await?;
+            let entrants = match race.entrants {
+                Entrants::Two(ref entrants) => &entrants[..],
+                Entrants::Three(ref entrants) => &entrants[..],
+                Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => return Err("Only results of races with 2 or 3 entrants can be recorded.".into()),
+            };
+            if results.len() != entrants.len() || !results.iter().map(|result| result.entrant).all_unique() {
+                return Err(format!("Each of the race's {} entrants must be listed exactly once.", entrants.len()).into())
+            }
+            let results = results.into_iter().map(|result| -> Result<cal::RaceResult> {
+                let entrant = usize::from(result.entrant).checked_sub(1).and_then(|idx| entrants.get(idx)).ok_or_else(|| format!("This race only has {} entrants.", entrants.len()))?;
+                if result.placement == 0 {
+                    return Err("Placements count from 1.".into())
+                }
+                let finish_time = result.finish_time.map(Duration::try_from_secs_f64).transpose().map_err(|e| format!("Invalid finish time: {e}"))?;
+                if finish_time.is_none() && !result.dnf {
+                    return Err("A finish time is required unless the entrant did not finish.".into())
+                }
+                Ok(cal::RaceResult {
+                    entrant: entrant.clone(),
+                    placement: result.placement,
+                    tfb_pieces: result.triforce_pieces,
+                    dnf: result.dnf,
+                    fpa_invoked: result.fpa_invoked,
+                    breaks_used: result.breaks_used,
+                    finish_time,
+                })
+            }).try_collect::<_, Vec<_>, _>()?;
+            race.record_results(&mut *db, ctx.data_unchecked(), &results).await?;
+            save_edited_race(ctx, &mut *db, race).await
+        })
+    }
+
     /// Requires permission to edit races and an API key with the `write` scope.
     #[graphql(guard = Scopes { write: true, ..Scopes::default() }.and(EditRace(id.clone())))]
     async fn set_race_restream_url(&self, ctx: &Context<'_>, id: GqlId, language: Language, restream_url: String) -> Result<Race> {
@@ -306,10 +586,122 @@ struct Series(crate::series::Series);
 struct Event(event::Data<'static>);
 
 #[Object] impl Event {
+    /// The event's URL part. Unique within the series.
+    async fn id(&self) -> &str { &self.0.event }
+
+    /// The event's full human-readable name.
+    async fn display_name(&self) -> &str { &self.0.display_name }
+
+    /// The event's originally scheduled starting time, not accounting for the 24-hour deadline extension in the event of an odd number of teams for events with qualifier asyncs.
+    /// Null if the start time has not been announced yet.
+    async fn base_start(&self) -> Option<UtcTimestamp> { self.0.base_start.map(UtcTimestamp::from) }
+
+    /// The time the event ended. Null if the event has not ended yet.
+    async fn end(&self) -> Option<UtcTimestamp> { self.0.end.map(UtcTimestamp::from) }
+
+    async fn team_config(&self) -> TeamConfig { self.0.team_config }
+
+    /// The main language of the event, used for example for Discord and racetime.gg messages.
+    async fn language(&self) -> Language { self.0.language }
+
     /// All past, upcoming, and unscheduled races for this event, sorted chronologically.
     async fn races(&self, ctx: &Context<'_>) -> Result<Vec<Race>, cal::Error> {
         Ok(db!(db = ctx; cal::Race::for_event(&mut *db, ctx.data_unchecked(), &self.0).await?).into_iter().map(Race).collect())
     }
+
+    /// All teams signed up for this event, sorted the same way as on the teams page of the website, i.e. by qualifier placement if the event has qualifiers.
+    /// Requires permission to view entrants and an API key with `entrants_read` scope.
+    #[graphql(guard = Scopes { entrants_read: true, ..Scopes::default() }.and(ShowEntrants(&self.0)))]
+    async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<Signup>> {
+        let me = &ctx.data_unchecked::<ApiKey>().user;
+        let signups = db!(db = ctx; {
+            let is_organizer = self.0.organizers(&mut *db).await?.contains(me);
+            let qualifier_kind = teams::qualifier_kind(&mut *db, &self.0, Some(me)).await?;
+            teams::signups_sorted(&mut *db, &mut teams::Cache::new(ctx.data_unchecked::<reqwest::Client>().clone()), Some(me), &self.0, is_organizer, qualifier_kind, None).await?
+        });
+        Ok(signups.into_iter().enumerate().map(|(idx, inner)| Signup { rank: idx + 1, inner, event: self.0.clone() }).collect())
+    }
+
+    /// The organizers of this event, sorted by display name.
+    async fn organizers(&self, ctx: &Context<'_>) -> Result<Vec<User>, event::Error> {
+        Ok(db!(db = ctx; self.0.organizers(&mut *db).await?).into_iter().map(User).collect())
+    }
+
+    /// The restream coordinators of this event, sorted by display name.
+    /// Requires permission to view entrants and an API key with `entrants_read` scope.
+    #[graphql(guard = Scopes { entrants_read: true, ..Scopes::default() }.and(ShowEntrants(&self.0)))]
+    async fn restreamers(&self, ctx: &Context<'_>) -> Result<Vec<User>, event::Error> {
+        Ok(db!(db = ctx; self.0.restreamers(&mut *db).await?).into_iter().map(User).collect())
+    }
+
+    /// The volunteer roles (e.g. commentary or tracking) this event is looking for, sorted by name.
+    async fn volunteer_roles(&self, ctx: &Context<'_>) -> sqlx::Result<Vec<VolunteerRole>> {
+        Ok(db!(db = ctx; roles::RoleBinding::for_event(&mut *db, self.0.series, &self.0.event).await?).into_iter().map(|binding| VolunteerRole {
+            id: binding.id.into(),
+            name: binding.role_type_name,
+            min_count: binding.min_count,
+            max_count: binding.max_count,
+            discord_role_id: binding.discord_role_id.map(|id| GqlId::from(id.to_string())),
+        }).collect())
+    }
+}
+
+struct Signup {
+    rank: usize,
+    inner: teams::SignupsTeam,
+    event: event::Data<'static>,
+}
+
+#[Object] impl Signup {
+    /// The team's position in the list of signups, counting from 1.
+    async fn rank(&self) -> usize { self.rank }
+
+    /// Null if this signup doesn't correspond to a Mido's House team.
+    async fn team(&self) -> Option<Team> {
+        self.inner.team.clone().map(|inner| Team { inner, event: self.event.clone() })
+    }
+
+    /// Whether the team has qualified for the main part of the event. Null for events whose qualifiers are scored across multiple races.
+    async fn qualified(&self) -> Option<bool> {
+        match self.inner.qualification {
+            teams::Qualification::Single { qualified } | teams::Qualification::TriforceBlitz { qualified, .. } => Some(qualified),
+            teams::Qualification::Multiple { .. } => None,
+        }
+    }
+
+    /// The number of Triforce pieces the team collected in the qualifier. Null for events other than Triforce Blitz.
+    async fn triforce_pieces(&self) -> Option<i16> {
+        if let teams::Qualification::TriforceBlitz { pieces, .. } = self.inner.qualification { Some(pieces) } else { None }
+    }
+
+    /// The number of qualifier races the team entered. Null for events whose qualifiers are not scored across multiple races.
+    async fn qualifiers_entered(&self) -> Option<usize> {
+        if let teams::Qualification::Multiple { num_entered, .. } = self.inner.qualification { Some(num_entered) } else { None }
+    }
+
+    /// The number of qualifier races the team finished. Null for events whose qualifiers are not scored across multiple races.
+    async fn qualifiers_finished(&self) -> Option<usize> {
+        if let teams::Qualification::Multiple { num_finished, .. } = self.inner.qualification { Some(num_finished) } else { None }
+    }
+
+    /// The team's overall qualifier score. Null for events whose qualifiers are not scored across multiple races.
+    async fn qualifier_score(&self) -> Option<f64> {
+        if let teams::Qualification::Multiple { score, .. } = self.inner.qualification { Some(score.raw()) } else { None }
+    }
+}
+
+#[derive(SimpleObject)]
+struct VolunteerRole {
+    /// The internal ID of this event's binding of the role. Only unique for volunteer roles.
+    id: GqlId,
+    /// The name of the role, e.g. “Commentary”.
+    name: String,
+    /// The minimum number of volunteers with this role needed for a race.
+    min_count: i32,
+    /// The maximum number of volunteers with this role accepted for a race.
+    max_count: i32,
+    /// The Discord role snowflake ID given to volunteers with this role, if any.
+    discord_role_id: Option<GqlId>,
 }
 
 struct Race(cal::Race);
@@ -360,6 +752,18 @@ struct Race(cal::Race);
         Ok(self.0.teams_opt().map(|teams| teams.map(|team| Team { inner: team.clone(), event: event.clone() }).collect()))
     }
 
+    /// The result of each entrant, ordered by placement.
+    /// Empty if the race has not finished yet or if its result was neither reported automatically nor recorded by an organizer, e.g. because FPA was called or the top finishers were within the retime window.
+    async fn results(&self, ctx: &Context<'_>) -> Result<Vec<RaceResult>, cal::Error> {
+        let (event, results) = db!(db = ctx; (self.0.event(&mut *db).await?, cal::RaceResult::for_race(&mut *db, self.0.id).await?));
+        Ok(results.into_iter().map(|inner| RaceResult { inner, event: event.clone() }).collect())
+    }
+
+    /// The seed for this race. Null if the seed has not been rolled yet or is not public yet.
+    async fn seed(&self) -> Option<Seed> {
+        (self.0.show_seed() && (self.0.seed.files.is_some() || self.0.seed.file_hash.is_some())).then(|| Seed(self.0.seed.clone()))
+    }
+
     /// Whether all teams in this race have consented to be restreamed.
     /// Null if the race is open (not invitational) or if the event does not use Mido's House to manage entrants.
     /// Requires permission to view restream consent and an API key with `entrants_read` scope.
@@ -369,6 +773,77 @@ struct Race(cal::Race);
     }
 }
 
+struct RaceResult {
+    inner: cal::RaceResult,
+    event: event::Data<'static>,
+}
+
+#[Object] impl RaceResult {
+    /// The team this result belongs to. Null if the entrant is not a Mido's House team.
+    async fn team(&self) -> Option<Team> {
+        if let Entrant::MidosHouseTeam(ref team) = self.inner.entrant {
+            Some(Team { inner: team.clone(), event: self.event.clone() })
+        } else {
+            None
+        }
+    }
+
+    /// The entrant's Discord user ID. Null if the entrant is a Mido's House team or not known by their Discord account.
+    async fn discord_id(&self) -> Option<GqlId> {
+        if let Entrant::Discord { id, .. } = self.inner.entrant { Some(GqlId::from(id.to_string())) } else { None }
+    }
+
+    /// The entrant's name. Null if the entrant is a Mido's House team or a Discord user.
+    async fn name(&self) -> Option<&str> {
+        if let Entrant::Named { ref name, .. } = self.inner.entrant { Some(name) } else { None }
+    }
+
+    /// The entrant's racetime.gg user ID. Null if the entrant is a Mido's House team (see the team's members instead) or if it is not known.
+    async fn racetime_id(&self) -> Option<GqlId> {
+        match self.inner.entrant {
+            Entrant::MidosHouseTeam(_) => None,
+            Entrant::Discord { ref racetime_id, .. } | Entrant::Named { ref racetime_id, .. } => racetime_id.as_ref().map(GqlId::from),
+        }
+    }
+
+    /// The entrant's placement, counting from 1. Entrants with the same score share a placement.
+    async fn placement(&self) -> u16 { self.inner.placement }
+
+    /// The entrant's finish time in seconds. For Triforce Blitz, the time the last Triforce piece was collected. Null if the entrant did not finish.
+    async fn finish_time(&self) -> Option<f64> { self.inner.finish_time.map(|finish_time| finish_time.as_secs_f64()) }
+
+    /// The number of Triforce pieces the entrant collected. Null for events other than Triforce Blitz.
+    async fn triforce_pieces(&self) -> Option<u8> { self.inner.tfb_pieces }
+
+    /// Whether the entrant did not finish. For Triforce Blitz, this means no Triforce pieces were collected.
+    async fn dnf(&self) -> bool { self.inner.dnf }
+
+    /// Whether FPA was called in the race room the entrant raced in.
+    async fn fpa_invoked(&self) -> bool { self.inner.fpa_invoked }
+
+    /// Whether breaks were used in the race room the entrant raced in.
+    async fn breaks_used(&self) -> bool { self.inner.breaks_used }
+}
+
+struct Seed(seed::Data);
+
+#[Object] impl Seed {
+    /// The seed's file hash as a list of 5 hash icon names. Null if the hash is not known.
+    async fn file_hash(&self) -> Result<Option<Vec<String>>, seed::ExtraDataError> {
+        Ok(self.0.extra(Utc::now()).await?.file_hash.map(|file_hash| file_hash.iter().map(|icon| icon.to_string()).collect()))
+    }
+
+    /// A page where the seed can be viewed or its patch file downloaded. Null if the seed files are not available.
+    async fn url(&self) -> Option<String> {
+        self.0.url(Utc::now()).map(|url| url.to_string())
+    }
+
+    /// The URL of the seed's spoiler log. Null if the spoiler log is not available or still locked.
+    async fn spoiler_log_url(&self) -> Result<Option<String>, seed::ExtraDataError> {
+        Ok(self.0.extra(Utc::now()).await?.spoiler_log_url().map(|url| url.to_string()))
+    }
+}
+
 struct Team {
     inner: team::Team,
     event: event::Data<'static>,
@@ -427,8 +902,113 @@ struct User(user::User);
     }
 }
 
+/// A kind of change to a race, as reported by the `raceUpdates` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Enum)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum RaceUpdateKind {
+    /// The race's start time (or one of its async start times) was set or changed.
+    Schedule,
+    /// A racetime.gg room for the race was opened.
+    Room,
+    /// The seed for the race was rolled.
+    Seed,
+    /// A step of the race's settings draft was completed or undone.
+    Draft,
+    /// The race (or one of its async parts) ended.
+    Results,
+}
+
+/// The payload of a notification on the `race_updates` database channel, see migration 011.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct RaceUpdateNotification {
+    pub(crate) race: i64,
+    pub(crate) series: String,
+    pub(crate) event: String,
+    pub(crate) kinds: Vec<RaceUpdateKind>,
+}
+
+static RACE_UPDATES: LazyLock<broadcast::Sender<RaceUpdateNotification>> = LazyLock::new(|| broadcast::channel(256).0);
+
+/// Receives the same race updates as GraphQL subscribers. Used for outgoing webhooks and for rescheduling background jobs.
+pub(crate) fn subscribe_race_updates() -> broadcast::Receiver<RaceUpdateNotification> {
+    RACE_UPDATES.subscribe()
+}
+
+/// Forwards race updates from the database to GraphQL subscribers.
+///
+/// The notifications are sent by a trigger on the `races` table, so they cover all changes regardless of where they were made, and only arrive once the change has been committed.
+pub(crate) async fn forward_race_updates(db_pool: PgPool, mut shutdown: rocket::Shutdown) -> sqlx::Result<()> {
+    let mut listener = PgListener::connect_with(&db_pool).await?;
+    listener.listen("race_updates").await?;
+    loop {
+        select! {
+            () = &mut shutdown => break,
+            res = listener.recv() => match serde_json::from_str::<RaceUpdateNotification>(res?.payload()) {
+                Ok(update) => { let _ = RACE_UPDATES.send(update); } // an error here just means that nobody is currently subscribed
+                Err(e) => eprintln!("received malformed race update notification: {e} ({e:?})"),
+            },
+        }
+    }
+    Ok(())
+}
+
+struct RaceUpdate(RaceUpdateNotification);
+
+#[Object] impl RaceUpdate {
+    /// What changed about the race. Newly created races are reported with all kinds.
+    async fn kinds(&self) -> Vec<RaceUpdateKind> { self.0.kinds.clone() }
+
+    /// The race as of when this update is delivered.
+    async fn race(&self, ctx: &Context<'_>) -> Result<Race, cal::Error> {
+        Ok(Race(db!(db = ctx; cal::Race::from_id(&mut *db, ctx.data_unchecked(), Id::from(self.0.race)).await?)))
+    }
+}
+
+pub(crate) struct Subscription;
+
+#[Subscription] impl Subscription {
+    /// Notifies about races being scheduled, getting racetime.gg rooms, having their seeds rolled, progressing in their settings drafts, and ending.
+    ///
+    /// All arguments are optional filters. `series` and `event` are URL parts, e.g. `s` and `7`.
+    ///
+    /// If the subscriber falls behind, the updates it missed are replaced with a single error saying how many were skipped. Since they could have been about any race, clients should refetch the races they're interested in when this happens.
+    async fn race_updates(&self, ctx: &Context<'_>, series: Option<String>, event: Option<String>, race: Option<GqlId>, kinds: Option<Vec<RaceUpdateKind>>) -> Result<impl Stream<Item = Result<RaceUpdate>>> {
+        if let Some(ref series) = series {
+            let exists = if let Ok(series) = crate::series::Series::from_str(series) {
+                db!(db = ctx; series.display_name(&mut *db).await?).is_some()
+            } else {
+                false
+            };
+            if !exists {
+                return Err(format!("There is no series with the URL part {series:?}.").into())
+            }
+        }
+        let race = race.as_ref().map(Id::<Races>::try_from).transpose()?.map(i64::from);
+        Ok(stream::unfold(RACE_UPDATES.subscribe(), |mut rx| async move {
+            match rx.recv().await {
+                Ok(update) => Some((Ok(update), rx)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => Some((Err(skipped), rx)),
+                Err(broadcast::error::RecvError::Closed) => None,
+            }
+        }).filter_map(move |update| future::ready(match update {
+            Ok(mut update) => {
+                if let Some(ref kinds) = kinds {
+                    update.kinds.retain(|kind| kinds.contains(kind));
+                }
+                (
+                    !update.kinds.is_empty()
+                    && series.as_ref().is_none_or(|series| update.series == *series)
+                    && event.as_ref().is_none_or(|event| update.event == *event)
+                    && race.is_none_or(|race| update.race == race)
+                ).then(|| Ok(RaceUpdate(update)))
+            }
+            Err(skipped) => Some(Err(format!("{skipped} race updates were skipped because this subscription fell behind. Refetch the races you're interested in to catch up.").into())),
+        })))
+    }
+}
+
 pub(crate) fn schema(db_pool: PgPool) -> MidosHouseSchema {
-    Schema::build(Query, Mutation, EmptySubscription)
+    Schema::build(Query, Mutation, Subscription)
         .data(db_pool)
         .finish()
 }
@@ -438,6 +1018,25 @@ pub(crate) struct ApiKey {
     user: user::User,
 }
 
+impl ApiKey {
+    async fn from_key(db_pool: &PgPool, api_key: &str) -> sqlx::Result<Option<Self>> {
+        let Some(row) = sqlx::query!(r#"SELECT
+            entrants_read,
+            user_search,
+            write,
+            user_id AS "user_id: Id<Users>"
+        FROM api_keys WHERE key = $1"#, api_key).fetch_optional(db_pool).await? else { return Ok(None) };
+        Ok(Some(Self {
+            scopes: Scopes {
+                entrants_read: row.entrants_read,
+                user_search: row.user_search,
+                write: row.write,
+            },
+            user: user::User::from_id(db_pool, row.user_id).await?.expect("database constraint validated: API keys belong to existing users"),
+        }))
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum ApiKeyFromRequestError {
     #[error(transparent)] Sql(#[from] sqlx::Error),
@@ -462,23 +1061,8 @@ impl<'r> FromRequest<'r> for ApiKey {
             request::Outcome::Error((status, ())) => return request::Outcome::Error((status, ApiKeyFromRequestError::DbPool)),
         };
         match req.headers().get("X-API-Key").at_most_one() {
-            Ok(Some(api_key)) => match sqlx::query!(r#"SELECT
-                entrants_read,
-                user_search,
-                write,
-                user_id AS "user_id: Id<Users>"
-            FROM api_keys WHERE key = $1"#, api_key).fetch_optional(&**db_pool).await {
-                Ok(Some(row)) => request::Outcome::Success(Self {
-                    scopes: Scopes {
-                        entrants_read: row.entrants_read,
-                        user_search: row.user_search,
-                        write: row.write,
-                    },
-                    user: match user::User::from_id(&**db_pool, row.user_id).await {
-                        Ok(user) => user.expect("database constraint validated: API keys belong to existing users"),
-                        Err(e) => return request::Outcome::Error((Status::InternalServerError, ApiKeyFromRequestError::Sql(e))),
-                    },
-                }),
+            Ok(Some(api_key)) => match Self::from_key(db_pool, api_key).await {
+                Ok(Some(api_key)) => request::Outcome::Success(api_key),
                 Ok(None) => request::Outcome::Error((Status::Unauthorized, ApiKeyFromRequestError::NoSuchApiKey)),
                 Err(e) => request::Outcome::Error((Status::InternalServerError, ApiKeyFromRequestError::Sql(e))),
             },
@@ -518,9 +1102,80 @@ pub(crate) async fn graphql_request(config: &State<Config>, db_pool: &State<PgPo
     Ok(response)
 }
 
+pub(crate) struct GraphQLProtocol(WebSocketProtocols);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for GraphQLProtocol {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, ()> {
+        match req.headers().get("Sec-WebSocket-Protocol").flat_map(|protocols| protocols.split(',')).find_map(|protocol| protocol.trim().parse().ok()) {
+            Some(protocol) => request::Outcome::Success(Self(protocol)),
+            None => request::Outcome::Error((Status::BadRequest, ())),
+        }
+    }
+}
+
+#[derive(Responder)]
+pub(crate) struct GraphQLSubscriptionResponse {
+    inner: Channel<'static>,
+    protocol: Header<'static>,
+}
+
+/// Handles GraphQL subscriptions using either the `graphql-transport-ws` or the legacy `graphql-ws` protocol.
+///
+/// Since browsers can't set headers on WebSocket connections, the API key can also be given as `apiKey` in the `connection_init` payload instead of using the X-API-Key header.
+#[rocket::get("/api/v1/graphql/ws")]
+pub(crate) fn graphql_subscription(config: &State<Config>, db_pool: &State<PgPool>, http_client: &State<reqwest::Client>, schema: &State<MidosHouseSchema>, api_key: Option<ApiKey>, GraphQLProtocol(protocol): GraphQLProtocol, ws: WebSocket) -> GraphQLSubscriptionResponse {
+    let config = (*config).clone();
+    let db_pool = (*db_pool).clone();
+    let http_client = (*http_client).clone();
+    let schema = (*schema).clone();
+    let protocol_header = Header::new("Sec-WebSocket-Protocol", protocol.sec_websocket_protocol());
+    GraphQLSubscriptionResponse {
+        inner: ws.channel(move |stream| Box::pin(async move {
+            let (mut sink, stream) = stream.split();
+            let stream = stream
+                .take_while(|msg| future::ready(msg.is_ok()))
+                .filter_map(|msg| future::ready(match msg {
+                    Ok(Message::Text(text)) => Some(text.into_bytes()),
+                    Ok(Message::Binary(data)) => Some(data),
+                    _ => None,
+                }));
+            let mut data = Data::default();
+            data.insert(config);
+            data.insert(http_client);
+            if let Some(api_key) = api_key {
+                data.insert(api_key);
+            }
+            let mut messages = GraphQLWebSocket::new(schema, stream, protocol)
+                .connection_data(data)
+                .on_connection_init(move |payload| async move {
+                    let mut data = Data::default();
+                    if let Some(api_key) = payload.get("apiKey") {
+                        let api_key = api_key.as_str().ok_or("apiKey must be a string")?;
+                        data.insert(ApiKey::from_key(&db_pool, api_key).await?.ok_or("the given API key does not exist")?);
+                    }
+                    Ok(data)
+                });
+            while let Some(msg) = messages.next().await {
+                match msg {
+                    WsMessage::Text(text) => sink.send(Message::Text(text)).await?,
+                    WsMessage::Close(code, reason) => {
+                        sink.send(Message::Close(Some(CloseFrame { code: code.into(), reason: reason.into() }))).await?;
+                        break
+                    }
+                }
+            }
+            Ok(())
+        })),
+        protocol: protocol_header,
+    }
+}
+
 #[rocket::get("/api/v1/graphql")]
 pub(crate) fn graphql_playground() -> RawHtml<String> {
-    RawHtml(playground_source(GraphQLPlaygroundConfig::new("/api/v1/graphql")))
+    RawHtml(playground_source(GraphQLPlaygroundConfig::new("/api/v1/graphql").subscription_endpoint("/api/v1/graphql/ws")))
 }
 
 #[derive(Debug, thiserror::Error, rocket_util::Error)]
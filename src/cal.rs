@@ -38,6 +38,13 @@ use {
 };
 pub(crate) use mhstatus::EventKind;
 
+#[cfg(test)] mod tests;
+
+/// How long before the start of a race its room is opened.
+const ROOM_LEAD_TIME: TimeDelta = TimeDelta::minutes(30);
+/// Open races get more time for entrants to join. This is also the longest lead time, see [`Event::rooms_to_open`].
+const OPEN_RACE_ROOM_LEAD_TIME: TimeDelta = TimeDelta::hours(1);
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Source {
     Manual,
@@ -88,6 +95,14 @@ impl Entrant {
         })
     }
 
+    fn to_db(&self) -> (Option<Id<Teams>>, Option<&String>, Option<UserId>, Option<&String>, Option<&String>) {
+        match self {
+            Self::MidosHouseTeam(team) => (Some(team.id), None, None, None, None),
+            Self::Discord { id, racetime_id, twitch_username } => (None, None, Some(*id), racetime_id.as_ref(), twitch_username.as_ref()),
+            Self::Named { name, racetime_id, twitch_username } => (None, Some(name), None, racetime_id.as_ref(), twitch_username.as_ref()),
+        }
+    }
+
     pub(crate) fn name_is_plural(&self) -> bool {
         match self {
             Self::MidosHouseTeam(team) => team.name_is_plural(),
@@ -160,46 +175,174 @@ pub(crate) enum Entrants {
 }
 
 impl Entrants {
-    fn to_db(&self) -> ([Option<Id<Teams>>; 3], [Option<&String>; 3], [Option<UserId>; 2], [Option<&String>; 2], [Option<&String>; 2], [Option<u32>; 2]) {
+    fn to_db(&self) -> ([Option<Id<Teams>>; 3], [Option<&String>; 3], [Option<UserId>; 3], [Option<&String>; 3], [Option<&String>; 3], [Option<u32>; 2]) {
         match *self {
-            Entrants::Open => ([None; 3], [None; 3], [None; 2], [None; 2], [None; 2], [None; 2]),
-            Entrants::Count { total, finished } => ([None; 3], [None; 3], [None; 2], [None; 2], [None; 2], [Some(total), Some(finished)]),
-            Entrants::Named(ref entrants) => ([None; 3], [Some(entrants), None, None], [None; 2], [None; 2], [None; 2], [None; 2]),
+            Entrants::Open => ([None; 3], [None; 3], [None; 3], [None; 3], [None; 3], [None; 2]),
+            Entrants::Count { total, finished } => ([None; 3], [None; 3], [None; 3], [None; 3], [None; 3], [Some(total), Some(finished)]),
+            Entrants::Named(ref entrants) => ([None; 3], [Some(entrants), None, None], [None; 3], [None; 3], [None; 3], [None; 2]),
             Entrants::Two([ref p1, ref p2]) => {
-                let (team1, p1, p1_discord, p1_racetime, p1_twitch) = match p1 {
-                    Entrant::MidosHouseTeam(team) => (Some(team.id), None, None, None, None),
-                    Entrant::Discord { id, racetime_id, twitch_username } => (None, None, Some(*id), racetime_id.as_ref(), twitch_username.as_ref()),
-                    Entrant::Named { name, racetime_id, twitch_username } => (None, Some(name), None, racetime_id.as_ref(), twitch_username.as_ref()),
-                };
-                let (team2, p2, p2_discord, p2_racetime, p2_twitch) = match p2 {
-                    Entrant::MidosHouseTeam(team) => (Some(team.id), None, None, None, None),
-                    Entrant::Discord { id, racetime_id, twitch_username } => (None, None, Some(*id), racetime_id.as_ref(), twitch_username.as_ref()),
-                    Entrant::Named { name, racetime_id, twitch_username } => (None, Some(name), None, racetime_id.as_ref(), twitch_username.as_ref()),
-                };
-                ([team1, team2, None], [p1, p2, None], [p1_discord, p2_discord], [p1_racetime, p2_racetime], [p1_twitch, p2_twitch], [None; 2])
+                let (team1, p1, p1_discord, p1_racetime, p1_twitch) = p1.to_db();
+                let (team2, p2, p2_discord, p2_racetime, p2_twitch) = p2.to_db();
+                ([team1, team2, None], [p1, p2, None], [p1_discord, p2_discord, None], [p1_racetime, p2_racetime, None], [p1_twitch, p2_twitch, None], [None; 2])
             }
             Entrants::Three([ref p1, ref p2, ref p3]) => {
-                let (team1, p1) = match p1 {
-                    Entrant::MidosHouseTeam(team) => (Some(team.id), None),
-                    Entrant::Named { name, racetime_id: None, twitch_username: None } => (None, Some(name)),
-                    _ => unimplemented!(), //TODO
-                };
-                let (team2, p2) = match p2 {
-                    Entrant::MidosHouseTeam(team) => (Some(team.id), None),
-                    Entrant::Named { name, racetime_id: None, twitch_username: None } => (None, Some(name)),
-                    _ => unimplemented!(), //TODO
-                };
-                let (team3, p3) = match p3 {
-                    Entrant::MidosHouseTeam(team) => (Some(team.id), None),
-                    Entrant::Named { name, racetime_id: None, twitch_username: None } => (None, Some(name)),
-                    _ => unimplemented!(), //TODO
-                };
-                ([team1, team2, team3], [p1, p2, p3], [None; 2], [None; 2], [None; 2], [None; 2])
+                let (team1, p1, p1_discord, p1_racetime, p1_twitch) = p1.to_db();
+                let (team2, p2, p2_discord, p2_racetime, p2_twitch) = p2.to_db();
+                let (team3, p3, p3_discord, p3_racetime, p3_twitch) = p3.to_db();
+                ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord, p3_discord], [p1_racetime, p2_racetime, p3_racetime], [p1_twitch, p2_twitch, p3_twitch], [None; 2])
             }
         }
     }
 }
 
+/// One entrant's result of a race, recorded when the result is reported automatically or entered by an organizer.
+#[derive(Clone)]
+pub(crate) struct RaceResult {
+    pub(crate) entrant: Entrant,
+    /// Counting from 1. Entrants with the same score share a placement.
+    pub(crate) placement: u16,
+    /// For Triforce Blitz, the time the last piece was collected.
+    pub(crate) finish_time: Option<Duration>,
+    pub(crate) tfb_pieces: Option<u8>,
+    pub(crate) dnf: bool,
+    /// Whether FPA was called in the room this entrant raced in.
+    pub(crate) fpa_invoked: bool,
+    /// Whether breaks were used in the room this entrant raced in.
+    pub(crate) breaks_used: bool,
+}
+
+impl RaceResult {
+    /// Returns the recorded results of the given race, ordered by placement.
+    pub(crate) async fn for_race(transaction: &mut Transaction<'_, Postgres>, race: Id<Races>) -> Result<Vec<Self>, Error> {
+        let rows = sqlx::query!(r#"SELECT
+            team AS "team: Id<Teams>",
+            discord_id AS "discord_id: PgSnowflake<UserId>",
+            name,
+            racetime_id,
+            twitch_username,
+            placement,
+            finish_time,
+            tfb_pieces,
+            dnf,
+            fpa_invoked,
+            breaks_used
+        FROM race_results WHERE race = $1 ORDER BY placement, finish_time NULLS LAST"#, race as _).fetch_all(&mut **transaction).await?;
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let entrant = if let Some(team) = row.team {
+                Entrant::MidosHouseTeam(Team::from_id(&mut *transaction, team).await?.ok_or(Error::UnknownTeam)?)
+            } else if let Some(PgSnowflake(id)) = row.discord_id {
+                Entrant::Discord {
+                    racetime_id: row.racetime_id,
+                    twitch_username: row.twitch_username,
+                    id,
+                }
+            } else {
+                Entrant::Named {
+                    name: row.name.expect("race result without entrant"),
+                    racetime_id: row.racetime_id,
+                    twitch_username: row.twitch_username,
+                }
+            };
+            results.push(Self {
+                placement: row.placement.try_into()?,
+                finish_time: row.finish_time.map(decode_pginterval).transpose()?,
+                tfb_pieces: row.tfb_pieces.map(u8::try_from).transpose()?,
+                dnf: row.dnf,
+                fpa_invoked: row.fpa_invoked,
+                breaks_used: row.breaks_used,
+                entrant,
+            });
+        }
+        Ok(results)
+    }
+
+    /// Returns the recorded results of the given team along with a description of each race (e.g. “Swiss Round 1, game 2”), ordered by when the races were scheduled.
+    pub(crate) async fn for_team(transaction: &mut Transaction<'_, Postgres>, team: &Team) -> Result<Vec<(Option<String>, Self)>, Error> {
+        let rows = sqlx::query!(r#"SELECT
+            phase,
+            round,
+            game,
+            placement,
+            finish_time,
+            tfb_pieces,
+            dnf,
+            race_results.fpa_invoked,
+            race_results.breaks_used
+        FROM race_results, races WHERE race = id AND team = $1 ORDER BY COALESCE(start, async_start1, async_start2, async_start3), id"#, team.id as _).fetch_all(&mut **transaction).await?;
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut description = match (row.phase, row.round) {
+                (Some(phase), Some(round)) => Some(format!("{phase} {round}")),
+                (Some(phase), None) => Some(phase),
+                (None, Some(round)) => Some(round),
+                (None, None) => None,
+            };
+            if let Some(game) = row.game {
+                description = Some(if let Some(description) = description { format!("{description}, game {game}") } else { format!("game {game}") });
+            }
+            results.push((description, Self {
+                entrant: Entrant::MidosHouseTeam(team.clone()),
+                placement: row.placement.try_into()?,
+                finish_time: row.finish_time.map(decode_pginterval).transpose()?,
+                tfb_pieces: row.tfb_pieces.map(u8::try_from).transpose()?,
+                dnf: row.dnf,
+                fpa_invoked: row.fpa_invoked,
+                breaks_used: row.breaks_used,
+            }));
+        }
+        Ok(results)
+    }
+
+    /// Replaces any previously recorded results of the given race.
+    pub(crate) async fn save_all(transaction: &mut Transaction<'_, Postgres>, race: Id<Races>, results: &[Self]) -> Result<(), Error> {
+        sqlx::query!("DELETE FROM race_results WHERE race = $1", race as _).execute(&mut **transaction).await?;
+        for result in results {
+            let (team, name, discord_id, racetime_id, twitch_username) = result.entrant.to_db();
+            sqlx::query!("INSERT INTO race_results (race, team, discord_id, name, racetime_id, twitch_username, placement, finish_time, tfb_pieces, dnf, fpa_invoked, breaks_used) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                race as _,
+                team as _,
+                discord_id.map(PgSnowflake) as _,
+                name,
+                racetime_id,
+                twitch_username,
+                i16::try_from(result.placement)?,
+                result.finish_time as _,
+                result.tfb_pieces.map(i16::from),
+                result.dnf,
+                result.fpa_invoked,
+                result.breaks_used,
+            ).execute(&mut **transaction).await?;
+        }
+        Ok(())
+    }
+
+    /// A short description of the result, e.g. “1st (1:23:45)” or “DNF”.
+    pub(crate) fn summary(&self, team_config: TeamConfig) -> String {
+        let mut summary = if self.dnf {
+            format!("DNF")
+        } else if let Some(pieces) = self.tfb_pieces {
+            format!("{} ({})", lang::english_ordinal(self.placement.into()), tfb::Score { last_collection_time: self.finish_time.unwrap_or_default(), team_config, pieces })
+        } else if let Some(finish_time) = self.finish_time {
+            format!("{} ({})", lang::english_ordinal(self.placement.into()), English.format_duration(finish_time, false))
+        } else {
+            lang::english_ordinal(self.placement.into())
+        };
+        if self.fpa_invoked {
+            summary.push_str(", FPA called");
+        }
+        if self.breaks_used {
+            summary.push_str(", with breaks");
+        }
+        summary
+    }
+}
+
+/// Whether `team` has won more than half of the games of a best-of-`game_count` match.
+pub(crate) fn has_clinched(game_winners: &[(i16, Id<Teams>)], team: Id<Teams>, game_count: i16) -> bool {
+    game_winners.iter().filter(|&&(_, winning_team)| winning_team == team).count() > usize::try_from(game_count / 2).unwrap_or_default()
+}
+
 #[derive(Default, Clone)]
 pub(crate) enum RaceSchedule {
     #[default]
@@ -400,10 +543,13 @@ impl Race {
             p3,
             p1_discord AS "p1_discord: PgSnowflake<UserId>",
             p2_discord AS "p2_discord: PgSnowflake<UserId>",
+            p3_discord AS "p3_discord: PgSnowflake<UserId>",
             p1_racetime,
             p2_racetime,
+            p3_racetime,
             p1_twitch,
             p2_twitch,
+            p3_twitch,
             total,
             finished,
             phase,
@@ -505,8 +651,18 @@ impl Race {
             };
             let p3 = if let Some(team3) = row.team3 {
                 Some(Entrant::MidosHouseTeam(Team::from_id(&mut *transaction, team3).await?.ok_or(Error::UnknownTeam)?))
+            } else if let Some(PgSnowflake(id)) = row.p3_discord {
+                Some(Entrant::Discord {
+                    racetime_id: row.p3_racetime,
+                    twitch_username: row.p3_twitch,
+                    id,
+                })
             } else if let Some(name) = row.p3 {
-                Some(Entrant::Named { racetime_id: None, twitch_username: None, name })
+                Some(Entrant::Named {
+                    racetime_id: row.p3_racetime,
+                    twitch_username: row.p3_twitch,
+                    name,
+                })
             } else {
                 None
             };
@@ -735,6 +891,7 @@ impl Race {
             | Series::TournoiFrancophone
             | Series::TriforceBlitz
             | Series::WeTryToBeBetter
+            | Series::Custom(_)
                 => {} // these series are now scheduled via Mido's House
         }
         races.retain(|race| !race.ignored);
@@ -758,9 +915,10 @@ impl Race {
         Ok(races)
     }
 
-    pub(crate) async fn game_count(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<i16, Error> {
-        let ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord], [p1_racetime, p2_racetime], [p1_twitch, p2_twitch], [total, finished]) = self.entrants.to_db();
-        Ok(sqlx::query_scalar!(r#"SELECT game AS "game!" FROM races WHERE
+    /// The ID and game number of each game of this race's match, i.e. each race with the same event, phase, round, and entrants, ordered by game number. Includes this race if it has a game number.
+    async fn match_games(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<Vec<(Id<Races>, i16)>, Error> {
+        let ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord, p3_discord], [p1_racetime, p2_racetime, p3_racetime], [p1_twitch, p2_twitch, p3_twitch], [total, finished]) = self.entrants.to_db();
+        Ok(sqlx::query!(r#"SELECT id AS "id: Id<Races>", game AS "game!" FROM races WHERE
             series = $1
             AND event = $2
             AND phase IS NOT DISTINCT FROM $3
@@ -780,7 +938,10 @@ impl Race {
             AND p2_twitch IS NOT DISTINCT FROM $16
             AND total IS NOT DISTINCT FROM $17
             AND finished IS NOT DISTINCT FROM $18
-            ORDER BY game DESC LIMIT 1
+            AND p3_discord IS NOT DISTINCT FROM $19
+            AND p3_racetime IS NOT DISTINCT FROM $20
+            AND p3_twitch IS NOT DISTINCT FROM $21
+            ORDER BY game
         "#,
             self.series as _,
             self.event,
@@ -800,54 +961,30 @@ impl Race {
             p2_twitch,
             total.map(|total| total as i32),
             finished.map(|finished| finished as i32),
-        ).fetch_optional(&mut **transaction).await?.unwrap_or(1))
+            p3_discord.map(PgSnowflake) as _,
+            p3_racetime,
+            p3_twitch,
+        ).fetch_all(&mut **transaction).await?.into_iter().map(|row| (row.id, row.game)).collect())
+    }
+
+    /// The number of games in this race's match. Games which won't be played because the match was already decided are ignored rather than deleted, so they still count.
+    pub(crate) async fn game_count(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<i16, Error> {
+        Ok(self.match_games(transaction).await?.into_iter().map(|(_, game)| game).max().unwrap_or(1))
+    }
+
+    /// The winning team of each game of this match whose result has been recorded, ordered by game number.
+    ///
+    /// A game's winner is the only team that placed first without forfeiting, so draws don't count for either team.
+    pub(crate) async fn game_winners(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<Vec<(i16, Id<Teams>)>, Error> {
+        let games = self.match_games(&mut *transaction).await?;
+        let first_places = sqlx::query!(r#"SELECT race AS "race: Id<Races>", team AS "team!: Id<Teams>" FROM race_results WHERE race = ANY($1) AND placement = 1 AND NOT dnf AND team IS NOT NULL"#, games.iter().map(|&(id, _)| id).collect_vec() as _).fetch_all(&mut **transaction).await?;
+        Ok(games.into_iter().filter_map(|(id, game)| first_places.iter().filter(|row| row.race == id).map(|row| row.team).exactly_one().ok().map(|team| (game, team))).collect())
     }
 
     pub(crate) async fn next_game(&self, transaction: &mut Transaction<'_, Postgres>, http_client: &reqwest::Client) -> Result<Option<Self>, Error> {
         Ok(if_chain! {
             if let Some(game) = self.game;
-            let ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord], [p1_racetime, p2_racetime], [p1_twitch, p2_twitch], [total, finished]) = self.entrants.to_db();
-            if let Some(id) = sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE
-                series = $1
-                AND event = $2
-                AND phase IS NOT DISTINCT FROM $3
-                AND round IS NOT DISTINCT FROM $4
-                AND game = $5
-                AND team1 IS NOT DISTINCT FROM $6
-                AND team2 IS NOT DISTINCT FROM $7
-                AND team3 IS NOT DISTINCT FROM $8
-                AND p1 IS NOT DISTINCT FROM $9
-                AND p2 IS NOT DISTINCT FROM $10
-                AND p3 IS NOT DISTINCT FROM $11
-                AND p1_discord IS NOT DISTINCT FROM $12
-                AND p2_discord IS NOT DISTINCT FROM $13
-                AND p1_racetime IS NOT DISTINCT FROM $14
-                AND p2_racetime IS NOT DISTINCT FROM $15
-                AND p1_twitch IS NOT DISTINCT FROM $16
-                AND p2_twitch IS NOT DISTINCT FROM $17
-                AND total IS NOT DISTINCT FROM $18
-                AND finished IS NOT DISTINCT FROM $19
-            "#,
-                self.series as _,
-                self.event,
-                self.phase,
-                self.round,
-                game + 1,
-                team1 as _,
-                team2 as _,
-                team3 as _,
-                p1,
-                p2,
-                p3,
-                p1_discord.map(PgSnowflake) as _,
-                p2_discord.map(PgSnowflake) as _,
-                p1_racetime,
-                p2_racetime,
-                p1_twitch,
-                p2_twitch,
-                total.map(|total| total as i32),
-                finished.map(|finished| finished as i32),
-            ).fetch_optional(&mut **transaction).await?;
+            if let Some((id, _)) = self.match_games(&mut *transaction).await?.into_iter().find(|&(_, next_game)| next_game == game + 1);
             then {
                 Some(Self::from_id(&mut *transaction, http_client, id).await?)
             } else {
@@ -856,6 +993,38 @@ impl Race {
         })
     }
 
+    /// Marks the games of this match after this one as ignored because the match has been decided. Games whose race rooms are already open are kept.
+    ///
+    /// The games are ignored rather than deleted so [`Self::game_count`] still reflects the length of the match.
+    pub(crate) async fn ignore_remaining_games(&self, transaction: &mut Transaction<'_, Postgres>, http_client: &reqwest::Client) -> Result<(), Error> {
+        let mut next_game = self.next_game(&mut *transaction, http_client).await?;
+        while let Some(race) = next_game {
+            next_game = race.next_game(&mut *transaction, http_client).await?;
+            if race.cal_events().all(|part| part.room().is_none()) {
+                sqlx::query!("UPDATE races SET ignored = TRUE WHERE id = $1", race.id as _).execute(&mut **transaction).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the recorded results of this race. If this game decided its match, the remaining games are ignored.
+    ///
+    /// This is used both for results reported by the race room and for results entered by organizers.
+    pub(crate) async fn record_results(&self, transaction: &mut Transaction<'_, Postgres>, http_client: &reqwest::Client, results: &[RaceResult]) -> Result<(), Error> {
+        RaceResult::save_all(&mut *transaction, self.id, results).await?;
+        if_chain! {
+            if self.game.is_some();
+            if let Ok(&RaceResult { entrant: Entrant::MidosHouseTeam(ref winner), .. }) = results.iter().filter(|result| result.placement == 1 && !result.dnf).exactly_one();
+            let game_winners = self.game_winners(&mut *transaction).await?;
+            let game_count = self.game_count(&mut *transaction).await?;
+            if has_clinched(&game_winners, winner.id, game_count);
+            then {
+                self.ignore_remaining_games(transaction, http_client).await?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn event(&self, transaction: &mut Transaction<'_, Postgres>) -> Result<event::Data<'static>, event::DataError> {
         event::Data::new(transaction, self.series, self.event.clone()).await?.ok_or(event::DataError::Missing)
     }
@@ -979,7 +1148,7 @@ impl Race {
                         2 => "layout4",
                         4 => "layout12",
                         6 => "layout18",
-                        _ => unimplemented!(),
+                        _ => return Ok(None), // no multistre.am layout for this number of streams
                     });
                     Some(url)
                 }
@@ -997,7 +1166,7 @@ impl Race {
                         0 => return Ok(None),
                         3 => "layout7",
                         6 => "layout17",
-                        _ => unimplemented!(),
+                        _ => return Ok(None), // no multistre.am layout for this number of streams
                     });
                     Some(url)
                 }
@@ -1090,7 +1259,7 @@ impl Race {
             Source::StartGG { ref event, ref set } => (None, None, None, Some(event), Some(set), None),
             Source::SpeedGaming { id } => (None, None, None, None, None, Some(id)),
         };
-        let ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord], [p1_racetime, p2_racetime], [p1_twitch, p2_twitch], [total, finished]) = self.entrants.to_db();
+        let ([team1, team2, team3], [p1, p2, p3], [p1_discord, p2_discord, p3_discord], [p1_racetime, p2_racetime, p3_racetime], [p1_twitch, p2_twitch, p3_twitch], [total, finished]) = self.entrants.to_db();
         let (start, [async_start1, async_start2, async_start3], end, [async_end1, async_end2, async_end3], room, [async_room1, async_room2, async_room3]) = match self.schedule {
             RaceSchedule::Unscheduled => (None, [None; 3], None, [None; 3], None, [None; 3]),
             RaceSchedule::Live { start, end, ref room } => (Some(start), [None; 3], end, [None; 3], room.as_ref(), [None; 3]),
@@ -1105,10 +1274,10 @@ impl Race {
             None => (None, None, None, None, false, None, None),
         };
         sqlx::query!("
-            INSERT INTO races              (startgg_set, start, series, event, async_start2, async_start1, room, scheduling_thread, async_room1, async_room2, draft_state, async_end1, async_end2, end_time, team1, team2, web_id, web_gen_time, file_stem, hash1, hash2, hash3, hash4, hash5, game, id,  p1,  p2,  last_edited_by, last_edited_at, video_url, phase, round, ignored, p3,  startgg_event, total, finished, tfb_uuid, video_url_fr, restreamer, restreamer_fr, locked_spoiler_log_path, video_url_pt, restreamer_pt, p1_twitch, p2_twitch, p1_discord, p2_discord, schedule_locked, team3, schedule_updated_at, video_url_de, restreamer_de, sheet_timestamp, league_id, p1_racetime, p2_racetime, async_start3, async_room3, async_end3, challonge_match, seed_password, speedgaming_id, notified, is_tfb_dev, fpa_invoked, breaks_used, xkeys_uuid, async_notified_1, async_notified_2, async_notified_3)
-            VALUES                         ($1,          $2,    $3,     $4,    $5,           $6,           $7,   $8,                $9,          $10,         $11,         $12,        $13,        $14,      $15,   $16,   $17,    $18,          $19,       $20,   $21,   $22,   $23,   $24,   $25,  $26, $27, $28, $29,            $30,            $31,       $32,   $33,   $34,     $35, $36,           $37,   $38,      $39,      $40,          $41,        $42,           $43,                     $44,          $45,           $46,       $47,       $48,        $49,        $50,             $51,   $52,                 $53,          $54,           $55,             $56,       $57,         $58,         $59,          $60,         $61,        $62,             $63,           $64,            $65,      $66,        $67,         $68,          $69,        $70,        $71,        $72)
-            ON CONFLICT (id) DO UPDATE SET (startgg_set, start, series, event, async_start2, async_start1, room, scheduling_thread, async_room1, async_room2, draft_state, async_end1, async_end2, end_time, team1, team2, web_id, web_gen_time, file_stem, hash1, hash2, hash3, hash4, hash5, game, id,  p1,  p2,  last_edited_by, last_edited_at, video_url, phase, round, ignored, p3,  startgg_event, total, finished, tfb_uuid, video_url_fr, restreamer, restreamer_fr, locked_spoiler_log_path, video_url_pt, restreamer_pt, p1_twitch, p2_twitch, p1_discord, p2_discord, schedule_locked, team3, schedule_updated_at, video_url_de, restreamer_de, sheet_timestamp, league_id, p1_racetime, p2_racetime, async_start3, async_room3, async_end3, challonge_match, seed_password, speedgaming_id, notified, is_tfb_dev, fpa_invoked, breaks_used, xkeys_uuid, async_notified_1, async_notified_2, async_notified_3)
-            =                              ($1,          $2,    $3,     $4,    $5,           $6,           $7,   $8,                $9,          $10,         $11,         $12,        $13,        $14,      $15,   $16,   $17,    $18,          $19,       $20,   $21,   $22,   $23,   $24,   $25,  $26, $27, $28, $29,            $30,            $31,       $32,   $33,   $34,     $35, $36,           $37,   $38,      $39,      $40,          $41,        $42,           $43,                     $44,          $45,           $46,       $47,       $48,        $49,        $50,             $51,   $52,                 $53,          $54,           $55,             $56,       $57,         $58,         $59,          $60,         $61,        $62,             $63,           $64,            $65,      $66,        $67,         $68,          $69,        $70,        $71,        $72)
+            INSERT INTO races              (startgg_set, start, series, event, async_start2, async_start1, room, scheduling_thread, async_room1, async_room2, draft_state, async_end1, async_end2, end_time, team1, team2, web_id, web_gen_time, file_stem, hash1, hash2, hash3, hash4, hash5, game, id,  p1,  p2,  last_edited_by, last_edited_at, video_url, phase, round, ignored, p3,  startgg_event, total, finished, tfb_uuid, video_url_fr, restreamer, restreamer_fr, locked_spoiler_log_path, video_url_pt, restreamer_pt, p1_twitch, p2_twitch, p1_discord, p2_discord, schedule_locked, team3, schedule_updated_at, video_url_de, restreamer_de, sheet_timestamp, league_id, p1_racetime, p2_racetime, async_start3, async_room3, async_end3, challonge_match, seed_password, speedgaming_id, notified, is_tfb_dev, fpa_invoked, breaks_used, xkeys_uuid, async_notified_1, async_notified_2, async_notified_3, p3_discord, p3_racetime, p3_twitch)
+            VALUES                         ($1,          $2,    $3,     $4,    $5,           $6,           $7,   $8,                $9,          $10,         $11,         $12,        $13,        $14,      $15,   $16,   $17,    $18,          $19,       $20,   $21,   $22,   $23,   $24,   $25,  $26, $27, $28, $29,            $30,            $31,       $32,   $33,   $34,     $35, $36,           $37,   $38,      $39,      $40,          $41,        $42,           $43,                     $44,          $45,           $46,       $47,       $48,        $49,        $50,             $51,   $52,                 $53,          $54,           $55,             $56,       $57,         $58,         $59,          $60,         $61,        $62,             $63,           $64,            $65,      $66,        $67,         $68,          $69,        $70,        $71,        $72,        $73,        $74,         $75)
+            ON CONFLICT (id) DO UPDATE SET (startgg_set, start, series, event, async_start2, async_start1, room, scheduling_thread, async_room1, async_room2, draft_state, async_end1, async_end2, end_time, team1, team2, web_id, web_gen_time, file_stem, hash1, hash2, hash3, hash4, hash5, game, id,  p1,  p2,  last_edited_by, last_edited_at, video_url, phase, round, ignored, p3,  startgg_event, total, finished, tfb_uuid, video_url_fr, restreamer, restreamer_fr, locked_spoiler_log_path, video_url_pt, restreamer_pt, p1_twitch, p2_twitch, p1_discord, p2_discord, schedule_locked, team3, schedule_updated_at, video_url_de, restreamer_de, sheet_timestamp, league_id, p1_racetime, p2_racetime, async_start3, async_room3, async_end3, challonge_match, seed_password, speedgaming_id, notified, is_tfb_dev, fpa_invoked, breaks_used, xkeys_uuid, async_notified_1, async_notified_2, async_notified_3, p3_discord, p3_racetime, p3_twitch)
+            =                              ($1,          $2,    $3,     $4,    $5,           $6,           $7,   $8,                $9,          $10,         $11,         $12,        $13,        $14,      $15,   $16,   $17,    $18,          $19,       $20,   $21,   $22,   $23,   $24,   $25,  $26, $27, $28, $29,            $30,            $31,       $32,   $33,   $34,     $35, $36,           $37,   $38,      $39,      $40,          $41,        $42,           $43,                     $44,          $45,           $46,       $47,       $48,        $49,        $50,             $51,   $52,                 $53,          $54,           $55,             $56,       $57,         $58,         $59,          $60,         $61,        $62,             $63,           $64,            $65,      $66,        $67,         $68,          $69,        $70,        $71,        $72,        $73,        $74,         $75)
         ",
             startgg_set as _,
             start,
@@ -1181,7 +1350,10 @@ impl Race {
             xkeys_uuid,
             self.async_notified_1,
             self.async_notified_2,
-            self.async_notified_3
+            self.async_notified_3,
+            p3_discord.map(PgSnowflake) as _,
+            p3_racetime,
+            p3_twitch,
         ).execute(&mut **transaction).await?;
         Ok(())
     }
@@ -1249,35 +1421,41 @@ impl Event {
         Ok(None)
     }
 
+    /// The events whose race rooms should be opened now, i.e. those whose [`room_opens_at`](Self::room_opens_at) has passed.
     pub(crate) async fn rooms_to_open(transaction: &mut Transaction<'_, Postgres>, http_client: &reqwest::Client) -> Result<Vec<Self>, Error> {
+        let now = Utc::now();
         let mut events = Vec::default();
-        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE room IS NULL AND start IS NOT NULL AND start > NOW() AND (start <= NOW() + TIME '00:30:00' OR (team1 IS NULL AND p1_discord IS NULL AND p1 IS NULL AND (series != 's' OR event != 'w') AND start <= NOW() + TIME '01:00:00'))"#).fetch_all(&mut **transaction).await? {
-            events.push(Self {
-                race: Race::from_id(&mut *transaction, http_client, id).await?,
-                kind: EventKind::Normal,
-            })
-        }
-        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE async_room1 IS NULL AND async_notified_1 IS NOT TRUE AND async_start1 IS NOT NULL AND async_start1 > NOW() AND async_start1 <= NOW() + TIME '00:30:00'"#).fetch_all(&mut **transaction).await? {
-            events.push(Self {
-                race: Race::from_id(&mut *transaction, http_client, id).await?,
-                kind: EventKind::Async1,
-            });
-        }
-        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE async_room2 IS NULL AND async_notified_2 IS NOT TRUE AND async_start2 IS NOT NULL AND async_start2 > NOW() AND async_start2 <= NOW() + TIME '00:30:00'"#).fetch_all(&mut **transaction).await? {
-            events.push(Self {
-                race: Race::from_id(&mut *transaction, http_client, id).await?,
-                kind: EventKind::Async2,
-            });
-        }
-        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE async_room3 IS NULL AND async_notified_3 IS NOT TRUE AND async_start3 IS NOT NULL AND async_start3 > NOW() AND async_start3 <= NOW() + TIME '00:30:00'"#).fetch_all(&mut **transaction).await? {
-            events.push(Self {
-                race: Race::from_id(&mut *transaction, http_client, id).await?,
-                kind: EventKind::Async3,
-            });
+        // only narrows down the candidates, whether a room is opened is decided by room_opens_at
+        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE
+            (room IS NULL AND start > $1 AND start <= $2)
+            OR (async_room1 IS NULL AND async_start1 > $1 AND async_start1 <= $2)
+            OR (async_room2 IS NULL AND async_start2 > $1 AND async_start2 <= $2)
+            OR (async_room3 IS NULL AND async_start3 > $1 AND async_start3 <= $2)
+        "#, now, now + OPEN_RACE_ROOM_LEAD_TIME).fetch_all(&mut **transaction).await? {
+            let race = Race::from_id(&mut *transaction, http_client, id).await?;
+            events.extend(race.cal_events().filter(|cal_event| cal_event.room_opens_at().is_some_and(|opens_at| opens_at <= now)));
         }
         Ok(events)
     }
 
+    /// The time at which this event's race room should be opened, or `None` if it won't be, e.g. because the room is already open.
+    pub(crate) fn room_opens_at(&self) -> Option<DateTime<Utc>> {
+        let start = self.start()?;
+        if start <= Utc::now() { return None }
+        let (room, notified, lead_time) = match (&self.kind, &self.race.schedule) {
+            (EventKind::Normal, RaceSchedule::Live { room, .. }) => (room, false, if matches!(self.race.entrants, Entrants::Open | Entrants::Count { .. }) && (self.race.series != Series::Standard || self.race.event != "w") {
+                OPEN_RACE_ROOM_LEAD_TIME
+            } else {
+                ROOM_LEAD_TIME
+            }),
+            (EventKind::Async1, RaceSchedule::Async { room1, .. }) => (room1, self.race.async_notified_1, ROOM_LEAD_TIME),
+            (EventKind::Async2, RaceSchedule::Async { room2, .. }) => (room2, self.race.async_notified_2, ROOM_LEAD_TIME),
+            (EventKind::Async3, RaceSchedule::Async { room3, .. }) => (room3, self.race.async_notified_3, ROOM_LEAD_TIME),
+            (_, _) => return None,
+        };
+        (room.is_none() && !notified).then(|| start - lead_time)
+    }
+
     pub(crate) fn active_teams(&self) -> impl Iterator<Item = &Team> + Send {
         match self.race.entrants {
             Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => Box::new(iter::empty()) as Box<dyn Iterator<Item = &Team> + Send>,
@@ -1293,7 +1471,7 @@ impl Event {
         }
     }
 
-    pub(crate) async fn racetime_users_to_invite(&self, transaction: &mut Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, event: &event::Data<'_>) -> Result<Vec<Result<String, String>>, discord_bot::Error> {
+    pub(crate) async fn racetime_users_to_invite(&self, transaction: &mut Transaction<'_, Postgres>, discord_ctx: &RwFuture<DiscordCtx>, event: &event::Data<'_>) -> Result<Vec<Result<String, String>>, discord_bot::Error> {
         let mut buf = Vec::default();
         let entrants = match self.race.entrants {
             Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => Box::new(iter::empty()) as Box<dyn Iterator<Item = &Entrant> + Send>,
@@ -1329,7 +1507,7 @@ impl Event {
                     buf.push(if let Some(racetime_id) = racetime_id {
                         Ok(racetime_id.clone())
                     } else {
-                        Err(format!("Warning: {} could not be invited. Please contact an organizer to invite them manually.", entrant.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)"))))
+                        Err(format!("Warning: {} could not be invited. Please contact an organizer to invite them manually.", entrant.name(&mut *transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)"))))
                     });
                 }
             }
@@ -1437,12 +1615,14 @@ pub(crate) enum Error {
     #[error(transparent)] Event(#[from] event::DataError),
     #[error(transparent)] OotrWeb(#[from] ootr_web::Error),
     #[error(transparent)] ParseInt(#[from] std::num::ParseIntError),
+    #[error(transparent)] PgInterval(#[from] PgIntervalDecodeError),
     #[error(transparent)] Reqwest(#[from] reqwest::Error),
     #[error(transparent)] SeedData(#[from] seed::ExtraDataError),
     #[error(transparent)] Sheets(#[from] sheets::Error),
     #[error(transparent)] Sql(#[from] sqlx::Error),
     #[error(transparent)] StartGG(#[from] startgg::Error),
     #[error(transparent)] TimeFromLocal(#[from] wheel::traits::TimeFromLocalError<DateTime<Tz>>),
+    #[error(transparent)] TryFromInt(#[from] std::num::TryFromIntError),
     #[error(transparent)] Url(#[from] url::ParseError),
     #[error(transparent)] Wheel(#[from] wheel::Error),
     #[error("no team with this ID")]
@@ -1472,12 +1652,14 @@ impl IsNetworkError for Error {
             Self::Event(_) => false,
             Self::OotrWeb(e) => e.is_network_error(),
             Self::ParseInt(_) => false,
+            Self::PgInterval(_) => false,
             Self::Reqwest(e) => e.is_network_error(),
             Self::SeedData(e) => e.is_network_error(),
             Self::Sheets(e) => e.is_network_error(),
             Self::Sql(_) => false,
             Self::StartGG(e) => e.is_network_error(),
             Self::TimeFromLocal(_) => false,
+            Self::TryFromInt(_) => false,
             Self::Url(_) => false,
             Self::Wheel(e) => e.is_network_error(),
             Self::UnknownTeam => false,
@@ -1520,9 +1702,28 @@ fn dtend<Z: TimeZone + IntoIcsTzid>(datetime: DateTime<Z>) -> DtEnd<'static> {
 }
 
 async fn add_event_races(transaction: &mut Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, http_client: &reqwest::Client, cal: &mut ICalendar<'_>, event: &event::Data<'_>) -> Result<(), Error> {
-    let now = Utc::now();
-    let mut latest_instantiated_weeklies = HashMap::new();
+    let mut races = Vec::default();
     for race in Race::for_event(transaction, http_client, event).await?.into_iter() {
+        let entrants = match race.entrants {
+            Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => &[][..],
+            Entrants::Two(ref entrants) => &entrants[..],
+            Entrants::Three(ref entrants) => &entrants[..],
+        };
+        let mut entrant_names = Vec::with_capacity(entrants.len());
+        for entrant in entrants {
+            entrant_names.push(entrant.name(&mut *transaction, discord_ctx).await?.map_or_else(|| format!("(unnamed)"), Cow::into_owned));
+        }
+        races.push((race, entrant_names));
+    }
+    add_races(cal, Utc::now(), event, &races)
+}
+
+/// Adds calendar entries for the given races of an event. The entrant names must be in the same order as the race's entrants.
+///
+/// Separate from [`add_event_races`] so it can be tested without a Discord connection.
+fn add_races(cal: &mut ICalendar<'_>, now: DateTime<Utc>, event: &event::Data<'_>, races: &[(Race, Vec<String>)]) -> Result<(), Error> {
+    let mut latest_instantiated_weeklies = HashMap::new();
+    for (race, entrant_names) in races {
         for race_event in race.cal_events() {
             if let Some(start) = race_event.start() {
                 let mut cal_event = ics::Event::new(format!("{}{}@midos.house",
@@ -1546,49 +1747,17 @@ async fn add_event_races(transaction: &mut Transaction<'_, Postgres>, discord_ct
                         EventKind::Normal => format!("{summary_prefix}: {entrants}"),
                         EventKind::Async1 | EventKind::Async2 | EventKind::Async3 => format!("{summary_prefix} (async): {entrants}"),
                     },
-                    Entrants::Two([ref team1, ref team2]) => match race_event.kind {
-                        EventKind::Normal => format!(
-                            "{summary_prefix}: {} vs {}",
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
-                        EventKind::Async1 => format!(
-                            "{summary_prefix} (async): {} vs {}",
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
-                        EventKind::Async2 => format!(
-                            "{summary_prefix} (async): {} vs {}",
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
+                    Entrants::Two(_) => match race_event.kind {
+                        EventKind::Normal => format!("{summary_prefix}: {} vs {}", entrant_names[0], entrant_names[1]),
+                        EventKind::Async1 => format!("{summary_prefix} (async): {} vs {}", entrant_names[0], entrant_names[1]),
+                        EventKind::Async2 => format!("{summary_prefix} (async): {} vs {}", entrant_names[1], entrant_names[0]),
                         EventKind::Async3 => unreachable!(),
                     },
-                    Entrants::Three([ref team1, ref team2, ref team3]) => match race_event.kind {
-                        EventKind::Normal => format!(
-                            "{summary_prefix}: {} vs {} vs {}",
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team3.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
-                        EventKind::Async1 => format!(
-                            "{summary_prefix} (async): {} vs {} vs {}",
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team3.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
-                        EventKind::Async2 => format!(
-                            "{summary_prefix} (async): {} vs {} vs {}",
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team3.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
-                        EventKind::Async3 => format!(
-                            "{summary_prefix} (async): {} vs {} vs {}",
-                            team3.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team1.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                            team2.name(&mut *transaction, discord_ctx).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                        ),
+                    Entrants::Three(_) => match race_event.kind {
+                        EventKind::Normal => format!("{summary_prefix}: {} vs {} vs {}", entrant_names[0], entrant_names[1], entrant_names[2]),
+                        EventKind::Async1 => format!("{summary_prefix} (async): {} vs {} vs {}", entrant_names[0], entrant_names[1], entrant_names[2]),
+                        EventKind::Async2 => format!("{summary_prefix} (async): {} vs {} vs {}", entrant_names[1], entrant_names[0], entrant_names[2]),
+                        EventKind::Async3 => format!("{summary_prefix} (async): {} vs {} vs {}", entrant_names[2], entrant_names[0], entrant_names[1]),
                     },
                 };
                 cal_event.push(Summary::new(ics::escape_text(if let Some(game) = race.game {
@@ -1597,7 +1766,7 @@ async fn add_event_races(transaction: &mut Transaction<'_, Postgres>, discord_ct
                     summary_prefix
                 })));
                 cal_event.push(dtstart(start));
-                cal_event.push(dtend(race_event.end().filter(|_| !race_event.is_private_async_part() || race.cal_events().all(|event| event.end().is_some())).unwrap_or_else(|| start + event.series.default_race_duration())));
+                cal_event.push(dtend(race_event.end().filter(|_| !race_event.is_private_async_part() || race.cal_events().all(|event| event.end().is_some())).unwrap_or_else(|| start + event.default_race_duration)));
                 let mut urls = Vec::default();
                 for (language, video_url) in &race.video_urls {
                     urls.push((Cow::Owned(format!("{language} restream")), video_url.clone()));
@@ -1633,7 +1802,7 @@ async fn add_event_races(transaction: &mut Transaction<'_, Postgres>, discord_ct
         cal_event.push(Summary::new(format!("{kind} Weekly")));
         let start = kind.next_weekly_after(start);
         cal_event.push(dtstart(start));
-        cal_event.push(dtend(start + Series::Standard.default_race_duration()));
+        cal_event.push(dtend(start + event.default_race_duration));
         cal_event.push(RRule::new("FREQ=WEEKLY;INTERVAL=2"));
         cal.add_event(cal_event);
     }
@@ -1968,6 +2137,21 @@ pub(crate) struct RaceTableOptions<'a> {
     pub(crate) challonge_import_ctx: Option<Context<'a>>,
 }
 
+/// The winners of a race with too many entrants to show each result, as a line to add below the entrants.
+async fn winners_html(transaction: &mut Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, results: &[RaceResult]) -> Result<Option<RawHtml<String>>, Error> {
+    let mut winners = Vec::default();
+    for result in results.iter().filter(|result| result.placement == 1 && !result.dnf) {
+        winners.push(result.entrant.to_html(&mut *transaction, discord_ctx, false).await?);
+    }
+    Ok(English.join_html_opt(winners).map(|winners| html! {
+        br;
+        small {
+            : "won by ";
+            : winners;
+        }
+    }))
+}
+
 pub(crate) async fn race_table(
     transaction: &mut Transaction<'_, Postgres>,
     discord_ctx: &DiscordCtx,
@@ -2045,7 +2229,12 @@ pub(crate) async fn race_table(
                             @if options.can_create {
                                 @if let Some(event) = event {
                                     @match event.match_source() {
-                                        MatchSource::Manual | MatchSource::Challonge { .. } => a(class = "clean_button", href = uri!(create_race(races[0].series, &*races[0].event, _)), target = "_blank") : "New Race";
+                                        MatchSource::Manual => {
+                                            a(class = "clean_button", href = uri!(create_race(races[0].series, &*races[0].event, _)), target = "_blank") : "New Race";
+                                            br;
+                                            a(class = "clean_button", href = uri!(event::swiss::get(races[0].series, &*races[0].event))) : "Pair Swiss Round";
+                                        }
+                                        MatchSource::Challonge { .. } => a(class = "clean_button", href = uri!(create_race(races[0].series, &*races[0].event, _)), target = "_blank") : "New Race";
                                         //MatchSource::Challonge { .. } => a(class = "clean_button", href = uri!(import_races(races[0].series, &*races[0].event))) : "Import"; // disabled due to Challonge pagination bug
                                         MatchSource::League => {}
                                         MatchSource::StartGG(_) => @if !event.auto_import {
@@ -2070,6 +2259,7 @@ pub(crate) async fn race_table(
                                 hash_map::Entry::Vacant(entry) => entry.insert(race.event(&mut *transaction).await?),
                             }, true)
                         };
+                        @let results = RaceResult::for_race(&mut *transaction, race.id).await?;
                         td {
                             @match race.schedule {
                                 RaceSchedule::Unscheduled => {}
@@ -2122,15 +2312,20 @@ pub(crate) async fn race_table(
                             }
                         }
                         @match race.entrants {
-                            Entrants::Open => td(colspan = "6") : "(open)";
+                            Entrants::Open => td(colspan = "6") {
+                                : "(open)";
+                                : winners_html(&mut *transaction, discord_ctx, &results).await?;
+                            }
                             Entrants::Count { total, finished } => td(colspan = "6") {
                                 : total;
                                 : " (";
                                 : finished;
                                 : " finishers)";
+                                : winners_html(&mut *transaction, discord_ctx, &results).await?;
                             }
                             Entrants::Named(ref entrants) => td(colspan = "6") {
                                 bdi : entrants;
+                                : winners_html(&mut *transaction, discord_ctx, &results).await?;
                             }
                             Entrants::Two([ref team1, ref team2]) => {
                                 td(class = "vs1", colspan = "3") {
@@ -2141,6 +2336,10 @@ pub(crate) async fn race_table(
                                             : format_datetime(start, DateTimeFormat { long: false, running_text: false });
                                         }
                                     }
+                                    @if let Some(result) = results.iter().find(|result| result.entrant == *team1) {
+                                        br;
+                                        small : result.summary(event.team_config);
+                                    }
                                 }
                                 td(class = "vs2", colspan = "3") {
                                     : team2.to_html(&mut *transaction, discord_ctx, false).await?;
@@ -2150,6 +2349,10 @@ pub(crate) async fn race_table(
                                             : format_datetime(start, DateTimeFormat { long: false, running_text: false });
                                         }
                                     }
+                                    @if let Some(result) = results.iter().find(|result| result.entrant == *team2) {
+                                        br;
+                                        small : result.summary(event.team_config);
+                                    }
                                 }
                             }
                             Entrants::Three([ref team1, ref team2, ref team3]) => {
@@ -2161,6 +2364,10 @@ pub(crate) async fn race_table(
                                             : format_datetime(start, DateTimeFormat { long: false, running_text: false });
                                         }
                                     }
+                                    @if let Some(result) = results.iter().find(|result| result.entrant == *team1) {
+                                        br;
+                                        small : result.summary(event.team_config);
+                                    }
                                 }
                                 td(colspan = "2") {
                                     : team2.to_html(&mut *transaction, discord_ctx, false).await?;
@@ -2170,6 +2377,10 @@ pub(crate) async fn race_table(
                                             : format_datetime(start, DateTimeFormat { long: false, running_text: false });
                                         }
                                     }
+                                    @if let Some(result) = results.iter().find(|result| result.entrant == *team2) {
+                                        br;
+                                        small : result.summary(event.team_config);
+                                    }
                                 }
                                 td(colspan = "2") {
                                     : team3.to_html(&mut *transaction, discord_ctx, false).await?;
@@ -2179,6 +2390,10 @@ pub(crate) async fn race_table(
                                             : format_datetime(start, DateTimeFormat { long: false, running_text: false });
                                         }
                                     }
+                                    @if let Some(result) = results.iter().find(|result| result.entrant == *team3) {
+                                        br;
+                                        small : result.summary(event.team_config);
+                                    }
                                 }
                             }
                         }
@@ -2574,7 +2789,7 @@ pub(crate) async fn import_races_post(discord_ctx: &State<RwFuture<DiscordCtx>>,
     })
 }
 
-async fn import_race<'a>(mut transaction: Transaction<'a, Postgres>, discord_ctx: &DiscordCtx, race: Race) -> Result<Transaction<'a, Postgres>, event::Error> {
+pub(crate) async fn import_race<'a>(mut transaction: Transaction<'a, Postgres>, discord_ctx: &DiscordCtx, race: Race) -> Result<Transaction<'a, Postgres>, event::Error> {
     let game_count = race.game.unwrap_or(1);
     let mut scheduling_thread = None;
     for game in 1..=game_count {
@@ -2593,263 +2808,284 @@ async fn import_race<'a>(mut transaction: Transaction<'a, Postgres>, discord_ctx
     Ok(transaction)
 }
 
-async fn auto_import_races_inner(db_pool: PgPool, http_client: reqwest::Client, config: Config, mut shutdown: rocket::Shutdown, discord_ctx: RwFuture<DiscordCtx>, new_room_lock: Arc<Mutex<()>>) -> Result<(), event::Error> {
-    loop {
-        lock!(new_room_lock = new_room_lock; {
-            let mut transaction = db_pool.begin().await?;
-            for row in sqlx::query!(r#"SELECT series AS "series: Series", event FROM events WHERE end_time IS NULL OR end_time > NOW()"#).fetch_all(&mut *transaction).await? {
-                let event = event::Data::new(&mut transaction, row.series, row.event).await?.expect("event deleted during transaction");
-                if event.auto_import && event.is_started(&mut transaction).await? {
-                    match event.match_source() {
-                        MatchSource::Manual => {}
-                        MatchSource::Challonge { .. } => {} // Challonge's API doesn't provide enough data to automate race imports
-                        MatchSource::League => {
-                            let mut races = Vec::default();
-                            for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE series = $1 AND event = $2"#, event.series as _, &event.event).fetch_all(&mut *transaction).await? {
-                                races.push(Race::from_id(&mut transaction, &http_client, id).await?);
-                            }
-                            let schedule = http_client.get("https://league.ootrandomizer.com/scheduleJson")
-                                .send().await?
-                                .detailed_error_for_status().await?
-                                .json_with_text_in_error::<league::Schedule>().await?;
-                            for match_data in schedule.matches {
-                                if match_data.id <= 938 { continue } // seasons 5 to 8
-                                let mut new_race = Race {
-                                    id: Id::dummy(),
-                                    series: event.series,
-                                    event: event.event.to_string(),
-                                    source: Source::League { id: match_data.id },
-                                    entrants: Entrants::Two([
-                                        match_data.player_a.into_entrant(&http_client).await?,
-                                        match_data.player_b.into_entrant(&http_client).await?,
-                                    ]),
-                                    phase: None,
-                                    round: Some(match_data.division),
-                                    game: None,
-                                    scheduling_thread: None,
-                                    schedule: RaceSchedule::Live {
-                                        start: match_data.time_utc,
-                                        end: None,
-                                        room: None,
-                                    },
-                                    schedule_updated_at: None,
-                                    fpa_invoked: false,
-                                    breaks_used: false,
-                                    draft: None,
-                                    seed: seed::Data::default(),
-                                    video_urls: if let Ok(twitch_username) = match_data.restreamers.iter().filter_map(|restreamer| restreamer.twitch_username.as_ref()).exactly_one() { //TODO notify on multiple restreams
-                                        iter::once((match_data.restream_language.unwrap_or(English), Url::parse(&format!("https://twitch.tv/{twitch_username}"))?)).collect()
+/// Lets the entrants know that a race's starting time was changed on SpeedGaming, and opens the race room right away if the race starts soon.
+pub(crate) async fn announce_speedgaming_schedule_change(transaction: &mut Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, event: &event::Data<'static>, race: &Race, change: sgl::ScheduleChange) -> Result<(), event::Error> {
+    let was_scheduled = match change {
+        sgl::ScheduleChange::Unchanged => return Ok(()),
+        sgl::ScheduleChange::Scheduled => false,
+        sgl::ScheduleChange::Rescheduled => true,
+    };
+    let RaceSchedule::Live { start, .. } = race.schedule else { return Ok(()) };
+    let cal_event = Event { race: race.clone(), kind: EventKind::Normal };
+    if start - Utc::now() < TimeDelta::minutes(30) {
+        let global_state = discord_ctx.data.read().await.get::<racetime_bot::GlobalState>().expect("global state missing from Discord context").clone();
+        if let Some((_, msg)) = global_state.create_room(transaction, discord_ctx, &cal_event, event).await? {
+            if let Some(channel) = event.discord_race_room_channel {
+                channel.say(discord_ctx, &msg).await?;
+            }
+            if let Some(thread) = race.scheduling_thread {
+                thread.say(discord_ctx, msg).await?;
+            }
+            return Ok(())
+        }
+    }
+    if let Some(thread) = race.scheduling_thread {
+        let mut msg = MessageBuilder::default();
+        msg.push(if let Some(game) = race.game { format!("Game {game}") } else { format!("This race") });
+        msg.push(if was_scheduled { " has been rescheduled on SpeedGaming for " } else { " is now scheduled on SpeedGaming for " });
+        msg.push_timestamp(start, serenity_utils::message::TimestampStyle::LongDateTime);
+        msg.push('.');
+        thread.say(discord_ctx, msg.build()).await?;
+    }
+    Ok(())
+}
+
+/// Imports races for all ongoing events with automatic imports enabled. Run every minute as a [scheduled job](crate::scheduler).
+pub(crate) async fn auto_import_races(db_pool: &PgPool, http_client: &reqwest::Client, config: &Config, discord_ctx: &RwFuture<DiscordCtx>, new_room_lock: &Mutex<()>) -> Result<(), event::Error> {
+    lock!(new_room_lock = new_room_lock; {
+        let mut transaction = db_pool.begin().await?;
+        for row in sqlx::query!(r#"SELECT series AS "series: Series", event FROM events WHERE end_time IS NULL OR end_time > NOW()"#).fetch_all(&mut *transaction).await? {
+            let event = event::Data::new(&mut transaction, row.series, row.event).await?.expect("event deleted during transaction");
+            if event.auto_import && event.is_started(&mut transaction).await? {
+                match event.match_source() {
+                    MatchSource::Manual => {}
+                    MatchSource::Challonge { .. } => {} // Challonge's API doesn't provide enough data to automate race imports
+                    MatchSource::League => {
+                        let mut races = Vec::default();
+                        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE series = $1 AND event = $2"#, event.series as _, &event.event).fetch_all(&mut *transaction).await? {
+                            races.push(Race::from_id(&mut transaction, http_client, id).await?);
+                        }
+                        let schedule = http_client.get("https://league.ootrandomizer.com/scheduleJson")
+                            .send().await?
+                            .detailed_error_for_status().await?
+                            .json_with_text_in_error::<league::Schedule>().await?;
+                        for match_data in schedule.matches {
+                            if match_data.id <= 938 { continue } // seasons 5 to 8
+                            let mut new_race = Race {
+                                id: Id::dummy(),
+                                series: event.series,
+                                event: event.event.to_string(),
+                                source: Source::League { id: match_data.id },
+                                entrants: Entrants::Two([
+                                    match_data.player_a.into_entrant(http_client).await?,
+                                    match_data.player_b.into_entrant(http_client).await?,
+                                ]),
+                                phase: None,
+                                round: Some(match_data.division),
+                                game: None,
+                                scheduling_thread: None,
+                                schedule: RaceSchedule::Live {
+                                    start: match_data.time_utc,
+                                    end: None,
+                                    room: None,
+                                },
+                                schedule_updated_at: None,
+                                fpa_invoked: false,
+                                breaks_used: false,
+                                draft: None,
+                                seed: seed::Data::default(),
+                                video_urls: if let Ok(twitch_username) = match_data.restreamers.iter().filter_map(|restreamer| restreamer.twitch_username.as_ref()).exactly_one() { //TODO notify on multiple restreams
+                                    iter::once((match_data.restream_language.unwrap_or(English), Url::parse(&format!("https://twitch.tv/{twitch_username}"))?)).collect()
+                                } else {
+                                    HashMap::default()
+                                },
+                                restreamers: if_chain! {
+                                    if let Ok(restreamer) = match_data.restreamers.into_iter().exactly_one(); //TODO notify on multiple restreams
+                                    if let Some(racetime_id) = restreamer.racetime_id(http_client).await?;
+                                    then {
+                                        iter::once((match_data.restream_language.unwrap_or(English), racetime_id)).collect()
                                     } else {
                                         HashMap::default()
-                                    },
-                                    restreamers: if_chain! {
-                                        if let Ok(restreamer) = match_data.restreamers.into_iter().exactly_one(); //TODO notify on multiple restreams
-                                        if let Some(racetime_id) = restreamer.racetime_id(&http_client).await?;
-                                        then {
-                                            iter::once((match_data.restream_language.unwrap_or(English), racetime_id)).collect()
-                                        } else {
-                                            HashMap::default()
-                                        }
-                                    },
-                                    last_edited_by: None,
-                                    last_edited_at: None,
-                                    ignored: match match_data.status {
-                                        league::MatchStatus::Canceled => true,
-                                        league::MatchStatus::Confirmed => false,
-                                    },
-                                    schedule_locked: false,
-                                    notified: false,
-                                    async_notified_1: false,
-                                    async_notified_2: false,
-                                    async_notified_3: false,
-
-                                };
-                                if let Some(race) = races.iter_mut().find(|race| if let Source::League { id } = race.source { id == match_data.id } else { false }) {
-                                    if !race.schedule_locked {
-                                        let is_upcoming = !race.has_any_room(); // stop automatically updating certain fields once a room is open
-                                        *race = Race {
-                                            id: race.id,
-                                            schedule: if is_upcoming { new_race.schedule } else { mem::take(&mut race.schedule) },
-                                            schedule_updated_at: race.schedule_updated_at,
-                                            seed: mem::take(&mut race.seed),
-                                            video_urls: if is_upcoming { new_race.video_urls } else { mem::take(&mut race.video_urls) },
-                                            restreamers: if is_upcoming { new_race.restreamers } else { mem::take(&mut race.restreamers) },
-                                            last_edited_at: race.last_edited_at,
-                                            last_edited_by: race.last_edited_by,
-                                            notified: race.notified,
-                                            ..new_race
-                                        };
                                     }
-                                    race
-                                } else {
-                                    new_race.id = Id::<Races>::new(&mut transaction).await?;
-                                    races.push(new_race);
-                                    races.last_mut().expect("just pushed")
-                                }.save(&mut transaction).await?;
-                            }
+                                },
+                                last_edited_by: None,
+                                last_edited_at: None,
+                                ignored: match match_data.status {
+                                    league::MatchStatus::Canceled => true,
+                                    league::MatchStatus::Confirmed => false,
+                                },
+                                schedule_locked: false,
+                                notified: false,
+                                async_notified_1: false,
+                                async_notified_2: false,
+                                async_notified_3: false,
+
+                            };
+                            if let Some(race) = races.iter_mut().find(|race| if let Source::League { id } = race.source { id == match_data.id } else { false }) {
+                                if !race.schedule_locked {
+                                    let is_upcoming = !race.has_any_room(); // stop automatically updating certain fields once a room is open
+                                    *race = Race {
+                                        id: race.id,
+                                        schedule: if is_upcoming { new_race.schedule } else { mem::take(&mut race.schedule) },
+                                        schedule_updated_at: race.schedule_updated_at,
+                                        seed: mem::take(&mut race.seed),
+                                        video_urls: if is_upcoming { new_race.video_urls } else { mem::take(&mut race.video_urls) },
+                                        restreamers: if is_upcoming { new_race.restreamers } else { mem::take(&mut race.restreamers) },
+                                        last_edited_at: race.last_edited_at,
+                                        last_edited_by: race.last_edited_by,
+                                        notified: race.notified,
+                                        ..new_race
+                                    };
+                                }
+                                race
+                            } else {
+                                new_race.id = Id::<Races>::new(&mut transaction).await?;
+                                races.push(new_race);
+                                races.last_mut().expect("just pushed")
+                            }.save(&mut transaction).await?;
                         }
-                        MatchSource::StartGG(event_slug) => {
-                            let (races, _) = startgg::races_to_import(&mut transaction, &http_client, &config, &event, event_slug).await?;
-                            for race in races {
-                                transaction = import_race(transaction, &*discord_ctx.read().await, race).await?;
-                            }
+                    }
+                    MatchSource::StartGG(event_slug) => {
+                        let (races, _) = startgg::races_to_import(&mut transaction, http_client, config, &event, event_slug).await?;
+                        for race in races {
+                            transaction = import_race(transaction, &*discord_ctx.read().await, race).await?;
                         }
                     }
                 }
-                if let Some(ref speedgaming_slug) = event.speedgaming_slug {
-                    let schedule = sgl::schedule(&http_client, speedgaming_slug).await?;
-                    let races = Race::for_event(&mut transaction, &http_client, &event).await?;
-                    let (mut existing_races, mut unassigned_races) = races.into_iter().partition::<Vec<_>, _>(|race| matches!(race.source, Source::SpeedGaming { .. }));
-                    existing_races.sort_unstable_by_key(|race| {
-                        let Source::SpeedGaming { id } = race.source else { unreachable!("partitioned above") };
-                        id
-                    });
-                    let disambiguation_messages = sqlx::query_scalar!(
-                        "SELECT speedgaming_id FROM speedgaming_disambiguation_messages WHERE speedgaming_id = ANY($1) ORDER BY speedgaming_id ASC",
-                        &schedule.iter().flat_map(|restream| restream.matches()).map(|restream_match| restream_match.id).collect_vec(),
-                    ).fetch_all(&mut *transaction).await?;
-                    for restream in schedule {
-                        for restream_match in restream.matches() {
-                            if let Ok(idx) = existing_races.binary_search_by_key(&restream_match.id, |race| {
-                                let Source::SpeedGaming { id } = race.source else { unreachable!("partitioned above") };
-                                id
-                            }) {
-                                // this match is already assigned to a race, update it in case it got rescheduled or its restream info got changed
-                                let race = &mut existing_races[idx];
-                                restream.update_race(race, restream_match.id)?;
-                                race.save(&mut transaction).await?;
-                            } else if disambiguation_messages.binary_search(&restream_match.id).is_ok() {
-                                // this match is pending manual assignment, ignore it for now
-                            } else {
-                                let mut matching_races = Vec::default();
-                                for (idx, race) in unassigned_races.iter().enumerate() {
-                                    if restream_match.matches(&mut transaction, &http_client, race).await? {
-                                        matching_races.push((idx, race));
-                                    }
+            }
+            if let Some(ref speedgaming_slug) = event.speedgaming_slug {
+                let schedule = sgl::schedule(http_client, speedgaming_slug).await?;
+                let races = Race::for_event(&mut transaction, http_client, &event).await?;
+                let (mut existing_races, mut unassigned_races) = races.into_iter().partition::<Vec<_>, _>(|race| matches!(race.source, Source::SpeedGaming { .. }));
+                existing_races.sort_unstable_by_key(|race| {
+                    let Source::SpeedGaming { id } = race.source else { unreachable!("partitioned above") };
+                    id
+                });
+                let speedgaming_ids = schedule.iter().flat_map(|restream| restream.matches()).map(|restream_match| restream_match.id).collect::<HashSet<_>>();
+                let disambiguation_messages = sqlx::query_scalar!(
+                    "SELECT speedgaming_id FROM speedgaming_disambiguation_messages WHERE speedgaming_id = ANY($1) ORDER BY speedgaming_id ASC",
+                    &speedgaming_ids.iter().copied().collect_vec(),
+                ).fetch_all(&mut *transaction).await?;
+                for restream in schedule {
+                    for restream_match in restream.matches() {
+                        if let Ok(idx) = existing_races.binary_search_by_key(&restream_match.id, |race| {
+                            let Source::SpeedGaming { id } = race.source else { unreachable!("partitioned above") };
+                            id
+                        }) {
+                            // this match is already assigned to a race, update it in case it got rescheduled or its restream info got changed
+                            let race = &mut existing_races[idx];
+                            let change = restream.update_race(&mut transaction, race, restream_match.id).await?;
+                            race.save(&mut transaction).await?;
+                            announce_speedgaming_schedule_change(&mut transaction, &*discord_ctx.read().await, &event, race, change).await?;
+                        } else if disambiguation_messages.binary_search(&restream_match.id).is_ok() {
+                            // this match is pending manual assignment, ignore it for now
+                        } else {
+                            let mut matching_races = Vec::default();
+                            for (idx, race) in unassigned_races.iter().enumerate() {
+                                if restream_match.matches(&mut transaction, http_client, race).await? {
+                                    matching_races.push((idx, race));
                                 }
-                                match matching_races.into_iter().at_most_one() {
-                                    Ok(None) => {
-                                        if let Some(organizer_channel) = event.discord_organizer_channel {
-                                            let msg = MessageBuilder::default()
-                                                .push("could not find any races matching SpeedGaming match ")
-                                                .push_mono(restream_match.id.to_string())
-                                                .push(" (")
-                                                .push_safe(restream_match.to_string())
-                                                .push(')')
-                                                //TODO instructions for how to fix?
-                                                .build();
-                                            let notification = organizer_channel.say(&*discord_ctx.read().await, msg).await?;
-                                            sqlx::query!(
-                                                "INSERT INTO speedgaming_disambiguation_messages (speedgaming_id, message_id) VALUES ($1, $2)",
-                                                restream_match.id, PgSnowflake(notification.id) as _,
-                                            ).execute(&mut *transaction).await?;
-                                        }
-                                    }
-                                    Ok(Some((idx, _))) => {
-                                        let mut race = unassigned_races.swap_remove(idx);
-                                        restream.update_race(&mut race, restream_match.id)?;
-                                        race.save(&mut transaction).await?;
+                            }
+                            match matching_races.into_iter().at_most_one() {
+                                Ok(None) => {
+                                    if let Some(organizer_channel) = event.discord_organizer_channel {
+                                        let msg = MessageBuilder::default()
+                                            .push("could not find any races matching SpeedGaming match ")
+                                            .push_mono(restream_match.id.to_string())
+                                            .push(" (")
+                                            .push_safe(restream_match.to_string())
+                                            .push(')')
+                                            //TODO instructions for how to fix?
+                                            .build();
+                                        let notification = organizer_channel.say(&*discord_ctx.read().await, msg).await?;
+                                        sqlx::query!(
+                                            "INSERT INTO speedgaming_disambiguation_messages (speedgaming_id, message_id) VALUES ($1, $2)",
+                                            restream_match.id, PgSnowflake(notification.id) as _,
+                                        ).execute(&mut *transaction).await?;
                                     }
-                                    Err(races) => {
-                                        if let Some(organizer_channel) = event.discord_organizer_channel {
-                                            let msg = MessageBuilder::default()
-                                                .push("found multiple races matching SpeedGaming match ")
-                                                .push_mono(restream_match.id.to_string())
-                                                .push(" (")
-                                                .push_safe(restream_match.to_string())
-                                                .push("), please select one to assign it to:")
-                                                .build();
-                                            let mut options = Vec::with_capacity(races.size_hint().0);
-                                            for (_, race) in races {
-                                                let info_prefix = format!("{}{}{}",
-                                                    race.phase.as_deref().unwrap_or(""),
-                                                    if race.phase.is_none() || race.round.is_none() { "" } else { " " },
-                                                    race.round.as_deref().unwrap_or(""),
-                                                );
-                                                let summary = match race.entrants {
-                                                    Entrants::Open | Entrants::Count { .. } => if info_prefix.is_empty() { format!("Untitled Race") } else { info_prefix },
-                                                    Entrants::Named(ref entrants) => format!("{info_prefix}{}{entrants}", if info_prefix.is_empty() { "" } else { ": " }),
-                                                    Entrants::Two([ref team1, ref team2]) => format!(
-                                                        "{info_prefix}{}{} vs {}",
-                                                        if info_prefix.is_empty() { "" } else { ": " },
-                                                        team1.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                                                        team2.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                                                    ),
-                                                    Entrants::Three([ref team1, ref team2, ref team3]) => format!(
-                                                        "{info_prefix}{}{} vs {} vs {}",
-                                                        if info_prefix.is_empty() { "" } else { ": " },
-                                                        team1.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                                                        team2.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                                                        team3.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
-                                                    ),
-                                                };
-                                                options.push(CreateSelectMenuOption::new(if let Some(game) = race.game {
-                                                    format!("{summary}, game {game}")
-                                                } else {
-                                                    summary
-                                                }, race.id.to_string()));
-                                            }
-                                            let notification = organizer_channel.send_message(&*discord_ctx.read().await, CreateMessage::default()
-                                                .content(msg)
-                                                .select_menu(
-                                                    CreateSelectMenu::new(format!("sgdisambig_{}", restream_match.id), CreateSelectMenuKind::String { options })
-                                                        .placeholder("Select Race")
-                                                )
-                                            ).await?;
-                                            sqlx::query!(
-                                                "INSERT INTO speedgaming_disambiguation_messages (speedgaming_id, message_id) VALUES ($1, $2)",
-                                                restream_match.id, PgSnowflake(notification.id) as _,
-                                            ).execute(&mut *transaction).await?;
+                                }
+                                Ok(Some((idx, _))) => {
+                                    let mut race = unassigned_races.swap_remove(idx);
+                                    let change = restream.update_race(&mut transaction, &mut race, restream_match.id).await?;
+                                    race.save(&mut transaction).await?;
+                                    announce_speedgaming_schedule_change(&mut transaction, &*discord_ctx.read().await, &event, &race, change).await?;
+                                }
+                                Err(races) => {
+                                    if let Some(organizer_channel) = event.discord_organizer_channel {
+                                        let msg = MessageBuilder::default()
+                                            .push("found multiple races matching SpeedGaming match ")
+                                            .push_mono(restream_match.id.to_string())
+                                            .push(" (")
+                                            .push_safe(restream_match.to_string())
+                                            .push("), please select one to assign it to:")
+                                            .build();
+                                        let mut options = Vec::with_capacity(races.size_hint().0);
+                                        for (_, race) in races {
+                                            let info_prefix = format!("{}{}{}",
+                                                race.phase.as_deref().unwrap_or(""),
+                                                if race.phase.is_none() || race.round.is_none() { "" } else { " " },
+                                                race.round.as_deref().unwrap_or(""),
+                                            );
+                                            let summary = match race.entrants {
+                                                Entrants::Open | Entrants::Count { .. } => if info_prefix.is_empty() { format!("Untitled Race") } else { info_prefix },
+                                                Entrants::Named(ref entrants) => format!("{info_prefix}{}{entrants}", if info_prefix.is_empty() { "" } else { ": " }),
+                                                Entrants::Two([ref team1, ref team2]) => format!(
+                                                    "{info_prefix}{}{} vs {}",
+                                                    if info_prefix.is_empty() { "" } else { ": " },
+                                                    team1.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
+                                                    team2.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
+                                                ),
+                                                Entrants::Three([ref team1, ref team2, ref team3]) => format!(
+                                                    "{info_prefix}{}{} vs {} vs {}",
+                                                    if info_prefix.is_empty() { "" } else { ": " },
+                                                    team1.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
+                                                    team2.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
+                                                    team3.name(&mut transaction, &*discord_ctx.read().await).await?.unwrap_or(Cow::Borrowed("(unnamed)")),
+                                                ),
+                                            };
+                                            options.push(CreateSelectMenuOption::new(if let Some(game) = race.game {
+                                                format!("{summary}, game {game}")
+                                            } else {
+                                                summary
+                                            }, race.id.to_string()));
                                         }
+                                        let notification = organizer_channel.send_message(&*discord_ctx.read().await, CreateMessage::default()
+                                            .content(msg)
+                                            .select_menu(
+                                                CreateSelectMenu::new(format!("sgdisambig_{}", restream_match.id), CreateSelectMenuKind::String { options })
+                                                    .placeholder("Select Race")
+                                            )
+                                        ).await?;
+                                        sqlx::query!(
+                                            "INSERT INTO speedgaming_disambiguation_messages (speedgaming_id, message_id) VALUES ($1, $2)",
+                                            restream_match.id, PgSnowflake(notification.id) as _,
+                                        ).execute(&mut *transaction).await?;
                                     }
                                 }
                             }
                         }
                     }
                 }
-            }
-            transaction.commit().await?;
-        });
-        select! {
-            () = &mut shutdown => break,
-            () = sleep(Duration::from_secs(60)) => {}
-        }
-    }
-    Ok(())
-}
-
-pub(crate) async fn auto_import_races(db_pool: PgPool, http_client: reqwest::Client, config: Config, shutdown: rocket::Shutdown, discord_ctx: RwFuture<DiscordCtx>, new_room_lock: Arc<Mutex<()>>) -> Result<(), event::Error> {
-    let mut last_crash = Instant::now();
-    let mut wait_time = Duration::from_secs(1);
-    loop {
-        match auto_import_races_inner(db_pool.clone(), http_client.clone(), config.clone(), shutdown.clone(), discord_ctx.clone(), new_room_lock.clone()).await {
-            Ok(()) => break Ok(()),
-            Err(event::Error::Discord(discord_bot::Error::UninitializedDiscordGuild(guild_id))) => {
-                let wait_time = Duration::from_secs(60);
-                eprintln!("failed to auto-import races for uninitialized Discord guild {guild_id} (retrying in {})", English.format_duration(wait_time, true));
-                sleep(wait_time).await;
-            }
-            Err(e) if e.is_network_error() => {
-                if last_crash.elapsed() >= Duration::from_secs(60 * 60 * 24) {
-                    wait_time = Duration::from_secs(1); // reset wait time after no crash for a day
-                } else {
-                    wait_time *= 2; // exponential backoff
-                }
-                if wait_time >= Duration::from_secs(2 * 60) {
-                    eprintln!("failed to auto-import races (retrying in {}): {e} ({e:?})", English.format_duration(wait_time, true));
-                    if wait_time >= Duration::from_secs(10 * 60) {
-                        wheel::night_report(&format!("{}/error", night_path()), Some(&format!("failed to auto-import races (retrying in {}): {e} ({e:?})", English.format_duration(wait_time, true)))).await?;
+                for race in &mut existing_races {
+                    let Source::SpeedGaming { id } = race.source else { unreachable!("partitioned above") };
+                    if_chain! {
+                        if !speedgaming_ids.contains(&id);
+                        // the schedule only lists upcoming matches, so races that have started are expected to be missing
+                        if let RaceSchedule::Live { start, room: None, .. } = race.schedule;
+                        if start > Utc::now();
+                        if !race.schedule_locked;
+                        then {
+                            // this match has been removed from the SpeedGaming schedule
+                            race.source = Source::Manual;
+                            race.schedule = RaceSchedule::Unscheduled;
+                            race.schedule_updated_at = Some(Utc::now());
+                            race.video_urls.clear();
+                            race.restreamers.clear();
+                            race.save(&mut transaction).await?;
+                            if let Some(thread) = race.scheduling_thread {
+                                let mut msg = MessageBuilder::default();
+                                msg.push(if let Some(game) = race.game { format!("Game {game}") } else { format!("This race") });
+                                msg.push(" has been removed from the SpeedGaming schedule, so it is no longer scheduled. Please schedule it again.");
+                                thread.say(&*discord_ctx.read().await, msg.build()).await?;
+                            }
+                        }
                     }
                 }
-                sleep(wait_time).await;
-                last_crash = Instant::now();
-            }
-            Err(e) => {
-                wheel::night_report(&format!("{}/error", night_path()), Some(&format!("failed to auto-import races: {e} ({e:?})"))).await?;
-                break Err(e)
             }
         }
-    }
+        transaction.commit().await?;
+    });
+    Ok(())
 }
 
 #[rocket::get("/event/<series>/<event>/races/<id>/practice")]
@@ -2869,6 +3105,11 @@ pub(crate) async fn practice_seed(pool: &State<PgPool>, http_client: &State<reqw
 pub(crate) async fn edit_race_form(mut transaction: Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: event::Data<'_>, race: Race, redirect_to: Option<Origin<'_>>, ctx: Option<Context<'_>>) -> Result<RawHtml<String>, event::Error> {
     let header = event.header(&mut transaction, me.as_ref(), Tab::Races, true).await?;
     let trez = User::from_id(&mut *transaction, Id::<Users>::from(16287394041462225947_u64)).await?.ok_or(PageError::TrezUserData(0))?;
+    let is_organizer = if let Some(ref me) = me {
+        event.organizers(&mut transaction).await?.contains(me)
+    } else {
+        false
+    };
     let form = if me.is_some() {
         let mut errors = ctx.as_ref().map(|ctx| ctx.errors().collect()).unwrap_or_default();
         full_form(uri!(edit_race_post(event.series, &*event.event, race.id, redirect_to)), csrf, html! {
@@ -3142,6 +3383,11 @@ pub(crate) async fn edit_race_form(mut transaction: Transaction<'_, Postgres>, d
             : trez;
             : " if you've spotted an error in it.";
         }
+        @if is_organizer && race.is_ended() {
+            p {
+                a(href = uri!(race_results(event.series, &*event.event, race.id))) : "Enter or correct the results of this race";
+            }
+        }
         : form;
     };
     Ok(page(transaction, &me, &uri, PageStyle { chests: event.chests().await?, ..PageStyle::default() }, &format!("Edit Race — {}", event.display_name), content).await?)
@@ -3725,3 +3971,221 @@ pub(crate) async fn add_file_hash_post(pool: &State<PgPool>, http_client: &State
         RedirectOrContent::Content(add_file_hash_form(transaction, Some(me), uri, csrf.as_ref(), event, race, form.context).await?)
     })
 }
+
+pub(crate) async fn race_results_form(mut transaction: Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: event::Data<'_>, race: Race, ctx: Option<Context<'_>>) -> Result<RawHtml<String>, event::Error> {
+    let header = event.header(&mut transaction, me.as_ref(), Tab::Races, true).await?;
+    let entrants = match race.entrants {
+        Entrants::Two(ref entrants) => Some(&entrants[..]),
+        Entrants::Three(ref entrants) => Some(&entrants[..]),
+        Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => None,
+    };
+    let form = if let Some(entrants) = entrants {
+        if me.is_some() {
+            let results = RaceResult::for_race(&mut transaction, race.id).await?;
+            let mut errors = ctx.as_ref().map(|ctx| ctx.errors().collect()).unwrap_or_default();
+            let mut fields = Vec::with_capacity(entrants.len());
+            for (idx, entrant) in entrants.iter().enumerate() {
+                let result = results.iter().find(|result| result.entrant.to_db() == entrant.to_db());
+                let entrant_html = entrant.to_html(&mut transaction, discord_ctx, false).await?;
+                let placement_field = format!("placement[{idx}]");
+                let finish_time_field = format!("finish_time[{idx}]");
+                let tfb_pieces_field = format!("tfb_pieces[{idx}]");
+                let dnf_field = format!("dnf[{idx}]");
+                fields.push(html! {
+                    fieldset {
+                        legend : entrant_html;
+                        : form_field(&placement_field, &mut errors, html! {
+                            label(for = &placement_field) : "Placement:";
+                            input(type = "number", min = "1", name = &placement_field, value? = if let Some(ref ctx) = ctx {
+                                ctx.field_value(&*placement_field).map(|placement| placement.to_owned())
+                            } else {
+                                result.map(|result| result.placement.to_string())
+                            });
+                        });
+                        : form_field(&finish_time_field, &mut errors, html! {
+                            label(for = &finish_time_field) : if let Series::TriforceBlitz = event.series { "Time of last piece:" } else { "Finish time:" };
+                            input(type = "text", name = &finish_time_field, value? = if let Some(ref ctx) = ctx {
+                                ctx.field_value(&*finish_time_field).map(|finish_time| finish_time.to_owned())
+                            } else {
+                                result.and_then(|result| result.finish_time).map(unparse_duration)
+                            });
+                        });
+                        @if let Series::TriforceBlitz = event.series {
+                            : form_field(&tfb_pieces_field, &mut errors, html! {
+                                label(for = &tfb_pieces_field) : "Triforce pieces:";
+                                input(type = "number", min = "0", name = &tfb_pieces_field, value? = if let Some(ref ctx) = ctx {
+                                    ctx.field_value(&*tfb_pieces_field).map(|tfb_pieces| tfb_pieces.to_owned())
+                                } else {
+                                    result.and_then(|result| result.tfb_pieces).map(|tfb_pieces| tfb_pieces.to_string())
+                                });
+                            });
+                        }
+                        : form_field(&dnf_field, &mut errors, html! {
+                            input(type = "checkbox", id = &dnf_field, name = &dnf_field, checked? = if let Some(ref ctx) = ctx {
+                                ctx.field_value(&*dnf_field).map_or(false, |value| value == "on")
+                            } else {
+                                result.is_some_and(|result| result.dnf)
+                            });
+                            label(for = &dnf_field) : "Did not finish";
+                        });
+                    }
+                });
+            }
+            full_form(uri!(race_results_post(event.series, &*event.event, race.id)), csrf, html! {
+                @for field in fields {
+                    : field;
+                }
+                : form_field("fpa_invoked", &mut errors, html! {
+                    input(type = "checkbox", id = "fpa_invoked", name = "fpa_invoked", checked? = if let Some(ref ctx) = ctx {
+                        ctx.field_value("fpa_invoked").map_or(false, |value| value == "on")
+                    } else {
+                        results.first().map_or(race.fpa_invoked, |result| result.fpa_invoked)
+                    });
+                    label(for = "fpa_invoked") : "FPA was called";
+                });
+                : form_field("breaks_used", &mut errors, html! {
+                    input(type = "checkbox", id = "breaks_used", name = "breaks_used", checked? = if let Some(ref ctx) = ctx {
+                        ctx.field_value("breaks_used").map_or(false, |value| value == "on")
+                    } else {
+                        results.first().map_or(race.breaks_used, |result| result.breaks_used)
+                    });
+                    label(for = "breaks_used") : "Breaks were used";
+                });
+            }, errors, "Save")
+        } else {
+            html! {
+                article {
+                    p {
+                        a(href = uri!(auth::login(Some(uri!(race_results(event.series, &*event.event, race.id)))))) : "Sign in or create a Hyrule Town Hall account";
+                        : " to enter the results of this race.";
+                    }
+                }
+            }
+        }
+    } else {
+        html! {
+            article {
+                p : "Results of races with more than 3 entrants are recorded from the race room and can't be entered here.";
+            }
+        }
+    };
+    let content = html! {
+        : header;
+        h2 : "Race results";
+        p : "Enter the results after adjusting the times, for example after an FPA call or a close finish. Saving replaces any results reported by the race room.";
+        @let rooms = race.rooms().collect_vec();
+        @for (idx, room) in rooms.iter().enumerate() {
+            p {
+                a(href = room.to_string()) : if rooms.len() > 1 { format!("Race room {}", idx + 1) } else { format!("Race room") };
+            }
+        }
+        : form;
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests: event.chests().await?, ..PageStyle::default() }, &format!("Race Results — {}", event.display_name), content).await?)
+}
+
+#[rocket::get("/event/<series>/<event>/races/<id>/results")]
+pub(crate) async fn race_results(discord_ctx: &State<RwFuture<DiscordCtx>>, pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, id: Id<Races>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = event::Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let race = Race::from_id(&mut transaction, http_client, id).await?;
+    if race.series != event.series || race.event != event.event {
+        return Ok(RedirectOrContent::Redirect(Redirect::permanent(uri!(race_results(race.series, race.event, id)))))
+    }
+    Ok(RedirectOrContent::Content(race_results_form(transaction, &*discord_ctx.read().await, me, uri, csrf.as_ref(), event, race, None).await?))
+}
+
+#[derive(FromForm, CsrfForm)]
+pub(crate) struct RaceResultsForm {
+    #[field(default = String::new())]
+    csrf: String,
+    #[field(default = HashMap::new())]
+    placement: HashMap<String, String>,
+    #[field(default = HashMap::new())]
+    finish_time: HashMap<String, String>,
+    #[field(default = HashMap::new())]
+    tfb_pieces: HashMap<String, String>,
+    #[field(default = HashMap::new())]
+    dnf: HashMap<String, bool>,
+    fpa_invoked: bool,
+    breaks_used: bool,
+}
+
+#[rocket::post("/event/<series>/<event>/races/<id>/results", data = "<form>")]
+pub(crate) async fn race_results_post(discord_ctx: &State<RwFuture<DiscordCtx>>, pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, id: Id<Races>, form: Form<Contextual<'_, RaceResultsForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = event::Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let race = Race::from_id(&mut transaction, http_client, id).await?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    if race.series != event.series || race.event != event.event {
+        form.context.push_error(form::Error::validation("This race is not part of this event."));
+    }
+    if !event.organizers(&mut transaction).await?.contains(&me) {
+        form.context.push_error(form::Error::validation("You must be an organizer of this event to enter race results."));
+    }
+    let entrants = match race.entrants {
+        Entrants::Two(ref entrants) => &entrants[..],
+        Entrants::Three(ref entrants) => &entrants[..],
+        Entrants::Open | Entrants::Count { .. } | Entrants::Named(_) => {
+            form.context.push_error(form::Error::validation("Results of races with more than 3 entrants are recorded from the race room."));
+            &[][..]
+        }
+    };
+    Ok(if let Some(ref value) = form.value {
+        let mut results = Vec::with_capacity(entrants.len());
+        for (idx, entrant) in entrants.iter().enumerate() {
+            let key = idx.to_string();
+            let dnf = value.dnf.get(&key).copied().unwrap_or_default();
+            let placement = match value.placement.get(&key).map(|placement| placement.parse::<u16>()) {
+                Some(Ok(placement)) if placement >= 1 => Some(placement),
+                Some(Ok(_)) | Some(Err(_)) | None => {
+                    form.context.push_error(form::Error::validation("Placements count from 1.").with_name(format!("placement[{idx}]")));
+                    None
+                }
+            };
+            let finish_time = match value.finish_time.get(&key).map(|finish_time| finish_time.trim()).filter(|finish_time| !finish_time.is_empty()) {
+                Some(finish_time) => if let Some(finish_time) = parse_duration(finish_time, DurationUnit::Hours) {
+                    Some(finish_time)
+                } else {
+                    form.context.push_error(form::Error::validation("Duration must be formatted like '1:23:45' or '1h 23m 45s'.").with_name(format!("finish_time[{idx}]")));
+                    None
+                },
+                None => {
+                    if !dnf {
+                        form.context.push_error(form::Error::validation("A finish time is required unless this entrant did not finish.").with_name(format!("finish_time[{idx}]")));
+                    }
+                    None
+                }
+            };
+            let tfb_pieces = if let Series::TriforceBlitz = event.series {
+                match value.tfb_pieces.get(&key).map(|tfb_pieces| tfb_pieces.parse::<u8>()) {
+                    Some(Ok(tfb_pieces)) => Some(tfb_pieces),
+                    Some(Err(_)) | None => {
+                        form.context.push_error(form::Error::validation("Please enter the number of Triforce pieces collected.").with_name(format!("tfb_pieces[{idx}]")));
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            if let Some(placement) = placement {
+                results.push(RaceResult {
+                    entrant: entrant.clone(),
+                    fpa_invoked: value.fpa_invoked,
+                    breaks_used: value.breaks_used,
+                    placement, finish_time, tfb_pieces, dnf,
+                });
+            }
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(race_results_form(transaction, &*discord_ctx.read().await, Some(me), uri, csrf.as_ref(), event, race, Some(form.context)).await?)
+        } else {
+            race.record_results(&mut transaction, http_client, &results).await?;
+            transaction.commit().await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(event::races(event.series, &*event.event))))
+        }
+    } else {
+        RedirectOrContent::Content(race_results_form(transaction, &*discord_ctx.read().await, Some(me), uri, csrf.as_ref(), event, race, Some(form.context)).await?)
+    })
+}
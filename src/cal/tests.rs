@@ -0,0 +1,249 @@
+use {
+    super::*,
+    crate::testing::{
+        self,
+        TestDb,
+    },
+};
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, hour, 0, 0).single().expect("valid timestamp")
+}
+
+fn live(start: u32, end: Option<u32>) -> RaceSchedule {
+    RaceSchedule::Live { start: at(start), end: end.map(at), room: None }
+}
+
+fn named(name: &str) -> Entrant {
+    Entrant::Named { name: name.to_owned(), racetime_id: None, twitch_username: None }
+}
+
+#[test]
+fn schedule_from_columns() {
+    assert!(matches!(RaceSchedule::new(None, None, None, None, None, None, None, None, None, None, None, None), RaceSchedule::Unscheduled));
+    assert!(matches!(
+        RaceSchedule::new(Some(at(12)), None, None, None, Some(at(15)), None, None, None, None, None, None, None),
+        RaceSchedule::Live { start, end: Some(end), room: None } if start == at(12) && end == at(15)
+    ));
+    assert!(matches!(
+        RaceSchedule::new(None, Some(at(12)), None, None, None, None, None, None, None, None, None, None),
+        RaceSchedule::Async { start1: Some(start1), start2: None, start3: None, .. } if start1 == at(12)
+    ));
+}
+
+#[test]
+fn async_end_time_needs_every_part() {
+    let two = RaceSchedule::Async { start1: Some(at(10)), start2: Some(at(12)), start3: None, end1: Some(at(13)), end2: None, end3: None, room1: None, room2: None, room3: None };
+    assert_eq!(two.end_time(&Entrants::Open), None);
+    let two = RaceSchedule::Async { end2: Some(at(14)), ..two };
+    assert_eq!(two.end_time(&Entrants::Open), Some(at(14)));
+    let three = Entrants::Three([named("a"), named("b"), named("c")]);
+    assert_eq!(two.end_time(&three), None, "third part of a three-entrant async hasn't finished");
+    let two = RaceSchedule::Async { end3: Some(at(11)), ..two };
+    assert_eq!(two.end_time(&three), Some(at(14)));
+}
+
+#[test]
+fn start_matches() {
+    assert!(RaceSchedule::Unscheduled.start_matches(&RaceSchedule::Unscheduled));
+    assert!(live(12, None).start_matches(&live(12, Some(15))), "end time is ignored");
+    assert!(!live(12, None).start_matches(&live(13, None)));
+    assert!(!live(12, None).start_matches(&RaceSchedule::Unscheduled));
+    let async_race = RaceSchedule::Async { start1: Some(at(12)), start2: None, start3: None, end1: None, end2: None, end3: None, room1: None, room2: None, room3: None };
+    assert!(!async_race.start_matches(&live(12, None)));
+}
+
+#[test]
+fn schedule_order() {
+    let cmp = |a: &RaceSchedule, b: &RaceSchedule| a.cmp(&Entrants::Open, b, &Entrants::Open);
+    assert_eq!(cmp(&live(20, Some(22)), &live(10, None)), Less, "races that have ended come first");
+    assert_eq!(cmp(&live(10, Some(13)), &live(9, Some(14))), Less, "races that ended earlier come first");
+    assert_eq!(cmp(&live(10, None), &live(12, None)), Less, "races that start earlier come first");
+    assert_eq!(cmp(&live(12, None), &RaceSchedule::Unscheduled), Less, "unscheduled races come last");
+    assert_eq!(cmp(&live(12, None), &live(12, None)), Equal);
+    let one_part = RaceSchedule::Async { start1: Some(at(12)), start2: None, start3: None, end1: None, end2: None, end3: None, room1: None, room2: None, room3: None };
+    let both_parts = RaceSchedule::Async { start2: Some(at(14)), ..one_part.clone() };
+    assert_eq!(cmp(&both_parts, &one_part), Less, "races with more starting times come first");
+}
+
+#[test]
+fn best_of_n_clinch() {
+    let team1 = Id::<Teams>::from(1_u64);
+    let team2 = Id::<Teams>::from(2_u64);
+    assert!(!has_clinched(&[(1, team1)], team1, 3), "one win isn't enough in a best-of-3");
+    assert!(has_clinched(&[(1, team1), (2, team1)], team1, 3));
+    assert!(!has_clinched(&[(1, team1), (2, team2)], team2, 3));
+    assert!(has_clinched(&[(1, team1), (2, team2), (3, team2)], team2, 3));
+    assert!(!has_clinched(&[(1, team1), (2, team1)], team1, 5));
+    assert!(has_clinched(&[(1, team1), (2, team1), (3, team1)], team1, 5));
+}
+
+#[tokio::test]
+async fn event_calendar() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let http_client = reqwest::Client::new();
+    let mut transaction = db.pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    let alpha = testing::team(&mut transaction, &event, Some("Alpha"), Some(1), &[]).await?;
+    let beta = testing::team(&mut transaction, &event, Some("Beta"), Some(2), &[]).await?;
+    let unnamed = testing::team(&mut transaction, &event, None, Some(3), &[]).await?;
+    let player = testing::user(&mut transaction, "Dana").await?;
+    let solo = testing::team(&mut transaction, &event, None, Some(4), &[(&player, event::Role::None)]).await?;
+    let mut live_race = testing::race(&mut transaction, &event, [&alpha, &beta]).await?;
+    live_race.round = Some(format!("Round 1"));
+    live_race.schedule = live(18, None);
+    live_race.save(&mut transaction).await?;
+    let mut async_race = testing::race(&mut transaction, &event, [&beta, &unnamed]).await?;
+    async_race.schedule = RaceSchedule::Async { start1: Some(at(9)), start2: Some(at(11)), start3: None, end1: None, end2: None, end3: None, room1: None, room2: None, room3: None };
+    async_race.save(&mut transaction).await?;
+    let mut solo_race = testing::race(&mut transaction, &event, [&solo, &alpha]).await?;
+    solo_race.schedule = live(20, Some(23));
+    solo_race.save(&mut transaction).await?;
+    let unscheduled_race = testing::race(&mut transaction, &event, [&alpha, &unnamed]).await?;
+    unscheduled_race.save(&mut transaction).await?;
+    let mut races = Vec::default();
+    for race in Race::for_event(&mut transaction, &http_client, &event).await? {
+        let mut entrant_names = Vec::default();
+        for team in race.teams() {
+            entrant_names.push(team.name(&mut transaction).await?.map_or_else(|| format!("(unnamed)"), Cow::into_owned));
+        }
+        races.push((race, entrant_names));
+    }
+    transaction.rollback().await?;
+    assert_eq!(races.len(), 4);
+    let mut cal = ICalendar::new("2.0", "test");
+    add_races(&mut cal, at(0), &event, &races)?;
+    let ics = cal.to_string();
+    assert!(ics.contains(&format!("UID:{}@midos.house\r\n", live_race.id)));
+    assert!(ics.contains("SUMMARY:Test Event t Round 1: Alpha vs Beta\r\n"));
+    assert!(ics.contains("DTSTART;TZID=Etc/UTC:20250601T180000\r\n"));
+    assert!(ics.contains("DTEND;TZID=Etc/UTC:20250601T220000\r\n"), "end defaults to the series' race duration");
+    assert!(ics.contains(&format!("UID:{}-1@midos.house\r\n", async_race.id)));
+    assert!(ics.contains("SUMMARY:Test Event t (async): Beta vs (unnamed)\r\n"));
+    assert!(ics.contains(&format!("UID:{}-2@midos.house\r\n", async_race.id)));
+    assert!(ics.contains("SUMMARY:Test Event t (async): (unnamed) vs Beta\r\n"), "second half lists the entrant who plays it first");
+    assert!(ics.contains("SUMMARY:Test Event t: Dana vs Alpha\r\n"), "unnamed solo teams are named after their member");
+    assert!(ics.contains("DTEND;TZID=Etc/UTC:20250601T230000\r\n"));
+    assert!(!ics.contains(&format!("UID:{}", unscheduled_race.id)), "unscheduled races have no calendar entry");
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 4);
+    db.cleanup().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn room_opening_times() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let mut transaction = db.pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    let alpha = testing::team(&mut transaction, &event, Some("Alpha"), Some(1), &[]).await?;
+    let beta = testing::team(&mut transaction, &event, Some("Beta"), Some(2), &[]).await?;
+    let mut race = testing::race(&mut transaction, &event, [&alpha, &beta]).await?;
+    transaction.rollback().await?;
+    let start = Utc::now() + TimeDelta::hours(2);
+    race.schedule = RaceSchedule::Live { start, end: None, room: None };
+    assert_eq!(Event { race: race.clone(), kind: EventKind::Normal }.room_opens_at(), Some(start - TimeDelta::minutes(30)));
+    race.entrants = Entrants::Open;
+    assert_eq!(Event { race: race.clone(), kind: EventKind::Normal }.room_opens_at(), Some(start - TimeDelta::hours(1)), "open races get more time to join");
+    race.schedule = RaceSchedule::Live { start, end: None, room: Some(Url::parse("https://racetime.gg/ootr/example-room-1234")?) };
+    assert_eq!(Event { race: race.clone(), kind: EventKind::Normal }.room_opens_at(), None, "room is already open");
+    race.schedule = RaceSchedule::Async { start1: Some(start), start2: Some(Utc::now() - TimeDelta::hours(1)), start3: None, end1: None, end2: None, end3: None, room1: None, room2: None, room3: None };
+    race.async_notified_1 = true;
+    assert_eq!(Event { race: race.clone(), kind: EventKind::Async1 }.room_opens_at(), None, "entrant has already been notified");
+    assert_eq!(Event { race: race.clone(), kind: EventKind::Async2 }.room_opens_at(), None, "part has already started");
+    db.cleanup().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn three_entrants_round_trip() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let http_client = reqwest::Client::new();
+    let mut transaction = db.pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    let alpha = testing::team(&mut transaction, &event, Some("Alpha"), Some(1), &[]).await?;
+    let beta = testing::team(&mut transaction, &event, Some("Beta"), Some(2), &[]).await?;
+    let mut race = testing::race(&mut transaction, &event, [&alpha, &beta]).await?;
+    race.entrants = Entrants::Three([
+        Entrant::MidosHouseTeam(alpha.clone()),
+        Entrant::Discord { id: UserId::new(1234), racetime_id: Some(format!("abcd")), twitch_username: None },
+        Entrant::Named { name: format!("Charlie"), racetime_id: Some(format!("efgh")), twitch_username: Some(format!("charlie")) },
+    ]);
+    race.save(&mut transaction).await?;
+    let saved = Race::from_id(&mut transaction, &http_client, race.id).await?;
+    transaction.rollback().await?;
+    let Entrants::Three([team, Entrant::Discord { id, racetime_id, twitch_username }, Entrant::Named { name, racetime_id: named_racetime_id, twitch_username: named_twitch_username }]) = &saved.entrants else { panic!("entrants changed shape when saved") };
+    assert!(*team == Entrant::MidosHouseTeam(alpha));
+    assert_eq!(*id, UserId::new(1234));
+    assert_eq!(racetime_id.as_deref(), Some("abcd"));
+    assert_eq!(twitch_username.as_deref(), None);
+    assert_eq!(name, "Charlie");
+    assert_eq!(named_racetime_id.as_deref(), Some("efgh"));
+    assert_eq!(named_twitch_username.as_deref(), Some("charlie"));
+    db.cleanup().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn race_results() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let mut transaction = db.pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    let alpha = testing::team(&mut transaction, &event, Some("Alpha"), Some(1), &[]).await?;
+    let beta = testing::team(&mut transaction, &event, Some("Beta"), Some(2), &[]).await?;
+    let mut race = testing::race(&mut transaction, &event, [&alpha, &beta]).await?;
+    race.round = Some(format!("Round 1"));
+    race.save(&mut transaction).await?;
+    let finish_time = Duration::from_secs(2 * 60 * 60 + 5 * 60 + 7);
+    RaceResult::save_all(&mut transaction, race.id, &[
+        RaceResult { entrant: Entrant::MidosHouseTeam(beta.clone()), placement: 1, finish_time: Some(finish_time), tfb_pieces: None, dnf: false, fpa_invoked: false, breaks_used: true },
+        RaceResult { entrant: Entrant::MidosHouseTeam(alpha.clone()), placement: 2, finish_time: None, tfb_pieces: None, dnf: true, fpa_invoked: false, breaks_used: false },
+    ]).await?;
+    let results = RaceResult::for_race(&mut transaction, race.id).await?;
+    let alpha_results = RaceResult::for_team(&mut transaction, &alpha).await?;
+    RaceResult::save_all(&mut transaction, race.id, &[]).await?;
+    let cleared = RaceResult::for_race(&mut transaction, race.id).await?;
+    transaction.rollback().await?;
+    assert_eq!(results.len(), 2);
+    assert!(results[0].entrant == Entrant::MidosHouseTeam(beta));
+    assert_eq!(results[0].finish_time, Some(finish_time));
+    assert_eq!(results[0].summary(TeamConfig::Multiworld), "1st (2:05:07), with breaks");
+    assert_eq!(results[1].summary(TeamConfig::Multiworld), "DNF");
+    assert_eq!(alpha_results.len(), 1);
+    assert_eq!(alpha_results[0].0.as_deref(), Some("Round 1"));
+    assert!(alpha_results[0].1.dnf);
+    assert!(cleared.is_empty(), "saving results replaces the previous ones");
+    db.cleanup().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn game_winners_come_from_results() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let mut transaction = db.pool.begin().await?;
+    let http_client = reqwest::Client::new();
+    let event = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    let alpha = testing::team(&mut transaction, &event, Some("Alpha"), Some(1), &[]).await?;
+    let beta = testing::team(&mut transaction, &event, Some("Beta"), Some(2), &[]).await?;
+    let mut games = Vec::default();
+    for game in 1..=3 {
+        let mut race = testing::race(&mut transaction, &event, [&alpha, &beta]).await?;
+        race.game = Some(game);
+        race.save(&mut transaction).await?;
+        games.push(race);
+    }
+    let result = |team: &Team, placement, dnf| RaceResult { entrant: Entrant::MidosHouseTeam(team.clone()), placement, finish_time: None, tfb_pieces: None, dnf, fpa_invoked: false, breaks_used: false };
+    RaceResult::save_all(&mut transaction, games[0].id, &[result(&alpha, 1, false), result(&beta, 2, false)]).await?;
+    RaceResult::save_all(&mut transaction, games[1].id, &[result(&alpha, 1, false), result(&beta, 1, false)]).await?;
+    let with_draw = games[0].game_winners(&mut transaction).await?;
+    games[1].record_results(&mut transaction, &http_client, &[result(&alpha, 1, false), result(&beta, 2, true)]).await?;
+    let winners = games[2].game_winners(&mut transaction).await?;
+    let game_count = games[0].game_count(&mut transaction).await?;
+    let third_game = Race::from_id(&mut transaction, &http_client, games[2].id).await?;
+    transaction.rollback().await?;
+    assert_eq!(with_draw, [(1, alpha.id)], "a draw has no winner");
+    assert_eq!(winners, [(1, alpha.id), (2, alpha.id)]);
+    assert!(third_game.ignored, "unplayed games are ignored once a recorded result decides the match");
+    assert_eq!(game_count, 3, "ignored games still count towards the length of the match");
+    db.cleanup().await;
+    Ok(())
+}
@@ -123,3 +123,55 @@ pub(crate) async fn races_to_import(transaction: &mut Transaction<'_, Postgres>,
     }
     Ok((races, skips))
 }
+
+/// Reports the score of a match so far. If `winner` is given, the match is marked as complete and that participant advances.
+pub(crate) async fn report_match(http_client: &reqwest::Client, api_key: &str, community: Option<&str>, tournament: &str, match_id: &str, scores: [(&str, usize); 2], winner: Option<&str>) -> wheel::Result<()> {
+    #[derive(Serialize)]
+    struct Request<'a> {
+        data: RequestData<'a>,
+    }
+
+    #[derive(Serialize)]
+    struct RequestData<'a> {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        attributes: Attributes<'a>,
+    }
+
+    #[derive(Serialize)]
+    struct Attributes<'a> {
+        #[serde(rename = "match")]
+        participants: [ParticipantScore<'a>; 2],
+    }
+
+    #[derive(Serialize)]
+    struct ParticipantScore<'a> {
+        participant_id: &'a str,
+        score_set: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rank: Option<u8>,
+        advancing: bool,
+    }
+
+    let endpoint = if let Some(community) = community {
+        format!("https://api.challonge.com/v2/communities/{community}/tournaments/{tournament}/matches/{match_id}.json")
+    } else {
+        format!("https://api.challonge.com/v2/tournaments/{tournament}/matches/{match_id}.json")
+    };
+    let participants = scores.map(|(participant_id, score)| ParticipantScore {
+        score_set: score.to_string(),
+        rank: winner.map(|winner| if winner == participant_id { 1 } else { 2 }),
+        advancing: winner == Some(participant_id),
+        participant_id,
+    });
+    println!("Challonge: Reporting score for match {match_id}");
+    http_client.put(endpoint)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .header(reqwest::header::CONTENT_TYPE, "application/vnd.api+json")
+        .header("Authorization-Type", "v1")
+        .header(reqwest::header::AUTHORIZATION, api_key)
+        .json(&Request { data: RequestData { kind: "Match", attributes: Attributes { participants } } })
+        .send().await?
+        .detailed_error_for_status().await?;
+    Ok(())
+}
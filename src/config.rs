@@ -28,6 +28,10 @@ pub(crate) struct Config {
     #[serde(rename = "startggOAuth")]
     pub(crate) startgg_oauth: ConfigOAuth,
     pub(crate) secret_key: String,
+    #[serde(default)]
+    pub(crate) seed_fallback: ConfigSeedFallback,
+    #[serde(default)]
+    pub(crate) seed_queue: ConfigSeedQueue,
 }
 
 impl Config {
@@ -58,6 +62,41 @@ impl TypeMapKey for ConfigRaceTime {
     type Value = Self;
 }
 
+/// What to do when ootrandomizer.com can't be reached while rolling a seed.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigSeedFallback {
+    /// Whether to roll the seed locally instead of reporting an error.
+    pub(crate) enabled: bool,
+    /// How long to keep rolling seeds locally after a network error before trying ootrandomizer.com again.
+    pub(crate) retry_after_secs: u64,
+}
+
+impl Default for ConfigSeedFallback {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retry_after_secs: 15 * 60,
+        }
+    }
+}
+
+/// Limits for rolling seeds on this server.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigSeedQueue {
+    /// How many seeds may be generated locally at the same time. Further seeds wait in a queue.
+    pub(crate) workers: usize,
+}
+
+impl Default for ConfigSeedQueue {
+    fn default() -> Self {
+        Self {
+            workers: 2,
+        }
+    }
+}
+
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ConfigDiscord {
@@ -2,7 +2,7 @@ use {
     crate::{
         config::ConfigRaceTime,
         prelude::*,
-        racetime_bot::{CleanShutdown, CrosskeysRaceOptions, GlobalState},
+        racetime_bot::{CleanShutdown, CrosskeysRaceOptions, GlobalState, generator::SeedRequest, queue::Priority},
     }, serenity::all::{
         CacheHttp,
         Content,
@@ -24,6 +24,9 @@ use {
     }
 };
 
+mod async_race;
+pub(crate) mod reminder;
+
 pub(crate) const ADMIN_USER: UserId = UserId::new(82783364175630336); // TreZ
 const BUTTONS_PER_PAGE: usize = 25;
 
@@ -500,7 +503,10 @@ async fn send_draft_settings_page(ctx: &DiscordCtx, interaction: &impl GenericIn
 
 async fn draft_action(ctx: &DiscordCtx, interaction: &impl GenericInteraction, action: draft::Action) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let Some((event, mut race, draft_kind, mut msg_ctx)) = check_draft_permissions(ctx, interaction).await? else { return Ok(()) };
-    match race.draft.as_mut().unwrap().apply(draft_kind, race.game, &mut msg_ctx, action).await? {
+    let state_before = race.draft.clone().unwrap();
+    let draft::MessageContext::Discord { ref team, .. } = msg_ctx else { unreachable!() };
+    let team_id = team.id;
+    match race.draft.as_mut().unwrap().apply(draft_kind, race.game, &mut msg_ctx, action.clone()).await? {
         Ok(apply_response) => {
             interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
                 .ephemeral(false)
@@ -511,6 +517,9 @@ async fn draft_action(ctx: &DiscordCtx, interaction: &impl GenericInteraction, a
                     .say(ctx, race.draft.as_ref().unwrap().next_step(draft_kind, race.game, &mut msg_ctx).await?.message).await?;
             }
             let mut transaction = msg_ctx.into_transaction();
+            let actor = User::from_discord(&mut *transaction, interaction.user_id()).await?;
+            draft::history::record(&mut transaction, race.id, actor.map(|actor| actor.id), Some(team_id), draft::history::Frontend::Discord, &action, &state_before).await?;
+            draft::timer::reset(&mut transaction, &event, race.id).await?;
             sqlx::query!("UPDATE races SET draft_state = $1 WHERE id = $2", Json(race.draft.as_ref().unwrap()) as _, race.id as _).execute(&mut *transaction).await?;
             transaction.commit().await?;
         }
@@ -557,7 +566,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let mut draft_kind = None;
             for event in &guild_events {
                 if let Some(new_kind) = event.draft_kind() {
-                    if draft_kind.is_some_and(|prev_kind| prev_kind != new_kind) {
+                    if draft_kind.is_some_and(|prev_kind| prev_kind != new_kind && !matches!((prev_kind, new_kind), (draft::Kind::Custom(_), draft::Kind::Custom(_)))) {
                         #[derive(Debug, thiserror::Error)]
                         #[error("multiple conflicting draft kinds in the same Discord guild")]
                         struct DraftKindsError;
@@ -571,7 +580,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let ban = draft_kind.map(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("ban")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("ban")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Locks a setting for this race to its default value."),
@@ -609,7 +618,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let draft = draft_kind.and_then(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("draft")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("draft")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Chooses a setting for this race (same as /pick)."),
@@ -626,7 +635,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let first = draft_kind.map(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("first")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("first")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Go first in the settings draft."),
@@ -664,7 +673,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let no = draft_kind.and_then(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::RslS7 => return None,
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) | draft::Kind::RslS7 => return None,
                     draft::Kind::TournoiFrancoS3 | draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 => CreateCommand::new("no")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
@@ -677,7 +686,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let pick = draft_kind.map(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("pick")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("pick")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Chooses a setting for this race."),
@@ -841,7 +850,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let second = draft_kind.map(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("second")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("second")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Go second in the settings draft."),
@@ -879,7 +888,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let skip = draft_kind.map(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => CreateCommand::new("skip")
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => CreateCommand::new("skip")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
                         .description("Skips your current turn of the settings draft."),
@@ -935,7 +944,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
             let yes = draft_kind.and_then(|draft_kind| {
                 let idx = commands.len();
                 commands.push(match draft_kind {
-                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::RslS7 => return None,
+                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) | draft::Kind::RslS7 => return None,
                     draft::Kind::TournoiFrancoS3 | draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 => CreateCommand::new("yes")
                         .kind(CommandType::ChatInput)
                         .add_context(InteractionContext::Guild)
@@ -1093,7 +1102,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                                             }
                                         }
                                     }
-                                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => {}
+                                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => {}
                                 }
                                 draft_action(ctx, interaction, draft::Action::GoFirst(true)).await?;
                             }
@@ -1360,22 +1369,10 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                                         _ => panic!("unexpected slash command option type"),
                                     };
                                     if let Some(start) = parse_timestamp(start) {
-                                        if (start - Utc::now()).to_std().map_or(true, |schedule_notice| schedule_notice < event.min_schedule_notice) {
+                                        if let Some(error) = event.schedule_notice_error(start) {
                                             interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
                                                 .ephemeral(true)
-                                                .content(if event.min_schedule_notice <= Duration::default() {
-                                                    if let French = event.language {
-                                                        format!("Désolé mais cette date est dans le passé.")
-                                                    } else {
-                                                        format!("Sorry, that timestamp is in the past.")
-                                                    }
-                                                } else {
-                                                    if let French = event.language {
-                                                        format!("Désolé, les races doivent être planifiées au moins {} en avance.", French.format_duration(event.min_schedule_notice, true))
-                                                    } else {
-                                                        format!("Sorry, races must be scheduled at least {} in advance.", English.format_duration(event.min_schedule_notice, true))
-                                                    }
-                                                })
+                                                .content(error)
                                             )).await?;
                                             transaction.rollback().await?;
                                         } else {
@@ -1498,22 +1495,10 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                                         _ => panic!("unexpected slash command option type"),
                                     };
                                     if let Some(start) = parse_timestamp(start) {
-                                        if (start - Utc::now()).to_std().map_or(true, |schedule_notice| schedule_notice < event.min_schedule_notice) {
+                                        if let Some(error) = event.schedule_notice_error(start) {
                                             interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
                                                 .ephemeral(true)
-                                                .content(if event.min_schedule_notice <= Duration::default() {
-                                                    if let French = event.language {
-                                                        format!("Désolé mais cette date est dans le passé.")
-                                                    } else {
-                                                        format!("Sorry, that timestamp is in the past.")
-                                                    }
-                                                } else {
-                                                    if let French = event.language {
-                                                        format!("Désolé, les races doivent être planifiées au moins {} en avance.", French.format_duration(event.min_schedule_notice, true))
-                                                    } else {
-                                                        format!("Sorry, races must be scheduled at least {} in advance.", English.format_duration(event.min_schedule_notice, true))
-                                                    }
-                                                })
+                                                .content(error)
                                             )).await?;
                                             transaction.rollback().await?;
                                         } else {
@@ -1847,7 +1832,7 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                                             }
                                         }
                                     }
-                                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => {}
+                                    draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => {}
                                 }
                                 draft_action(ctx, interaction, draft::Action::GoFirst(false)).await?;
                             }
@@ -2112,6 +2097,14 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                         }
                     } else if let Some((setting, value)) = custom_id.strip_prefix("draft_option_").and_then(|setting_value| setting_value.split_once("__")) {
                         draft_action(ctx, interaction, draft::Action::Pick { setting: setting.to_owned(), value: value.to_owned() }).await?;
+                    } else if let Some(part_id) = custom_id.strip_prefix("asyncready_") {
+                        async_race::ready(ctx, interaction, async_race::PartId::parse(part_id).expect("asyncready interaction with unexpected custom ID")).await?;
+                    } else if let Some(part_id) = custom_id.strip_prefix("asyncdone_") {
+                        async_race::finish(ctx, interaction, async_race::PartId::parse(part_id).expect("asyncdone interaction with unexpected custom ID"), false).await?;
+                    } else if let Some(part_id) = custom_id.strip_prefix("asyncforfeit_") {
+                        async_race::finish(ctx, interaction, async_race::PartId::parse(part_id).expect("asyncforfeit interaction with unexpected custom ID"), true).await?;
+                    } else if let Some(part_id) = custom_id.strip_prefix("asyncvod_") {
+                        async_race::request_vod(ctx, interaction, async_race::PartId::parse(part_id).expect("asyncvod interaction with unexpected custom ID")).await?;
                     } else if let Some(speedgaming_id) = custom_id.strip_prefix("sgdisambig_") {
                         let (mut transaction, http_client) = {
                             let data = ctx.data.read().await;
@@ -2124,16 +2117,26 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
                         let ComponentInteractionDataKind::StringSelect { ref values } = interaction.data.kind else { panic!("sgdisambig interaction with unexpected payload") };
                         let race_id = values.iter().exactly_one().expect("sgdisambig interaction with unexpected payload").parse()?;
                         let mut race = Race::from_id(&mut transaction, &http_client, race_id).await?;
-                        let Some(speedgaming_slug) = race.event(&mut transaction).await?.speedgaming_slug else { panic!("sgdisambig interaction for race from non-SpeedGaming event") };
-                        let schedule = sgl::schedule(&http_client, &speedgaming_slug).await?;
+                        let event = race.event(&mut transaction).await?;
+                        let Some(ref speedgaming_slug) = event.speedgaming_slug else { panic!("sgdisambig interaction for race from non-SpeedGaming event") };
+                        let schedule = sgl::schedule(&http_client, speedgaming_slug).await?;
                         let restream = schedule.into_iter().find(|restream| restream.matches().any(|restream_match| restream_match.id == speedgaming_id)).expect("no such SpeedGaming match ID");
-                        restream.update_race(&mut race, speedgaming_id)?;
+                        let change = restream.update_race(&mut transaction, &mut race, speedgaming_id).await?;
                         race.save(&mut transaction).await?;
-                        transaction.commit().await?;
+                        let new_room_lock = ctx.data.read().await.get::<NewRoomLock>().expect("new room lock missing from Discord context").clone();
+                        lock!(new_room_lock = new_room_lock; {
+                            cal::announce_speedgaming_schedule_change(&mut transaction, ctx, &event, &race, change).await?;
+                            transaction.commit().await?;
+                        });
                     } else {
                         panic!("received message component interaction with unknown custom ID {custom_id:?}")
                     },
                 },
+                Interaction::Modal(interaction) => if let Some(part_id) = interaction.data.custom_id.strip_prefix("asyncvod_") {
+                    async_race::submit_vod(ctx, interaction, async_race::PartId::parse(part_id).expect("asyncvod modal with unexpected custom ID")).await?;
+                } else {
+                    panic!("received modal submit interaction with unknown custom ID {:?}", interaction.data.custom_id)
+                },
                 _ => {}
             }
             Ok(())
@@ -2157,7 +2160,9 @@ pub(crate) fn configure_builder(discord_builder: serenity_utils::Builder, global
         }))
         .task(|ctx_fut, _| async move {
             let db_pool = ctx_fut.read().await.data.read().await.get::<DbPool>().expect("database connection pool missing from Discord context").clone();
-            
+            if let Err(e) = async_race::resume(&*ctx_fut.read().await).await {
+                eprintln!("failed to resume Discord async part countdowns: {e} ({e:?})");
+            }
             let mut shutdown = shutdown;
             // Clean up expired invites every hour
             let mut interval = tokio::time::interval(Duration::from_secs(3600));
@@ -2318,6 +2323,9 @@ pub(crate) async fn create_scheduling_thread<'a>(ctx: &DiscordCtx, mut transacti
             content.push_line("");
             content.push(draft.next_step(draft_kind, race.game, &mut msg_ctx).await?.message);
             transaction = msg_ctx.into_transaction();
+            content.push_line("");
+            content.push(format!("You can also do the settings draft on the website: <{}>", uri!(base_uri(), draft::web::get(race.series, &*race.event, race.id))));
+            draft::timer::reset(&mut transaction, &event, race.id).await?;
         }
     }
     if let racetime_bot::Goal::Crosskeys2025 = racetime_bot::Goal::for_event(race.series, &race.event).expect("Goal not found for event") {
@@ -2342,15 +2350,10 @@ pub(crate) async fn create_scheduling_thread<'a>(ctx: &DiscordCtx, mut transacti
 }
 
 pub(crate) async fn handle_race(discord_ctx: DiscordCtx, cal_event: cal::Event, event: event::Data<'_>) -> Result<(),Error > {
-    // This is a temporary implementation. It checks the race and sees if a seed is rolled. 
-    // If it is not, it rolls a seed and adds it to the database.
-    // If it is, it pulls the seed from the database instead.
-    // It posts in the event.discord_organizer_channel channel a link to the seed, the player who is playing in the async, and gives admin instructions.
-    // Use previous mechanisms (async channels/etc) to manage race manually. 
-    // If the race is the second half, remind admin in the message to post the race result when it's over and report it on start.gg
-    // Set "notified" on the race to avoid this being called again.
+    // Rolls the seed if it hasn't been rolled yet, then hands the async part over to the entrants in direct messages (see the async_race module).
+    // Organizers are notified here, and again by the async_race module as the part progresses.
 
-    // This explicitly only handles asyncs for the crosskeys tournament. This should be remoed and replaced with something generic.
+    // This explicitly only rolls seeds for the crosskeys tournament. This should be removed and replaced with something generic.
     let discord_ctx = discord_ctx.clone();
     let cal_event = cal_event.clone();
     let event = event.clone();
@@ -2370,7 +2373,7 @@ pub(crate) async fn handle_race(discord_ctx: DiscordCtx, cal_event: cal::Event,
             let discord_data = discord_ctx.data.read().await;
             let global_state = discord_data.get::<GlobalState>().expect("Global State missing from Discord context");
             let crosskeys_options = CrosskeysRaceOptions::for_race(&global_state.db_pool, &cal_event.race).await;
-            let mut updates = global_state.clone().roll_crosskeys2025_seed(crosskeys_options);
+            let mut updates = global_state.clone().roll(None, None, Priority::Official, SeedRequest::Crosskeys2025(crosskeys_options), UnlockSpoilerLog::Never).await;
 
             // Loop until we get an update saying the seed data is done rolling.
             let seed = loop {
@@ -2407,7 +2410,7 @@ pub(crate) async fn handle_race(discord_ctx: DiscordCtx, cal_event: cal::Event,
         let mut content = MessageBuilder::default();
         content.push("Async starting for ");
         content.mention_team(&mut transaction, event.discord_guild, team).await?;
-        content.push(format!(". Seed URL is {}. Please work with them in their async channel to run the race.",seed_url));
+        content.push(format!(". Seed URL is {}. The async will be run in direct messages, you will be notified when they start and finish.",seed_url));
         if let Some([hash1, hash2, hash3, hash4, hash5]) = file_hash {
             content.push_line("");
             content.push(format!("The hash for the seed is {hash1}, {hash2}, {hash3}, {hash4}, {hash5}"));
@@ -2425,6 +2428,7 @@ pub(crate) async fn handle_race(discord_ctx: DiscordCtx, cal_event: cal::Event,
             ADMIN_USER.create_dm_channel(&discord_ctx).await?.say(&discord_ctx, msg).await?;
         }
     }
+    async_race::announce(&discord_ctx, &mut transaction, &cal_event).await?;
 
     match cal_event.kind {
         cal::EventKind::Async1 => {
@@ -2441,12 +2445,4 @@ pub(crate) async fn handle_race(discord_ctx: DiscordCtx, cal_event: cal::Event,
     
     transaction.commit().await?;
     Ok(())
-    // Leave the TODO below for posterity.
-
-    //TODO start Discord race handler (in DMs for private async parts, in scheduling thread for public ones):
-    // * post seed 15 minutes before start
-    // * reminder to go live for public async parts
-    // * Ready button to post password and start countdown
-    // * Done/Forfeit/FPA buttons
-    // * reminder to send vod to organizers
 }
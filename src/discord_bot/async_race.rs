@@ -0,0 +1,294 @@
+//! Async parts of races that are run in Discord direct messages rather than a racetime.gg room.
+//!
+//! [`announce`] is called when the part would otherwise get its room opened. Everything after that is driven by the buttons on the messages
+//! sent to the entrants, with the state of each part stored in the `discord_async_parts` table so it survives restarts.
+//! Countdowns that were interrupted by a restart are picked up again by [`resume`].
+
+use {
+    serenity::all::{
+        CreateActionRow,
+        CreateButton,
+        CreateInputText,
+        CreateInteractionResponse,
+        CreateInteractionResponseMessage,
+        CreateMessage,
+        CreateModal,
+        InputTextStyle,
+    },
+    crate::{
+        discord_bot::{
+            ADMIN_USER,
+            DbPool,
+            Error,
+            HttpClient,
+            MessageBuilderExt as _,
+        },
+        prelude::*,
+    },
+};
+
+/// Time between an entrant pressing the Ready button and the seed being posted.
+const COUNTDOWN: Duration = Duration::from_secs(10);
+
+/// Identifies an async part in the custom IDs of buttons and modals.
+#[derive(Clone, Copy)]
+pub(super) struct PartId {
+    race: Id<Races>,
+    part: i16,
+}
+
+impl PartId {
+    fn new(cal_event: &cal::Event) -> Self {
+        Self {
+            race: cal_event.race.id,
+            part: match cal_event.kind {
+                cal::EventKind::Async1 => 1,
+                cal::EventKind::Async2 => 2,
+                cal::EventKind::Async3 => 3,
+                cal::EventKind::Normal => unreachable!("Discord race handler started for a race that isn't async"),
+            },
+        }
+    }
+
+    pub(super) fn parse(custom_id: &str) -> Option<Self> {
+        let (race, part) = custom_id.split_once('_')?;
+        Some(Self {
+            race: race.parse().ok()?,
+            part: part.parse().ok()?,
+        })
+    }
+}
+
+impl fmt::Display for PartId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.race, self.part)
+    }
+}
+
+fn describe(race: &Race, part: i16) -> String {
+    let info_prefix = match (&race.phase, &race.round) {
+        (Some(phase), Some(round)) => Some(format!("{phase} {round}")),
+        (Some(phase), None) => Some(phase.clone()),
+        (None, Some(round)) => Some(round.clone()),
+        (None, None) => None,
+    };
+    match (info_prefix, race.game) {
+        (Some(prefix), Some(game)) => format!("async part {part} of {prefix}, game {game}"),
+        (Some(prefix), None) => format!("async part {part} of {prefix}"),
+        (None, Some(game)) => format!("async part {part} of game {game}"),
+        (None, None) => format!("async part {part}"),
+    }
+}
+
+async fn say_to_organizers(ctx: &DiscordCtx, event: &event::Data<'_>, msg: String) -> serenity::Result<()> {
+    if let Some(channel) = event.discord_organizer_channel {
+        channel.say(ctx, msg).await?;
+    } else {
+        ADMIN_USER.create_dm_channel(ctx).await?.say(ctx, msg).await?;
+    }
+    Ok(())
+}
+
+/// Sends each member of the team playing this async part a direct message with a button to start the part.
+pub(super) async fn announce(ctx: &DiscordCtx, transaction: &mut Transaction<'_, Postgres>, cal_event: &cal::Event) -> Result<(), Error> {
+    let part_id = PartId::new(cal_event);
+    for team in cal_event.active_teams() {
+        sqlx::query!("INSERT INTO discord_async_parts (race, part, team) VALUES ($1, $2, $3) ON CONFLICT (race, part) DO NOTHING", part_id.race as _, part_id.part, team.id as _).execute(&mut **transaction).await?;
+        let mut msg = MessageBuilder::default();
+        msg.push("Your ");
+        msg.push(describe(&cal_event.race, part_id.part));
+        if let Some(start) = cal_event.start() {
+            msg.push(" is scheduled for ");
+            msg.push_timestamp(start, serenity_utils::message::TimestampStyle::LongDateTime);
+        }
+        msg.push(". Please start recording, then press Ready once you're ready to go. The seed will be posted after a ");
+        msg.push(English.format_duration(COUNTDOWN, true));
+        msg.push(" countdown.");
+        let msg = msg.build();
+        for member in team.members(&mut *transaction).await? {
+            if let Some(discord) = member.discord {
+                discord.id.create_dm_channel(ctx).await?.send_message(ctx, CreateMessage::default()
+                    .content(&msg)
+                    .button(CreateButton::new(format!("asyncready_{part_id}")).label("Ready"))
+                ).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handles the Ready button: counts down, then posts the seed along with Done and Forfeit buttons.
+pub(super) async fn ready(ctx: &DiscordCtx, interaction: &ComponentInteraction, part_id: PartId) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let db_pool = ctx.data.read().await.get::<DbPool>().expect("database connection pool missing from Discord context").clone();
+    // claim the countdown so it only runs once if multiple team members press Ready
+    // the start time is stored right away so the seed is still posted if the countdown is interrupted by a restart
+    let claimed = sqlx::query!("UPDATE discord_async_parts SET ready_at = NOW(), start = $3 WHERE race = $1 AND part = $2 AND ready_at IS NULL", part_id.race as _, part_id.part, Utc::now() + COUNTDOWN).execute(&db_pool).await?.rows_affected() > 0;
+    if !claimed {
+        interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content("This async part has already been started.")
+        )).await?;
+        return Ok(())
+    }
+    interaction.create_response(ctx, CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+        .content(format!("The seed will be posted in {}.", English.format_duration(COUNTDOWN, true)))
+        .components(Vec::default())
+    )).await?;
+    sleep(COUNTDOWN).await;
+    post_seed(ctx, part_id).await?;
+    Ok(())
+}
+
+/// Posts the seed for an async part whose countdown has ended, unless it has already been posted.
+async fn post_seed(ctx: &DiscordCtx, part_id: PartId) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (db_pool, http_client) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<DbPool>().expect("database connection pool missing from Discord context").clone(),
+            data.get::<HttpClient>().expect("HTTP client missing from Discord context").clone(),
+        )
+    };
+    let mut transaction = db_pool.begin().await?;
+    let Some(team_id) = sqlx::query_scalar!(r#"UPDATE discord_async_parts SET seed_posted = TRUE WHERE race = $1 AND part = $2 AND NOT seed_posted RETURNING team AS "team: Id<Teams>""#, part_id.race as _, part_id.part).fetch_optional(&mut *transaction).await? else { return Ok(()) };
+    let race = Race::from_id(&mut transaction, &http_client, part_id.race).await?;
+    let event = race.event(&mut transaction).await?;
+    let team = Team::from_id(&mut transaction, team_id).await?.expect("database constraint violated: nonexistent team");
+    let mut msg = MessageBuilder::default();
+    msg.push("Go! ");
+    if let Some(seed_url) = race.seed.url(Utc::now()) {
+        msg.push("Seed: <");
+        msg.push(seed_url.to_string());
+        msg.push('>');
+    } else {
+        msg.push("The seed isn't available, please contact a tournament organizer.");
+    }
+    if let Some([hash1, hash2, hash3, hash4, hash5]) = race.seed.file_hash {
+        msg.push_line("");
+        msg.push(format!("The hash for the seed is {hash1}, {hash2}, {hash3}, {hash4}, {hash5}"));
+    }
+    msg.push_line("");
+    msg.push("Press Done as soon as you finish, or Forfeit if you can't finish.");
+    let msg = msg.build();
+    for member in team.members(&mut transaction).await? {
+        if let Some(discord) = member.discord {
+            discord.id.create_dm_channel(ctx).await?.send_message(ctx, CreateMessage::default()
+                .content(&msg)
+                .button(CreateButton::new(format!("asyncdone_{part_id}")).label("Done").style(ButtonStyle::Success))
+                .button(CreateButton::new(format!("asyncforfeit_{part_id}")).label("Forfeit").style(ButtonStyle::Danger))
+            ).await?;
+        }
+    }
+    let mut msg = MessageBuilder::default();
+    msg.mention_team(&mut transaction, event.discord_guild, &team).await?;
+    msg.push(" started ");
+    msg.push(describe(&race, part_id.part));
+    say_to_organizers(ctx, &event, msg.build()).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Posts the seeds for async parts whose Ready button was pressed but whose countdown was interrupted, e.g. by a restart. Called when the Discord bot starts.
+pub(super) async fn resume(ctx: &DiscordCtx) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let db_pool = ctx.data.read().await.get::<DbPool>().expect("database connection pool missing from Discord context").clone();
+    for row in sqlx::query!(r#"SELECT race AS "race: Id<Races>", part, start AS "start!" FROM discord_async_parts WHERE ready_at IS NOT NULL AND NOT seed_posted ORDER BY start"#).fetch_all(&db_pool).await? {
+        if let Ok(countdown) = (row.start - Utc::now()).to_std() {
+            sleep(countdown).await;
+        }
+        post_seed(ctx, PartId { race: row.race, part: row.part }).await?;
+    }
+    Ok(())
+}
+
+/// Handles the Done and Forfeit buttons: records the end of the part and asks for a VoD.
+pub(super) async fn finish(ctx: &DiscordCtx, interaction: &ComponentInteraction, part_id: PartId, forfeited: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (mut transaction, http_client) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<DbPool>().expect("database connection pool missing from Discord context").begin().await?,
+            data.get::<HttpClient>().expect("HTTP client missing from Discord context").clone(),
+        )
+    };
+    let Some(row) = sqlx::query!(r#"UPDATE discord_async_parts SET finish = NOW(), forfeited = $3 WHERE race = $1 AND part = $2 AND seed_posted AND finish IS NULL RETURNING team AS "team: Id<Teams>", start AS "start!", finish AS "finish!""#, part_id.race as _, part_id.part, forfeited).fetch_optional(&mut *transaction).await? else {
+        interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content("This async part has already ended.")
+        )).await?;
+        return Ok(())
+    };
+    match part_id.part {
+        1 => { sqlx::query!("UPDATE races SET async_end1 = $1 WHERE id = $2", row.finish, part_id.race as _).execute(&mut *transaction).await?; }
+        2 => { sqlx::query!("UPDATE races SET async_end2 = $1 WHERE id = $2", row.finish, part_id.race as _).execute(&mut *transaction).await?; }
+        3 => { sqlx::query!("UPDATE races SET async_end3 = $1 WHERE id = $2", row.finish, part_id.race as _).execute(&mut *transaction).await?; }
+        _ => unreachable!("database constraint violated: async part number out of range"),
+    }
+    let time = (row.finish - row.start).to_std().unwrap_or_default();
+    let mut response = MessageBuilder::default();
+    if forfeited {
+        response.push("You have forfeited ");
+    } else {
+        response.push("Done! Your time is ");
+        response.push(English.format_duration(time, false));
+        response.push(", finished ");
+    }
+    response.push_timestamp(row.finish, serenity_utils::message::TimestampStyle::LongDateTime);
+    response.push(". Please submit a link to your VoD once it's available.");
+    interaction.create_response(ctx, CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+        .content(response.build())
+        .button(CreateButton::new(format!("asyncvod_{part_id}")).label("Submit VoD"))
+    )).await?;
+    let race = Race::from_id(&mut transaction, &http_client, part_id.race).await?;
+    let event = race.event(&mut transaction).await?;
+    let team = Team::from_id(&mut transaction, row.team).await?.expect("database constraint violated: nonexistent team");
+    let mut msg = MessageBuilder::default();
+    msg.mention_team(&mut transaction, event.discord_guild, &team).await?;
+    if forfeited {
+        msg.push(" forfeited ");
+        msg.push(describe(&race, part_id.part));
+    } else {
+        msg.push(" finished ");
+        msg.push(describe(&race, part_id.part));
+        msg.push(" in ");
+        msg.push(English.format_duration(time, false));
+    }
+    say_to_organizers(ctx, &event, msg.build()).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Handles the Submit VoD button by asking for the link.
+pub(super) async fn request_vod(ctx: &DiscordCtx, interaction: &ComponentInteraction, part_id: PartId) -> serenity::Result<()> {
+    interaction.create_response(ctx, CreateInteractionResponse::Modal(CreateModal::new(format!("asyncvod_{part_id}"), "Submit VoD")
+        .components(vec![CreateActionRow::InputText(CreateInputText::new(InputTextStyle::Short, "VoD link", "vod").placeholder("https://"))])
+    )).await
+}
+
+pub(super) async fn submit_vod(ctx: &DiscordCtx, interaction: &ModalInteraction, part_id: PartId) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (mut transaction, http_client) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<DbPool>().expect("database connection pool missing from Discord context").begin().await?,
+            data.get::<HttpClient>().expect("HTTP client missing from Discord context").clone(),
+        )
+    };
+    let vod = interaction.data.components.iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| if let ActionRowComponent::InputText(input) = component { input.value.clone() } else { None })
+        .expect("VoD modal submitted without input");
+    let team_id = sqlx::query_scalar!(r#"UPDATE discord_async_parts SET vod = $3 WHERE race = $1 AND part = $2 RETURNING team AS "team: Id<Teams>""#, part_id.race as _, part_id.part, vod).fetch_one(&mut *transaction).await?;
+    interaction.create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .ephemeral(false)
+        .content("Thank you, your VoD has been submitted.")
+    )).await?;
+    let race = Race::from_id(&mut transaction, &http_client, part_id.race).await?;
+    let event = race.event(&mut transaction).await?;
+    let team = Team::from_id(&mut transaction, team_id).await?.expect("database constraint violated: nonexistent team");
+    let mut msg = MessageBuilder::default();
+    msg.mention_team(&mut transaction, event.discord_guild, &team).await?;
+    msg.push(" submitted their VoD for ");
+    msg.push(describe(&race, part_id.part));
+    msg.push(": ");
+    msg.push_safe(vod);
+    say_to_organizers(ctx, &event, msg.build()).await?;
+    transaction.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Reminders posted in scheduling threads ahead of races.
+//!
+//! The start time each reminder was sent for is stored in the `race_reminders` table, so a race is reminded again if it's rescheduled.
+
+use crate::prelude::*;
+
+/// How long before the start of a race its entrants are reminded.
+const LEAD_TIME: TimeDelta = TimeDelta::days(1);
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)] Cal(#[from] cal::Error),
+    #[error(transparent)] EventData(#[from] event::DataError),
+    #[error(transparent)] Serenity(#[from] serenity::Error),
+    #[error(transparent)] Sql(#[from] sqlx::Error),
+}
+
+/// When the reminder for the given race is due, or `None` if it doesn't get one, e.g. because it has no scheduling thread or has already been reminded of its current start time.
+pub(crate) async fn due_at(transaction: &mut Transaction<'_, Postgres>, race: &Race) -> sqlx::Result<Option<DateTime<Utc>>> {
+    let (Some(_), RaceSchedule::Live { start, room: None, .. }) = (race.scheduling_thread, &race.schedule) else { return Ok(None) };
+    if race.ignored || *start <= Utc::now() { return Ok(None) }
+    let remind_at = *start - LEAD_TIME;
+    // the confirmation for a race scheduled this close to its start serves as the reminder
+    if race.schedule_updated_at.is_some_and(|schedule_updated_at| schedule_updated_at > remind_at) { return Ok(None) }
+    let reminded = sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM race_reminders WHERE race = $1 AND start = $2) AS "exists!""#, race.id as _, start).fetch_one(&mut **transaction).await?;
+    Ok((!reminded).then_some(remind_at))
+}
+
+/// Reminds the entrants of the given race in its scheduling thread if the reminder is due. Run as a [scheduled job](crate::scheduler) at the time returned by [`due_at`].
+pub(crate) async fn send(db_pool: &PgPool, http_client: &reqwest::Client, discord_ctx: &DiscordCtx, race_id: Id<Races>) -> Result<(), Error> {
+    let mut transaction = db_pool.begin().await?;
+    let race = Race::from_id(&mut transaction, http_client, race_id).await?;
+    if_chain! {
+        if let Some(remind_at) = due_at(&mut transaction, &race).await?;
+        if remind_at <= Utc::now();
+        if let (Some(scheduling_thread), RaceSchedule::Live { start, .. }) = (race.scheduling_thread, &race.schedule);
+        then {
+            let event = race.event(&mut transaction).await?;
+            let mut message = MessageBuilder::default();
+            message.push("Reminder: ");
+            for (i, team) in race.teams().enumerate() {
+                if i > 0 {
+                    message.push(", ");
+                }
+                message.mention_team(&mut transaction, event.discord_guild, team).await?;
+            }
+            if race.teams().next().is_some() {
+                message.push(", ");
+            }
+            message.push(if let Some(game) = race.game { format!("game {game}") } else { format!("your race") });
+            message.push(" starts ");
+            message.push_timestamp(*start, serenity_utils::message::TimestampStyle::Relative);
+            message.push(". If you can't make it, please reschedule as soon as possible.");
+            sqlx::query!("INSERT INTO race_reminders (race, start) VALUES ($1, $2) ON CONFLICT (race) DO UPDATE SET start = EXCLUDED.start", race.id as _, start).execute(&mut *transaction).await?;
+            transaction.commit().await?;
+            scheduling_thread.say(discord_ctx, message.build()).await?;
+            return Ok(())
+        }
+    }
+    transaction.commit().await?;
+    Ok(())
+}
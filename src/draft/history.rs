@@ -0,0 +1,109 @@
+//! A log of every action applied to a race's settings draft, so drafts can be reviewed after the fact and organizers can undo mistakes.
+
+use {
+    sqlx::types::Json,
+    crate::{
+        draft::{
+            Action,
+            Draft,
+        },
+        prelude::*,
+    },
+};
+
+/// Where a draft action was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "draft_frontend", rename_all = "lowercase")]
+pub(crate) enum Frontend {
+    Discord,
+    RaceTime,
+    Web,
+    /// Taken automatically because the active team ran out of time for the step.
+    Timer,
+}
+
+impl fmt::Display for Frontend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Discord => write!(f, "Discord"),
+            Self::RaceTime => write!(f, "racetime.gg"),
+            Self::Web => write!(f, "website"),
+            Self::Timer => write!(f, "time limit"),
+        }
+    }
+}
+
+pub(crate) struct Entry {
+    pub(crate) actor: Option<Id<Users>>,
+    pub(crate) team: Option<Id<Teams>>,
+    pub(crate) frontend: Frontend,
+    pub(crate) action: Action,
+    pub(crate) applied_at: DateTime<Utc>,
+    pub(crate) undone_by: Option<Id<Users>>,
+    pub(crate) undone_at: Option<DateTime<Utc>>,
+}
+
+impl Entry {
+    /// Describes the action in the third person, e.g. “banned Bridge”. Setting and option names are looked up in the custom draft spec if there is one.
+    pub(crate) fn describe(&self, spec: Option<&draft::spec::Spec>) -> String {
+        let setting_display = |name: &str| spec.and_then(|spec| spec.setting(name)).map_or_else(|| name.to_owned(), |setting| setting.display.clone());
+        match self.action {
+            Action::GoFirst(true) => format!("chose to go first"),
+            Action::GoFirst(false) => format!("chose to go second"),
+            Action::Ban { ref setting } => format!("banned {}", setting_display(setting)),
+            Action::Pick { ref setting, ref value } => {
+                let value_display = spec.and_then(|spec| spec.setting(setting))
+                    .and_then(|setting| iter::once(&setting.default).chain(&setting.other).find(|option| option.name == *value))
+                    .map_or_else(|| value.clone(), |option| option.display.clone());
+                format!("picked {value_display} for {}", setting_display(setting))
+            }
+            Action::Skip => format!("skipped"),
+            Action::BooleanChoice(true) => format!("answered yes"),
+            Action::BooleanChoice(false) => format!("answered no"),
+        }
+    }
+}
+
+/// Records an action which has just been applied successfully. `state_before` is the draft state before the action, which is what [`undo_last`] restores.
+pub(crate) async fn record(transaction: &mut Transaction<'_, Postgres>, race: Id<Races>, actor: Option<Id<Users>>, team: Option<Id<Teams>>, frontend: Frontend, action: &Action, state_before: &Draft) -> sqlx::Result<()> {
+    sqlx::query!(
+        "INSERT INTO draft_actions (race, actor, team, frontend, action, state_before) VALUES ($1, $2, $3, $4, $5, $6)",
+        race as _, actor as _, team as _, frontend as _, Json(action) as _, Json(state_before) as _,
+    ).execute(&mut **transaction).await?;
+    Ok(())
+}
+
+/// All actions taken in this race's draft in chronological order, including ones which have been undone.
+pub(crate) async fn for_race(transaction: &mut Transaction<'_, Postgres>, race: Id<Races>) -> sqlx::Result<Vec<Entry>> {
+    Ok(sqlx::query!(r#"SELECT
+        actor AS "actor: Id<Users>",
+        team AS "team: Id<Teams>",
+        frontend AS "frontend: Frontend",
+        action AS "action: Json<Action>",
+        applied_at,
+        undone_by AS "undone_by: Id<Users>",
+        undone_at
+    FROM draft_actions WHERE race = $1 ORDER BY applied_at, id"#, race as _).fetch_all(&mut **transaction).await?
+        .into_iter()
+        .map(|row| Entry {
+            actor: row.actor,
+            team: row.team,
+            frontend: row.frontend,
+            action: row.action.0,
+            applied_at: row.applied_at,
+            undone_by: row.undone_by,
+            undone_at: row.undone_at,
+        })
+        .collect())
+}
+
+/// Marks the latest action which hasn't been undone yet as undone and restores the race's draft state from before that action.
+///
+/// Returns the restored draft state, or `None` if there was nothing to undo.
+pub(crate) async fn undo_last(transaction: &mut Transaction<'_, Postgres>, race: Id<Races>, organizer: Id<Users>) -> sqlx::Result<Option<Draft>> {
+    let Some(Json(state_before)) = sqlx::query_scalar!(r#"UPDATE draft_actions SET undone_by = $2, undone_at = NOW() WHERE id = (
+        SELECT id FROM draft_actions WHERE race = $1 AND undone_at IS NULL ORDER BY applied_at DESC, id DESC LIMIT 1
+    ) RETURNING state_before AS "state_before: Json<Draft>""#, race as _, organizer as _).fetch_optional(&mut **transaction).await? else { return Ok(None) };
+    sqlx::query!("UPDATE races SET draft_state = $1 WHERE id = $2", Json(&state_before) as _, race as _).execute(&mut **transaction).await?;
+    Ok(Some(state_before))
+}
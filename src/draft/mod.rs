@@ -12,6 +12,12 @@ use {
     },
 };
 
+pub(crate) mod history;
+pub(crate) mod spec;
+#[cfg(test)] mod tests;
+pub(crate) mod timer;
+pub(crate) mod web;
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
     #[error(transparent)] RslScriptPath(#[from] rsl::ScriptPathError),
@@ -21,6 +27,10 @@ pub(crate) enum Error {
     RslExtraType,
     #[error("unexpected type of `remove_` option in RSL override")]
     RslRemoveType,
+    #[error("the current step of the settings draft has nothing left to choose from")]
+    NoAvailableChoices,
+    #[error("a randomly chosen settings draft action was rejected: {0}")]
+    IllegalRandomAction(String),
 }
 
 impl IsNetworkError for Error {
@@ -31,6 +41,8 @@ impl IsNetworkError for Error {
             Self::Wheel(e) => e.is_network_error(),
             Self::RslExtraType => false,
             Self::RslRemoveType => false,
+            Self::NoAvailableChoices => false,
+            Self::IllegalRandomAction(_) => false,
         }
     }
 }
@@ -64,6 +76,7 @@ impl fmt::Display for Team {
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) enum Kind {
     // when defining a new variant, make sure to add it to event::Data::draft_kind and racetime_bot::Goal::draft_kind
+    // new events should prefer a draft spec stored in the database, see Custom
     S7,
     MultiworldS3,
     MultiworldS4,
@@ -72,6 +85,8 @@ pub(crate) enum Kind {
     TournoiFrancoS3,
     TournoiFrancoS4,
     TournoiFrancoS5,
+    /// A draft defined by the `draft_spec` column of the event.
+    Custom(&'static spec::Spec),
 }
 
 impl Kind {
@@ -84,6 +99,7 @@ impl Kind {
             | Self::RslS7
             | Self::TournoiFrancoS4
             | Self::TournoiFrancoS5
+            | Self::Custom(_)
                 => English,
             | Self::TournoiFrancoS3
                 => French,
@@ -203,6 +219,12 @@ pub(crate) enum MessageContext<'a> {
         low_seed_name: &'a str,
         reply_to: &'a str,
     },
+    /// The draft page on the website. Messages refer to the forms on the page rather than chat commands.
+    Web {
+        high_seed_name: &'a str,
+        low_seed_name: &'a str,
+        reply_to: &'a str,
+    },
 }
 
 impl<'a> MessageContext<'a> {
@@ -213,6 +235,8 @@ impl<'a> MessageContext<'a> {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) enum Action {
     GoFirst(bool),
     Ban {
@@ -226,7 +250,7 @@ pub(crate) enum Action {
     BooleanChoice(bool),
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub(crate) struct Draft {
     pub(crate) high_seed: Id<Teams>,
     pub(crate) went_first: Option<bool>,
@@ -302,11 +326,15 @@ impl Draft {
                     }
                 }
             },
-            Kind::TournoiFrancoS3 | Kind::TournoiFrancoS4 | Kind::TournoiFrancoS5 => {
+            Kind::TournoiFrancoS3 | Kind::TournoiFrancoS4 | Kind::TournoiFrancoS5 | Kind::Custom(&spec::Spec { high_seed: spec::HighSeed::CoinFlip, .. }) => {
                 let mut team_ids = [team1.id, team2.id];
                 team_ids.shuffle(&mut rng());
                 team_ids
             }
+            Kind::Custom(&spec::Spec { high_seed: spec::HighSeed::QualifierRank, .. }) => [
+                min_by_key(team1, team2, |team| team.qualifier_rank).id,
+                max_by_key(team1, team2, |team| team.qualifier_rank).id,
+            ],
         };
         Ok(Self::for_next_game(transaction, kind, high_seed, low_seed).await?)
     }
@@ -339,6 +367,15 @@ impl Draft {
                         Cow::Borrowed("mq_ok") => Cow::Borrowed(if mq_ok { "ok" } else { "no" }),
                     ]
                 }
+                Kind::Custom(spec) => if spec.setting("hard_settings_ok").is_some() {
+                    let team_rows = sqlx::query!("SELECT hard_settings_ok, mq_ok FROM teams WHERE id = $1 OR id = $2", loser as _, winner as _).fetch_all(&mut **transaction).await?;
+                    let hard_settings_ok = team_rows.iter().all(|row| row.hard_settings_ok);
+                    collect![as HashMap<_, _>:
+                        Cow::Borrowed("hard_settings_ok") => Cow::Borrowed(if hard_settings_ok { "ok" } else { "no" }),
+                    ]
+                } else {
+                    HashMap::default()
+                },
             },
         })
     }
@@ -355,6 +392,7 @@ impl Draft {
             Kind::TournoiFrancoS3 => self.skipped_bans + u8::try_from(fr::S3_SETTINGS.into_iter().filter(|&fr::Setting { name, .. }| self.settings.contains_key(name)).count()).unwrap(),
             Kind::TournoiFrancoS4 => self.skipped_bans + u8::try_from(fr::S4_SETTINGS.into_iter().filter(|&fr::Setting { name, .. }| self.settings.contains_key(name)).count()).unwrap(),
             Kind::TournoiFrancoS5 => self.skipped_bans + u8::try_from(fr::S5_SETTINGS.into_iter().filter(|&fr::Setting { name, .. }| self.settings.contains_key(name)).count()).unwrap(),
+            Kind::Custom(spec) => self.skipped_bans + u8::try_from(spec.pick_count(&self.settings)).unwrap(),
         }
     }
 
@@ -414,6 +452,10 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                         if prev_bans == 0 { " Use “!settings” for a list of available settings." } else { "" },
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default, or skip if you don't want to ban anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -467,6 +509,12 @@ impl Draft {
                                         4 | 5 => format!("{}, pick a minor setting.", team.choose(high_seed_name, low_seed_name)),
                                         0..=1 | 6.. => unreachable!(),
                                     },
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => match n {
+                                        2 => format!("{}, pick a major setting.", team.choose(high_seed_name, low_seed_name)),
+                                        3 => format!("{}, pick a major setting.", team.choose(high_seed_name, low_seed_name)),
+                                        4 | 5 => format!("{}, pick a minor setting.", team.choose(high_seed_name, low_seed_name)),
+                                        0..=1 | 6.. => unreachable!(),
+                                    },
                                 },
                             }
                         }
@@ -475,7 +523,7 @@ impl Draft {
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
                                 MessageContext::Discord { .. } => format!("Settings draft completed. You will be playing with {}.", s::display_s7_draft_picks(&self.settings)),
-                                MessageContext::RaceTime { .. } => s::display_s7_draft_picks(&self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => s::display_s7_draft_picks(&self.settings),
                             },
                         },
                     }
@@ -509,6 +557,7 @@ impl Draft {
                                     .build()
                             }
                             MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
                         },
                     }
                 }
@@ -604,6 +653,10 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                         if n == 0 { " Use “!settings” for a list of available weights." } else { "" },
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, ban a setting, or skip if you don't want to ban anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -673,6 +726,10 @@ impl Draft {
                                         "{}, block a weight from being modified using “!block <setting>”, or use “!skip” if you don't want to block anything.",
                                         team.choose(high_seed_name, low_seed_name),
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, block a weight from being modified, or skip if you don't want to block anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -690,7 +747,7 @@ impl Draft {
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
                                 MessageContext::Discord { .. } => format!("Weights draft completed. You will be playing with {}.", rsl::display_s7_draft_picks(&self.settings)),
-                                MessageContext::RaceTime { .. } => rsl::display_s7_draft_picks(&self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => rsl::display_s7_draft_picks(&self.settings),
                             },
                         },
                     }
@@ -727,6 +784,7 @@ impl Draft {
                                 builder.build()
                             }
                             MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
                         },
                     }
                 }
@@ -775,6 +833,10 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                         if prev_bans == 0 { " Use “!settings” for a list of available settings." } else { "" },
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default, or skip if you don't want to ban anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -845,6 +907,13 @@ impl Draft {
                                         5 => format!("{}, pick the final setting. You can also use “!skip” if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
                                         0..=1 | 6.. => unreachable!(),
                                     },
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => match n {
+                                        2 => format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name)),
+                                        3 => format!("{}, pick two settings.", team.choose(high_seed_name, low_seed_name)),
+                                        4 => format!("And your second pick?"),
+                                        5 => format!("{}, pick the final setting. You can also skip if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
+                                        0..=1 | 6.. => unreachable!(),
+                                    },
                                 },
                             }
                         }
@@ -853,7 +922,7 @@ impl Draft {
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
                                 MessageContext::Discord { .. } => format!("Settings draft completed. You will be playing with {}.", mw::display_s3_draft_picks(&self.settings)),
-                                MessageContext::RaceTime { .. } => mw::display_s3_draft_picks(&self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => mw::display_s3_draft_picks(&self.settings),
                             },
                         },
                     }
@@ -885,6 +954,7 @@ impl Draft {
                                 builder.build()
                             }
                             MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
                         },
                     }
                 }
@@ -944,6 +1014,10 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                         if prev_bans == 0 { " Use “!settings” for a list of available settings." } else { "" },
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default, or skip if you don't want to ban anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -1028,6 +1102,14 @@ impl Draft {
                                         9 => format!("{}, pick the final setting. You can also use “!skip” if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
                                         0..=1 | 6..=7 | 10.. => unreachable!(),
                                     },
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => match n {
+                                        2 => format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name)),
+                                        3 => format!("{}, pick two settings.", team.choose(high_seed_name, low_seed_name)),
+                                        4 => format!("And your second pick?"),
+                                        5 | 8 => format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name)),
+                                        9 => format!("{}, pick the final setting. You can also skip if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
+                                        0..=1 | 6..=7 | 10.. => unreachable!(),
+                                    },
                                 },
                             }
                         }
@@ -1036,7 +1118,7 @@ impl Draft {
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
                                 MessageContext::Discord { .. } => format!("Settings draft completed. You will be playing with {}.", mw::display_s4_draft_picks(&self.settings)),
-                                MessageContext::RaceTime { .. } => mw::display_s4_draft_picks(&self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => mw::display_s4_draft_picks(&self.settings),
                             },
                         },
                     }
@@ -1072,6 +1154,7 @@ impl Draft {
                                 builder.build()
                             }
                             MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
                         },
                     }
                 }
@@ -1120,6 +1203,10 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                         if prev_bans == 0 { " Use “!settings” for a list of available settings." } else { "" },
                                     ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default, or skip if you don't want to ban anything.",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    ),
                                 },
                             }
                         }
@@ -1191,6 +1278,14 @@ impl Draft {
                                         9 => format!("{}, pick the final setting. You can also use “!skip” if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
                                         0..=1 | 6..=7 | 10.. => unreachable!(),
                                     },
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => match n {
+                                        2 => format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name)),
+                                        3 => format!("{}, pick two settings.", team.choose(high_seed_name, low_seed_name)),
+                                        4 => format!("And your second pick?"),
+                                        5 | 8 => format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name)),
+                                        9 => format!("{}, pick the final setting. You can also skip if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name)),
+                                        0..=1 | 6..=7 | 10.. => unreachable!(),
+                                    },
                                 },
                             }
                         }
@@ -1199,7 +1294,7 @@ impl Draft {
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
                                 MessageContext::Discord { .. } => format!("Settings draft completed. You will be playing with {}.", mw::display_s5_draft_picks(&self.settings)),
-                                MessageContext::RaceTime { .. } => mw::display_s5_draft_picks(&self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => mw::display_s5_draft_picks(&self.settings),
                             },
                         },
                     }
@@ -1231,6 +1326,135 @@ impl Draft {
                                 builder.build()
                             }
                             MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
+                        },
+                    }
+                }
+            }
+            Kind::Custom(spec) => {
+                if let Some(went_first) = self.went_first {
+                    let step_idx = usize::from(self.pick_count(kind));
+                    if let Some(step) = spec.steps.get(step_idx) {
+                        let team = step.team(went_first);
+                        match step.kind {
+                            spec::StepKind::Ban => Step {
+                                kind: StepKind::Ban {
+                                    available_settings: spec.ban_settings(step, &self.settings),
+                                    skippable: step.skippable,
+                                    rsl: false,
+                                    team,
+                                },
+                                message: match msg_ctx {
+                                    MessageContext::None => String::default(),
+                                    MessageContext::Discord { transaction, guild_id, command_ids, teams, .. } => {
+                                        let (mut high_seed, mut low_seed) = teams.iter().partition::<Vec<_>, _>(|team| team.id == self.high_seed);
+                                        let high_seed = high_seed.remove(0);
+                                        let low_seed = low_seed.remove(0);
+                                        let mut builder = MessageBuilder::default();
+                                        builder.mention_team(transaction, Some(*guild_id), team.choose(high_seed, low_seed)).await?;
+                                        builder.push(": lock a setting to its default using ");
+                                        builder.mention_command(command_ids.ban.unwrap(), "ban");
+                                        if step.skippable {
+                                            builder.push(", or use ");
+                                            builder.mention_command(command_ids.skip.unwrap(), "skip");
+                                            builder.push(" if you don't want to ban anything.");
+                                        } else {
+                                            builder.push('.');
+                                        }
+                                        builder.build()
+                                    }
+                                    MessageContext::RaceTime { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default using “!ban <setting>”{}{}",
+                                        team.choose(high_seed_name, low_seed_name),
+                                        if step.skippable { ", or use “!skip” if you don't want to ban anything." } else { "." },
+                                        if step_idx == 0 { " Use “!settings” for a list of available settings." } else { "" },
+                                    ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, lock a setting to its default{}",
+                                        team.choose(high_seed_name, low_seed_name),
+                                        if step.skippable { ", or skip if you don't want to ban anything." } else { "." },
+                                    ),
+                                },
+                            },
+                            spec::StepKind::Pick => Step {
+                                kind: StepKind::Pick {
+                                    available_choices: spec.draft_settings(step, &self.settings),
+                                    skippable: step.skippable,
+                                    rsl: false,
+                                    team,
+                                },
+                                message: match msg_ctx {
+                                    MessageContext::None => String::default(),
+                                    MessageContext::Discord { transaction, guild_id, command_ids, teams, .. } => {
+                                        let (mut high_seed, mut low_seed) = teams.iter().partition::<Vec<_>, _>(|team| team.id == self.high_seed);
+                                        let high_seed = high_seed.remove(0);
+                                        let low_seed = low_seed.remove(0);
+                                        let mut builder = MessageBuilder::default();
+                                        builder.mention_team(transaction, Some(*guild_id), team.choose(high_seed, low_seed)).await?;
+                                        builder.push(": pick a setting using ");
+                                        builder.mention_command(command_ids.pick.unwrap(), "pick");
+                                        if step.skippable {
+                                            builder.push(". You can also use ");
+                                            builder.mention_command(command_ids.skip.unwrap(), "skip");
+                                            builder.push(" if you don't want to pick anything.");
+                                        } else {
+                                            builder.push('.');
+                                        }
+                                        builder.build()
+                                    }
+                                    MessageContext::RaceTime { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, pick a setting using “!pick <setting> <value>”.{}{}",
+                                        team.choose(high_seed_name, low_seed_name),
+                                        if step.skippable { " You can also use “!skip” if you don't want to pick anything." } else { "" },
+                                        if step_idx == 0 { " Use “!settings” for a list of available settings." } else { "" },
+                                    ),
+                                    MessageContext::Web { high_seed_name, low_seed_name, .. } => format!(
+                                        "{}, pick a setting.{}",
+                                        team.choose(high_seed_name, low_seed_name),
+                                        if step.skippable { " You can also skip if you don't want to pick anything." } else { "" },
+                                    ),
+                                },
+                            },
+                        }
+                    } else {
+                        Step {
+                            kind: StepKind::Done(spec.resolve(&self.settings)),
+                            message: match msg_ctx {
+                                MessageContext::None => String::default(),
+                                MessageContext::Discord { .. } => format!("Settings draft completed. You will be playing with {}.", spec.display_picks(&self.settings)),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => spec.display_picks(&self.settings),
+                            },
+                        }
+                    }
+                } else {
+                    Step {
+                        kind: StepKind::GoFirst,
+                        message: match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { transaction, guild_id, command_ids, teams, .. } => {
+                                let (mut high_seed, _) = teams.iter().partition::<Vec<_>, _>(|team| team.id == self.high_seed);
+                                let high_seed = high_seed.remove(0);
+                                let mut builder = MessageBuilder::default();
+                                builder.mention_team(transaction, Some(*guild_id), high_seed).await?;
+                                if game.is_some_and(|game| game > 1) {
+                                    builder.push(": as the losers of the previous race, please choose whether you want to go ");
+                                } else {
+                                    builder.push(": you have the higher seed. Choose whether you want to go ");
+                                }
+                                builder.mention_command(command_ids.first.unwrap(), "first");
+                                builder.push(" or ");
+                                builder.mention_command(command_ids.second.unwrap(), "second");
+                                if let Some(game) = game {
+                                    builder.push(" in the settings draft for game ");
+                                    builder.push(game.to_string());
+                                    builder.push('.');
+                                } else {
+                                    builder.push(" in the settings draft.");
+                                }
+                                builder.build()
+                            }
+                            MessageContext::RaceTime { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go !first or !second"),
+                            MessageContext::Web { high_seed_name, .. } => format!("{high_seed_name}, you have the higher seed. Choose whether you want to go first or second."),
                         },
                     }
                 }
@@ -1240,7 +1464,7 @@ impl Draft {
                     Kind::TournoiFrancoS3 => &fr::S3_SETTINGS[..],
                     Kind::TournoiFrancoS4 => &fr::S4_SETTINGS[..],
                     Kind::TournoiFrancoS5 => &fr::S5_SETTINGS[..],
-                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 => unreachable!(),
+                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_) => unreachable!(),
                 };
                 if let Some(went_first) = self.went_first {
                     let mut pick_count = self.pick_count(kind);
@@ -1257,7 +1481,7 @@ impl Draft {
                                 Kind::TournoiFrancoS3 => fr::resolve_s3_draft_settings(&self.settings),
                                 Kind::TournoiFrancoS4 => fr::resolve_s4_draft_settings(&self.settings),
                                 Kind::TournoiFrancoS5 => fr::resolve_s5_draft_settings(&self.settings),
-                                Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 => unreachable!(),
+                                Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_) => unreachable!(),
                             }),
                             message: match msg_ctx {
                                 MessageContext::None => String::default(),
@@ -1266,10 +1490,10 @@ impl Draft {
                                 } else {
                                     format!("Settings draft completed. You will be playing with {}.", fr::display_draft_picks(kind.language(), all_settings, &self.settings))
                                 },
-                                MessageContext::RaceTime { .. } => fr::display_draft_picks(kind.language(), all_settings, &self.settings),
+                                MessageContext::RaceTime { .. } | MessageContext::Web { .. } => fr::display_draft_picks(kind.language(), all_settings, &self.settings),
                             },
                         }),
-                        (Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7, _, _) => unreachable!(),
+                        (Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_), _, _) => unreachable!(),
                     };
                     if select_mixed_dungeons {
                         Step {
@@ -1304,6 +1528,17 @@ impl Draft {
                                         team.choose(high_seed_name, low_seed_name),
                                     )
                                 },
+                                MessageContext::Web { high_seed_name, low_seed_name, .. } => if let French = kind.language() {
+                                    format!(
+                                        "{}, est-ce que les donjons seront mixés avec les intérieurs et les grottos ?",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    )
+                                } else {
+                                    format!(
+                                        "{}, should dungeon entrances be mixed with interiors and grottos?",
+                                        team.choose(high_seed_name, low_seed_name),
+                                    )
+                                },
                             },
                         }
                     } else {
@@ -1375,6 +1610,17 @@ impl Draft {
                                                 if prev_bans == 0 { " Use “!settings” for a list of available settings." } else { "" },
                                             )
                                         },
+                                        MessageContext::Web { high_seed_name, low_seed_name, .. } => if let French = kind.language() {
+                                            format!(
+                                                "{}, veuillez ban un setting.",
+                                                team.choose(high_seed_name, low_seed_name),
+                                            )
+                                        } else {
+                                            format!(
+                                                "{}, lock a setting to its default.",
+                                                team.choose(high_seed_name, low_seed_name),
+                                            )
+                                        },
                                     },
                                 }
                             }
@@ -1383,13 +1629,13 @@ impl Draft {
                                     Kind::TournoiFrancoS3 => 10,
                                     Kind::TournoiFrancoS4 => 8,
                                     Kind::TournoiFrancoS5 => 8,
-                                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 => unreachable!(),
+                                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_) => unreachable!(),
                                 };
                                 let hard_settings_ok = self.settings.get("hard_settings_ok").map(|hard_settings_ok| &**hard_settings_ok).unwrap_or("no") == "ok";
                                 let can_ban = match kind {
                                     Kind::TournoiFrancoS3 | Kind::TournoiFrancoS4 => n < round_count - 2 || self.settings.get(team.choose("high_seed_has_picked", "low_seed_has_picked")).map(|has_picked| &**has_picked).unwrap_or("no") == "yes",
                                     Kind::TournoiFrancoS5 => n == 4 || n == 5,
-                                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 => unreachable!(),
+                                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_) => unreachable!(),
                                 };
                                 let skippable = n == round_count - 1 && can_ban;
                                 let (hard_settings, classic_settings) = all_settings.iter()
@@ -1535,6 +1781,39 @@ impl Draft {
                                             },
                                             (_, 0..=1 | 10..) => unreachable!(),
                                         },
+                                        MessageContext::Web { high_seed_name, low_seed_name, .. } => match (kind, n) {
+                                            (Kind::TournoiFrancoS4, 7) | (_, 9) if skippable => if let French = kind.language() {
+                                                format!("{}, choisissez le dernier setting. Vous pouvez également passer si vous voulez laisser les settings comme ils sont.", team.choose(high_seed_name, low_seed_name))
+                                            } else {
+                                                format!("{}, pick the final setting. You can also skip if you want to leave the settings as they are.", team.choose(high_seed_name, low_seed_name))
+                                            },
+                                            (Kind::TournoiFrancoS4, 7) | (_, 9) => if let French = kind.language() {
+                                                format!("{}, choisissez votre dernier setting.", team.choose(high_seed_name, low_seed_name))
+                                            } else {
+                                                format!("{}, pick the final setting.", team.choose(high_seed_name, low_seed_name))
+                                            },
+                                            (_, 2) => if let French = kind.language() {
+                                                format!("{}, choisissez un setting et sa configuration.", team.choose(high_seed_name, low_seed_name))
+                                            } else {
+                                                format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name))
+                                            },
+                                            (_, 3 | 5) => if let French = kind.language() {
+                                                format!("{}, choisissez deux settings. Quel est votre premier ?", team.choose(high_seed_name, low_seed_name))
+                                            } else {
+                                                format!("{}, pick two settings.", team.choose(high_seed_name, low_seed_name))
+                                            },
+                                            (_, 4 | 6) => if let French = kind.language() {
+                                                format!("Et votre second ?")
+                                            } else {
+                                                format!("And your second pick?")
+                                            },
+                                            (_, 7 | 8) => if let French = kind.language() {
+                                                format!("{}, choisissez un setting.", team.choose(high_seed_name, low_seed_name))
+                                            } else {
+                                                format!("{}, pick a setting.", team.choose(high_seed_name, low_seed_name))
+                                            },
+                                            (_, 0..=1 | 10..) => unreachable!(),
+                                        },
                                     },
                                 }
                             }
@@ -1580,6 +1859,11 @@ impl Draft {
                             } else {
                                 format!("{high_seed_name}, you have won the coin flip. Choose whether you want to go !first or !second in the settings draft.")
                             },
+                            MessageContext::Web { high_seed_name, .. } => if let French = kind.language() {
+                                format!("{high_seed_name}, vous avez été sélectionné pour décider qui commencera le draft en premier. Veuillez choisir si vous voulez commencer ou non.")
+                            } else {
+                                format!("{high_seed_name}, you have won the coin flip. Choose whether you want to go first or second in the settings draft.")
+                            },
                         },
                     }
                 }
@@ -1624,7 +1908,7 @@ impl Draft {
                                 }
                                 content.build()
                             }
-                            MessageContext::RaceTime { reply_to, .. } => format!(
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                 "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
                                 s::S7_SETTINGS.into_iter().map(|setting| setting.name).format(" or "),
                             ),
@@ -1638,7 +1922,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have" } else { " has" })
@@ -1651,13 +1935,13 @@ impl Draft {
                         StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
@@ -1671,12 +1955,13 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have locked in " } else { " has locked in " })
@@ -1692,6 +1977,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "ban")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
                                 })
                             }
                         } else {
@@ -1724,13 +2010,19 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
                             if let Some(option) = setting.options.iter().find(|option| option.name == value) {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have picked " } else { " has picked " })
@@ -1752,7 +2044,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -1788,13 +2080,19 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -1806,7 +2104,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
                             let skip_kind = match self.pick_count(kind) {
@@ -1815,7 +2114,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -1829,13 +2128,13 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -1844,12 +2143,12 @@ impl Draft {
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                         _ => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
                         }),
                     },
                 }
@@ -1872,7 +2171,7 @@ impl Draft {
                                 }
                                 content.build()
                             }
-                            MessageContext::RaceTime { reply_to, .. } => format!(
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                 "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
                                 mw::S3_SETTINGS.iter().copied().map(|setting| setting.name).format(" or "),
                             ),
@@ -1886,7 +2185,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have" } else { " has" })
@@ -1899,13 +2198,13 @@ impl Draft {
                         StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
@@ -1919,12 +2218,13 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have locked in " } else { " has locked in " })
@@ -1940,6 +2240,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "ban")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
                                 })
                             }
                         } else {
@@ -1972,13 +2273,19 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
                             if let Some(option) = setting.options.iter().find(|option| option.name == value) {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have picked " } else { " has picked " })
@@ -2000,7 +2307,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -2036,13 +2343,19 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2054,7 +2367,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
                             let skip_kind = match self.pick_count(kind) {
@@ -2063,7 +2377,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -2077,13 +2391,13 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2092,12 +2406,12 @@ impl Draft {
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                         _ => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
                         }),
                     },
                 }
@@ -2127,7 +2441,7 @@ impl Draft {
                                 }
                                 content.build()
                             }
-                            MessageContext::RaceTime { reply_to, .. } => format!(
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                 "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
                                 mw::S4_SETTINGS.iter().copied().map(|setting| setting.name).format(" or "),
                             ),
@@ -2141,7 +2455,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have" } else { " has" })
@@ -2154,13 +2468,13 @@ impl Draft {
                         StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
@@ -2174,12 +2488,13 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have locked in " } else { " has locked in " })
@@ -2195,6 +2510,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "ban")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
                                 })
                             }
                         } else {
@@ -2227,13 +2543,19 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
                             if let Some(option) = setting.options.iter().find(|option| option.name == value) {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have picked " } else { " has picked " })
@@ -2255,7 +2577,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -2291,13 +2613,19 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2309,7 +2637,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
                             let skip_kind = match self.pick_count(kind) {
@@ -2319,7 +2648,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -2333,13 +2662,13 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2348,12 +2677,12 @@ impl Draft {
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                         _ => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
                         }),
                     },
                 }
@@ -2379,7 +2708,7 @@ impl Draft {
                                 }
                                 content.build()
                             }
-                            MessageContext::RaceTime { reply_to, .. } => format!(
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                 "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
                                 mw::S5_SETTINGS.iter().copied().map(|setting| setting.name).format(" or "),
                             ),
@@ -2393,7 +2722,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have" } else { " has" })
@@ -2406,13 +2735,13 @@ impl Draft {
                         StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
@@ -2426,12 +2755,13 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have locked in " } else { " has locked in " })
@@ -2447,6 +2777,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "ban")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
                                 })
                             }
                         } else {
@@ -2479,13 +2810,19 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
                             if let Some(option) = setting.options.iter().find(|option| option.name == value) {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have picked " } else { " has picked " })
@@ -2507,7 +2844,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -2543,13 +2880,19 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2561,7 +2904,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
                             let skip_kind = match self.pick_count(kind) {
@@ -2571,7 +2915,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -2585,13 +2929,13 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                     },
                     Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2600,12 +2944,278 @@ impl Draft {
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
                         }),
                         _ => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                        }),
+                    },
+                }
+            }
+            Kind::Custom(spec) => {
+                let resolved_action = match action {
+                    Action::Ban { setting } => if let Some(setting) = spec.setting(&setting) {
+                        Action::Pick {
+                            setting: setting.name.clone(),
+                            value: setting.default.name.clone(),
+                        }
+                    } else {
+                        return Ok(Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => {
+                                let mut content = MessageBuilder::default();
+                                content.push("Sorry, I don't recognize that setting. Use one of the following: ");
+                                for (i, setting) in spec.settings.iter().enumerate() {
+                                    if i > 0 {
+                                        content.push(" or ");
+                                    }
+                                    content.push_mono(&setting.name);
+                                }
+                                content.build()
+                            }
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
+                                "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
+                                spec.settings.iter().map(|setting| &setting.name).format(" or "),
+                            ),
+                        }))
+                    },
+                    Action::BooleanChoice(value) if matches!(self.next_step(kind, game, &mut MessageContext::None).await?.kind, StepKind::GoFirst) => Action::GoFirst(value),
+                    _ => action,
+                };
+                match resolved_action {
+                    Action::GoFirst(first) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
+                        StepKind::GoFirst => {
+                            self.went_first = Some(first);
+                            Ok(match msg_ctx {
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
+                                MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
+                                    .mention_team(transaction, Some(*guild_id), team).await?
+                                    .push(if team.name_is_plural() { " have" } else { " has" })
+                                    .push(" chosen to go ")
+                                    .push(if first { "first" } else { "second" })
+                                    .push(" in the settings draft.")
+                                    .build(),
+                            })
+                        }
+                        StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                        }),
+                        StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
+                        StepKind::Done(_) => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                        }),
+                    },
+                    Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
+                    Action::Pick { setting, value } => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
+                        StepKind::GoFirst => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { command_ids, .. } => MessageBuilder::default()
+                                .push("Sorry, first pick hasn't been chosen yet, use ")
+                                .mention_command(command_ids.first.unwrap(), "first")
+                                .push(" or ")
+                                .mention_command(command_ids.second.unwrap(), "second")
+                                .build(),
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
+                        }),
+                        StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
+                            if value == setting.default {
+                                self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
+                                Ok(match msg_ctx {
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
+                                    MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
+                                        .mention_team(transaction, Some(*guild_id), team).await?
+                                        .push(if team.name_is_plural() { " have locked in " } else { " has locked in " })
+                                        .push(setting.default_display)
+                                        .push('.')
+                                        .build(),
+                                })
+                            } else {
+                                Err(match msg_ctx {
+                                    MessageContext::None => String::default(),
+                                    MessageContext::Discord { command_ids, .. } => MessageBuilder::default()
+                                        .push("Sorry, bans haven't been chosen yet, use ")
+                                        .mention_command(command_ids.ban.unwrap(), "ban")
+                                        .build(),
+                                    MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
+                                })
+                            }
+                        } else {
+                            let exists = spec.setting(&setting).is_some();
+                            Err(match msg_ctx {
+                                MessageContext::None => String::default(),
+                                MessageContext::Discord { command_ids, .. } => {
+                                    let mut content = MessageBuilder::default();
+                                    if exists {
+                                        content.push("Sorry, that setting is already locked in. Use one of the following: ");
+                                    } else {
+                                        content.push("Sorry, I don't recognize that setting. Use one of the following: ");
+                                    }
+                                    for (i, setting) in available_settings.all().enumerate() {
+                                        if i > 0 {
+                                            content.push(" or ");
+                                        }
+                                        content.push_mono(setting.name);
+                                    }
+                                    if exists && skippable {
+                                        content.push(". Use ");
+                                        content.mention_command(command_ids.skip.unwrap(), "skip");
+                                        content.push(" if you don't want to ban anything.");
+                                    }
+                                    content.build()
+                                }
+                                MessageContext::RaceTime { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
+                                ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
+                            })
+                        },
+                        StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
+                            if let Some(option) = setting.options.iter().find(|option| option.name == value) {
+                                self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
+                                Ok(match msg_ctx {
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
+                                    MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
+                                        .mention_team(transaction, Some(*guild_id), team).await?
+                                        .push(if team.name_is_plural() { " have picked " } else { " has picked " })
+                                        .push(&*option.display)
+                                        .push('.')
+                                        .build(),
+                                })
+                            } else {
+                                Err(match msg_ctx {
+                                    MessageContext::None => String::default(),
+                                    MessageContext::Discord { .. } => {
+                                        let mut content = MessageBuilder::default();
+                                        content.push("Sorry, that's not a possible value for this setting. Use one of the following: ");
+                                        for (i, value) in setting.options.into_iter().enumerate() {
+                                            if i > 0 {
+                                                content.push(" or ");
+                                            }
+                                            content.push_mono(value.name);
+                                        }
+                                        content.build()
+                                    }
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
+                                        "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
+                                        setting.options.into_iter().map(|value| value.name).format(" or "),
+                                    ),
+                                })
+                            }
+                        } else {
+                            let exists = spec.setting(&setting).is_some();
+                            Err(match msg_ctx {
+                                MessageContext::None => String::default(),
+                                MessageContext::Discord { command_ids, .. } => {
+                                    let mut content = MessageBuilder::default();
+                                    if exists {
+                                        content.push("Sorry, that setting is already locked in. Use one of the following: ");
+                                    } else {
+                                        content.push("Sorry, I don't recognize that setting. Use one of the following: ");
+                                    }
+                                    for (i, setting) in available_choices.all().enumerate() {
+                                        if i > 0 {
+                                            content.push(" or ");
+                                        }
+                                        content.push_mono(setting.name);
+                                    }
+                                    if exists && skippable {
+                                        content.push(". Use ");
+                                        content.mention_command(command_ids.skip.unwrap(), "skip");
+                                        content.push(" if you don't want to pick anything.");
+                                    }
+                                    content.build()
+                                }
+                                MessageContext::RaceTime { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
+                                ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
+                            })
+                        },
+                        StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
+                        StepKind::Done(_) => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                        }),
+                    },
+                    Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
+                        StepKind::GoFirst => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { command_ids, .. } => MessageBuilder::default()
+                                .push("Sorry, first pick hasn't been chosen yet, use ")
+                                .mention_command(command_ids.first.unwrap(), "first")
+                                .push(" or ")
+                                .mention_command(command_ids.second.unwrap(), "second")
+                                .build(),
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
+                        }),
+                        StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
+                            let skip_kind = match spec.steps[usize::from(self.pick_count(kind))].kind {
+                                spec::StepKind::Ban => "ban",
+                                spec::StepKind::Pick => "pick",
+                            };
+                            self.skipped_bans += 1;
+                            Ok(match msg_ctx {
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
+                                MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
+                                    .mention_team(&mut *transaction, Some(*guild_id), team).await?
+                                    .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
+                                    .push(team.possessive_determiner(transaction).await?)
+                                    .push(' ')
+                                    .push(skip_kind)
+                                    .push('.')
+                                    .build(),
+                            })
+                        }
+                        StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                        }),
+                        StepKind::BooleanChoice { .. } | StepKind::DoneRsl { .. } => unreachable!(),
+                        StepKind::Done(_) => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                        }),
+                    },
+                    Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
+                        StepKind::GoFirst => unreachable!("normalized to Action::GoFirst above"),
+                        StepKind::BooleanChoice { .. } => unreachable!(),
+                        StepKind::Done(_) => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, this settings draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this settings draft is already completed."),
+                        }),
+                        _ => Err(match msg_ctx {
+                            MessageContext::None => String::default(),
+                            MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
                         }),
                     },
                 }
@@ -2624,7 +3234,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => {
                                     let mut content = MessageBuilder::default();
                                     content.mention_team(transaction, Some(*guild_id), team).await?;
@@ -2645,13 +3255,13 @@ impl Draft {
                         StepKind::Ban { .. } | StepKind::Pick { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, first pick has already been chosen."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick has already been chosen."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::Done(_) => unreachable!(),
                         StepKind::DoneRsl { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this weights draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
                         }),
                     },
                     Action::Ban { .. } => unreachable!("normalized to Action::Pick above"),
@@ -2665,12 +3275,13 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have blocked " } else { " has blocked " })
@@ -2686,6 +3297,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "block")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is a block, not a ban. Use “!block <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is a block, not a ban. Use the Block button."),
                                 })
                             }
                         } else {
@@ -2712,6 +3324,11 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if skippable { ". Use “!skip” if you don't want to block anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, that setting doesn't exist or can no longer be blocked. Use one of the following: {}{}",
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if skippable { ". Use the Skip button if you don't want to block anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { team, available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
@@ -2731,7 +3348,7 @@ impl Draft {
                                     hash_map::Entry::Vacant(entry) => { entry.insert(Cow::Owned(team.to_string())); }
                                 }
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                         .mention_team(transaction, Some(*guild_id), team).await?
                                         .push(if team.name_is_plural() { " have banned " } else { " has banned " })
@@ -2753,7 +3370,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -2783,13 +3400,18 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, that setting doesn't exist or can no longer be banned. Use one of the following: {}{}",
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } | StepKind::Done(_) => unreachable!(),
                         StepKind::DoneRsl { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this weights draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
                         }),
                     },
                     Action::Skip => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2801,7 +3423,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         kind @ (StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. }) => {
                             let skip_kind = match kind {
@@ -2811,7 +3434,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -2825,13 +3448,13 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } | StepKind::Done(_) => unreachable!(),
                         StepKind::DoneRsl { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this weights draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
                         }),
                     },
                     Action::BooleanChoice(_) => match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
@@ -2840,12 +3463,12 @@ impl Draft {
                         StepKind::Done(_) => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this weights draft is already completed."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this weights draft is already completed."),
                         }),
                         _ => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, the current step is not a yes/no question."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, the current step is not a yes/no question."),
                         }),
                     },
                 }
@@ -2855,7 +3478,7 @@ impl Draft {
                     Kind::TournoiFrancoS3 => &fr::S3_SETTINGS[..],
                     Kind::TournoiFrancoS4 => &fr::S4_SETTINGS[..],
                     Kind::TournoiFrancoS5 => &fr::S5_SETTINGS[..],
-                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 => unreachable!(),
+                    Kind::MultiworldS3 | Kind::MultiworldS4 | Kind::MultiworldS5 | Kind::RslS7 | Kind::S7 | Kind::Custom(_) => unreachable!(),
                 };
                 let resolved_action = match action {
                     Action::Ban { setting } => if let Some(setting) = all_settings.iter().find(|&&fr::Setting { name, .. }| *name == setting) {
@@ -2874,7 +3497,7 @@ impl Draft {
                                 }
                                 content.build()
                             }
-                            MessageContext::RaceTime { reply_to, .. } => format!(
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                 "Sorry {reply_to}, I don't recognize that setting. Use one of the following: {}",
                                 all_settings.iter().map(|setting| setting.name).format(" or "),
                             ),
@@ -2887,7 +3510,7 @@ impl Draft {
                         StepKind::GoFirst => {
                             self.went_first = Some(first);
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => if let French = kind.language() {
                                     let mut content = MessageBuilder::default();
                                     content.mention_team(transaction, Some(*guild_id), team).await?;
@@ -2935,7 +3558,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, first pick has already been chosen.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, le premier pick a déjà été sélectionné.")
                             } else {
                                 format!("Sorry {reply_to}, first pick has already been chosen.")
@@ -2965,6 +3588,11 @@ impl Draft {
                             } else {
                                 format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use !yes or !no")
                             },
+                            MessageContext::Web { reply_to, .. } => if let French = kind.language() {
+                                format!("Désolé {reply_to}, avant que le draft ne puisse continuer, vous devez d'abord choisir si les donjons seront mixés ou non avec le reste. Utilisez les boutons Yes ou No.")
+                            } else {
+                                format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use the Yes or No button.")
+                            },
                         }),
                         StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
@@ -2974,7 +3602,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, this settings draft is already completed.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, ce draft est terminé.")
                             } else {
                                 format!("Sorry {reply_to}, this settings draft is already completed.")
@@ -2992,13 +3620,14 @@ impl Draft {
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
                             MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { available_settings, skippable, .. } => if let Some(setting) = available_settings.get(&setting) {
                             if value == setting.default {
                                 let hard_settings_ok = self.settings.get("hard_settings_ok").map(|hard_settings_ok| &**hard_settings_ok).unwrap_or("no") == "ok";
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(setting.default));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => if let French = kind.language() {
                                         MessageBuilder::default()
                                             .mention_team(transaction, Some(*guild_id), team).await?
@@ -3024,6 +3653,7 @@ impl Draft {
                                         .mention_command(command_ids.ban.unwrap(), "ban")
                                         .build(),
                                     MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Use “!ban <setting>”"),
+                                    MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, bans haven't been chosen yet. Please ban a setting or skip first."),
                                 })
                             }
                         } else {
@@ -3057,6 +3687,12 @@ impl Draft {
                                     available_settings.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to ban anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_settings.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to ban anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::Pick { available_choices, skippable, .. } => if let Some(setting) = available_choices.get(&setting) {
@@ -3068,7 +3704,7 @@ impl Draft {
                                 }
                                 self.settings.insert(Cow::Borrowed(setting.name), Cow::Borrowed(option.name));
                                 Ok(match msg_ctx {
-                                    MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                    MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                     MessageContext::Discord { transaction, guild_id, team, .. } => if let French = kind.language() {
                                         MessageBuilder::default()
                                             .mention_team(transaction, Some(*guild_id), team).await?
@@ -3099,7 +3735,7 @@ impl Draft {
                                         }
                                         content.build()
                                     }
-                                    MessageContext::RaceTime { reply_to, .. } => format!(
+                                    MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!(
                                         "Sorry {reply_to}, that's not a possible value for this setting. Use one of the following: {}",
                                         setting.options.into_iter().map(|value| value.name).format(" or "),
                                     ),
@@ -3135,6 +3771,12 @@ impl Draft {
                                     available_choices.all().map(|setting| setting.name).format(" or "),
                                     if exists && skippable { ". Use “!skip” if you don't want to pick anything." } else { "" },
                                 ),
+                                MessageContext::Web { reply_to, .. } => format!(
+                                    "Sorry {reply_to}, {}. Use one of the following: {}{}",
+                                    if exists { "that setting is already locked in" } else { "I don't recognize that setting" },
+                                    available_choices.all().map(|setting| setting.name).format(" or "),
+                                    if exists && skippable { ". Use the Skip button if you don't want to pick anything." } else { "" },
+                                ),
                             })
                         },
                         StepKind::BooleanChoice { .. } => Err(match msg_ctx {
@@ -3161,6 +3803,11 @@ impl Draft {
                             } else {
                                 format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use !yes or !no")
                             },
+                            MessageContext::Web { reply_to, .. } => if let French = kind.language() {
+                                format!("Désolé {reply_to}, avant que le draft ne puisse continuer, vous devez d'abord choisir si les donjons seront mixés ou non avec le reste. Utilisez les boutons Yes ou No.")
+                            } else {
+                                format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use the Yes or No button.")
+                            },
                         }),
                         StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
@@ -3170,7 +3817,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, this settings draft is already completed.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, ce draft est terminé.")
                             } else {
                                 format!("Sorry {reply_to}, this settings draft is already completed.")
@@ -3186,7 +3833,8 @@ impl Draft {
                                 .push(" or ")
                                 .mention_command(command_ids.second.unwrap(), "second")
                                 .build(),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”")
+                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet, use “!first” or “!second”"),
+                            MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, first pick hasn't been chosen yet. Please choose whether to go first or second."),
                         }),
                         StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } => {
                             let skip_kind = match self.pick_count(kind) {
@@ -3195,7 +3843,7 @@ impl Draft {
                             };
                             self.skipped_bans += 1;
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => MessageBuilder::default()
                                     .mention_team(&mut *transaction, Some(*guild_id), team).await?
                                     .push(if team.name_is_plural() { " have skipped " } else { " has skipped " })
@@ -3209,7 +3857,7 @@ impl Draft {
                         StepKind::Ban { skippable: false, .. } | StepKind::Pick { skippable: false, .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
                             MessageContext::Discord { .. } => format!("Sorry, this part of the draft can't be skipped."),
-                            MessageContext::RaceTime { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => format!("Sorry {reply_to}, this part of the draft can't be skipped."),
                         }),
                         StepKind::BooleanChoice { .. } => Err(match msg_ctx {
                             MessageContext::None => String::default(),
@@ -3235,6 +3883,11 @@ impl Draft {
                             } else {
                                 format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use !yes or !no")
                             },
+                            MessageContext::Web { reply_to, .. } => if let French = kind.language() {
+                                format!("Désolé {reply_to}, avant que le draft ne puisse continuer, vous devez d'abord choisir si les donjons seront mixés ou non avec le reste. Utilisez les boutons Yes ou No.")
+                            } else {
+                                format!("Sorry {reply_to}, before the settings draft can continue, you first have to choose whether dungeons entrances should be mixed. Use the Yes or No button.")
+                            },
                         }),
                         StepKind::DoneRsl { .. } => unreachable!(),
                         StepKind::Done(_) => Err(match msg_ctx {
@@ -3244,7 +3897,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, this settings draft is already completed.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, ce draft est terminé.")
                             } else {
                                 format!("Sorry {reply_to}, this settings draft is already completed.")
@@ -3255,7 +3908,7 @@ impl Draft {
                         StepKind::BooleanChoice { .. } => {
                             self.settings.insert(Cow::Borrowed("mixed-dungeons"), Cow::Borrowed(if value { "mixed" } else { "separate" }));
                             Ok(match msg_ctx {
-                                MessageContext::None | MessageContext::RaceTime { .. } => String::default(),
+                                MessageContext::None | MessageContext::RaceTime { .. } | MessageContext::Web { .. } => String::default(),
                                 MessageContext::Discord { transaction, guild_id, team, .. } => if let French = kind.language() {
                                     MessageBuilder::default()
                                         .mention_team(&mut *transaction, Some(*guild_id), team).await?
@@ -3284,7 +3937,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, this settings draft is already completed.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, ce draft est terminé.")
                             } else {
                                 format!("Sorry {reply_to}, this settings draft is already completed.")
@@ -3297,7 +3950,7 @@ impl Draft {
                             } else {
                                 format!("Sorry, the current step is not a yes/no question.")
                             },
-                            MessageContext::RaceTime { reply_to, .. } => if let French = kind.language() {
+                            MessageContext::RaceTime { reply_to, .. } | MessageContext::Web { reply_to, .. } => if let French = kind.language() {
                                 format!("Désolé {reply_to}, vous n'avez pas à répondre oui ou non.")
                             } else {
                                 format!("Sorry {reply_to}, the current step is not a yes/no question.")
@@ -3309,36 +3962,49 @@ impl Draft {
         })
     }
 
-    pub(crate) async fn complete_randomly(mut self, kind: Kind) -> Result<Picks, Error> {
-        Ok(loop {
-            let action = match self.next_step(kind, None, &mut MessageContext::None).await?.kind {
-                StepKind::GoFirst => Action::GoFirst(rng().random()),
-                StepKind::Ban { available_settings, skippable, .. } => {
-                    let mut settings = available_settings.all().map(Some).collect_vec();
-                    if skippable {
-                        settings.push(None);
-                    }
-                    if let Some(setting) = settings.into_iter().choose(&mut rng()).expect("no available settings") {
-                        Action::Ban { setting: setting.name.to_owned() }
-                    } else {
-                        Action::Skip
-                    }
+    /// Chooses a random legal action for the current step, or returns `None` if the draft is completed.
+    ///
+    /// If `prefer_skip` is set, steps that can be skipped are always skipped. Otherwise, skipping is one of the random options.
+    async fn random_action(&self, kind: Kind, game: Option<i16>, prefer_skip: bool) -> Result<Option<Action>, Error> {
+        Ok(Some(match self.next_step(kind, game, &mut MessageContext::None).await?.kind {
+            StepKind::GoFirst => Action::GoFirst(rng().random()),
+            StepKind::Ban { skippable: true, .. } | StepKind::Pick { skippable: true, .. } if prefer_skip => Action::Skip,
+            StepKind::Ban { available_settings, skippable, .. } => {
+                let mut settings = available_settings.all().map(Some).collect_vec();
+                if skippable {
+                    settings.push(None);
                 }
-                StepKind::Pick { available_choices, skippable, .. } => {
-                    let mut settings = available_choices.all().map(Some).collect_vec();
-                    if skippable {
-                        settings.push(None);
-                    }
-                    if let Some(setting) = settings.into_iter().choose(&mut rng()).expect("no available settings") {
-                        Action::Pick { setting: setting.name.to_owned(), value: setting.options.choose(&mut rng()).expect("no available values").name.to_owned() }
-                    } else {
-                        Action::Skip
-                    }
+                if let Some(setting) = settings.into_iter().choose(&mut rng()).ok_or(Error::NoAvailableChoices)? {
+                    Action::Ban { setting: setting.name.to_owned() }
+                } else {
+                    Action::Skip
                 }
-                StepKind::BooleanChoice { .. } => Action::BooleanChoice(rng().random()),
-                StepKind::Done(_) | StepKind::DoneRsl { .. } => break self.settings,
-            };
-            self.apply(kind, None, &mut MessageContext::None, action).await?.expect("random draft made illegal action");
-        })
+            }
+            StepKind::Pick { available_choices, skippable, .. } => {
+                let mut settings = available_choices.all().map(Some).collect_vec();
+                if skippable {
+                    settings.push(None);
+                }
+                if let Some(setting) = settings.into_iter().choose(&mut rng()).ok_or(Error::NoAvailableChoices)? {
+                    Action::Pick { setting: setting.name.to_owned(), value: setting.options.choose(&mut rng()).ok_or(Error::NoAvailableChoices)?.name.to_owned() }
+                } else {
+                    Action::Skip
+                }
+            }
+            StepKind::BooleanChoice { .. } => Action::BooleanChoice(rng().random()),
+            StepKind::Done(_) | StepKind::DoneRsl { .. } => return Ok(None),
+        }))
+    }
+
+    /// The action taken on behalf of the active team when they run out of time for the current step: skip if possible, otherwise a random choice.
+    pub(crate) async fn timeout_action(&self, kind: Kind, game: Option<i16>) -> Result<Option<Action>, Error> {
+        self.random_action(kind, game, true).await
+    }
+
+    pub(crate) async fn complete_randomly(mut self, kind: Kind) -> Result<Picks, Error> {
+        while let Some(action) = self.random_action(kind, None, false).await? {
+            self.apply(kind, None, &mut MessageContext::None, action).await?.map_err(Error::IllegalRandomAction)?;
+        }
+        Ok(self.settings)
     }
 }
@@ -0,0 +1,481 @@
+//! Declarative settings draft definitions which can be stored in the database and are interpreted by [`Kind::Custom`](super::Kind::Custom).
+
+use {
+    std::{
+        collections::BTreeMap,
+        sync::Mutex,
+    },
+    crate::{
+        draft::{
+            BanSetting,
+            BanSettings,
+            DraftSetting,
+            DraftSettingChoice,
+            DraftSettings,
+            Picks,
+            Team,
+        },
+        prelude::*,
+    },
+};
+
+/// Specs are leaked so `draft::Kind` can stay `Copy`. Only the current spec of each event is kept here, so a spec is leaked once per edit rather than once per load.
+static INTERNED: LazyLock<Mutex<HashMap<(Series, String), &'static Spec>>> = LazyLock::new(Mutex::default);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Spec {
+    /// How the team which gets to choose whether to go first or second in game 1 is determined.
+    #[serde(default)]
+    pub(crate) high_seed: HighSeed,
+    pub(crate) settings: Vec<Setting>,
+    /// The bans and picks after the high seed has chosen whether to go first or second, in order.
+    pub(crate) steps: Vec<Step>,
+    /// Seed settings which apply regardless of picks.
+    #[serde(default)]
+    pub(crate) base: seed::Settings,
+    /// Applied in order after the base settings and the settings of each picked option.
+    #[serde(default)]
+    pub(crate) rules: Vec<Rule>,
+    /// Groups of settings which exclude each other: while one of them is picked with an option other than its default, the others in the group can't be banned.
+    #[serde(default)]
+    pub(crate) exclusive: Vec<Vec<String>>,
+    /// Numeric seed settings computed from the picks, applied in order after the rules.
+    #[serde(default)]
+    pub(crate) derived: Vec<Derived>,
+}
+
+/// Drafts of past events which organizers can start from when configuring a new draft.
+///
+/// The RSL season 7 draft isn't included since it picks weights rather than settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence, FromFormField)]
+pub(crate) enum Template {
+    #[field(value = "s7")]
+    S7,
+    #[field(value = "mw3")]
+    MultiworldS3,
+    #[field(value = "mw4")]
+    MultiworldS4,
+    #[field(value = "mw5")]
+    MultiworldS5,
+    #[field(value = "fr3")]
+    TournoiFrancoS3,
+    #[field(value = "fr4")]
+    TournoiFrancoS4,
+    #[field(value = "fr5")]
+    TournoiFrancoS5,
+}
+
+impl Template {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::S7 => "s7",
+            Self::MultiworldS3 => "mw3",
+            Self::MultiworldS4 => "mw4",
+            Self::MultiworldS5 => "mw5",
+            Self::TournoiFrancoS3 => "fr3",
+            Self::TournoiFrancoS4 => "fr4",
+            Self::TournoiFrancoS5 => "fr5",
+        }
+    }
+
+    pub(crate) fn display_name(&self) -> &'static str {
+        match self {
+            Self::S7 => "Standard Tournament Season 7",
+            Self::MultiworldS3 => "3rd Multiworld Tournament",
+            Self::MultiworldS4 => "4th Multiworld Tournament",
+            Self::MultiworldS5 => "5th Multiworld Tournament",
+            Self::TournoiFrancoS3 => "Tournoi Francophone Saison 3",
+            Self::TournoiFrancoS4 => "Tournoi Francophone Saison 4",
+            Self::TournoiFrancoS5 => "Tournoi Francophone Saison 5",
+        }
+    }
+
+    pub(crate) fn spec(&self) -> &'static Spec {
+        match self {
+            Self::S7 => s::s7_draft_spec(),
+            Self::MultiworldS3 => mw::s3_draft_spec(),
+            Self::MultiworldS4 => mw::s4_draft_spec(),
+            Self::MultiworldS5 => mw::s5_draft_spec(),
+            Self::TournoiFrancoS3 => fr::s3_draft_spec(),
+            Self::TournoiFrancoS4 => fr::s4_draft_spec(),
+            Self::TournoiFrancoS5 => fr::s5_draft_spec(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum HighSeed {
+    /// The team with the better qualifier placement is the high seed.
+    QualifierRank,
+    #[default]
+    CoinFlip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Setting {
+    pub(crate) name: String,
+    pub(crate) display: String,
+    /// Name of the page this setting is listed on in the Discord draft UI. Settings without a page are listed under “All Settings”.
+    #[serde(default)]
+    pub(crate) page: Option<String>,
+    pub(crate) default: Choice,
+    pub(crate) other: Vec<Choice>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Choice {
+    pub(crate) name: String,
+    pub(crate) display: String,
+    /// Seed settings which apply if this option is chosen.
+    #[serde(default)]
+    pub(crate) settings: seed::Settings,
+    /// Maps setting names to option names. This option is only offered if all of these match (unpicked settings count as their default option).
+    ///
+    /// At the start of the draft, a setting named `hard_settings_ok` is picked as `ok` if both teams allowed hard settings when signing up, or as `no` otherwise.
+    #[serde(default)]
+    pub(crate) requires: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Step {
+    pub(crate) team: StepTeam,
+    pub(crate) kind: StepKind,
+    #[serde(default)]
+    pub(crate) skippable: bool,
+    /// If present, only the named settings can be banned or picked in this step.
+    #[serde(default)]
+    pub(crate) pool: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum StepTeam {
+    /// The team which chose to go first.
+    First,
+    /// The team which chose to go second.
+    Second,
+    HighSeed,
+    LowSeed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum StepKind {
+    /// Lock a setting to its default option.
+    Ban,
+    /// Set a setting to any of its options.
+    Pick,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Rule {
+    /// Maps setting names to option names. The rule applies if all of these match (unpicked settings count as their default option).
+    pub(crate) when: BTreeMap<String, String>,
+    /// Settings which must not have been banned or picked for the rule to apply. Unlike `when`, this tells a ban or a pick of the default option apart from no pick.
+    #[serde(default)]
+    pub(crate) unpicked: Vec<String>,
+    pub(crate) settings: seed::Settings,
+}
+
+/// A seed setting whose value is computed by adding up the contributions of the picked options. The result is rounded to the nearest integer before `min` and `max` are applied.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Derived {
+    /// The seed setting which is set to the computed value.
+    pub(crate) setting: String,
+    /// The seed setting whose value the computation starts from, after the base settings, the picked options, the rules, and any earlier derived settings have been applied. Defaults to `setting`.
+    #[serde(default)]
+    pub(crate) from: Option<String>,
+    /// Each of these whose conditions match is added to the value.
+    #[serde(default)]
+    pub(crate) add: Vec<Term>,
+    /// After adding, the value is divided by the first of these whose conditions match.
+    #[serde(default)]
+    pub(crate) divide_by: Vec<Term>,
+    #[serde(default)]
+    pub(crate) min: Option<i64>,
+    #[serde(default)]
+    pub(crate) max: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Term {
+    /// Maps setting names to option names, matched like [`Rule::when`].
+    #[serde(default)]
+    pub(crate) when: BTreeMap<String, String>,
+    pub(crate) value: serde_json::Number,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ValidationError {
+    #[error("the setting {0} is defined multiple times")]
+    DuplicateSetting(String),
+    #[error("the setting {setting} has multiple options named {option}")]
+    DuplicateOption {
+        setting: String,
+        option: String,
+    },
+    #[error("a step or rule refers to the setting {0} which is not defined")]
+    UnknownSetting(String),
+    #[error("a rule refers to the option {option} which is not defined for the setting {setting}")]
+    UnknownOption {
+        setting: String,
+        option: String,
+    },
+    #[error("the setting {0} has no options other than its default")]
+    NoOtherOptions(String),
+    #[error("step {0} can't be skipped but all settings it allows may already have been banned or picked")]
+    UnsatisfiableStep(usize),
+    #[error("the derived setting {0} doesn't start from a number in the base settings")]
+    NonNumericDerivedSetting(String),
+    #[error("the derived setting {0} is divided by zero")]
+    DivisionByZero(String),
+}
+
+impl Spec {
+    pub(crate) fn validate(&self) -> Result<(), ValidationError> {
+        let mut names = HashSet::new();
+        for setting in &self.settings {
+            if !names.insert(&*setting.name) { return Err(ValidationError::DuplicateSetting(setting.name.clone())) }
+            let mut options = HashSet::new();
+            for option in setting.options() {
+                if !options.insert(&*option.name) {
+                    return Err(ValidationError::DuplicateOption { setting: setting.name.clone(), option: option.name.clone() })
+                }
+            }
+        }
+        for setting in &self.settings {
+            if setting.other.is_empty() { return Err(ValidationError::NoOtherOptions(setting.name.clone())) }
+            for option in setting.options() {
+                self.validate_condition(&option.requires)?;
+            }
+        }
+        for name in self.exclusive.iter().flatten() {
+            if !names.contains(&**name) { return Err(ValidationError::UnknownSetting(name.clone())) }
+        }
+        for (idx, step) in self.steps.iter().enumerate() {
+            for name in step.pool.iter().flatten() {
+                if !names.contains(&**name) { return Err(ValidationError::UnknownSetting(name.clone())) }
+            }
+            if !step.skippable {
+                // each earlier step which can use a setting from this step's pool might use one up
+                let pool = self.settings.iter().filter(|setting| step.allows(setting) && self.always_offered(step, setting)).collect_vec();
+                let earlier = self.steps[..idx].iter().filter(|earlier| pool.iter().any(|setting| earlier.allows(setting))).count();
+                if pool.len() <= earlier { return Err(ValidationError::UnsatisfiableStep(idx + 1)) }
+            }
+        }
+        for rule in &self.rules {
+            self.validate_condition(&rule.when)?;
+            for name in &rule.unpicked {
+                if !names.contains(&**name) { return Err(ValidationError::UnknownSetting(name.clone())) }
+            }
+        }
+        for derived in &self.derived {
+            let from = derived.from.as_ref().unwrap_or(&derived.setting);
+            if !self.base.get(from).is_some_and(|value| value.is_number()) {
+                return Err(ValidationError::NonNumericDerivedSetting(derived.setting.clone()))
+            }
+            for term in &derived.add {
+                self.validate_condition(&term.when)?;
+            }
+            for term in &derived.divide_by {
+                self.validate_condition(&term.when)?;
+                if term.value.as_f64().is_none_or(|value| value == 0.0) { return Err(ValidationError::DivisionByZero(derived.setting.clone())) }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_condition(&self, condition: &BTreeMap<String, String>) -> Result<(), ValidationError> {
+        for (name, value) in condition {
+            let setting = self.setting(name).ok_or_else(|| ValidationError::UnknownSetting(name.clone()))?;
+            if !setting.options().any(|option| option.name == *value) {
+                return Err(ValidationError::UnknownOption { setting: name.clone(), option: value.clone() })
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the given setting can be used in the given step regardless of earlier picks, i.e. it's not exclusive with other settings for bans and has an option without requirements.
+    fn always_offered(&self, step: &Step, setting: &Setting) -> bool {
+        setting.other.iter().any(|option| option.requires.is_empty())
+            && (step.kind == StepKind::Pick || !self.exclusive.iter().any(|group| group.contains(&setting.name)))
+    }
+
+    /// Returns a reference to an equal spec which lives for the remainder of the program.
+    ///
+    /// If the event's spec hasn't changed since it was last interned, the same reference is returned. Otherwise, the new spec replaces the old one for future calls.
+    pub(crate) fn intern(self, series: Series, event: &str) -> &'static Self {
+        let mut interned = INTERNED.lock().expect("draft spec interner poisoned");
+        match interned.entry((series, event.to_owned())) {
+            hash_map::Entry::Occupied(mut entry) => if **entry.get() == self {
+                *entry.get()
+            } else {
+                let spec = Box::leak(Box::new(self));
+                entry.insert(spec);
+                spec
+            },
+            hash_map::Entry::Vacant(entry) => *entry.insert(Box::leak(Box::new(self))),
+        }
+    }
+
+    pub(crate) fn setting(&self, name: &str) -> Option<&Setting> {
+        self.settings.iter().find(|setting| setting.name == name)
+    }
+
+    /// Settings which can be banned or picked in at least one step. Others, such as `hard_settings_ok`, are only set outside of the draft steps.
+    fn drafted(&self) -> impl Iterator<Item = &Setting> {
+        self.settings.iter().filter(move |setting| self.steps.iter().any(|step| step.allows(setting)))
+    }
+
+    /// The number of settings from this spec which have been banned or picked.
+    pub(crate) fn pick_count(&self, picks: &Picks) -> usize {
+        self.drafted().filter(|setting| picks.contains_key(&*setting.name)).count()
+    }
+
+    /// Whether the given conditions, mapping setting names to option names, match the picks. Unpicked settings count as their default option.
+    fn matches(&self, condition: &BTreeMap<String, String>, picks: &Picks) -> bool {
+        condition.iter().all(|(name, value)| self.setting(name).is_some_and(|setting| setting.chosen(picks).name == *value))
+    }
+
+    /// Whether another setting from one of the given setting's exclusive groups has been picked with an option other than its default.
+    fn excluded(&self, setting: &Setting, picks: &Picks) -> bool {
+        self.exclusive.iter()
+            .filter(|group| group.contains(&setting.name))
+            .flatten()
+            .filter(|name| **name != setting.name)
+            .filter_map(|name| self.setting(name))
+            .any(|other| other.chosen(picks).name != other.default.name)
+    }
+
+    /// The non-default options of the given setting whose requirements are met.
+    fn offered<'a>(&'a self, setting: &'a Setting, picks: &'a Picks) -> impl Iterator<Item = &'a Choice> {
+        setting.other.iter().filter(move |option| self.matches(&option.requires, picks))
+    }
+
+    fn available(&'static self, step: &'static Step, picks: &Picks) -> impl Iterator<Item = &'static Setting> {
+        self.settings.iter()
+            .filter(move |setting| !picks.contains_key(&*setting.name))
+            .filter(move |setting| step.allows(setting))
+            .filter(move |setting| self.offered(setting, picks).next().is_some())
+    }
+
+    fn paged<T>(settings: impl Iterator<Item = (&'static Setting, T)>) -> Vec<(&'static str, Vec<T>)> {
+        let mut pages = Vec::<(&'static str, Vec<T>)>::default();
+        for (setting, item) in settings {
+            let page_name = setting.page.as_deref().unwrap_or("All Settings");
+            if let Some((_, page)) = pages.iter_mut().find(|(name, _)| *name == page_name) {
+                page.push(item);
+            } else {
+                pages.push((page_name, vec![item]));
+            }
+        }
+        pages
+    }
+
+    pub(crate) fn ban_settings(&'static self, step: &'static Step, picks: &Picks) -> BanSettings {
+        BanSettings(Self::paged(self.available(step, picks).filter(|setting| !self.excluded(setting, picks)).map(|setting| (setting, BanSetting {
+            name: &setting.name,
+            display: &setting.display,
+            default: &setting.default.name,
+            default_display: &setting.default.display,
+            description: Cow::Owned(setting.description()),
+        }))))
+    }
+
+    pub(crate) fn draft_settings(&'static self, step: &'static Step, picks: &Picks) -> DraftSettings {
+        DraftSettings(Self::paged(self.available(step, picks).map(|setting| (setting, DraftSetting {
+            name: &setting.name,
+            display: &setting.display,
+            options: iter::once(&setting.default).chain(self.offered(setting, picks)).map(|option| DraftSettingChoice { name: &option.name, display: Cow::Borrowed(&option.display) }).collect(),
+            description: Cow::Owned(setting.description()),
+        }))))
+    }
+
+    /// Lists the non-default options which have been picked, for announcing the outcome of the draft.
+    pub(crate) fn display_picks(&self, picks: &Picks) -> String {
+        English.join_str_opt(
+            self.drafted()
+                .filter_map(|setting| picks.get(&*setting.name).and_then(|pick| setting.other.iter().find(|option| option.name == **pick)))
+                .map(|option| &option.display),
+        ).unwrap_or_else(|| format!("base settings"))
+    }
+
+    pub(crate) fn resolve(&self, picks: &Picks) -> seed::Settings {
+        let mut settings = self.base.clone();
+        for setting in &self.settings {
+            settings.extend(setting.chosen(picks).settings.clone());
+        }
+        for rule in &self.rules {
+            if self.matches(&rule.when, picks) && !rule.unpicked.iter().any(|name| picks.contains_key(&**name)) {
+                settings.extend(rule.settings.clone());
+            }
+        }
+        for derived in &self.derived {
+            let from = derived.from.as_ref().unwrap_or(&derived.setting);
+            let mut value = settings.get(from).and_then(|value| value.as_f64()).unwrap_or_default();
+            for term in &derived.add {
+                if self.matches(&term.when, picks) {
+                    value += term.value.as_f64().unwrap_or_default();
+                }
+            }
+            if let Some(term) = derived.divide_by.iter().find(|term| self.matches(&term.when, picks)) {
+                value /= term.value.as_f64().unwrap_or(1.0);
+            }
+            let mut value = value.round() as i64;
+            if let Some(min) = derived.min {
+                value = value.max(min);
+            }
+            if let Some(max) = derived.max {
+                value = value.min(max);
+            }
+            settings.insert(derived.setting.clone(), json!(value));
+        }
+        settings
+    }
+}
+
+impl Setting {
+    fn options(&self) -> impl Iterator<Item = &Choice> {
+        iter::once(&self.default).chain(&self.other)
+    }
+
+    fn chosen(&self, picks: &Picks) -> &Choice {
+        picks.get(&*self.name)
+            .and_then(|pick| self.other.iter().find(|option| option.name == **pick))
+            .unwrap_or(&self.default)
+    }
+
+    pub(crate) fn description(&self) -> String {
+        if let Some(ref description) = self.description {
+            description.clone()
+        } else {
+            format!("{}: {}", self.name, English.join_str_opt_with("or",
+                iter::once(format!("{} (default)", self.default.name))
+                    .chain(self.other.iter().map(|option| option.name.clone())),
+            ).expect("setting has a default option"))
+        }
+    }
+}
+
+impl Step {
+    fn allows(&self, setting: &Setting) -> bool {
+        self.pool.as_ref().is_none_or(|pool| pool.contains(&setting.name))
+    }
+
+    pub(crate) fn team(&self, went_first: bool) -> Team {
+        match (self.team, went_first) {
+            (StepTeam::HighSeed, _) | (StepTeam::First, true) | (StepTeam::Second, false) => Team::HighSeed,
+            (StepTeam::LowSeed, _) | (StepTeam::First, false) | (StepTeam::Second, true) => Team::LowSeed,
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use {
+    super::*,
+    crate::testing::{
+        self,
+        TestDb,
+    },
+};
+
+fn new_draft() -> Draft {
+    Draft {
+        high_seed: Id::from(1_u64),
+        went_first: None,
+        skipped_bans: 0,
+        settings: Picks::default(),
+    }
+}
+
+fn web() -> MessageContext<'static> {
+    MessageContext::Web {
+        high_seed_name: "Team High",
+        low_seed_name: "Team Low",
+        reply_to: "someone",
+    }
+}
+
+fn race_room() -> MessageContext<'static> {
+    MessageContext::RaceTime {
+        high_seed_name: "Team High",
+        low_seed_name: "Team Low",
+        reply_to: "someone",
+    }
+}
+
+fn ban(setting: &str) -> Action {
+    Action::Ban { setting: setting.to_owned() }
+}
+
+fn pick(setting: &str, value: &str) -> Action {
+    Action::Pick { setting: setting.to_owned(), value: value.to_owned() }
+}
+
+async fn assert_active(draft: &Draft, kind: Kind, expected: Option<Team>) -> Result<(), Error> {
+    let active = draft.active_team(kind, None).await?;
+    assert_eq!(active.map(|team| team.to_string()), expected.map(|team| team.to_string()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn s7_step_order() -> Result<(), Error> {
+    let kind = Kind::S7;
+    let mut draft = new_draft();
+    assert!(matches!(draft.next_step(kind, None, &mut MessageContext::None).await?.kind, StepKind::GoFirst));
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, Action::GoFirst(false)).await?.expect("high seed chooses to go second");
+    // bans alternate starting with the team that went first
+    assert_active(&draft, kind, Some(Team::LowSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, ban("trials")).await?.expect("ban a major setting");
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, Action::Skip).await?.expect("bans are skippable");
+    assert_eq!(draft.skipped_bans, 1);
+    // picks go first, second, second, first
+    assert_active(&draft, kind, Some(Team::LowSeed)).await?;
+    assert!(draft.apply(kind, None, &mut MessageContext::None, Action::Skip).await?.is_err(), "picks can't be skipped");
+    assert!(draft.apply(kind, None, &mut MessageContext::None, pick("camc", "off")).await?.is_err(), "first two picks are major settings");
+    assert!(draft.apply(kind, None, &mut MessageContext::None, pick("trials", "on")).await?.is_err(), "banned settings can't be picked");
+    draft.apply(kind, None, &mut MessageContext::None, pick("bridge", "open")).await?.expect("pick a major setting");
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, pick("deku", "open")).await?.expect("pick a major setting");
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    assert!(draft.apply(kind, None, &mut MessageContext::None, pick("shops", "on")).await?.is_err(), "last two picks are minor settings");
+    draft.apply(kind, None, &mut MessageContext::None, pick("camc", "off")).await?.expect("pick a minor setting");
+    assert_active(&draft, kind, Some(Team::LowSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, pick("cuccos", "1")).await?.expect("pick a minor setting");
+    assert_active(&draft, kind, None).await?;
+    let StepKind::Done(settings) = draft.next_step(kind, None, &mut MessageContext::None).await?.kind else { panic!("draft not done after 6 steps") };
+    assert_eq!(settings, s::resolve_s7_draft_settings(&draft.settings));
+    assert_eq!(settings["trials"], json!(0));
+    assert_eq!(settings["bridge"], json!("open"));
+    assert_eq!(settings["open_forest"], json!("open"));
+    assert_eq!(settings["correct_chest_appearances"], json!("off"));
+    assert_eq!(settings["chicken_count"], json!(1));
+    assert!(draft.apply(kind, None, &mut MessageContext::None, Action::Skip).await?.is_err(), "draft is already completed");
+    Ok(())
+}
+
+#[tokio::test]
+async fn s7_messages() -> Result<(), Error> {
+    let kind = Kind::S7;
+    let mut draft = new_draft();
+    assert_eq!(draft.next_step(kind, None, &mut web()).await?.message, "Team High, you have the higher seed. Choose whether you want to go first or second.");
+    assert_eq!(draft.apply(kind, None, &mut web(), pick("bridge", "open")).await?, Err(format!("Sorry someone, first pick hasn't been chosen yet. Please choose whether to go first or second.")));
+    draft.apply(kind, None, &mut web(), Action::GoFirst(true)).await?.expect("high seed chooses to go first");
+    assert_eq!(draft.next_step(kind, None, &mut web()).await?.message, "Team High, lock a setting to its default, or skip if you don't want to ban anything.");
+    assert_eq!(draft.next_step(kind, None, &mut race_room()).await?.message, "Team High, lock a setting to its default using “!ban <setting>”, or use “!skip” if you don't want to ban anything. Use “!settings” for a list of available settings.");
+    assert!(draft.apply(kind, None, &mut web(), ban("not_a_setting")).await?.is_err_and(|msg| msg.starts_with("Sorry someone, I don't recognize that setting.")));
+    assert_eq!(draft.apply(kind, None, &mut web(), Action::GoFirst(false)).await?, Err(format!("Sorry someone, first pick has already been chosen.")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn s7_timeout_skips_bans() -> Result<(), Error> {
+    let kind = Kind::S7;
+    let mut draft = new_draft();
+    draft.went_first = Some(true);
+    assert!(matches!(draft.timeout_action(kind, None).await?, Some(Action::Skip)));
+    draft.skipped_bans = 2;
+    assert!(matches!(draft.timeout_action(kind, None).await?, Some(Action::Pick { .. })), "picks can't be skipped");
+    Ok(())
+}
+
+fn custom_spec() -> &'static spec::Spec {
+    let spec = serde_json::from_value::<spec::Spec>(json!({
+        "highSeed": "qualifierRank",
+        "settings": [
+            {
+                "name": "bridge",
+                "display": "Rainbow Bridge",
+                "default": {"name": "meds", "display": "6 medallions", "settings": {"bridge": "medallions"}},
+                "other": [{"name": "open", "display": "open bridge", "settings": {"bridge": "open"}}],
+            },
+            {
+                "name": "keys",
+                "display": "Keys",
+                "default": {"name": "dungeon", "display": "own dungeon keys"},
+                "other": [{"name": "keysy", "display": "keysy", "settings": {"shuffle_smallkeys": "remove"}}],
+            },
+            {
+                "name": "trials",
+                "display": "Trials",
+                "default": {"name": "0", "display": "no trials"},
+                "other": [{"name": "3", "display": "3 trials", "settings": {"trials": 3}}],
+            },
+        ],
+        "steps": [
+            {"team": "first", "kind": "ban", "skippable": true},
+            {"team": "second", "kind": "pick", "pool": ["keys", "trials"]},
+            {"team": "first", "kind": "pick"},
+        ],
+        "base": {"trials": 0, "user_message": "Custom Draft"},
+        "rules": [
+            {"when": {"keys": "keysy", "bridge": "meds"}, "settings": {"shuffle_bosskeys": "remove"}},
+        ],
+    })).expect("invalid draft spec");
+    spec.validate().expect("invalid draft spec");
+    spec.intern(Series::Standard, "custom-draft-test")
+}
+
+#[tokio::test]
+async fn custom_step_order() -> Result<(), Error> {
+    let kind = Kind::Custom(custom_spec());
+    let mut draft = new_draft();
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, Action::GoFirst(false)).await?.expect("high seed chooses to go second");
+    assert_active(&draft, kind, Some(Team::LowSeed)).await?;
+    draft.apply(kind, None, &mut MessageContext::None, ban("bridge")).await?.expect("ban a setting");
+    assert_active(&draft, kind, Some(Team::HighSeed)).await?;
+    assert!(draft.apply(kind, None, &mut MessageContext::None, Action::Skip).await?.is_err(), "step isn't skippable");
+    assert!(draft.apply(kind, None, &mut MessageContext::None, pick("bridge", "open")).await?.is_err(), "banned settings can't be picked");
+    assert!(draft.apply(kind, None, &mut MessageContext::None, pick("keys", "vanilla")).await?.is_err(), "not an option of this setting");
+    draft.apply(kind, None, &mut MessageContext::None, pick("keys", "keysy")).await?.expect("pick a setting from the pool");
+    assert_active(&draft, kind, Some(Team::LowSeed)).await?;
+    assert!(matches!(draft.next_step(kind, None, &mut MessageContext::None).await?.kind, StepKind::Pick { skippable: false, .. }));
+    draft.apply(kind, None, &mut MessageContext::None, pick("trials", "0")).await?.expect("pick the default option");
+    assert_active(&draft, kind, None).await?;
+    let StepKind::Done(settings) = draft.next_step(kind, None, &mut MessageContext::None).await?.kind else { panic!("draft not done after all steps") };
+    assert_eq!(settings, collect![as seed::Settings:
+        format!("user_message") => json!("Custom Draft"),
+        format!("trials") => json!(0),
+        format!("bridge") => json!("medallions"),
+        format!("shuffle_smallkeys") => json!("remove"),
+        format!("shuffle_bosskeys") => json!("remove"),
+    ]);
+    assert_eq!(draft.next_step(kind, None, &mut web()).await?.message, "keysy");
+    Ok(())
+}
+
+#[test]
+fn spec_interning_is_per_event() {
+    let spec = custom_spec().clone().intern(Series::Standard, "interning-test");
+    assert!(std::ptr::eq(spec, spec.clone().intern(Series::Standard, "interning-test")), "unchanged spec is reused");
+    let mut edited = spec.clone();
+    edited.base.insert(format!("user_message"), json!("Edited Draft"));
+    let edited = edited.intern(Series::Standard, "interning-test");
+    assert!(!std::ptr::eq(spec, edited));
+    assert!(std::ptr::eq(edited, edited.clone().intern(Series::Standard, "interning-test")), "edit replaces the previous spec");
+    assert!(!std::ptr::eq(spec, spec.clone().intern(Series::Standard, "other-interning-test")), "specs are interned separately per event");
+}
+
+#[test]
+fn custom_spec_validation() {
+    let mut spec = custom_spec().clone();
+    spec.steps[1].pool = Some(vec![format!("not_a_setting")]);
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnknownSetting(setting)) if setting == "not_a_setting"));
+    let mut spec = custom_spec().clone();
+    spec.rules[0].when.insert(format!("keys"), format!("vanilla"));
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnknownOption { .. })));
+    let mut spec = custom_spec().clone();
+    spec.settings.push(spec.settings[0].clone());
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::DuplicateSetting(_))));
+    let mut spec = custom_spec().clone();
+    spec.settings[1].other.clear();
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::NoOtherOptions(setting)) if setting == "keys"));
+    let mut spec = custom_spec().clone();
+    spec.steps[1].pool = Some(Vec::default());
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnsatisfiableStep(2))));
+    let mut spec = custom_spec().clone();
+    spec.steps[1].pool = Some(vec![format!("keys")]);
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnsatisfiableStep(2))), "the ban in step 1 may use up the only setting");
+    spec.steps[1].skippable = true;
+    assert!(spec.validate().is_ok(), "skippable steps don't need an available setting");
+    let mut spec = custom_spec().clone();
+    spec.steps.push(spec.steps[2].clone());
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnsatisfiableStep(4))), "more steps than settings");
+    let mut spec = custom_spec().clone();
+    spec.settings[1].other[0].requires.insert(format!("bridge"), format!("rainbow"));
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnknownOption { .. })));
+    let mut spec = custom_spec().clone();
+    spec.steps[1].pool = Some(vec![format!("keys"), format!("trials")]);
+    spec.exclusive.push(vec![format!("keys"), format!("trials")]);
+    assert!(spec.validate().is_ok(), "exclusive settings can still be picked");
+    spec.steps[1].kind = spec::StepKind::Ban;
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::UnsatisfiableStep(2))), "exclusive settings may not be bannable");
+    let mut spec = custom_spec().clone();
+    spec.derived.push(serde_json::from_value(json!({"setting": "user_message", "add": [{"when": {"trials": "3"}, "value": 1}]})).expect("invalid derived setting"));
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::NonNumericDerivedSetting(_))));
+    let mut spec = custom_spec().clone();
+    spec.derived.push(serde_json::from_value(json!({"setting": "trials", "divideBy": [{"value": 0}]})).expect("invalid derived setting"));
+    assert!(matches!(spec.validate(), Err(spec::ValidationError::DivisionByZero(_))));
+    assert!(std::ptr::eq(custom_spec(), custom_spec()), "equal specs are only interned once");
+}
+
+#[tokio::test]
+async fn complete_randomly_terminates() -> Result<(), Error> {
+    for kind in [
+        Kind::S7,
+        Kind::MultiworldS3,
+        Kind::MultiworldS4,
+        Kind::MultiworldS5,
+        Kind::TournoiFrancoS3,
+        Kind::TournoiFrancoS4,
+        Kind::TournoiFrancoS5,
+        Kind::Custom(custom_spec()),
+    ] {
+        for _ in 0..20 {
+            // panics if the draft gets stuck on a step with no legal actions
+            new_draft().complete_randomly(kind).await?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn next_game_reads_team_preferences() -> Result<(), cal::Error> {
+    let db = TestDb::new().await;
+    let mut transaction = db.pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::TournoiFrancophone, "t", TeamConfig::CoOp).await?;
+    let loser = testing::team(&mut transaction, &event, Some("Loser"), None, &[]).await?;
+    let winner = testing::team(&mut transaction, &event, Some("Winner"), None, &[]).await?;
+    sqlx::query("UPDATE teams SET hard_settings_ok = TRUE, mq_ok = (id = $1) WHERE id = $1 OR id = $2").bind(loser.id).bind(winner.id).execute(&mut *transaction).await?;
+    let draft = Draft::for_next_game(&mut transaction, Kind::TournoiFrancoS5, loser.id, winner.id).await?;
+    assert_eq!(draft.high_seed, loser.id, "loser of the previous game chooses whether to go first");
+    assert_eq!(draft.settings.get("hard_settings_ok").map(|value| &**value), Some("ok"));
+    assert_eq!(draft.settings.get("mq_ok").map(|value| &**value), Some("no"), "both teams must opt into MQ");
+    let draft = Draft::for_next_game(&mut transaction, Kind::S7, loser.id, winner.id).await?;
+    assert!(draft.settings.is_empty());
+    transaction.rollback().await?;
+    db.cleanup().await;
+    Ok(())
+}
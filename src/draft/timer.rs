@@ -0,0 +1,131 @@
+//! Optional per-step time limits for settings drafts.
+//!
+//! For drafts on Discord or the website, deadlines are stored in the database so they survive restarts. Drafts in race rooms are timed by the race handler instead, see [`race_room_time_limit`].
+
+use {
+    sqlx::types::Json,
+    crate::prelude::*,
+};
+
+/// The event's draft step time limit, if any. Limits too long to represent as a [`TimeDelta`] are rejected when configuring the event, so they're treated as no limit here.
+fn time_limit(event: &event::Data<'_>) -> Option<TimeDelta> {
+    event.draft_step_time_limit.and_then(|time_limit| TimeDelta::from_std(time_limit).ok())
+}
+
+/// The time limit for each step of a settings draft continued in the given event's race rooms. Since race rooms hold their draft state in memory, the timer restarts if the room is reopened.
+pub(crate) fn race_room_time_limit(event: &event::Data<'_>) -> Option<Duration> {
+    event.draft_step_time_limit.filter(|_| time_limit(event).is_some())
+}
+
+/// Starts the timer for the current step of the race's draft, replacing any previous one. Does nothing if the event has no draft step time limit.
+pub(crate) async fn reset(transaction: &mut Transaction<'_, Postgres>, event: &event::Data<'_>, race: Id<Races>) -> sqlx::Result<()> {
+    if let Some(time_limit) = time_limit(event) {
+        let deadline = Utc::now() + time_limit;
+        sqlx::query!("INSERT INTO draft_timers (race, deadline) VALUES ($1, $2) ON CONFLICT (race) DO UPDATE SET deadline = EXCLUDED.deadline, reminded = FALSE", race as _, deadline).execute(&mut **transaction).await?;
+    }
+    Ok(())
+}
+
+async fn check(db_pool: &PgPool, http_client: &reqwest::Client, discord_ctx: &DiscordCtx, race_id: Id<Races>, deadline: DateTime<Utc>, reminded: bool) -> Result<(), event::Error> {
+    let mut transaction = db_pool.begin().await?;
+    let race = Race::from_id(&mut transaction, http_client, race_id).await?;
+    let event = race.event(&mut transaction).await?;
+    let (Some(draft_kind), Some(mut draft), Some(time_limit)) = (event.draft_kind(), race.draft.clone(), time_limit(&event)) else {
+        sqlx::query!("DELETE FROM draft_timers WHERE race = $1", race.id as _).execute(&mut *transaction).await?;
+        transaction.commit().await?;
+        return Ok(())
+    };
+    let active_team = if race.has_any_room() {
+        None // the draft continues in the race room
+    } else {
+        draft.active_team(draft_kind, race.game).await?
+    };
+    let Some(active_team) = active_team.and_then(|active_team| race.teams().find(|team| (team.id == draft.high_seed) == matches!(active_team, draft::Team::HighSeed)).cloned()) else {
+        sqlx::query!("DELETE FROM draft_timers WHERE race = $1", race.id as _).execute(&mut *transaction).await?;
+        transaction.commit().await?;
+        return Ok(())
+    };
+    let command_ids = if let Some(guild_id) = event.discord_guild {
+        discord_ctx.data.read().await.get::<CommandIds>().and_then(|command_ids| command_ids.get(&guild_id).copied().flatten()).map(|command_ids| (guild_id, command_ids))
+    } else {
+        None
+    };
+    let now = Utc::now();
+    if deadline <= now {
+        let Some(action) = draft.timeout_action(draft_kind, race.game).await? else { return Err(event::Error::DraftTimeoutNoAction(race.id)) };
+        let state_before = draft.clone();
+        let message = if let (Some((guild_id, command_ids)), Some(scheduling_thread)) = (command_ids, race.scheduling_thread) {
+            let mut msg_ctx = draft::MessageContext::Discord {
+                teams: race.teams().cloned().collect(),
+                team: active_team.clone(),
+                transaction, guild_id, command_ids,
+            };
+            let response = draft.apply(draft_kind, race.game, &mut msg_ctx, action.clone()).await?.map_err(|message| event::Error::DraftTimeoutIllegal { race: race.id, message })?;
+            let mut next_step_ctx = draft::MessageContext::Discord {
+                teams: race.teams().cloned().collect(),
+                team: Team::dummy(),
+                transaction: msg_ctx.into_transaction(), guild_id, command_ids,
+            };
+            let next_step = draft.next_step(draft_kind, race.game, &mut next_step_ctx).await?.message;
+            transaction = next_step_ctx.into_transaction();
+            let mut message = MessageBuilder::default();
+            message.push("Time's up for ");
+            message.mention_team(&mut transaction, Some(guild_id), &active_team).await?;
+            message.push_line(".");
+            message.push_line(response);
+            message.push(next_step);
+            Some((scheduling_thread, message.build()))
+        } else {
+            draft.apply(draft_kind, race.game, &mut draft::MessageContext::None, action.clone()).await?.map_err(|message| event::Error::DraftTimeoutIllegal { race: race.id, message })?;
+            None
+        };
+        draft::history::record(&mut transaction, race.id, None, Some(active_team.id), draft::history::Frontend::Timer, &action, &state_before).await?;
+        sqlx::query!("UPDATE races SET draft_state = $1 WHERE id = $2", Json(&draft) as _, race.id as _).execute(&mut *transaction).await?;
+        reset(&mut transaction, &event, race.id).await?;
+        transaction.commit().await?;
+        if let Some((scheduling_thread, message)) = message {
+            scheduling_thread.say(discord_ctx, message).await?;
+        }
+    } else if !reminded && deadline - now <= time_limit / 4 {
+        sqlx::query!("UPDATE draft_timers SET reminded = TRUE WHERE race = $1", race.id as _).execute(&mut *transaction).await?;
+        if let (Some((guild_id, command_ids)), Some(scheduling_thread)) = (command_ids, race.scheduling_thread) {
+            let mut msg_ctx = draft::MessageContext::Discord {
+                teams: race.teams().cloned().collect(),
+                team: active_team.clone(),
+                transaction, guild_id, command_ids,
+            };
+            let next_step = draft.next_step(draft_kind, race.game, &mut msg_ctx).await?.message;
+            transaction = msg_ctx.into_transaction();
+            let mut message = MessageBuilder::default();
+            message.push("Reminder: ");
+            message.mention_team(&mut transaction, Some(guild_id), &active_team).await?;
+            message.push(" has ");
+            message.push(English.format_duration((deadline - now).to_std().unwrap_or_default(), true));
+            message.push_line(" left for this step of the settings draft. If no choice is made in time, the step will be skipped if possible, or otherwise decided randomly.");
+            message.push(next_step);
+            transaction.commit().await?;
+            scheduling_thread.say(discord_ctx, message.build()).await?;
+        } else {
+            transaction.commit().await?;
+        }
+    } else {
+        transaction.commit().await?;
+    }
+    Ok(())
+}
+
+/// When the draft timer of the given race needs to be checked next, i.e. when the reminder is due or otherwise when time runs out. `None` if the race's draft isn't timed.
+pub(crate) async fn next_check(transaction: &mut Transaction<'_, Postgres>, event: &event::Data<'_>, race: Id<Races>) -> sqlx::Result<Option<DateTime<Utc>>> {
+    Ok(sqlx::query!("SELECT deadline, reminded FROM draft_timers WHERE race = $1", race as _).fetch_optional(&mut **transaction).await?.map(|timer| match time_limit(event) {
+        Some(time_limit) if !timer.reminded => timer.deadline - time_limit / 4,
+        _ => timer.deadline,
+    }))
+}
+
+/// Resolves the current step of the given race's draft if it has expired, or sends a reminder if time is running out. Run as a [scheduled job](crate::scheduler) at the time returned by [`next_check`].
+pub(crate) async fn check_race(db_pool: &PgPool, http_client: &reqwest::Client, discord_ctx: &DiscordCtx, race: Id<Races>) -> Result<(), event::Error> {
+    if let Some(timer) = sqlx::query!("SELECT deadline, reminded FROM draft_timers WHERE race = $1", race as _).fetch_optional(db_pool).await? {
+        check(db_pool, http_client, discord_ctx, race, timer.deadline, timer.reminded).await?;
+    }
+    Ok(())
+}
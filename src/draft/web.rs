@@ -0,0 +1,458 @@
+use {
+    sqlx::types::Json,
+    crate::{
+        event::Tab,
+        prelude::*,
+    },
+};
+
+async fn seed_names(transaction: &mut Transaction<'_, Postgres>, race: &Race, draft: &Draft) -> sqlx::Result<[String; 2]> {
+    let mut high_seed_name = format!("Team A");
+    let mut low_seed_name = format!("Team B");
+    for team in race.teams() {
+        if let Some(name) = team.name(&mut *transaction).await? {
+            if team.id == draft.high_seed {
+                high_seed_name = name.into_owned();
+            } else {
+                low_seed_name = name.into_owned();
+            }
+        }
+    }
+    Ok([high_seed_name, low_seed_name])
+}
+
+async fn my_team(transaction: &mut Transaction<'_, Postgres>, race: &Race, me: Option<&User>) -> sqlx::Result<Option<Team>> {
+    if let Some(me) = me {
+        for team in race.teams() {
+            if team.members(&mut *transaction).await?.contains(me) {
+                return Ok(Some(team.clone()))
+            }
+        }
+    }
+    Ok(None)
+}
+
+async fn draft_page(mut transaction: Transaction<'_, Postgres>, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: event::Data<'_>, race: Race, ctx: Context<'_>) -> Result<RawHtml<String>, event::Error> {
+    let header = event.header(&mut transaction, me.as_ref(), Tab::Races, true).await?;
+    let content = if let (Some(draft_kind), Some(draft)) = (event.draft_kind(), race.draft.as_ref()) {
+        let [high_seed_name, low_seed_name] = seed_names(&mut transaction, &race, draft).await?;
+        let step = draft.next_step(draft_kind, race.game, &mut draft::MessageContext::Web {
+            high_seed_name: &high_seed_name,
+            low_seed_name: &low_seed_name,
+            reply_to: me.as_ref().map_or("friend", User::display_name),
+        }).await?;
+        let my_team = my_team(&mut transaction, &race, me.as_ref()).await?;
+        let can_act = if let Some(ref my_team) = my_team {
+            !race.has_any_room() && draft.is_active_team(draft_kind, race.game, my_team.id).await?
+        } else {
+            false
+        };
+        let active_team_name = draft.active_team(draft_kind, race.game).await?.map(|team| team.choose(&high_seed_name, &low_seed_name));
+        let done = matches!(step.kind, draft::StepKind::Done(_) | draft::StepKind::DoneRsl { .. });
+        let is_organizer = if let Some(ref me) = me { event.organizers(&mut transaction).await?.contains(me) } else { false };
+        let mut history = Vec::default();
+        for entry in draft::history::for_race(&mut transaction, race.id).await? {
+            let actor = if let Some(actor) = entry.actor { User::from_id(&mut *transaction, actor).await? } else { None };
+            let undone_by = if let Some(undone_by) = entry.undone_by { User::from_id(&mut *transaction, undone_by).await? } else { None };
+            history.push((entry, actor, undone_by));
+        }
+        let mut errors = ctx.errors().collect_vec();
+        let undo_form = (is_organizer && !race.has_any_room() && history.iter().any(|(entry, _, _)| entry.undone_at.is_none())).then(|| {
+            let undo_errors = if can_act { Vec::default() } else { mem::take(&mut errors) };
+            button_form(uri!(undo(event.series, &*event.event, race.id)), csrf, undo_errors, "Undo last action")
+        });
+        let (form, errors) = if can_act {
+            let uri = uri!(post(event.series, &*event.event, race.id));
+            (Some(match step.kind {
+                draft::StepKind::GoFirst => full_form(uri, csrf, html! {
+                    @if let draft::Kind::RslS7 = draft_kind {
+                        @if draft.settings.get("lite_ok").is_some_and(|lite_ok| lite_ok == "ok") {
+                            : form_field("lite", &mut errors, html! {
+                                input(type = "checkbox", id = "lite", name = "lite", checked? = ctx.field_value("lite").is_some_and(|value| value == "on"));
+                                label(for = "lite") : "Use RSL-Lite weights";
+                            });
+                        }
+                    }
+                    @if let draft::Kind::TournoiFrancoS3 | draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 = draft_kind {
+                        @if draft.settings.get("mq_ok").is_some_and(|mq_ok| mq_ok == "ok") {
+                            : form_field("mq", &mut errors, html! {
+                                label(for = "mq") : "Number of MQ dungeons:";
+                                input(type = "number", min = "0", max = "12", name = "mq", value = ctx.field_value("mq").unwrap_or("0"));
+                            });
+                        }
+                    }
+                    : form_field("action", &mut errors, html! {
+                        input(type = "radio", id = "action-first", name = "action", value = "first", checked? = ctx.field_value("action").is_none_or(|value| value == "first"));
+                        label(for = "action-first") : "Go first";
+                        input(type = "radio", id = "action-second", name = "action", value = "second", checked? = ctx.field_value("action") == Some("second"));
+                        label(for = "action-second") : "Go second";
+                    });
+                }, errors, "Confirm"),
+                draft::StepKind::Ban { available_settings, skippable, rsl, .. } => {
+                    let (_, skip_button) = button_form_ext(uri.clone(), csrf, Vec::default(), html! {
+                        input(type = "hidden", name = "action", value = "skip");
+                    }, "Skip");
+                    html! {
+                        : full_form(uri, csrf, html! {
+                            input(type = "hidden", name = "action", value = "ban");
+                            : form_field("setting", &mut errors, html! {
+                                label(for = "setting") : if rsl { "Block:" } else { "Ban:" };
+                                select(name = "setting") {
+                                    @for setting in available_settings.all() {
+                                        option(value = setting.name, selected? = ctx.field_value("setting") == Some(setting.name)) : format!("{} (locks {})", setting.display, setting.default_display);
+                                    }
+                                }
+                            });
+                        }, errors, if rsl { "Block" } else { "Ban" });
+                        @if skippable {
+                            : skip_button;
+                        }
+                    }
+                }
+                draft::StepKind::Pick { available_choices, skippable, rsl, .. } => {
+                    let (_, skip_button) = button_form_ext(uri.clone(), csrf, Vec::default(), html! {
+                        input(type = "hidden", name = "action", value = "skip");
+                    }, "Skip");
+                    // one form per setting so the setting and its option are submitted as separate fields
+                    let settings = available_choices.all().collect::<Vec<_>>();
+                    let submitted_setting = ctx.field_value("setting");
+                    let error_setting = settings.iter().find(|setting| Some(setting.name) == submitted_setting).or(settings.first()).map(|setting| setting.name);
+                    let mut pick_forms = Vec::default();
+                    for setting in settings {
+                        let mut setting_errors = if Some(setting.name) == error_setting { mem::take(&mut errors) } else { Vec::default() };
+                        let field_id = format!("value-{}", setting.name);
+                        pick_forms.push(full_form(uri.clone(), csrf, html! {
+                            input(type = "hidden", name = "action", value = "pick");
+                            input(type = "hidden", name = "setting", value = setting.name);
+                            : form_field("value", &mut setting_errors, html! {
+                                label(for = &field_id) : format!("{}:", setting.display);
+                                select(id = &field_id, name = "value") {
+                                    @for option in setting.options {
+                                        option(value = option.name, selected? = submitted_setting == Some(setting.name) && ctx.field_value("value") == Some(option.name)) : option.display;
+                                    }
+                                }
+                            });
+                        }, setting_errors, if rsl { "Ban" } else { "Pick" }));
+                    }
+                    html! {
+                        @for pick_form in pick_forms {
+                            : pick_form;
+                        }
+                        @if skippable {
+                            : skip_button;
+                        }
+                    }
+                }
+                draft::StepKind::BooleanChoice { .. } => {
+                    let (errors, yes_button) = button_form_ext(uri.clone(), csrf, errors, html! {
+                        input(type = "hidden", name = "action", value = "yes");
+                    }, "Yes");
+                    let (_, no_button) = button_form_ext(uri, csrf, Vec::default(), html! {
+                        input(type = "hidden", name = "action", value = "no");
+                    }, "No");
+                    html! {
+                        : errors;
+                        div(class = "button-row") {
+                            : yes_button;
+                            : no_button;
+                        }
+                    }
+                }
+                draft::StepKind::Done(_) | draft::StepKind::DoneRsl { .. } => html! {},
+            }), Vec::default())
+        } else {
+            (None, errors)
+        };
+        html! {
+            h2 : "Settings draft";
+            @for error in errors {
+                p(class = "error") : error;
+            }
+            p {
+                : "High seed: ";
+                : high_seed_name;
+            }
+            @if done {
+                p : step.message;
+            } else {
+                @if let Some(active_team_name) = active_team_name {
+                    p {
+                        : "Current turn: ";
+                        : active_team_name;
+                    }
+                }
+                @if let Some(form) = form {
+                    p : step.message;
+                    : form;
+                } else if race.has_any_room() {
+                    p : "The race room is already open. Please continue the settings draft there.";
+                } else if my_team.is_some() {
+                    p : "Waiting for your opponent.";
+                }
+            }
+            h3 : "Picks so far";
+            @if draft.went_first.is_none() && draft.settings.is_empty() {
+                p : "(none yet)";
+            } else {
+                ul {
+                    @if let Some(went_first) = draft.went_first {
+                        li {
+                            : high_seed_name;
+                            : if went_first { " chose to go first" } else { " chose to go second" };
+                        }
+                    }
+                    @for (name, value) in draft.settings.iter().sorted() {
+                        li {
+                            @if let Some(setting) = event.draft_spec.and_then(|spec| spec.setting(name)) {
+                                : setting.display;
+                                : ": ";
+                                : iter::once(&setting.default).chain(&setting.other).find(|option| option.name == **value).map_or(&**value, |option| &*option.display);
+                            } else {
+                                code : name;
+                                : ": ";
+                                code : value;
+                            }
+                        }
+                    }
+                }
+            }
+            h3 : "Draft history";
+            @if history.is_empty() {
+                p : "No draft actions have been recorded for this race.";
+            } else {
+                table {
+                    thead {
+                        tr {
+                            th : "Time";
+                            th : "Team";
+                            th : "Player";
+                            th : "Via";
+                            th : "Action";
+                        }
+                    }
+                    tbody {
+                        @for (entry, actor, undone_by) in history {
+                            tr {
+                                td : format_datetime(entry.applied_at, DateTimeFormat { long: false, running_text: false });
+                                td {
+                                    @if let Some(team) = entry.team {
+                                        : if team == draft.high_seed { &high_seed_name } else { &low_seed_name };
+                                    }
+                                }
+                                td {
+                                    @if let Some(actor) = actor {
+                                        : actor;
+                                    }
+                                }
+                                td : entry.frontend.to_string();
+                                td {
+                                    @if entry.undone_at.is_some() {
+                                        del : entry.describe(event.draft_spec);
+                                    } else {
+                                        : entry.describe(event.draft_spec);
+                                    }
+                                    @if let Some(undone_at) = entry.undone_at {
+                                        : " (undone ";
+                                        @if let Some(undone_by) = undone_by {
+                                            : "by ";
+                                            : undone_by;
+                                            : " ";
+                                        }
+                                        : format_datetime(undone_at, DateTimeFormat { long: false, running_text: true });
+                                        : ")";
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            @if let Some((undo_errors, undo_button)) = undo_form {
+                : undo_errors;
+                : undo_button;
+            }
+        }
+    } else {
+        html! {
+            article {
+                p : "This race doesn't have a settings draft.";
+            }
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests: event.chests().await?, ..PageStyle::default() }, &format!("Settings Draft — {}", event.display_name), html! {
+        : header;
+        : content;
+    }).await?)
+}
+
+#[rocket::get("/event/<series>/<event>/races/<id>/draft")]
+pub(crate) async fn get(pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, id: Id<Races>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = event::Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let race = Race::from_id(&mut transaction, http_client, id).await?;
+    if race.series != event.series || race.event != event.event {
+        return Ok(RedirectOrContent::Redirect(Redirect::permanent(uri!(get(race.series, &*race.event, id)))))
+    }
+    Ok(RedirectOrContent::Content(draft_page(transaction, me, uri, csrf.as_ref(), event, race, Context::default()).await?))
+}
+
+#[derive(FromForm, CsrfForm)]
+pub(crate) struct DraftForm {
+    #[field(default = String::new())]
+    csrf: String,
+    #[field(default = String::new())]
+    action: String,
+    #[field(default = String::new())]
+    setting: String,
+    #[field(default = String::new())]
+    value: String,
+    lite: bool,
+    mq: Option<u8>,
+}
+
+#[rocket::post("/event/<series>/<event>/races/<id>/draft", data = "<form>")]
+pub(crate) async fn post(discord_ctx: &State<RwFuture<DiscordCtx>>, pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, id: Id<Races>, form: Form<Contextual<'_, DraftForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = event::Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut race = Race::from_id(&mut transaction, http_client, id).await?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    if race.series != event.series || race.event != event.event {
+        form.context.push_error(form::Error::validation("This race is not part of this event."));
+    }
+    let my_team = my_team(&mut transaction, &race, Some(&me)).await?;
+    Ok(if let Some(ref value) = form.value {
+        if let (Some(draft_kind), Some(mut draft), Some(my_team)) = (event.draft_kind(), race.draft.clone(), my_team) {
+            let state_before = draft.clone();
+            if race.has_any_room() {
+                form.context.push_error(form::Error::validation("The race room is already open. Please continue the settings draft there."));
+            } else if !draft.is_active_team(draft_kind, race.game, my_team.id).await? {
+                form.context.push_error(form::Error::validation(if let TeamConfig::Solo = event.team_config { "It's not your turn in the settings draft." } else { "It's not your team's turn in the settings draft." }));
+            }
+            let action = match &*value.action {
+                "first" | "second" => {
+                    match draft_kind {
+                        draft::Kind::RslS7 => if draft.settings.get("lite_ok").is_some_and(|lite_ok| lite_ok == "ok") {
+                            draft.settings.insert(Cow::Borrowed("preset"), Cow::Borrowed(if value.lite { "lite" } else { "league" }));
+                        } else if value.lite {
+                            form.context.push_error(form::Error::validation("Either you or your opponent didn't opt into RSL-Lite.").with_name("lite"));
+                        },
+                        draft::Kind::TournoiFrancoS3 | draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 => if draft.settings.get("mq_ok").is_some_and(|mq_ok| mq_ok == "ok") {
+                            if let Some(mq) = value.mq.filter(|&mq| mq <= 12) {
+                                draft.settings.insert(Cow::Borrowed("mq_dungeons_count"), Cow::Owned(mq.to_string()));
+                            } else {
+                                form.context.push_error(form::Error::validation("Please enter a number of MQ dungeons between 0 and 12.").with_name("mq"));
+                            }
+                        } else if value.mq.is_some_and(|mq| mq != 0) {
+                            form.context.push_error(form::Error::validation("Either you or your opponent didn't opt into MQ dungeons.").with_name("mq"));
+                        },
+                        draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => {}
+                    }
+                    Some(draft::Action::GoFirst(value.action == "first"))
+                }
+                "ban" => Some(draft::Action::Ban { setting: value.setting.clone() }),
+                "pick" => if value.setting.is_empty() {
+                    form.context.push_error(form::Error::validation("Please select a setting.").with_name("setting"));
+                    None
+                } else if value.value.is_empty() {
+                    form.context.push_error(form::Error::validation("Please select an option for this setting.").with_name("value"));
+                    None
+                } else {
+                    Some(draft::Action::Pick { setting: value.setting.clone(), value: value.value.clone() })
+                },
+                "skip" => Some(draft::Action::Skip),
+                "yes" => Some(draft::Action::BooleanChoice(true)),
+                "no" => Some(draft::Action::BooleanChoice(false)),
+                _ => {
+                    form.context.push_error(form::Error::validation("Unknown draft action.").with_name("action"));
+                    None
+                }
+            };
+            if let Some(action) = action.filter(|_| form.context.errors().next().is_none()) {
+                let [high_seed_name, low_seed_name] = seed_names(&mut transaction, &race, &draft).await?;
+                match draft.apply(draft_kind, race.game, &mut draft::MessageContext::Web {
+                    high_seed_name: &high_seed_name,
+                    low_seed_name: &low_seed_name,
+                    reply_to: me.display_name(),
+                }, action.clone()).await? {
+                    Ok(_) => {
+                        draft::history::record(&mut transaction, race.id, Some(me.id), Some(my_team.id), draft::history::Frontend::Web, &action, &state_before).await?;
+                        draft::timer::reset(&mut transaction, &event, race.id).await?;
+                        sqlx::query!("UPDATE races SET draft_state = $1 WHERE id = $2", Json(&draft) as _, race.id as _).execute(&mut *transaction).await?;
+                        if let (Some(guild_id), Some(scheduling_thread)) = (event.discord_guild, race.scheduling_thread) {
+                            let discord_ctx = discord_ctx.read().await;
+                            let command_ids = discord_ctx.data.read().await.get::<CommandIds>().and_then(|command_ids| command_ids.get(&guild_id).copied().flatten());
+                            if let Some(command_ids) = command_ids {
+                                let mut msg_ctx = draft::MessageContext::Discord {
+                                    teams: race.teams().cloned().collect(),
+                                    team: my_team,
+                                    transaction, guild_id, command_ids,
+                                };
+                                let message = draft.next_step(draft_kind, race.game, &mut msg_ctx).await?.message;
+                                transaction = msg_ctx.into_transaction();
+                                scheduling_thread.say(&*discord_ctx, message).await?;
+                            }
+                        }
+                        transaction.commit().await?;
+                        return Ok(RedirectOrContent::Redirect(Redirect::to(uri!(get(series, &*event.event, id)))))
+                    }
+                    Err(error_msg) => form.context.push_error(form::Error::validation(error_msg)),
+                }
+            }
+            race.draft = Some(draft);
+        } else {
+            form.context.push_error(form::Error::validation("Only participants of this race can take part in its settings draft."));
+        }
+        RedirectOrContent::Content(draft_page(transaction, Some(me), uri, csrf.as_ref(), event, race, form.context).await?)
+    } else {
+        RedirectOrContent::Content(draft_page(transaction, Some(me), uri, csrf.as_ref(), event, race, form.context).await?)
+    })
+}
+
+#[rocket::post("/event/<series>/<event>/races/<id>/draft/undo", data = "<form>")]
+pub(crate) async fn undo(discord_ctx: &State<RwFuture<DiscordCtx>>, pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, id: Id<Races>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = event::Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let race = Race::from_id(&mut transaction, http_client, id).await?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    if race.series != event.series || race.event != event.event {
+        form.context.push_error(form::Error::validation("This race is not part of this event."));
+    }
+    if !event.organizers(&mut transaction).await?.contains(&me) {
+        form.context.push_error(form::Error::validation("You must be an organizer of this event to undo draft actions."));
+    }
+    if race.has_any_room() {
+        form.context.push_error(form::Error::validation("The race room is already open, so the settings draft can no longer be changed."));
+    }
+    Ok(if form.value.is_some() && form.context.errors().next().is_none() {
+        if let Some(draft) = draft::history::undo_last(&mut transaction, race.id, me.id).await? {
+            draft::timer::reset(&mut transaction, &event, race.id).await?;
+            if let (Some(draft_kind), Some(guild_id), Some(scheduling_thread)) = (event.draft_kind(), event.discord_guild, race.scheduling_thread) {
+                let discord_ctx = discord_ctx.read().await;
+                let command_ids = discord_ctx.data.read().await.get::<CommandIds>().and_then(|command_ids| command_ids.get(&guild_id).copied().flatten());
+                if let Some(command_ids) = command_ids {
+                    let mut msg_ctx = draft::MessageContext::Discord {
+                        teams: race.teams().cloned().collect(),
+                        team: Team::dummy(),
+                        transaction, guild_id, command_ids,
+                    };
+                    let message = MessageBuilder::default()
+                        .push("An organizer has undone the last settings draft action.")
+                        .push_line("")
+                        .push(draft.next_step(draft_kind, race.game, &mut msg_ctx).await?.message)
+                        .build();
+                    transaction = msg_ctx.into_transaction();
+                    scheduling_thread.say(&*discord_ctx, message).await?;
+                }
+            }
+            transaction.commit().await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(get(series, &*event.event, id))))
+        } else {
+            form.context.push_error(form::Error::validation("There are no draft actions to undo."));
+            RedirectOrContent::Content(draft_page(transaction, Some(me), uri, csrf.as_ref(), event, race, form.context).await?)
+        }
+    } else {
+        RedirectOrContent::Content(draft_page(transaction, Some(me), uri, csrf.as_ref(), event, race, form.context).await?)
+    })
+}
@@ -2,13 +2,17 @@ use crate::{
     event::{
         Data,
         Tab,
+        standings,
     },
+    id::Webhooks,
     prelude::*,
     racetime_bot::VersionedBranch,
     startgg,
     user::DisplaySource,
+    webhook,
 };
 use rocket::response::content::RawText;
+use sqlx::types::Json;
 use serde::Serializer;
 
 async fn configure_form(mut transaction: Transaction<'_, Postgres>, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: Data<'_>, ctx: Context<'_>) -> Result<RawHtml<String>, event::Error> {
@@ -54,6 +58,11 @@ async fn configure_form(mut transaction: Transaction<'_, Postgres>, me: Option<U
     } else if let Some(ref me) = me {
         if event.organizers(&mut transaction).await?.contains(me) {
             let mut errors = ctx.errors().collect_vec();
+            let phase_standings_formats = if let MatchSource::Manual = event.match_source() {
+                standings::Format::phases(&mut transaction, &event).await?
+            } else {
+                Vec::default()
+            };
             html! {
                 @if event.series == Series::Standard && event.event == "w" {
                     p {
@@ -91,6 +100,34 @@ async fn configure_form(mut transaction: Transaction<'_, Postgres>, me: Option<U
                     } //TODO make editable
                 } else {
                     : full_form(uri!(post(event.series, &*event.event)), csrf, html! {
+                        : form_field("display_name", &mut errors, html! {
+                            label(for = "display_name") : "Name:";
+                            input(type = "text", name = "display_name", value = ctx.field_value("display_name").unwrap_or(&event.display_name));
+                        });
+                        @if !event.is_started(&mut transaction).await? {
+                            : form_field("start", &mut errors, html! {
+                                label(for = "start") : "Start date (UTC):";
+                                input(type = "date", name = "start", value = ctx.field_value("start").map(Cow::Borrowed).unwrap_or_else(|| Cow::Owned(event.base_start.map(|start| start.format("%Y-%m-%d").to_string()).unwrap_or_default())));
+                                label(class = "help") : "(Leave empty if not known yet.)";
+                            });
+                        }
+                        : form_field("end", &mut errors, html! {
+                            label(for = "end") : "End date (UTC):";
+                            input(type = "date", name = "end", value = ctx.field_value("end").map(Cow::Borrowed).unwrap_or_else(|| Cow::Owned(event.end.map(|end| end.format("%Y-%m-%d").to_string()).unwrap_or_default())));
+                            label(class = "help") : "(Leave empty if not known yet.)";
+                        });
+                        : form_field("discord_guild", &mut errors, html! {
+                            label(for = "discord_guild") : "Discord server ID:";
+                            input(type = "text", name = "discord_guild", value = ctx.field_value("discord_guild").map(Cow::Borrowed).unwrap_or_else(|| Cow::Owned(event.discord_guild.map(|guild| guild.to_string()).unwrap_or_default())));
+                            label(class = "help") : "(Leave empty if this event doesn't have a Discord server.)";
+                        });
+                        @if event.draft_kind().is_none() {
+                            : form_field("single_settings", &mut errors, html! {
+                                label(for = "single_settings") : "Settings:";
+                                textarea(name = "single_settings") : if let Some(value) = ctx.field_value("single_settings") { value.to_owned() } else { event.single_settings.as_ref().map(serde_json::to_string_pretty).transpose()?.unwrap_or_default() };
+                                label(class = "help") : "(A JSON object of randomizer settings used for all races of this event. Leave empty if settings are chosen per race. Changes don't affect seeds that have already been rolled.)";
+                            });
+                        }
                         @if let MatchSource::StartGG(_) = event.match_source() {
                             : form_field("auto_import", &mut errors, html! {
                                 input(type = "checkbox", id = "auto_import", name = "auto_import", checked? = ctx.field_value("auto_import").map_or(event.auto_import, |value| value == "on"));
@@ -126,6 +163,54 @@ async fn configure_form(mut transaction: Transaction<'_, Postgres>, me: Option<U
                                 label(for = "manual_reporting_with_breaks") : "Disable automatic result reporting if !breaks command is used";
                             });
                         }
+                        @if let None | Some(draft::Kind::Custom(_)) = event.draft_kind() {
+                            : form_field("draft_spec", &mut errors, html! {
+                                label(for = "draft_spec") : "Settings draft:";
+                                textarea(name = "draft_spec") : if let Some(value) = ctx.field_value("draft_spec") { value.to_owned() } else { event.draft_spec.map(serde_json::to_string_pretty).transpose()?.unwrap_or_default() };
+                                label(class = "help") : "(A JSON description of the ban/pick steps and the settings that can be drafted. Leave empty if this event doesn't have a settings draft. Can't be changed while a race of this event has a draft in progress.)";
+                            });
+                            : form_field("draft_spec_template", &mut errors, html! {
+                                label(for = "draft_spec_template") : "Start from a past draft:";
+                                select(name = "draft_spec_template") {
+                                    option(value = "", selected? = ctx.field_value("draft_spec_template").is_none_or(|value| value.is_empty())) : "(none)";
+                                    @for template in all::<draft::spec::Template>() {
+                                        option(value = template.as_str(), selected? = ctx.field_value("draft_spec_template") == Some(template.as_str())) : template.display_name();
+                                    }
+                                }
+                                label(class = "help") : "(Fills in the settings draft if the field above is empty. It can be edited afterwards.)";
+                            });
+                        }
+                        @if event.draft_kind().is_some() {
+                            : form_field("draft_step_time_limit", &mut errors, html! {
+                                label(for = "draft_step_time_limit") : "Draft step time limit:";
+                                input(type = "text", name = "draft_step_time_limit", value = ctx.field_value("draft_step_time_limit").map(Cow::Borrowed).unwrap_or_else(|| Cow::Owned(event.draft_step_time_limit.map(unparse_duration).unwrap_or_default())));
+                                label(class = "help") : "(If a team takes longer than this for a single ban or pick in a settings draft, the step is skipped if possible or otherwise chosen randomly. The team is reminded when a quarter of the time is left. Leave empty for no time limit.)";
+                            });
+                        }
+                        @if let MatchSource::Manual = event.match_source() {
+                            : form_field("standings_format", &mut errors, html! {
+                                label(for = "standings_format") : "Standings format:";
+                                select(name = "standings_format") {
+                                    @for format in all::<standings::Format>() {
+                                        option(value = format.as_str(), selected? = ctx.field_value("standings_format").map_or(event.standings_format == format, |value| value == format.as_str())) : format.display_name();
+                                    }
+                                }
+                                label(class = "help") : "(How the Standings tab shows races without a phase and phases which don't have their own format below.)";
+                            });
+                            @for (idx, (phase, format)) in phase_standings_formats.into_iter().enumerate() {
+                                @let field_name = format!("phase_standings_format[{idx}]");
+                                : form_field(&field_name, &mut errors, html! {
+                                    input(type = "hidden", name = format!("standings_phase[{idx}]"), value = &phase);
+                                    label(for = &field_name) : format!("Standings format for {phase}:");
+                                    select(name = &field_name) {
+                                        option(value = "", selected? = ctx.field_value(&*field_name).map_or(format.is_none(), |value| value.is_empty())) : "(same as above)";
+                                        @for choice in all::<standings::Format>() {
+                                            option(value = choice.as_str(), selected? = ctx.field_value(&*field_name).map_or(format == Some(choice), |value| value == choice.as_str())) : choice.display_name();
+                                        }
+                                    }
+                                });
+                            }
+                        }
                     }, errors, "Save");
                 }
                 h2 : "More options";
@@ -133,6 +218,9 @@ async fn configure_form(mut transaction: Transaction<'_, Postgres>, me: Option<U
                     li {
                         a(href = uri!(restreamers_get(event.series, &*event.event))) : "Manage restream coordinators";
                     }
+                    li {
+                        a(href = uri!(webhooks_get(event.series, &*event.event))) : "Manage webhooks";
+                    }
                 }
             }
         } else {
@@ -170,12 +258,25 @@ pub(crate) async fn get(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>,
 pub(crate) struct ConfigureForm {
     #[field(default = String::new())]
     csrf: String,
+    display_name: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    discord_guild: Option<String>,
+    single_settings: Option<String>,
     auto_import: bool,
     #[field(default = String::new())]
     min_schedule_notice: String,
     retime_window: Option<String>,
     manual_reporting_with_breaks: bool,
     sync_startgg_ids: Option<String>,
+    draft_spec: Option<String>,
+    draft_spec_template: Option<draft::spec::Template>,
+    draft_step_time_limit: Option<String>,
+    standings_format: Option<standings::Format>,
+    #[field(default = HashMap::new())]
+    standings_phase: HashMap<String, String>,
+    #[field(default = HashMap::new())]
+    phase_standings_format: HashMap<String, Option<standings::Format>>,
 }
 
 #[rocket::post("/event/<series>/<event>/configure", data = "<form>")]
@@ -191,6 +292,71 @@ pub(crate) async fn post(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf:
         if !data.organizers(&mut transaction).await?.contains(&me) {
             form.context.push_error(form::Error::validation("You must be an organizer to configure this event."));
         }
+        let display_name = if let Some(display_name) = &value.display_name {
+            if display_name.trim().is_empty() {
+                form.context.push_error(form::Error::validation("This field is required.").with_name("display_name"));
+                None
+            } else {
+                Some(display_name.trim())
+            }
+        } else {
+            None
+        };
+        let is_started = data.is_started(&mut transaction).await?;
+        let start = if let Some(start) = value.start.as_deref().filter(|_| !is_started) {
+            if start.trim().is_empty() {
+                Some(None)
+            } else if let Some(start) = parse_date(start) {
+                Some(Some(start))
+            } else {
+                form.context.push_error(form::Error::validation("Dates must be formatted like 2025-12-31.").with_name("start"));
+                None
+            }
+        } else {
+            None
+        };
+        let end = if let Some(end) = &value.end {
+            if end.trim().is_empty() {
+                Some(None)
+            } else if let Some(end) = parse_date(end) {
+                if start.flatten().or(data.base_start).is_some_and(|start| end < start) {
+                    form.context.push_error(form::Error::validation("The event can't end before it starts.").with_name("end"));
+                }
+                Some(Some(end))
+            } else {
+                form.context.push_error(form::Error::validation("Dates must be formatted like 2025-12-31.").with_name("end"));
+                None
+            }
+        } else {
+            None
+        };
+        let discord_guild = if let Some(discord_guild) = &value.discord_guild {
+            if discord_guild.trim().is_empty() {
+                Some(None)
+            } else if let Some(guild_id) = discord_guild.trim().parse().ok().filter(|&id| id != 0) {
+                Some(Some(GuildId::new(guild_id)))
+            } else {
+                form.context.push_error(form::Error::validation("Discord server IDs are numbers. You can copy one by right-clicking the server with developer mode enabled.").with_name("discord_guild"));
+                None
+            }
+        } else {
+            None
+        };
+        let single_settings = if let Some(single_settings) = value.single_settings.as_deref().filter(|_| data.draft_kind().is_none()) {
+            if single_settings.trim().is_empty() {
+                Some(None)
+            } else {
+                match serde_json::from_str::<seed::Settings>(single_settings) {
+                    Ok(settings) => Some(Some(settings)),
+                    Err(e) => {
+                        form.context.push_error(form::Error::validation(format!("Failed to parse settings: {e}")).with_name("single_settings"));
+                        None
+                    }
+                }
+            }
+        } else {
+            None
+        };
         let min_schedule_notice = if let Some(time) = parse_duration(&value.min_schedule_notice, DurationUnit::Hours) {
             Some(time)
         } else {
@@ -207,9 +373,79 @@ pub(crate) async fn post(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf:
         } else {
             None
         };
+        let draft_spec = if let Some(draft_spec) = value.draft_spec.as_deref().filter(|_| matches!(data.draft_kind(), None | Some(draft::Kind::Custom(_)))) {
+            if draft_spec.trim().is_empty() {
+                Some(value.draft_spec_template.map(|template| template.spec().clone()))
+            } else if value.draft_spec_template.is_some() {
+                form.context.push_error(form::Error::validation("Clear the settings draft to start from a past draft.").with_name("draft_spec_template"));
+                None
+            } else {
+                match serde_json::from_str::<draft::spec::Spec>(draft_spec) {
+                    Ok(spec) => match spec.validate() {
+                        Ok(()) => Some(Some(spec)),
+                        Err(e) => {
+                            form.context.push_error(form::Error::validation(format!("Invalid settings draft: {e}")).with_name("draft_spec"));
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        form.context.push_error(form::Error::validation(format!("Failed to parse settings draft: {e}")).with_name("draft_spec"));
+                        None
+                    }
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(kind @ draft::Kind::Custom(spec)) = data.draft_kind() {
+            if draft_spec.as_ref().is_some_and(|new_spec| new_spec.as_ref() != Some(spec)) {
+                let mut draft_states = sqlx::query_scalar!(r#"SELECT draft_state AS "draft_state!: Json<draft::Draft>" FROM races WHERE series = $1 AND event = $2 AND draft_state IS NOT NULL AND end_time IS NULL AND NOT ignored"#, data.series as _, &data.event).fetch(&mut *transaction);
+                while let Some(Json(draft)) = draft_states.try_next().await? {
+                    if draft.active_team(kind, None).await?.is_some() {
+                        form.context.push_error(form::Error::validation("The settings draft can't be changed while a race of this event has a draft in progress.").with_name("draft_spec"));
+                        break
+                    }
+                }
+            }
+        }
+        let draft_step_time_limit = if let Some(draft_step_time_limit) = value.draft_step_time_limit.as_deref().filter(|_| data.draft_kind().is_some()) {
+            if draft_step_time_limit.trim().is_empty() {
+                Some(None)
+            } else if let Some(time) = parse_duration(draft_step_time_limit, DurationUnit::Minutes) {
+                if time.is_zero() {
+                    form.context.push_error(form::Error::validation("Draft step time limit must be greater than zero.").with_name("draft_step_time_limit"));
+                    None
+                } else if TimeDelta::from_std(time).is_err() {
+                    form.context.push_error(form::Error::validation("Draft step time limit is too long.").with_name("draft_step_time_limit"));
+                    None
+                } else {
+                    Some(Some(time))
+                }
+            } else {
+                form.context.push_error(form::Error::validation("Duration must be formatted like '1:23:45' or '1h 23m 45s'.").with_name("draft_step_time_limit"));
+                None
+            }
+        } else {
+            None
+        };
         if form.context.errors().next().is_some() {
             RedirectOrContent::Content(configure_form(transaction, Some(me), uri, csrf.as_ref(), data, form.context).await?)
         } else {
+            if let Some(display_name) = display_name {
+                sqlx::query!("UPDATE events SET display_name = $1 WHERE series = $2 AND event = $3", display_name, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let Some(start) = start {
+                sqlx::query!("UPDATE events SET start = $1 WHERE series = $2 AND event = $3", start, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let Some(end) = end {
+                sqlx::query!("UPDATE events SET end_time = $1 WHERE series = $2 AND event = $3", end, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let Some(discord_guild) = discord_guild {
+                sqlx::query!("UPDATE events SET discord_guild = $1 WHERE series = $2 AND event = $3", discord_guild.map(PgSnowflake) as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let Some(single_settings) = single_settings {
+                sqlx::query!("UPDATE events SET single_settings = $1 WHERE series = $2 AND event = $3", single_settings.map(Json) as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
             if let MatchSource::StartGG(_) = data.match_source() {
                 sqlx::query!("UPDATE events SET auto_import = $1 WHERE series = $2 AND event = $3", value.auto_import, data.series as _, &data.event).execute(&mut *transaction).await?;
             }
@@ -219,6 +455,20 @@ pub(crate) async fn post(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf:
             if let Some(retime_window) = retime_window {
                 sqlx::query!("UPDATE events SET retime_window = $1 WHERE series = $2 AND event = $3", retime_window as _, data.series as _, &data.event).execute(&mut *transaction).await?;
             }
+            if let Some(draft_spec) = draft_spec {
+                sqlx::query!("UPDATE events SET draft_spec = $1 WHERE series = $2 AND event = $3", draft_spec.map(Json) as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let Some(draft_step_time_limit) = draft_step_time_limit {
+                sqlx::query!("UPDATE events SET draft_step_time_limit = $1 WHERE series = $2 AND event = $3", draft_step_time_limit as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+            }
+            if let MatchSource::Manual = data.match_source() {
+                if let Some(standings_format) = value.standings_format {
+                    sqlx::query!("UPDATE events SET standings_format = $1 WHERE series = $2 AND event = $3", standings_format as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+                }
+                for (idx, phase) in &value.standings_phase {
+                    standings::Format::set_for_phase(&mut transaction, &data, phase, value.phase_standings_format.get(idx).copied().flatten()).await?;
+                }
+            }
             if matches!(data.match_source(), MatchSource::StartGG(_)) || data.discord_race_results_channel.is_some() {
                 sqlx::query!("UPDATE events SET manual_reporting_with_breaks = $1 WHERE series = $2 AND event = $3", value.manual_reporting_with_breaks, data.series as _, &data.event).execute(&mut *transaction).await?;
             }
@@ -450,6 +700,358 @@ pub(crate) async fn remove_restreamer(pool: &State<PgPool>, me: User, uri: Origi
     })
 }
 
+enum WebhooksFormDefaults<'v> {
+    None,
+    AddContext(Context<'v>),
+    WebhookContext(Id<Webhooks>, Context<'v>),
+    /// A webhook was just added or had its secret regenerated. This is the only time the secret is shown.
+    NewSecret(Id<Webhooks>, String),
+}
+
+impl<'v> WebhooksFormDefaults<'v> {
+    fn webhook_errors(&self, for_webhook: Id<Webhooks>) -> Vec<&form::Error<'v>> {
+        match self {
+            Self::WebhookContext(webhook, ctx) if *webhook == for_webhook => ctx.errors().collect(),
+            _ => Vec::default(),
+        }
+    }
+
+    fn add_errors(&self) -> Vec<&form::Error<'v>> {
+        if let Self::AddContext(ctx) = self {
+            ctx.errors().collect()
+        } else {
+            Vec::default()
+        }
+    }
+
+    fn add_field_value(&self, name: &str) -> Option<&str> {
+        if let Self::AddContext(ctx) = self {
+            ctx.field_value(name)
+        } else {
+            None
+        }
+    }
+
+    fn add_event_type_checked(&self, event_type: webhook::EventType) -> bool {
+        if let Self::AddContext(ctx) = self {
+            ctx.field_values("event_types").any(|value| value == event_type.as_str())
+        } else {
+            true
+        }
+    }
+}
+
+async fn webhooks_form(mut transaction: Transaction<'_, Postgres>, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: Data<'_>, defaults: WebhooksFormDefaults<'_>) -> Result<RawHtml<String>, event::Error> {
+    let header = event.header(&mut transaction, me.as_ref(), Tab::Configure, true).await?;
+    let content = if event.is_ended() {
+        html! {
+            article {
+                p : "This event has ended and can no longer be configured.";
+            }
+        }
+    } else if let Some(ref me) = me {
+        if event.organizers(&mut transaction).await?.contains(me) {
+            let webhooks = sqlx::query!(r#"SELECT id AS "id: Id<Webhooks>", url, event_types AS "event_types: Vec<webhook::EventType>" FROM webhooks WHERE series = $1 AND event = $2 ORDER BY created_at"#, event.series as _, &event.event).fetch_all(&mut *transaction).await?;
+            let deliveries = sqlx::query!(r#"SELECT
+                webhook_deliveries.id,
+                webhooks.url,
+                webhook_deliveries.event_type AS "event_type: webhook::EventType",
+                webhook_deliveries.created_at,
+                webhook_deliveries.attempts,
+                webhook_deliveries.next_attempt_at,
+                webhook_deliveries.delivered_at,
+                webhook_deliveries.last_status,
+                webhook_deliveries.last_error
+            FROM webhook_deliveries JOIN webhooks ON webhooks.id = webhook_deliveries.webhook WHERE webhooks.series = $1 AND webhooks.event = $2 ORDER BY webhook_deliveries.created_at DESC LIMIT 50"#, event.series as _, &event.event).fetch_all(&mut *transaction).await?;
+            html! {
+                h2 : "Manage webhooks";
+                p {
+                    : "Webhooks notify other services (such as a restream team's bot) about this event. Each notification is sent as a JSON ";
+                    code : "POST";
+                    : " request. The ";
+                    code : "X-MidosHouse-Signature-256";
+                    : " header contains ";
+                    code : "sha256=";
+                    : " followed by the hex-encoded HMAC-SHA256 of the request body, keyed with the webhook's secret. Failed deliveries are retried with exponential backoff.";
+                }
+                @if webhooks.is_empty() {
+                    p : "No webhooks so far.";
+                } else {
+                    table {
+                        thead {
+                            tr {
+                                th : "URL";
+                                th : "Events";
+                                th : "Secret";
+                                th;
+                            }
+                        }
+                        tbody {
+                            @for webhook in webhooks {
+                                tr {
+                                    td : webhook.url;
+                                    td {
+                                        ul {
+                                            @for event_type in webhook.event_types {
+                                                li : event_type.description();
+                                            }
+                                        }
+                                    }
+                                    td {
+                                        @if let WebhooksFormDefaults::NewSecret(new_secret_webhook, new_secret) = &defaults {
+                                            @if *new_secret_webhook == webhook.id {
+                                                code : new_secret;
+                                                br;
+                                                : "Copy this secret now, it won't be shown again.";
+                                            } else {
+                                                : "(hidden)";
+                                            }
+                                        } else {
+                                            : "(hidden)";
+                                        }
+                                    }
+                                    td {
+                                        : defaults.webhook_errors(webhook.id);
+                                        div(class = "button-row") {
+                                            @let (_, button) = button_form(uri!(test_webhook(event.series, &*event.event, webhook.id)), csrf, Vec::default(), "Send test payload");
+                                            : button;
+                                            @let (_, button) = button_form(uri!(regenerate_webhook_secret(event.series, &*event.event, webhook.id)), csrf, Vec::default(), "Regenerate secret");
+                                            : button;
+                                            @let (_, button) = button_form(uri!(remove_webhook(event.series, &*event.event, webhook.id)), csrf, Vec::default(), "Remove");
+                                            : button;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                h3 : "Add webhook";
+                @let mut errors = defaults.add_errors();
+                : full_form(uri!(add_webhook(event.series, &*event.event)), csrf, html! {
+                    : form_field("url", &mut errors, html! {
+                        label(for = "url") : "URL:";
+                        input(type = "text", name = "url", value? = defaults.add_field_value("url"));
+                        @if Environment::default().is_dev() {
+                            label(class = "help") : "(Must be an https URL. Since this is a development instance, http URLs and local addresses are also allowed for testing with a local receiver.)";
+                        } else {
+                            label(class = "help") : "(Must be an https URL pointing to a public address.)";
+                        }
+                    });
+                    : form_field("secret", &mut errors, html! {
+                        label(for = "secret") : "Secret:";
+                        input(type = "text", name = "secret", value? = defaults.add_field_value("secret"));
+                        label(class = "help") : "(Used to sign notifications so the receiver can verify they come from Mido's House. Leave empty to generate a random secret. The secret is only shown once, right after adding the webhook.)";
+                    });
+                    : form_field("event_types", &mut errors, html! {
+                        label : "Events:";
+                        @for event_type in all::<webhook::EventType>().filter(|&event_type| event_type != webhook::EventType::Test) {
+                            input(type = "checkbox", id = format!("event_types-{}", event_type.as_str()), name = "event_types", value = event_type.as_str(), checked? = defaults.add_event_type_checked(event_type));
+                            label(for = format!("event_types-{}", event_type.as_str())) : event_type.description();
+                            br;
+                        }
+                    });
+                }, errors, "Add");
+                h3 : "Recent deliveries";
+                @if deliveries.is_empty() {
+                    p : "No deliveries so far.";
+                } else {
+                    table {
+                        thead {
+                            tr {
+                                th : "Created";
+                                th : "URL";
+                                th : "Event";
+                                th : "Status";
+                                th : "Attempts";
+                                th : "Last error";
+                            }
+                        }
+                        tbody {
+                            @for delivery in deliveries {
+                                tr {
+                                    td : format_datetime(delivery.created_at, DateTimeFormat { long: false, running_text: false });
+                                    td : delivery.url;
+                                    td : delivery.event_type.description();
+                                    td {
+                                        @if let Some(delivered_at) = delivery.delivered_at {
+                                            : "Delivered ";
+                                            : format_datetime(delivered_at, DateTimeFormat { long: false, running_text: true });
+                                        } else if let Some(next_attempt_at) = delivery.next_attempt_at {
+                                            : "Pending, next attempt ";
+                                            : format_datetime(next_attempt_at, DateTimeFormat { long: false, running_text: true });
+                                        } else {
+                                            : "Failed";
+                                        }
+                                        @if let Some(last_status) = delivery.last_status {
+                                            : format!(" (HTTP {last_status})");
+                                        }
+                                    }
+                                    td : delivery.attempts;
+                                    td : delivery.last_error;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            html! {
+                article {
+                    p : "This page is for organizers of this event only.";
+                }
+            }
+        }
+    } else {
+        html! {
+            article {
+                p {
+                    a(href = uri!(auth::login(Some(uri!(webhooks_get(event.series, &*event.event)))))) : "Sign in or create a Mido's House account";
+                    : " to configure this event.";
+                }
+            }
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests: event.chests().await?, ..PageStyle::default() }, &format!("Manage webhooks — {}", event.display_name), html! {
+        : header;
+        : content;
+    }).await?)
+}
+
+#[rocket::get("/event/<series>/<event>/configure/webhooks")]
+pub(crate) async fn webhooks_get(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: String) -> Result<RawHtml<String>, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    Ok(webhooks_form(transaction, me, uri, csrf.as_ref(), data, WebhooksFormDefaults::None).await?)
+}
+
+#[derive(FromForm, CsrfForm)]
+pub(crate) struct AddWebhookForm {
+    #[field(default = String::new())]
+    csrf: String,
+    url: String,
+    secret: String,
+    event_types: Vec<webhook::EventType>,
+}
+
+#[rocket::post("/event/<series>/<event>/configure/webhooks", data = "<form>")]
+pub(crate) async fn add_webhook(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, form: Form<Contextual<'_, AddWebhookForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if let Some(ref value) = form.value {
+        if data.is_ended() {
+            form.context.push_error(form::Error::validation("This event has ended and can no longer be configured"));
+        }
+        if !data.organizers(&mut transaction).await?.contains(&me) {
+            form.context.push_error(form::Error::validation("You must be an organizer to configure this event."));
+        }
+        match Url::parse(&value.url) {
+            Ok(url) => if let Err(message) = webhook::check_url(&url).await {
+                form.context.push_error(form::Error::validation(message).with_name("url"));
+            },
+            Err(e) => form.context.push_error(form::Error::validation(format!("Failed to parse URL: {e}")).with_name("url")),
+        }
+        if value.event_types.is_empty() {
+            form.context.push_error(form::Error::validation("Select at least one event.").with_name("event_types"));
+        }
+        if value.event_types.contains(&webhook::EventType::Test) {
+            form.context.push_error(form::Error::validation("Test payloads are always delivered and can't be selected.").with_name("event_types"));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::AddContext(form.context)).await?)
+        } else {
+            let id = Id::<Webhooks>::new(&mut transaction).await?;
+            let secret = if value.secret.is_empty() { webhook::generate_secret() } else { value.secret.clone() };
+            let event_types = value.event_types.iter().copied().unique().collect_vec();
+            sqlx::query!("INSERT INTO webhooks (id, series, event, url, secret, event_types, created_by) VALUES ($1, $2, $3, $4, $5, $6, $7)", id as _, data.series as _, &data.event, value.url, secret, event_types as _, me.id as _).execute(&mut *transaction).await?;
+            RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::NewSecret(id, secret)).await?)
+        }
+    } else {
+        RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::AddContext(form.context)).await?)
+    })
+}
+
+#[rocket::post("/event/<series>/<event>/configure/webhooks/<webhook>/remove", data = "<form>")]
+pub(crate) async fn remove_webhook(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, webhook: Id<Webhooks>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if form.value.is_some() {
+        if data.is_ended() {
+            form.context.push_error(form::Error::validation("This event has ended and can no longer be configured"));
+        }
+        if !data.organizers(&mut transaction).await?.contains(&me) {
+            form.context.push_error(form::Error::validation("You must be an organizer to configure this event."));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+        } else {
+            sqlx::query!("DELETE FROM webhooks WHERE id = $1 AND series = $2 AND event = $3", webhook as _, data.series as _, &data.event).execute(&mut *transaction).await?;
+            transaction.commit().await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(webhooks_get(series, event))))
+        }
+    } else {
+        RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+    })
+}
+
+#[rocket::post("/event/<series>/<event>/configure/webhooks/<webhook>/test", data = "<form>")]
+pub(crate) async fn test_webhook(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, webhook: Id<Webhooks>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if form.value.is_some() {
+        if !data.organizers(&mut transaction).await?.contains(&me) {
+            form.context.push_error(form::Error::validation("You must be an organizer to configure this event."));
+        }
+        if !sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM webhooks WHERE id = $1 AND series = $2 AND event = $3) AS "exists!""#, webhook as _, data.series as _, &data.event).fetch_one(&mut *transaction).await? {
+            form.context.push_error(form::Error::validation("There is no such webhook for this event."));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+        } else {
+            webhook::enqueue_test(&mut transaction, webhook, data.series, &data.event).await?;
+            transaction.commit().await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(webhooks_get(series, event))))
+        }
+    } else {
+        RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+    })
+}
+
+#[rocket::post("/event/<series>/<event>/configure/webhooks/<webhook>/secret", data = "<form>")]
+pub(crate) async fn regenerate_webhook_secret(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, webhook: Id<Webhooks>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if form.value.is_some() {
+        if data.is_ended() {
+            form.context.push_error(form::Error::validation("This event has ended and can no longer be configured"));
+        }
+        if !data.organizers(&mut transaction).await?.contains(&me) {
+            form.context.push_error(form::Error::validation("You must be an organizer to configure this event."));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+        } else {
+            let secret = webhook::generate_secret();
+            if sqlx::query!("UPDATE webhooks SET secret = $1 WHERE id = $2 AND series = $3 AND event = $4", secret, webhook as _, data.series as _, &data.event).execute(&mut *transaction).await?.rows_affected() == 0 {
+                form.context.push_error(form::Error::validation("There is no such webhook for this event."));
+                RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+            } else {
+                RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::NewSecret(webhook, secret)).await?)
+            }
+        }
+    } else {
+        RedirectOrContent::Content(webhooks_form(transaction, Some(me), uri, csrf.as_ref(), data, WebhooksFormDefaults::WebhookContext(webhook, form.context)).await?)
+    })
+}
+
 #[derive(Debug)]
 struct SyncResult {
     synced_count: usize,
@@ -12,6 +12,7 @@ use {
             teams,
         },
         prelude::*,
+        webhook,
     },
 };
 
@@ -1427,6 +1428,11 @@ pub(crate) async fn post(config: &State<Config>, pool: &State<PgPool>, http_clie
                         value.mw_impl as _,
                     ).execute(&mut *transaction).await?;
                     sqlx::query!("INSERT INTO team_members (team, member, status, role) VALUES ($1, $2, 'created', 'none')", id as _, me.id as _).execute(&mut *transaction).await?;
+                    // solo signups don't need to be confirmed by anyone else
+                    if let Some(team) = Team::from_id(&mut transaction, id).await? {
+                        let team = webhook::team_json(&mut transaction, &team).await?;
+                        webhook::enqueue(&mut transaction, series, event, webhook::EventType::SignupConfirmed, json!({"team": team})).await?;
+                    }
                     if let Some(async_kind) = request_qualifier {
                         sqlx::query!("INSERT INTO async_teams (team, kind, requested) VALUES ($1, $2, NOW())", id as _, async_kind as _).execute(&mut *transaction).await?;
                     }
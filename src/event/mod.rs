@@ -13,6 +13,7 @@ use {
         notification::SimpleNotificationKind,
         prelude::*,
         racetime_bot::VersionedBranch,
+        webhook,
     },
 };
 
@@ -20,6 +21,8 @@ pub(crate) mod configure;
 pub(crate) mod enter;
 pub(crate) mod teams;
 pub(crate) mod roles;
+pub(crate) mod standings;
+pub(crate) mod swiss;
 
 #[derive(Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "signup_status", rename_all = "snake_case")]
@@ -87,7 +90,7 @@ pub(crate) enum MatchSource<'a> {
     StartGG(&'a str),
 }
 
-#[derive(Debug, Clone, Copy, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence, sqlx::Type, async_graphql::Enum, FromFormField)]
 #[sqlx(type_name = "team_config", rename_all = "lowercase")]
 pub(crate) enum TeamConfig {
     Solo,
@@ -123,6 +126,16 @@ impl TeamConfig {
         }
     }
 
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            Self::Solo => "solo",
+            Self::CoOp => "2-player co-op",
+            Self::TfbCoOp => "2-player Triforce Blitz co-op",
+            Self::Pictionary => "Pictionary (runner and pilot)",
+            Self::Multiworld => "3-player multiworld",
+        }
+    }
+
     /// Whether team members with the given role should be invited to race rooms.
     pub(crate) fn role_is_racing(&self, role: Role) -> bool {
         !matches!(self, Self::Pictionary) || matches!(role, Role::Sheikah)
@@ -173,6 +186,7 @@ pub(crate) struct Data<'a> {
     pub(crate) single_settings: Option<seed::Settings>,
     pub(crate) team_config: TeamConfig,
     enter_flow: Option<enter::Flow>,
+    pub(crate) draft_spec: Option<&'static draft::spec::Spec>,
     show_opt_out: bool,
     pub(crate) show_qualifier_times: bool,
     pub(crate) default_game_count: i16,
@@ -180,9 +194,17 @@ pub(crate) struct Data<'a> {
     pub(crate) open_stream_delay: Duration,
     pub(crate) invitational_stream_delay: Duration,
     pub(crate) retime_window: Duration,
+    /// How long a team may take for a single step of the settings draft before it is resolved automatically.
+    pub(crate) draft_step_time_limit: Option<Duration>,
     pub(crate) auto_import: bool,
     pub(crate) manual_reporting_with_breaks: bool,
+    /// How the standings are shown for races without a phase and for phases without their own format.
+    pub(crate) standings_format: standings::Format,
     pub(crate) language: Language,
+    /// Used for races without a known end time, from the `series` table.
+    pub(crate) default_race_duration: Duration,
+    pub(crate) emulator_settings_reminder: bool,
+    pub(crate) prevent_late_joins: bool,
 }
 
 #[derive(Debug, thiserror::Error, rocket_util::Error)]
@@ -190,6 +212,8 @@ pub(crate) enum DataError {
     #[error(transparent)] PgInterval(#[from] PgIntervalDecodeError),
     #[error(transparent)] Sql(#[from] sqlx::Error),
     #[error(transparent)] Url(#[from] url::ParseError),
+    #[error("the event's settings draft is invalid: {0}")]
+    InvalidDraftSpec(#[from] draft::spec::ValidationError),
     #[error("no event with this series and identifier")]
     Missing,
     #[error("team with nonexistent user")]
@@ -223,6 +247,7 @@ impl<'a> Data<'a> {
             single_settings AS "single_settings: Json<seed::Settings>",
             team_config AS "team_config: TeamConfig",
             enter_flow AS "enter_flow: Json<enter::Flow>",
+            draft_spec AS "draft_spec: Json<draft::spec::Spec>",
             show_opt_out,
             show_qualifier_times,
             default_game_count,
@@ -230,9 +255,14 @@ impl<'a> Data<'a> {
             open_stream_delay,
             invitational_stream_delay,
             retime_window,
+            draft_step_time_limit,
             auto_import,
             manual_reporting_with_breaks,
-            language AS "language: Language"
+            standings_format AS "standings_format: standings::Format",
+            language AS "language: Language",
+            (SELECT default_race_duration FROM series WHERE series.series = events.series) AS "default_race_duration!",
+            COALESCE(emulator_settings_reminder, (SELECT emulator_settings_reminder FROM series WHERE series.series = events.series)) AS "emulator_settings_reminder!",
+            COALESCE(prevent_late_joins, (SELECT prevent_late_joins FROM series WHERE series.series = events.series)) AS "prevent_late_joins!"
         FROM events WHERE series = $1 AND event = $2"#, series as _, &event).fetch_optional(&mut **transaction).await?
             .map(|row| Ok::<_, DataError>(Self {
                 display_name: row.display_name,
@@ -262,6 +292,8 @@ impl<'a> Data<'a> {
                 },
                 team_config: row.team_config,
                 enter_flow: row.enter_flow.map(|Json(flow)| flow),
+                // specs are validated when they're configured, but may have been written to the database directly or been made invalid by a stricter check
+                draft_spec: row.draft_spec.map(|Json(spec)| spec.validate().map(|()| spec.intern(series, &event))).transpose()?,
                 show_opt_out: row.show_opt_out,
                 show_qualifier_times: row.show_qualifier_times,
                 default_game_count: row.default_game_count,
@@ -269,9 +301,14 @@ impl<'a> Data<'a> {
                 open_stream_delay: decode_pginterval(row.open_stream_delay)?,
                 invitational_stream_delay: decode_pginterval(row.invitational_stream_delay)?,
                 retime_window: decode_pginterval(row.retime_window)?,
+                draft_step_time_limit: row.draft_step_time_limit.map(decode_pginterval).transpose()?,
                 auto_import: row.auto_import,
                 manual_reporting_with_breaks: row.manual_reporting_with_breaks,
+                standings_format: row.standings_format,
                 language: row.language,
+                default_race_duration: decode_pginterval(row.default_race_duration)?,
+                emulator_settings_reminder: row.emulator_settings_reminder,
+                prevent_late_joins: row.prevent_late_joins,
                 series, event,
             }))
             .transpose()
@@ -352,6 +389,24 @@ impl<'a> Data<'a> {
         })
     }
 
+    /// The reason a race in this event can't be scheduled for the given time, i.e. because it's in the past or doesn't give enough notice.
+    pub(crate) fn schedule_notice_error(&self, start: DateTime<Utc>) -> Option<String> {
+        if (start - Utc::now()).to_std().is_ok_and(|schedule_notice| schedule_notice >= self.min_schedule_notice) { return None }
+        Some(if self.min_schedule_notice <= Duration::default() {
+            if let French = self.language {
+                format!("Désolé mais cette date est dans le passé.")
+            } else {
+                format!("Sorry, that timestamp is in the past.")
+            }
+        } else {
+            if let French = self.language {
+                format!("Désolé, les races doivent être planifiées au moins {} en avance.", French.format_duration(self.min_schedule_notice, true))
+            } else {
+                format!("Sorry, races must be scheduled at least {} in advance.", English.format_duration(self.min_schedule_notice, true))
+            }
+        })
+    }
+
     pub(crate) fn asyncs_allowed(&self) -> bool {
         match self.series {
             Series::SpeedGaming => false,
@@ -379,6 +434,7 @@ impl<'a> Data<'a> {
             Series::TournoiFrancophone => false,
             Series::TriforceBlitz => false,
             Series::WeTryToBeBetter => false,
+            Series::Custom(_) => false,
         }
     }
 
@@ -408,7 +464,7 @@ impl<'a> Data<'a> {
             (Series::TournoiFrancophone, "3") => Some(draft::Kind::TournoiFrancoS3),
             (Series::TournoiFrancophone, "4") => Some(draft::Kind::TournoiFrancoS4),
             (Series::TournoiFrancophone, "5") => Some(draft::Kind::TournoiFrancoS5),
-            (_, _) => None,
+            (_, _) => self.draft_spec.map(draft::Kind::Custom),
         }
     }
 
@@ -515,6 +571,11 @@ impl<'a> Data<'a> {
         } else {
             false
         };
+        let has_standings = matches!(self.match_source(), MatchSource::Manual) && !self.is_single_race() && sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM race_results, races WHERE
+            race = id
+            AND series = $1
+            AND event = $2
+        ) AS "exists!""#, self.series as _, &self.event).fetch_one(&mut **transaction).await?;
         Ok(html! {
             h1 {
                 a(class = "nav", href? = (!matches!(tab, Tab::Info) || is_subpage).then(|| uri!(info(self.series, &*self.event)))) : &self.display_name;
@@ -551,6 +612,13 @@ impl<'a> Data<'a> {
                         a(class = "button", href = uri!(races(self.series, &*self.event))) : "Races";
                     }
                 }
+                @if has_standings {
+                    @if let Tab::Standings = tab {
+                        a(class = "button selected", href? = is_subpage.then(|| uri!(standings::get(self.series, &*self.event)))) : "Standings";
+                    } else {
+                        a(class = "button", href = uri!(standings::get(self.series, &*self.event))) : "Standings";
+                    }
+                }
                 @if signed_up {
                     @if let Tab::MyStatus = tab {
                         a(class = "button selected", href? = is_subpage.then(|| uri!(status(self.series, &*self.event)))) : "My Status";
@@ -687,6 +755,7 @@ pub(crate) enum Tab {
     Info,
     Teams,
     Races,
+    Standings,
     MyStatus,
     Enter,
     FindTeam,
@@ -700,16 +769,25 @@ pub(crate) enum Error {
     #[error(transparent)] Calendar(#[from] cal::Error),
     #[error(transparent)] Data(#[from] DataError),
     #[error(transparent)] Discord(#[from] crate::discord_bot::Error),
+    #[error(transparent)] Draft(#[from] draft::Error),
     #[error(transparent)] Io(#[from] io::Error),
     #[error(transparent)] Json(#[from] serde_json::Error),
     #[error(transparent)] OotrWeb(#[from] ootr_web::Error),
     #[error(transparent)] Page(#[from] PageError),
+    #[error(transparent)] RaceTime(#[from] racetime::Error),
     #[error(transparent)] Reqwest(#[from] reqwest::Error),
     #[error(transparent)] SeedData(#[from] seed::ExtraDataError),
     #[error(transparent)] Serenity(#[from] serenity::Error),
     #[error(transparent)] Sql(#[from] sqlx::Error),
     #[error(transparent)] Url(#[from] url::ParseError),
     #[error(transparent)] Wheel(#[from] wheel::Error),
+    #[error("the settings draft of race {0} has an active team but nothing to do when its time runs out")]
+    DraftTimeoutNoAction(Id<Races>),
+    #[error("the settings draft of race {race} rejected its timeout action: {message}")]
+    DraftTimeoutIllegal {
+        race: Id<Races>,
+        message: String,
+    },
     #[error("missing user data for an event organizer")]
     OrganizerUserData,
     #[error("missing user data for a restreamer")]
@@ -728,16 +806,20 @@ impl IsNetworkError for Error {
             Self::Calendar(e) => e.is_network_error(),
             Self::Data(_) => false,
             Self::Discord(_) => false,
+            Self::Draft(e) => e.is_network_error(),
             Self::Io(e) => e.is_network_error(),
             Self::Json(_) => false,
             Self::OotrWeb(e) => e.is_network_error(),
             Self::Page(e) => e.is_network_error(),
+            Self::RaceTime(_) => false,
             Self::Reqwest(e) => e.is_network_error(),
             Self::SeedData(e) => e.is_network_error(),
             Self::Serenity(_) => false,
             Self::Sql(_) => false,
             Self::Url(_) => false,
             Self::Wheel(e) => e.is_network_error(),
+            Self::DraftTimeoutNoAction(_) => false,
+            Self::DraftTimeoutIllegal { .. } => false,
             Self::OrganizerUserData => false,
             Self::RestreamerUserData => false,
         }
@@ -798,6 +880,7 @@ pub(crate) async fn info(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>
         Series::TournoiFrancophone => fr::info(&mut transaction, &data).await?,
         Series::TriforceBlitz => tfb::info(&mut transaction, &data).await?,
         Series::WeTryToBeBetter => wttbb::info(&mut transaction, &data).await?,
+        Series::Custom(_) => None,
     };
     let content = html! {
         : header;
@@ -870,7 +953,11 @@ pub(crate) async fn races(discord_ctx: &State<RwFuture<DiscordCtx>>, pool: &Stat
         } else if can_create && !any_races_ongoing_or_upcoming {
             div(class = "button-row") {
                 @match data.match_source() {
-                    MatchSource::Manual | MatchSource::Challonge { .. } => a(class = "button", href = uri!(crate::cal::create_race(series, event, _))) : "New Race";
+                    MatchSource::Manual => {
+                        a(class = "button", href = uri!(crate::cal::create_race(series, event, _))) : "New Race";
+                        a(class = "button", href = uri!(swiss::get(series, event))) : "Pair Swiss Round";
+                    }
+                    MatchSource::Challonge { .. } => a(class = "button", href = uri!(crate::cal::create_race(series, event, _))) : "New Race";
                     //MatchSource::Challonge { .. } => a(class = "button", href = uri!(crate::cal::import_races(series, event))) : "Import"; // disabled due to Challonge pagination bug
                     MatchSource::League => {}
                     MatchSource::StartGG(_) => @if !data.auto_import {
@@ -931,6 +1018,11 @@ async fn status_page(mut transaction: Transaction<'_, Postgres>, me: Option<User
             AND member = $3
             AND NOT EXISTS (SELECT 1 FROM team_members WHERE team = id AND status = 'unconfirmed')
         "#, data.series as _, &data.event, me.id as _).fetch_optional(&mut *transaction).await? {
+            let results = if let Some(team) = Team::from_id(&mut transaction, row.id).await? {
+                cal::RaceResult::for_team(&mut transaction, &team).await?
+            } else {
+                Vec::default()
+            };
             html! {
                 : header;
                 @if !matches!(data.team_config, TeamConfig::Solo) {
@@ -1146,6 +1238,7 @@ async fn status_page(mut transaction: Transaction<'_, Postgres>, me: Option<User
                             | Series::Standard
                             | Series::TournoiFrancophone
                             | Series::WeTryToBeBetter
+                            | Series::Custom(_)
                                 => @if let French = data.language {
                                     p : "Planifiez vos matches dans les fils du canal dédié.";
                                 } else {
@@ -1165,7 +1258,13 @@ async fn status_page(mut transaction: Transaction<'_, Postgres>, me: Option<User
                                 p : "Waiting for the start of the tournament and round 1 pairings. Keep an eye out for an announcement on Discord."; //TODO include start date?
                             }
                             Series::NineDaysOfSaws => @if data.is_ended() {
-                                p : "This race has been completed."; //TODO ranking and finish time
+                                p {
+                                    : "This race has been completed.";
+                                    @if let Some((_, result)) = results.first() {
+                                        : " Your result: ";
+                                        : result.summary(data.team_config);
+                                    }
+                                }
                             } else if let Some(ref race_room) = data.url {
                                 p {
                                     : "Please join ";
@@ -1176,7 +1275,13 @@ async fn status_page(mut transaction: Transaction<'_, Postgres>, me: Option<User
                                 : "Waiting for the race room to be opened, which should happen around 30 minutes before the scheduled starting time. Keep an eye out for an announcement on Discord.";
                             }
                             Series::Pictionary => @if data.is_ended() {
-                                p : "This race has been completed."; //TODO ranking and finish time
+                                p {
+                                    : "This race has been completed.";
+                                    @if let Some((_, result)) = results.first() {
+                                        : " Your result: ";
+                                        : result.summary(data.team_config);
+                                    }
+                                }
                             } else if let Some(ref race_room) = data.url {
                                 @match row.role.try_into().expect("non-Pictionary role in Pictionary team") {
                                     pic::Role::Sheikah => p {
@@ -1216,6 +1321,20 @@ async fn status_page(mut transaction: Transaction<'_, Postgres>, me: Option<User
                             }
                         }
                     }
+                    @if !results.is_empty() && !matches!(data.series, Series::NineDaysOfSaws | Series::Pictionary) {
+                        h2 : "Results";
+                        ul {
+                            @for (description, result) in &results {
+                                li {
+                                    @if let Some(description) = description {
+                                        : description;
+                                        : ": ";
+                                    }
+                                    : result.summary(data.team_config);
+                                }
+                            }
+                        }
+                    }
                     @if !data.is_ended() {
                         h2 : "Options";
                         @let ctx = ctx.take_edit();
@@ -1485,6 +1604,10 @@ pub(crate) async fn confirm_signup(pool: &State<PgPool>, http_client: &State<req
             sqlx::query!("UPDATE team_members SET status = 'confirmed' WHERE team = $1 AND member = $2", team as _, me.id as _).execute(&mut *transaction).await?;
             if !sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM team_members WHERE team = $1 AND status = 'unconfirmed') AS "exists!""#, team as _).fetch_one(&mut *transaction).await? {
                 // this confirms the team
+                if let Some(team) = Team::from_id(&mut transaction, team).await? {
+                    let team = webhook::team_json(&mut transaction, &team).await?;
+                    webhook::enqueue(&mut transaction, series, event, webhook::EventType::SignupConfirmed, json!({"team": team})).await?;
+                }
                 // remove all members from looking_for_team
                 sqlx::query!("DELETE FROM looking_for_team WHERE EXISTS (SELECT 1 FROM team_members WHERE team = $1 AND member = user_id)", team as _).execute(&mut *transaction).await?;
                 //TODO also remove all other teams with member overlap, and notify
@@ -1631,6 +1754,8 @@ pub(crate) async fn resign_post(pool: &State<PgPool>, http_client: &State<reqwes
             .push_safe(&data.display_name)
             .push(".")
             .build();
+        // serialized before the team members are deleted below
+        let team_data = webhook::team_json(&mut transaction, &team).await?;
         let members = if keep_record {
             sqlx::query!(r#"UPDATE teams SET resigned = TRUE WHERE id = $1"#, team.id as _).execute(&mut *transaction).await?;
             sqlx::query!(r#"SELECT member AS "id: Id<Users>", status AS "status: SignupStatus" FROM team_members WHERE team = $1"#, team.id as _).fetch(&mut *transaction)
@@ -1676,6 +1801,9 @@ pub(crate) async fn resign_post(pool: &State<PgPool>, http_client: &State<reqwes
                 }
             })
         } else {
+            if members.iter().all(|&(_, status)| status.is_confirmed()) {
+                webhook::enqueue(&mut transaction, series, event, webhook::EventType::TeamResigned, json!({"team": team_data})).await?;
+            }
             for (member_id, status) in members {
                 if member_id != me.id && status.is_confirmed() {
                     let notification_id = Id::<Notifications>::new(&mut transaction).await?;
@@ -544,24 +544,23 @@ impl Signup {
         user_id: Id<Users>,
     ) -> sqlx::Result<()> {
         let confirmed_signup = sqlx::query!(
-            r#"SELECT s.race_id, s.role_binding_id, r.series as "series: Series", r.start
+            r#"SELECT s.race_id, s.role_binding_id, r.start, r.start + sr.default_race_duration AS "end_time"
                FROM signups s
                JOIN races r ON s.race_id = r.id
+               JOIN series sr ON r.series = sr.series
                WHERE s.id = $1"#,
             confirmed_signup_id as _
         )
         .fetch_one(&mut **pool)
         .await?;
 
-        if let Some(start_time) = confirmed_signup.start {
-            let duration = confirmed_signup.series.default_race_duration();
-            let end_time = start_time + duration;
-
+        if let (Some(start_time), Some(end_time)) = (confirmed_signup.start, confirmed_signup.end_time) {
          
             let all_user_signups = sqlx::query!(
-                r#"SELECT s.id, s.race_id, r.series as "series: Series", r.start
+                r#"SELECT s.id, s.race_id, r.start, r.start + sr.default_race_duration AS "end_time"
                    FROM signups s
                    JOIN races r ON s.race_id = r.id
+                   JOIN series sr ON r.series = sr.series
                    WHERE s.user_id = $1 
                    AND s.id != $2
                    AND s.status = 'pending'
@@ -573,10 +572,7 @@ impl Signup {
             .await?;
 
             for signup in all_user_signups {
-                if let Some(signup_start_time) = signup.start {
-                    let signup_duration = signup.series.default_race_duration();
-                    let signup_end_time = signup_start_time + signup_duration;
-
+                if let (Some(signup_start_time), Some(signup_end_time)) = (signup.start, signup.end_time) {
                     if start_time < signup_end_time && signup_start_time < end_time {
                         sqlx::query!(
                             r#"UPDATE signups SET status = 'declined', updated_at = NOW() WHERE id = $1"#,
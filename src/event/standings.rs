@@ -0,0 +1,386 @@
+//! Standings computed from the recorded race results, for events which don't use an external bracket site.
+
+use {
+    std::cmp::Reverse,
+    noisy_float::prelude::*,
+    crate::{
+        event::{
+            Data,
+            Error,
+            MatchSource,
+            Tab,
+        },
+        prelude::*,
+    },
+};
+
+#[cfg(test)] mod tests;
+
+/// How the standings of a phase are shown, configured by the organizers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence, sqlx::Type, FromFormField)]
+#[sqlx(type_name = "standings_format", rename_all = "lowercase")]
+pub(crate) enum Format {
+    #[field(value = "swiss")]
+    Swiss,
+    #[field(value = "groups")]
+    Groups,
+    #[field(value = "bracket")]
+    Bracket,
+}
+
+impl Format {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Swiss => "swiss",
+            Self::Groups => "groups",
+            Self::Bracket => "bracket",
+        }
+    }
+
+    pub(crate) fn display_name(&self) -> &'static str {
+        match self {
+            Self::Swiss => "Swiss records",
+            Self::Groups => "Group tables",
+            Self::Bracket => "Elimination bracket",
+        }
+    }
+
+    /// The format configured for the given phase, or the event's default if the phase doesn't have its own.
+    pub(crate) async fn for_phase(transaction: &mut Transaction<'_, Postgres>, data: &Data<'_>, phase: Option<&str>) -> sqlx::Result<Self> {
+        Ok(if let Some(phase) = phase {
+            sqlx::query_scalar!(r#"SELECT format AS "format: Format" FROM phase_standings_formats WHERE series = $1 AND event = $2 AND phase = $3"#, data.series as _, &data.event, phase).fetch_optional(&mut **transaction).await?
+        } else {
+            None
+        }.unwrap_or(data.standings_format))
+    }
+
+    /// The phases of the event's races along with each phase's own format, if any, ordered by name.
+    pub(crate) async fn phases(transaction: &mut Transaction<'_, Postgres>, data: &Data<'_>) -> sqlx::Result<Vec<(String, Option<Self>)>> {
+        Ok(sqlx::query!(r#"SELECT phases.phase AS "phase!", format AS "format?: Format" FROM (SELECT DISTINCT phase FROM races WHERE series = $1 AND event = $2 AND phase IS NOT NULL) AS phases LEFT JOIN phase_standings_formats ON phase_standings_formats.series = $1 AND phase_standings_formats.event = $2 AND phase_standings_formats.phase = phases.phase ORDER BY phases.phase"#, data.series as _, &data.event).fetch_all(&mut **transaction).await?
+            .into_iter()
+            .map(|row| (row.phase, row.format))
+            .collect())
+    }
+
+    /// Sets the format of the given phase. `None` makes the phase use the event's default.
+    pub(crate) async fn set_for_phase(transaction: &mut Transaction<'_, Postgres>, data: &Data<'_>, phase: &str, format: Option<Self>) -> sqlx::Result<()> {
+        if let Some(format) = format {
+            sqlx::query!("INSERT INTO phase_standings_formats (series, event, phase, format) VALUES ($1, $2, $3, $4) ON CONFLICT (series, event, phase) DO UPDATE SET format = EXCLUDED.format", data.series as _, &data.event, phase, format as _).execute(&mut **transaction).await?;
+        } else {
+            sqlx::query!("DELETE FROM phase_standings_formats WHERE series = $1 AND event = $2 AND phase = $3", data.series as _, &data.event, phase).execute(&mut **transaction).await?;
+        }
+        Ok(())
+    }
+}
+
+/// All games between two teams in the same phase and round.
+#[derive(Debug, Clone)]
+pub(crate) struct Match {
+    pub(crate) phase: Option<String>,
+    pub(crate) round: Option<String>,
+    pub(crate) teams: [Id<Teams>; 2],
+    /// The number of games won by each team.
+    pub(crate) wins: [u16; 2],
+    /// Whether every game has been played and has a recorded result.
+    pub(crate) finished: bool,
+}
+
+impl Match {
+    /// The team which won the match. `None` if the match isn't finished yet or ended in a draw.
+    pub(crate) fn winner(&self) -> Option<Id<Teams>> {
+        if !self.finished { return None }
+        match self.wins[0].cmp(&self.wins[1]) {
+            Greater => Some(self.teams[0]),
+            Less => Some(self.teams[1]),
+            Equal => None,
+        }
+    }
+}
+
+/// Groups the races between two Mido's House teams into matches, ordered by the first game of each match.
+pub(crate) async fn matches(transaction: &mut Transaction<'_, Postgres>, races: &[Race]) -> Result<Vec<Match>, cal::Error> {
+    let mut matches = Vec::<Match>::default();
+    for race in races {
+        let Entrants::Two([Entrant::MidosHouseTeam(ref team1), Entrant::MidosHouseTeam(ref team2)]) = race.entrants else { continue };
+        let winner = if race.is_ended() {
+            cal::RaceResult::for_race(&mut *transaction, race.id).await?
+                .into_iter()
+                .filter(|result| result.placement == 1 && !result.dnf)
+                .exactly_one().ok()
+                .and_then(|result| if let Entrant::MidosHouseTeam(team) = result.entrant { Some(team.id) } else { None })
+        } else {
+            None
+        };
+        let match_idx = if let Some(idx) = matches.iter().position(|m| m.phase == race.phase && m.round == race.round && (m.teams == [team1.id, team2.id] || m.teams == [team2.id, team1.id])) {
+            idx
+        } else {
+            matches.push(Match {
+                phase: race.phase.clone(),
+                round: race.round.clone(),
+                teams: [team1.id, team2.id],
+                wins: [0; 2],
+                finished: true,
+            });
+            matches.len() - 1
+        };
+        let m = &mut matches[match_idx];
+        match winner {
+            Some(winner) if winner == m.teams[0] => m.wins[0] += 1,
+            Some(winner) if winner == m.teams[1] => m.wins[1] += 1,
+            // unplayed, or the result has to be reported manually
+            _ => m.finished = false,
+        }
+    }
+    Ok(matches)
+}
+
+/// A round of a Swiss phase which a team sat out, see [`crate::event::swiss`].
+#[derive(Debug, Clone)]
+pub(crate) struct Bye {
+    pub(crate) phase: String,
+    pub(crate) round: String,
+    pub(crate) team: Id<Teams>,
+}
+
+impl Bye {
+    pub(crate) async fn for_event(transaction: &mut Transaction<'_, Postgres>, data: &Data<'_>) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as!(Self, r#"SELECT phase, round, team AS "team: Id<Teams>" FROM swiss_byes WHERE series = $1 AND event = $2"#, data.series as _, &data.event).fetch_all(&mut **transaction).await
+    }
+}
+
+/// The teams playing in the given matches, in order of their first match.
+fn teams(matches: &[&Match]) -> Vec<Id<Teams>> {
+    matches.iter().flat_map(|m| m.teams).unique().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Record {
+    pub(crate) team: Id<Teams>,
+    pub(crate) wins: u16,
+    pub(crate) draws: u16,
+    pub(crate) losses: u16,
+    /// Byes count as a win for points but not for the win percentage.
+    pub(crate) byes: u16,
+}
+
+impl Record {
+    fn new(team: Id<Teams>, matches: &[&Match]) -> Self {
+        let mut record = Self { team, wins: 0, draws: 0, losses: 0, byes: 0 };
+        for m in matches.iter().filter(|m| m.finished && m.teams.contains(&team)) {
+            match m.winner() {
+                Some(winner) if winner == team => record.wins += 1,
+                Some(_) => record.losses += 1,
+                None => record.draws += 1,
+            }
+        }
+        record
+    }
+
+    fn played(&self) -> u16 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// 2 points for a win or bye and 1 for a draw.
+    pub(crate) fn points(&self) -> u16 {
+        2 * (self.wins + self.byes) + self.draws
+    }
+
+    fn win_percentage(&self) -> R64 {
+        if self.played() == 0 {
+            r64(0.0)
+        } else {
+            r64(f64::from(2 * self.wins + self.draws)) / r64(f64::from(2 * self.played()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SwissRecord {
+    pub(crate) record: Record,
+    /// The average win percentage of the team's opponents in finished matches, used as the tiebreaker.
+    pub(crate) opponent_win_percentage: R64,
+}
+
+/// Each team's Swiss record, ordered by points and then by opponent win percentage.
+pub(crate) fn swiss_standings(matches: &[&Match], byes: &[&Bye]) -> Vec<SwissRecord> {
+    let teams = teams(matches).into_iter().chain(byes.iter().map(|bye| bye.team)).unique().collect_vec();
+    let records = teams.iter().map(|&team| (team, Record {
+        byes: byes.iter().filter(|bye| bye.team == team).count().try_into().expect("too many byes"),
+        ..Record::new(team, matches)
+    })).collect::<HashMap<_, _>>();
+    let mut standings = teams.into_iter().map(|team| {
+        let opponent_win_percentages = matches.iter()
+            .filter(|m| m.finished)
+            .filter_map(|m| match m.teams {
+                [team1, team2] if team1 == team => Some(records[&team2].win_percentage()),
+                [team1, team2] if team2 == team => Some(records[&team1].win_percentage()),
+                _ => None,
+            })
+            .collect_vec();
+        SwissRecord {
+            record: records[&team],
+            opponent_win_percentage: if opponent_win_percentages.is_empty() {
+                r64(0.0)
+            } else {
+                opponent_win_percentages.iter().copied().sum::<R64>() / r64(opponent_win_percentages.len() as f64)
+            },
+        }
+    }).collect_vec();
+    standings.sort_by_key(|standing| (Reverse(standing.record.points()), Reverse(standing.opponent_win_percentage)));
+    standings
+}
+
+/// The name of the group a match belongs to. Group stage rounds are expected to be named like “Group A” or “Group A Round 2”, otherwise the phase is used as the group.
+fn group_name(m: &Match) -> Option<&str> {
+    if let Some(round) = m.round.as_deref().filter(|round| round.to_lowercase().contains("group")) {
+        Some(round.split_once(" Round ").map_or(round, |(group, _)| group).trim_end_matches(','))
+    } else {
+        m.phase.as_deref()
+    }
+}
+
+/// Each group's table, ordered by points and then by wins. Groups are in order of their first match.
+pub(crate) fn group_tables<'a>(matches: &[&'a Match]) -> Vec<(Option<&'a str>, Vec<Record>)> {
+    matches.iter().map(|&m| group_name(m)).unique().map(|group| {
+        let group_matches = matches.iter().copied().filter(|&m| group_name(m) == group).collect_vec();
+        let mut records = teams(&group_matches).into_iter().map(|team| Record::new(team, &group_matches)).collect_vec();
+        records.sort_by_key(|record| (Reverse(record.points()), Reverse(record.wins)));
+        (group, records)
+    }).collect()
+}
+
+/// The matches of each round of an elimination bracket, in order of each round's first match.
+pub(crate) fn bracket_rounds<'a>(matches: &[&'a Match]) -> Vec<(Option<&'a str>, Vec<&'a Match>)> {
+    matches.iter().map(|m| m.round.as_deref()).unique().map(|round| (round, matches.iter().copied().filter(|m| m.round.as_deref() == round).collect())).collect()
+}
+
+#[rocket::get("/event/<series>/<event>/standings")]
+pub(crate) async fn get(pool: &State<PgPool>, http_client: &State<reqwest::Client>, me: Option<User>, uri: Origin<'_>, series: Series, event: &str) -> Result<RawHtml<String>, StatusOrError<Error>> {
+    let mut transaction = pool.begin().await?;
+    let data = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    if !matches!(data.match_source(), MatchSource::Manual) {
+        // standings are shown on the external bracket site
+        return Err(StatusOrError::Status(Status::NotFound))
+    }
+    let header = data.header(&mut transaction, me.as_ref(), Tab::Standings, false).await?;
+    let races = Race::for_event(&mut transaction, http_client, &data).await?;
+    let mut teams = HashMap::new();
+    for race in &races {
+        for team in race.teams() {
+            teams.entry(team.id).or_insert_with(|| team.clone());
+        }
+    }
+    let matches = matches(&mut transaction, &races).await?;
+    let byes = Bye::for_event(&mut transaction, &data).await?;
+    let phases = matches.iter().map(|m| m.phase.as_deref()).chain(byes.iter().map(|bye| Some(&*bye.phase))).unique().collect_vec();
+    let content = html! {
+        : header;
+        @if phases.is_empty() {
+            p : "No matches have been scheduled yet.";
+        }
+        @for phase in phases {
+            @let phase_matches = matches.iter().filter(|m| m.phase.as_deref() == phase).collect_vec();
+            @let phase_byes = byes.iter().filter(|bye| Some(&*bye.phase) == phase).collect_vec();
+            @if let Some(phase) = phase {
+                h2 : phase;
+            }
+            @match Format::for_phase(&mut transaction, &data, phase).await? {
+                Format::Swiss => {
+                    table {
+                        thead {
+                            tr {
+                                th : "Rank";
+                                th : "Team";
+                                th : "Wins";
+                                th : "Draws";
+                                th : "Losses";
+                                th : "Byes";
+                                th : "Opponent win %";
+                            }
+                        }
+                        tbody {
+                            @for (idx, standing) in swiss_standings(&phase_matches, &phase_byes).into_iter().enumerate() {
+                                tr {
+                                    td : idx + 1;
+                                    td {
+                                        @if let Some(team) = teams.get(&standing.record.team) {
+                                            : team.to_html(&mut transaction, false).await?;
+                                        }
+                                    }
+                                    td : standing.record.wins;
+                                    td : standing.record.draws;
+                                    td : standing.record.losses;
+                                    td : standing.record.byes;
+                                    td : format!("{:.1}%", standing.opponent_win_percentage.raw() * 100.0);
+                                }
+                            }
+                        }
+                    }
+                }
+                Format::Groups => {
+                    @for (group, records) in group_tables(&phase_matches) {
+                        @if group != phase {
+                            @if let Some(group) = group {
+                                h3 : group;
+                            }
+                        }
+                        table {
+                            thead {
+                                tr {
+                                    th : "Team";
+                                    th : "Played";
+                                    th : "Wins";
+                                    th : "Draws";
+                                    th : "Losses";
+                                    th : "Points";
+                                }
+                            }
+                            tbody {
+                                @for record in records {
+                                    tr {
+                                        td {
+                                            @if let Some(team) = teams.get(&record.team) {
+                                                : team.to_html(&mut transaction, false).await?;
+                                            }
+                                        }
+                                        td : record.played();
+                                        td : record.wins;
+                                        td : record.draws;
+                                        td : record.losses;
+                                        td : record.points();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Format::Bracket => {
+                    div(class = "bracket") {
+                        @for (round, round_matches) in bracket_rounds(&phase_matches) {
+                            div(class = "bracket-round") {
+                                @if let Some(round) = round {
+                                    h3 : round;
+                                }
+                                @for m in round_matches {
+                                    div(class = "bracket-match") {
+                                        @for (team, wins) in m.teams.into_iter().zip_eq(m.wins) {
+                                            div(class? = (m.winner() == Some(team)).then_some("winner")) {
+                                                @if let Some(team) = teams.get(&team) {
+                                                    : team.to_html(&mut transaction, false).await?;
+                                                }
+                                                @if m.finished || wins > 0 {
+                                                    span : wins;
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests: data.chests().await?, ..PageStyle::default() }, &format!("Standings — {}", data.display_name), content).await?)
+}
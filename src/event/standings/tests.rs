@@ -0,0 +1,99 @@
+use {
+    super::*,
+    crate::testing::{
+        self,
+        TestDb,
+    },
+};
+
+fn team(id: u64) -> Id<Teams> {
+    Id::from(id)
+}
+
+fn finished(phase: &str, round: &str, teams: [u64; 2], wins: [u16; 2]) -> Match {
+    Match {
+        phase: Some(phase.to_owned()),
+        round: Some(round.to_owned()),
+        teams: teams.map(team),
+        wins,
+        finished: true,
+    }
+}
+
+#[test]
+fn match_winner() {
+    assert_eq!(finished("Top 8", "Final", [1, 2], [2, 1]).winner(), Some(team(1)));
+    assert_eq!(finished("Top 8", "Final", [1, 2], [0, 2]).winner(), Some(team(2)));
+    assert_eq!(finished("Top 8", "Final", [1, 2], [1, 1]).winner(), None, "draw");
+    let unfinished = Match { finished: false, ..finished("Top 8", "Final", [1, 2], [1, 0]) };
+    assert_eq!(unfinished.winner(), None, "match still in progress");
+}
+
+#[tokio::test]
+async fn format_per_phase() -> Result<(), Error> {
+    let db = TestDb::new().await;
+    let mut transaction = db.pool.begin().await?;
+    let data = testing::event(&mut transaction, Series::Multiworld, "t", TeamConfig::Multiworld).await?;
+    Format::set_for_phase(&mut transaction, &data, "Qualifiers", Some(Format::Swiss)).await?;
+    let configured = Format::for_phase(&mut transaction, &data, Some("Qualifiers")).await?;
+    let unconfigured = Format::for_phase(&mut transaction, &data, Some("Swiss")).await?;
+    let no_phase = Format::for_phase(&mut transaction, &data, None).await?;
+    Format::set_for_phase(&mut transaction, &data, "Qualifiers", None).await?;
+    let reset = Format::for_phase(&mut transaction, &data, Some("Qualifiers")).await?;
+    transaction.rollback().await?;
+    assert_eq!(configured, Format::Swiss);
+    assert_eq!(unconfigured, Format::Bracket, "the format isn't guessed from the phase name");
+    assert_eq!(no_phase, Format::Bracket, "races without a phase use the event's default");
+    assert_eq!(reset, Format::Bracket);
+    db.cleanup().await;
+    Ok(())
+}
+
+#[test]
+fn swiss_tiebreakers() {
+    let matches = [
+        finished("Swiss", "Round 1", [1, 2], [1, 0]),
+        finished("Swiss", "Round 1", [3, 4], [1, 0]),
+        finished("Swiss", "Round 2", [1, 3], [1, 0]),
+        finished("Swiss", "Round 2", [2, 4], [0, 1]),
+        Match { finished: false, ..finished("Swiss", "Round 3", [3, 4], [0, 0]) },
+    ];
+    let standings = swiss_standings(&matches.iter().collect_vec(), &[]);
+    assert_eq!(standings.iter().map(|standing| standing.record.team).collect_vec(), [1, 3, 4, 2].map(team), "teams 3 and 4 are tied on points, but team 3 played stronger opponents");
+    assert_eq!(standings[0].record, Record { team: team(1), wins: 2, draws: 0, losses: 0, byes: 0 });
+    assert_eq!(standings[0].opponent_win_percentage, r64(0.25));
+    assert_eq!(standings[1].opponent_win_percentage, r64(0.75));
+    assert_eq!(standings[2].opponent_win_percentage, r64(0.25));
+    assert_eq!(standings[3].record, Record { team: team(2), wins: 0, draws: 0, losses: 2, byes: 0 });
+}
+
+#[test]
+fn group_names() {
+    let matches = [
+        finished("Group Stage", "Group A Round 1", [1, 2], [1, 0]),
+        finished("Group Stage", "Group B Round 1", [3, 4], [0, 1]),
+        finished("Group Stage", "Group A Round 2", [2, 1], [1, 1]),
+    ];
+    let tables = group_tables(&matches.iter().collect_vec());
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[0].0, Some("Group A"));
+    assert_eq!(tables[0].1, [
+        Record { team: team(1), wins: 1, draws: 1, losses: 0, byes: 0 },
+        Record { team: team(2), wins: 0, draws: 1, losses: 1, byes: 0 },
+    ]);
+    assert_eq!(tables[1].0, Some("Group B"));
+    assert_eq!(tables[1].1[0].team, team(4));
+    let matches = [finished("Groups", "Round 1", [1, 2], [1, 0])];
+    assert_eq!(group_tables(&matches.iter().collect_vec())[0].0, Some("Groups"), "rounds not named after a group use the phase");
+}
+
+#[test]
+fn bracket_round_order() {
+    let matches = [
+        finished("Top 4", "Semifinals", [1, 2], [2, 0]),
+        finished("Top 4", "Semifinals", [3, 4], [2, 1]),
+        finished("Top 4", "Final", [1, 3], [0, 0]),
+    ];
+    let rounds = bracket_rounds(&matches.iter().collect_vec());
+    assert_eq!(rounds.iter().map(|(round, matches)| (*round, matches.len())).collect_vec(), [(Some("Semifinals"), 2), (Some("Final"), 1)]);
+}
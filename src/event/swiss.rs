@@ -0,0 +1,383 @@
+//! Pairing the rounds of a Swiss phase on Hyrule Town Hall, for events which don't use an external bracket site.
+
+use {
+    std::cmp::Reverse,
+    noisy_float::prelude::*,
+    crate::{
+        event::{
+            Data,
+            Error,
+            Tab,
+            standings::{
+                self,
+                Bye,
+            },
+            teams,
+        },
+        prelude::*,
+    },
+};
+
+#[cfg(test)] mod tests;
+
+/// A team's position going into the next round.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Standing {
+    pub(crate) team: Id<Teams>,
+    pub(crate) points: u16,
+    pub(crate) had_bye: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Pairings {
+    /// The higher-ranked team of each match comes first.
+    pub(crate) matches: Vec<[Id<Teams>; 2]>,
+    pub(crate) byes: Vec<Id<Teams>>,
+}
+
+fn match_key(team1: Id<Teams>, team2: Id<Teams>) -> [Id<Teams>; 2] {
+    let mut key = [team1, team2];
+    key.sort_unstable();
+    key
+}
+
+/// Pairs the next round. `standings` must be ordered by rank.
+///
+/// Byes go to the lowest-ranked teams which haven't had one yet. The remaining teams play within their score group where possible, with the top half of a score group facing the bottom half, so the first round pairs seed 1 against the top seed of the bottom half. Returns `None` if the teams can't be paired without rematches, or if no such pairing was found within a reasonable amount of time.
+pub(crate) fn pair(standings: &[Standing], previous_matches: &HashSet<[Id<Teams>; 2]>, num_byes: usize) -> Option<Pairings> {
+    if num_byes > standings.len() || (standings.len() - num_byes) % 2 != 0 { return None }
+    let mut byes = standings.iter().rev().filter(|standing| !standing.had_bye).take(num_byes).map(|standing| standing.team).collect_vec();
+    // once everyone has had a bye, the lowest-ranked teams get another one
+    let repeat_byes = standings.iter().rev().map(|standing| standing.team).filter(|team| !byes.contains(team)).take(num_byes - byes.len()).collect_vec();
+    byes.extend(repeat_byes);
+    let remaining = standings.iter().filter(|standing| !byes.contains(&standing.team)).collect_vec();
+    Some(Pairings {
+        matches: pair_remaining(&remaining, previous_matches)?,
+        byes,
+    })
+}
+
+/// Upper bound on the number of partial pairings considered by [`pair_remaining`], so a history of rematches that makes the search expensive can't stall the organizer's request.
+const MAX_SEARCH_STEPS: usize = 10_000;
+
+/// Backtracking search for a pairing without rematches, returning the matches in rank order.
+fn pair_remaining(remaining: &[&Standing], previous_matches: &HashSet<[Id<Teams>; 2]>) -> Option<Vec<[Id<Teams>; 2]>> {
+    let mut steps = 0;
+    let mut matches = search_pairing(remaining.to_vec(), previous_matches, &mut steps)?;
+    // teams with only one possible opponent are paired out of order
+    let rank = |team: &Id<Teams>| remaining.iter().position(|standing| standing.team == *team).expect("paired team not in standings");
+    for teams in &mut matches {
+        teams.sort_unstable_by_key(rank);
+    }
+    matches.sort_unstable_by_key(|teams| rank(&teams[0]));
+    Some(matches)
+}
+
+/// Pairs the top-ranked team with its preferred opponent and recurses, backtracking on failure.
+///
+/// Branches which can't lead to a pairing are cut off early: if some teams can only play each other and there's an odd number of them, there's no pairing at all, which also covers teams who have already played everyone. Teams with only one possible opponent are paired first since they have no other choice.
+fn search_pairing(remaining: Vec<&Standing>, previous_matches: &HashSet<[Id<Teams>; 2]>, steps: &mut usize) -> Option<Vec<[Id<Teams>; 2]>> {
+    if remaining.is_empty() { return Some(Vec::default()) }
+    *steps += 1;
+    if *steps > MAX_SEARCH_STEPS { return None }
+    let opponents = remaining.iter().map(|team| remaining.iter().positions(|opponent| opponent.team != team.team && !previous_matches.contains(&match_key(team.team, opponent.team))).collect_vec()).collect_vec();
+    if has_odd_component(&opponents) { return None }
+    let (team_idx, candidates) = if let Some(forced) = opponents.iter().position(|opponents| opponents.len() == 1) {
+        (forced, opponents[forced].clone())
+    } else {
+        let top = remaining[0];
+        let score_group_len = 1 + remaining[1..].iter().take_while(|standing| standing.points == top.points).count();
+        let ideal_idx = score_group_len / 2;
+        (0, opponents[0].iter().copied().sorted_by_key(|&idx| (top.points.abs_diff(remaining[idx].points), idx.abs_diff(ideal_idx.max(1)), idx)).collect())
+    };
+    for opponent_idx in candidates {
+        let rest = remaining.iter().enumerate().filter(|&(idx, _)| idx != team_idx && idx != opponent_idx).map(|(_, standing)| *standing).collect();
+        if let Some(mut matches) = search_pairing(rest, previous_matches, steps) {
+            matches.push([remaining[team_idx].team, remaining[opponent_idx].team]);
+            return Some(matches)
+        }
+    }
+    None
+}
+
+/// Whether the graph given by the adjacency lists has a connected component with an odd number of nodes, in which case it has no perfect matching.
+fn has_odd_component(adjacency: &[Vec<usize>]) -> bool {
+    let mut visited = vec![false; adjacency.len()];
+    for start in 0..adjacency.len() {
+        if visited[start] { continue }
+        visited[start] = true;
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(node) = stack.pop() {
+            size += 1;
+            for &neighbor in &adjacency[node] {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        if size % 2 != 0 { return true }
+    }
+    false
+}
+
+/// The next round of a Swiss phase, as it will be published.
+pub(crate) struct Round {
+    phase: String,
+    round: String,
+    game_count: i16,
+    /// One race per match, with `game` set to the number of games, as expected by [`cal::import_race`].
+    races: Vec<Race>,
+    byes: Vec<Team>,
+}
+
+/// Pairs the next round of the given phase. The inner error is a message for the organizer.
+async fn next_round(transaction: &mut Transaction<'_, Postgres>, http_client: &reqwest::Client, data: &Data<'_>, phase: &str, num_byes: Option<u16>, game_count: i16) -> Result<Result<Round, String>, Error> {
+    let races = Race::for_event(&mut *transaction, http_client, data).await?
+        .into_iter()
+        .filter(|race| race.phase.as_deref() == Some(phase))
+        .collect_vec();
+    let matches = standings::matches(&mut *transaction, &races).await?;
+    if let Some(unfinished) = matches.iter().find(|m| !m.finished) {
+        return Ok(Err(format!("{} is not finished yet. The next round can be paired once the results of all matches have been recorded.", unfinished.round.as_deref().unwrap_or("The previous round"))))
+    }
+    let byes = Bye::for_event(&mut *transaction, data).await?
+        .into_iter()
+        .filter(|bye| bye.phase == phase)
+        .collect_vec();
+    let round = format!("Round {}", 1 + matches.iter().map(|m| m.round.as_deref()).chain(byes.iter().map(|bye| Some(&*bye.round))).unique().count());
+    let qualifier_kind = teams::qualifier_kind(&mut *transaction, data, None).await?;
+    let seeds = teams::signups_sorted(&mut *transaction, &mut teams::Cache::new(http_client.clone()), None, data, true, qualifier_kind, None).await?
+        .into_iter()
+        .filter(|signup| signup.members.iter().all(|member| member.is_confirmed))
+        .filter_map(|signup| signup.team)
+        .collect_vec();
+    if seeds.len() < 2 {
+        return Ok(Err(format!("At least two teams must be signed up to pair a round.")))
+    }
+    let num_byes = num_byes.map_or(seeds.len() % 2, usize::from);
+    if num_byes > seeds.len() || (seeds.len() - num_byes) % 2 != 0 {
+        return Ok(Err(format!("With {} teams, the number of byes must be {}.", seeds.len(), if seeds.len() % 2 == 0 { "even" } else { "odd" })))
+    }
+    let records = standings::swiss_standings(&matches.iter().collect_vec(), &byes.iter().collect_vec())
+        .into_iter()
+        .map(|standing| (standing.record.team, standing))
+        .collect::<HashMap<_, _>>();
+    // teams are ranked by their Swiss record and then by seed
+    let standings = seeds.iter()
+        .map(|team| records.get(&team.id).map_or((team.id, 0, r64(0.0)), |standing| (team.id, standing.record.points(), standing.opponent_win_percentage)))
+        .sorted_by_key(|&(_, points, opponent_win_percentage)| (Reverse(points), Reverse(opponent_win_percentage)))
+        .map(|(team, points, _)| Standing {
+            team,
+            points,
+            had_bye: byes.iter().any(|bye| bye.team == team),
+        })
+        .collect_vec();
+    let previous_matches = matches.iter().map(|m| match_key(m.teams[0], m.teams[1])).collect();
+    let Some(pairings) = pair(&standings, &previous_matches, num_byes) else {
+        return Ok(Err(format!("No pairing without rematches was found for this round. You can still create its races manually.")))
+    };
+    let team = |id: Id<Teams>| seeds.iter().find(|team| team.id == id).expect("paired team not in seeding").clone();
+    Ok(Ok(Round {
+        races: pairings.matches.into_iter().map(|[team1, team2]| Race {
+            id: Id::dummy(),
+            series: data.series,
+            event: data.event.to_string(),
+            source: cal::Source::Manual,
+            entrants: Entrants::Two([
+                Entrant::MidosHouseTeam(team(team1)),
+                Entrant::MidosHouseTeam(team(team2)),
+            ]),
+            phase: Some(phase.to_owned()),
+            round: Some(round.clone()),
+            game: Some(game_count),
+            schedule: RaceSchedule::Unscheduled,
+            schedule_updated_at: None,
+            fpa_invoked: false,
+            breaks_used: false,
+            draft: None,
+            seed: seed::Data::default(),
+            video_urls: HashMap::default(),
+            restreamers: HashMap::default(),
+            last_edited_by: None,
+            last_edited_at: None,
+            ignored: false,
+            schedule_locked: false,
+            notified: false,
+            async_notified_1: false,
+            async_notified_2: false,
+            async_notified_3: false,
+            scheduling_thread: None,
+        }).collect(),
+        byes: pairings.byes.into_iter().map(team).collect(),
+        phase: phase.to_owned(),
+        round,
+        game_count,
+    }))
+}
+
+async fn swiss_form(mut transaction: Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, http_client: &reqwest::Client, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, event: Data<'_>, ctx: Context<'_>, preview: Option<Round>) -> Result<RawHtml<String>, Error> {
+    let header = event.header(&mut transaction, me.as_ref(), Tab::Races, true).await?;
+    let form = if let Some(ref me) = me {
+        if !matches!(event.match_source(), MatchSource::Manual) {
+            html! {
+                article {
+                    p : "Races for this event are paired on an external bracket site.";
+                }
+            }
+        } else if event.organizers(&mut transaction).await?.contains(me) {
+            let mut errors = ctx.errors().collect_vec();
+            let settings = full_form(uri!(post(event.series, &*event.event)), csrf, html! {
+                : form_field("phase", &mut errors, html! {
+                    label(for = "phase") : "Phase:";
+                    input(type = "text", name = "phase", value = ctx.field_value("phase").unwrap_or("Swiss"));
+                });
+                : form_field("byes", &mut errors, html! {
+                    label(for = "byes") : "Number of byes:";
+                    input(type = "number", min = "0", name = "byes", value? = ctx.field_value("byes"));
+                    label(class = "help") : "(Leave blank for one bye if there is an odd number of teams. Byes go to the lowest-ranked teams who haven't had one yet and count as a win.)";
+                });
+                : form_field("game_count", &mut errors, html! {
+                    label(for = "game_count") : "Number of games per match:";
+                    input(type = "number", min = "1", max = "255", name = "game_count", value = ctx.field_value("game_count").map_or_else(|| event.default_game_count.to_string(), |game_count| game_count.to_owned()));
+                });
+            }, errors, "Preview");
+            let preview = if let Some(Round { phase, round, game_count, races, byes }) = preview {
+                let table = cal::race_table(&mut transaction, discord_ctx, http_client, &uri, Some(&event), cal::RaceTableOptions { game_count: true, show_multistreams: false, can_create: false, can_edit: false, show_restream_consent: false, challonge_import_ctx: None }, &races, None, None).await?;
+                let mut bye_teams = Vec::with_capacity(byes.len());
+                for team in &byes {
+                    bye_teams.push(team.to_html(&mut transaction, false).await?);
+                }
+                let (_, button) = button_form_ext(uri!(post(event.series, &*event.event)), csrf, Vec::default(), html! {
+                    input(type = "hidden", name = "phase", value = &phase);
+                    input(type = "hidden", name = "byes", value = byes.len());
+                    input(type = "hidden", name = "game_count", value = game_count);
+                    input(type = "hidden", name = "round", value = &round);
+                }, &format!("Publish {round}"));
+                html! {
+                    h3 : format!("{phase} {round}");
+                    @if races.is_empty() {
+                        p : "This round has no matches.";
+                    } else {
+                        : table;
+                    }
+                    @if let Some(bye_teams) = English.join_html_opt(bye_teams) {
+                        p {
+                            : "Byes: ";
+                            : bye_teams;
+                        }
+                    }
+                    p : "Publishing creates the races and opens a scheduling thread for each match.";
+                    : button;
+                }
+            } else {
+                html! {}
+            };
+            html! {
+                : settings;
+                : preview;
+            }
+        } else {
+            html! {
+                article {
+                    p : "This page is only available to event organizers.";
+                }
+            }
+        }
+    } else {
+        html! {
+            article {
+                p {
+                    a(href = uri!(auth::login(Some(uri!(get(event.series, &*event.event)))))) : "Sign in or create a Hyrule Town Hall account";
+                    : " to pair Swiss rounds.";
+                }
+            }
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests: event.chests().await?, ..PageStyle::default() }, &format!("Swiss Pairings — {}", event.display_name), html! {
+        : header;
+        h2 : "Pair next Swiss round";
+        : form;
+    }).await?)
+}
+
+#[rocket::get("/event/<series>/<event>/races/swiss")]
+pub(crate) async fn get(pool: &State<PgPool>, discord_ctx: &State<RwFuture<DiscordCtx>>, http_client: &State<reqwest::Client>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str) -> Result<RawHtml<String>, StatusOrError<Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    Ok(swiss_form(transaction, &*discord_ctx.read().await, http_client, me, uri, csrf.as_ref(), event, Context::default(), None).await?)
+}
+
+#[derive(FromForm, CsrfForm)]
+pub(crate) struct SwissForm {
+    #[field(default = String::new())]
+    csrf: String,
+    phase: String,
+    byes: Option<u16>,
+    game_count: i16,
+    /// The round shown in the preview. Empty when requesting a preview.
+    #[field(default = String::new())]
+    round: String,
+}
+
+#[rocket::post("/event/<series>/<event>/races/swiss", data = "<form>")]
+pub(crate) async fn post(pool: &State<PgPool>, discord_ctx: &State<RwFuture<DiscordCtx>>, http_client: &State<reqwest::Client>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, series: Series, event: &str, form: Form<Contextual<'_, SwissForm>>) -> Result<RedirectOrContent, StatusOrError<Error>> {
+    let mut transaction = pool.begin().await?;
+    let event = Data::new(&mut transaction, series, event).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    if !event.organizers(&mut transaction).await?.contains(&me) {
+        form.context.push_error(form::Error::validation("You must be an organizer of this event to pair Swiss rounds."));
+    }
+    if !matches!(event.match_source(), MatchSource::Manual) {
+        form.context.push_error(form::Error::validation("Races for this event are paired on an external bracket site."));
+    }
+    Ok(if let Some(ref value) = form.value {
+        if value.phase.trim().is_empty() {
+            form.context.push_error(form::Error::validation("This field is required.").with_name("phase"));
+        }
+        if value.game_count < 1 {
+            form.context.push_error(form::Error::validation("Each match must have at least one game.").with_name("game_count"));
+        }
+        let round = if form.context.errors().next().is_none() {
+            match next_round(&mut transaction, http_client, &event, &value.phase, value.byes, value.game_count).await? {
+                Ok(round) => Some(round),
+                Err(msg) => {
+                    form.context.push_error(form::Error::validation(msg));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(round) = round {
+            if value.round.is_empty() {
+                RedirectOrContent::Content(swiss_form(transaction, &*discord_ctx.read().await, http_client, Some(me), uri, csrf.as_ref(), event, form.context, Some(round)).await?)
+            } else if value.round != round.round {
+                form.context.push_error(form::Error::validation("The results have changed since the preview. Please review the new pairings."));
+                RedirectOrContent::Content(swiss_form(transaction, &*discord_ctx.read().await, http_client, Some(me), uri, csrf.as_ref(), event, form.context, Some(round)).await?)
+            } else {
+                standings::Format::set_for_phase(&mut transaction, &event, &round.phase, Some(standings::Format::Swiss)).await?;
+                for bye in &round.byes {
+                    sqlx::query!("INSERT INTO swiss_byes (series, event, phase, round, team) VALUES ($1, $2, $3, $4, $5)", event.series as _, &event.event, &round.phase, &round.round, bye.id as _).execute(&mut *transaction).await?;
+                }
+                let discord_ctx = discord_ctx.read().await;
+                for mut race in round.races {
+                    if let Some(draft_kind) = event.draft_kind() {
+                        let Entrants::Two([Entrant::MidosHouseTeam(ref team1), Entrant::MidosHouseTeam(ref team2)]) = race.entrants else { unreachable!("Swiss pairings are between two teams") };
+                        let draft = Draft::for_game1(&mut transaction, http_client, draft_kind, &event, race.phase.as_deref(), [team1, team2]).await?;
+                        race.draft = Some(draft);
+                    }
+                    transaction = cal::import_race(transaction, &discord_ctx, race).await?;
+                }
+                transaction.commit().await?;
+                RedirectOrContent::Redirect(Redirect::to(uri!(super::races(event.series, &*event.event))))
+            }
+        } else {
+            RedirectOrContent::Content(swiss_form(transaction, &*discord_ctx.read().await, http_client, Some(me), uri, csrf.as_ref(), event, form.context, None).await?)
+        }
+    } else {
+        RedirectOrContent::Content(swiss_form(transaction, &*discord_ctx.read().await, http_client, Some(me), uri, csrf.as_ref(), event, form.context, None).await?)
+    })
+}
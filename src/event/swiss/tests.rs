@@ -0,0 +1,76 @@
+use super::*;
+
+fn team(id: u64) -> Id<Teams> {
+    Id::from(id)
+}
+
+fn ranked(points_and_byes: &[(u64, u16, bool)]) -> Vec<Standing> {
+    points_and_byes.iter().map(|&(id, points, had_bye)| Standing { team: team(id), points, had_bye }).collect()
+}
+
+#[test]
+fn first_round_folds_seeding() {
+    let standings = ranked(&(1..=8).map(|id| (id, 0, false)).collect_vec());
+    assert_eq!(pair(&standings, &HashSet::default(), 0), Some(Pairings {
+        matches: vec![[1, 5], [2, 6], [3, 7], [4, 8]].into_iter().map(|teams| teams.map(team)).collect(),
+        byes: Vec::default(),
+    }));
+}
+
+#[test]
+fn pairs_within_score_groups() {
+    let standings = ranked(&[(1, 2, false), (3, 2, false), (2, 0, false), (4, 0, false)]);
+    let pairings = pair(&standings, &HashSet::default(), 0).expect("no pairing found");
+    assert_eq!(pairings.matches, [[1, 3], [2, 4]].map(|teams| teams.map(team)));
+}
+
+#[test]
+fn avoids_rematches() {
+    let standings = ranked(&[(1, 2, false), (3, 2, false), (2, 0, false), (4, 0, false)]);
+    let previous_matches = [match_key(team(1), team(3)), match_key(team(2), team(4))].into_iter().collect();
+    let pairings = pair(&standings, &previous_matches, 0).expect("no pairing found");
+    assert_eq!(pairings.matches, [[1, 2], [3, 4]].map(|teams| teams.map(team)));
+}
+
+#[test]
+fn no_pairing_without_rematches() {
+    let standings = ranked(&[(1, 2, false), (2, 0, false)]);
+    let previous_matches = [match_key(team(1), team(2))].into_iter().collect();
+    assert_eq!(pair(&standings, &previous_matches, 0), None);
+}
+
+#[test]
+fn byes() {
+    let standings = ranked(&[(1, 2, false), (2, 2, false), (3, 0, false), (4, 0, false), (5, 0, true)]);
+    let pairings = pair(&standings, &HashSet::default(), 1).expect("no pairing found");
+    assert_eq!(pairings.byes, [team(4)], "the lowest-ranked team already had a bye");
+    assert_eq!(pairings.matches, [[1, 2], [3, 5]].map(|teams| teams.map(team)));
+    assert_eq!(pair(&standings, &HashSet::default(), 2), None, "an odd number of teams would be left");
+    let standings = ranked(&[(1, 2, true), (2, 0, true), (3, 0, true)]);
+    assert_eq!(pair(&standings, &HashSet::default(), 1).expect("no pairing found").byes, [team(3)], "everyone already had a bye");
+}
+
+#[test]
+fn team_who_played_everyone_fails_fast() {
+    let standings = ranked(&(1..=32).map(|id| (id, 0, false)).collect_vec());
+    let previous_matches = (1..32).map(|id| match_key(team(id), team(32))).collect();
+    assert_eq!(pair(&standings, &previous_matches, 0), None);
+}
+
+#[test]
+fn odd_group_that_can_only_play_each_other() {
+    let standings = ranked(&(1..=32).map(|id| (id, 0, false)).collect_vec());
+    // teams 30 to 32 have played everyone except each other, so one of them would be left over
+    let previous_matches = (30..=32).flat_map(|id| (1..30).map(move |opponent| match_key(team(opponent), team(id)))).collect();
+    assert_eq!(pair(&standings, &previous_matches, 0), None);
+}
+
+#[test]
+fn pairs_team_with_its_only_possible_opponent() {
+    let standings = ranked(&(1..=8).map(|id| (id, 0, false)).collect_vec());
+    // team 8 can only play team 1, so team 1 doesn't get its usual opponent
+    let previous_matches = (2..8).map(|id| match_key(team(id), team(8))).collect();
+    let pairings = pair(&standings, &previous_matches, 0).expect("no pairing found");
+    assert_eq!(pairings.matches[0], [team(1), team(8)]);
+    assert_eq!(pairings.matches.len(), 4);
+}
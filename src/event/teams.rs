@@ -799,6 +799,28 @@ impl<E: Into<Error>> From<E> for StatusOrError<Error> {
     }
 }
 
+/// Determines how qualification for this event is displayed on the teams page.
+pub(crate) async fn qualifier_kind(transaction: &mut Transaction<'_, Postgres>, data: &Data<'_>, me: Option<&User>) -> Result<QualifierKind, DataError> {
+    Ok(match (data.series, &*data.event) {
+        (Series::SongsOfHope, "1") => QualifierKind::SongsOfHope,
+        (Series::SpeedGaming, "2023onl") => QualifierKind::Score(QualifierScoreKind::Sgl2023Online),
+        (Series::SpeedGaming, "2024onl") => QualifierKind::Score(QualifierScoreKind::Sgl2024Online),
+        (Series::Standard, "8") => QualifierKind::Score(QualifierScoreKind::Standard),
+        (_, _) => if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM teams WHERE series = $1 AND event = $2 AND qualifier_rank IS NOT NULL) AS "exists!""#, data.series as _, &data.event).fetch_one(&mut **transaction).await? {
+            QualifierKind::Rank
+        } else if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM asyncs WHERE series = $1 AND event = $2 AND kind = 'qualifier') AS "exists!""#, data.series as _, &data.event).fetch_one(&mut **transaction).await? {
+            QualifierKind::Single {
+                show_times: data.show_qualifier_times && (
+                    sqlx::query_scalar!(r#"SELECT submitted IS NOT NULL AS "qualified!" FROM teams, async_teams, team_members WHERE async_teams.team = teams.id AND teams.series = $1 AND teams.event = $2 AND async_teams.team = team_members.team AND member = $3 AND kind = 'qualifier'"#, data.series as _, &data.event, me.map(|me| PgSnowflake(me.id)) as _).fetch_optional(&mut **transaction).await?.unwrap_or(false)
+                    || data.is_started(transaction).await?
+                ),
+            }
+        } else {
+            QualifierKind::None
+        },
+    })
+}
+
 pub(crate) async fn list(pool: &PgPool, http_client: &reqwest::Client, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>, ctx: Context<'_>, series: Series, event: &str) -> Result<RawHtml<String>, StatusOrError<Error>> {
     enum ShowStatus {
         Detailed,
@@ -816,34 +838,14 @@ pub(crate) async fn list(pool: &PgPool, http_client: &reqwest::Client, me: Optio
     } else {
         false
     };
-    let qualifier_kind = match (data.series, &*data.event) {
-        (Series::SongsOfHope, "1") => QualifierKind::SongsOfHope,
-        (Series::SpeedGaming, "2023onl" | "2024onl") | (Series::Standard, "8") => {
-            if is_organizer {
-                show_status = ShowStatus::Detailed;
-            } else if !data.is_started(&mut transaction).await? && Race::for_event(&mut transaction, http_client, &data).await?.into_iter().all(|race| race.phase.as_ref().is_none_or(|phase| phase != "Qualifier") || race.is_ended()) {
-                show_status = ShowStatus::Confirmed;
-            }
-            QualifierKind::Score(match (data.series, &*data.event) {
-                (Series::SpeedGaming, "2023onl") => QualifierScoreKind::Sgl2023Online,
-                (Series::SpeedGaming, "2024onl") => QualifierScoreKind::Sgl2024Online,
-                (Series::Standard, "8") => QualifierScoreKind::Standard,
-                _ => unreachable!("checked by outer match"),
-            })
+    let qualifier_kind = qualifier_kind(&mut transaction, &data, me.as_ref()).await?;
+    if let QualifierKind::Score(_) = qualifier_kind {
+        if is_organizer {
+            show_status = ShowStatus::Detailed;
+        } else if !data.is_started(&mut transaction).await? && Race::for_event(&mut transaction, http_client, &data).await?.into_iter().all(|race| race.phase.as_ref().is_none_or(|phase| phase != "Qualifier") || race.is_ended()) {
+            show_status = ShowStatus::Confirmed;
         }
-        (_, _) => if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM teams WHERE series = $1 AND event = $2 AND qualifier_rank IS NOT NULL) AS "exists!""#, series as _, event).fetch_one(&mut *transaction).await? {
-            QualifierKind::Rank
-        } else if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM asyncs WHERE series = $1 AND event = $2 AND kind = 'qualifier') AS "exists!""#, series as _, event).fetch_one(&mut *transaction).await? {
-            QualifierKind::Single {
-                show_times: data.show_qualifier_times && (
-                    sqlx::query_scalar!(r#"SELECT submitted IS NOT NULL AS "qualified!" FROM teams, async_teams, team_members WHERE async_teams.team = teams.id AND teams.series = $1 AND teams.event = $2 AND async_teams.team = team_members.team AND member = $3 AND kind = 'qualifier'"#, series as _, event, me.as_ref().map(|me| PgSnowflake(me.id)) as _).fetch_optional(&mut *transaction).await?.unwrap_or(false)
-                    || data.is_started(&mut transaction).await?
-                ),
-            }
-        } else {
-            QualifierKind::None
-        },
-    };
+    }
     let show_restream_consent = is_organizer || if let Some(ref me) = me {
         data.restreamers(&mut transaction).await?.contains(me)
     } else {
@@ -909,7 +911,7 @@ pub(crate) async fn list(pool: &PgPool, http_client: &reqwest::Client, me: Optio
         ShowStatus::None => {}
     }
     match data.draft_kind() {
-        None | Some(draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5) => {}
+        None | Some(draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_)) => {}
         Some(draft::Kind::RslS7) => column_headers.push(html! {
             th : "RSL-Lite OK";
         }),
@@ -1231,7 +1233,7 @@ pub(crate) async fn list(pool: &PgPool, http_client: &reqwest::Client, me: Optio
                                 ShowStatus::None => {}
                             }
                             @match data.draft_kind() {
-                                None | Some(draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5) => {}
+                                None | Some(draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_)) => {}
                                 Some(draft::Kind::RslS7) => td {
                                     @if lite_ok {
                                         : "✓";
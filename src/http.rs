@@ -378,6 +378,7 @@ async fn archive(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, sort:
     for row in sqlx::query!(r#"SELECT series AS "series: Series", event FROM events WHERE listed AND end_time IS NOT NULL AND end_time <= NOW() ORDER BY end_time DESC"#).fetch_all(&mut *transaction).await? {
         past_events.push(event::Data::new(&mut transaction, row.series, row.event).await?.expect("event deleted during transaction"));
     }
+    let series_names = Series::all(&mut transaction).await?.into_iter().collect::<HashMap<_, _>>();
     let chests_event = past_events.choose(&mut rng());
     let chests = if let Some(event) = chests_event { event.chests().await? } else { ChestAppearances::random() };
     let page_content = html! {
@@ -407,8 +408,8 @@ async fn archive(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, sort:
                 ArchiveSortKey::Series => Either::Right(
                     past_events.into_iter().into_group_map_by(|event| event.series)
                         .into_iter()
-                        .sorted_by_key(|(series, _)| series.display_name())
-                        .map(|(series, events)| (Cow::Borrowed(series.display_name()), events))
+                        .map(|(series, events)| (Cow::Borrowed(&*series_names[&series]), events))
+                        .sorted_by(|(name1, _), (name2, _)| name1.cmp(name2))
                 ),
             };
             @for (heading, events) in past_events {
@@ -428,13 +429,96 @@ async fn archive(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, sort:
     Ok(page(transaction, &me, &uri, PageStyle { chests, ..PageStyle::default() }, "Event Archive — Hyrule Town Hall", page_content).await?)
 }
 
+#[rocket::get("/series/<series>")]
+async fn series_page(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, series: Series) -> Result<RawHtml<String>, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let display_name = series.display_name(&mut transaction).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut ongoing_events = Vec::default();
+    let mut upcoming_events = Vec::default();
+    let mut past_events = Vec::default();
+    for event in sqlx::query_scalar!(r#"SELECT event FROM events WHERE listed AND series = $1 ORDER BY start ASC NULLS LAST"#, series as _).fetch_all(&mut *transaction).await? {
+        let event = event::Data::new(&mut transaction, series, event).await?.expect("event deleted during transaction");
+        if event.end.is_some_and(|end| end <= Utc::now()) {
+            past_events.push(event);
+        } else if event.is_started(&mut transaction).await? {
+            ongoing_events.push(event);
+        } else {
+            upcoming_events.push(event);
+        }
+    }
+    past_events.sort_by_key(|event| Reverse(event.end));
+    let chests_event = ongoing_events.iter().chain(&upcoming_events).chain(&past_events).collect_vec().choose(&mut rng()).copied();
+    let chests = if let Some(event) = chests_event { event.chests().await? } else { ChestAppearances::random() };
+    let page_content = html! {
+        h1 : &display_name;
+        div(class = "section-list") {
+            div {
+                h2 : "Ongoing events";
+                ul {
+                    @if ongoing_events.is_empty() {
+                        i : "(none currently)";
+                    } else {
+                        @for event in ongoing_events {
+                            li : event;
+                        }
+                    }
+                }
+            }
+            div {
+                h2 : "Upcoming events";
+                ul {
+                    @if upcoming_events.is_empty() {
+                        i : "(none currently)";
+                    } else {
+                        @for event in upcoming_events {
+                            li {
+                                : event;
+                                @if let Some(start) = event.start(&mut transaction).await? {
+                                    : " — ";
+                                    : format_datetime(start, DateTimeFormat { long: false, running_text: false });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        h2 : "Past events";
+        ul {
+            @if past_events.is_empty() {
+                i : "(none currently)";
+            } else {
+                @for event in past_events {
+                    li {
+                        : event;
+                        @if let Some(start) = event.start(&mut transaction).await? {
+                            : " — ";
+                            : format_date_range(start, event.end.expect("checked above"));
+                        }
+                    }
+                }
+            }
+        }
+        p {
+            a(href = uri!(cal::for_series(series))) : "Race calendar";
+            : " (can be added to most calendar apps)";
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle { chests, ..PageStyle::default() }, &format!("{display_name} — Hyrule Town Hall"), page_content).await?)
+}
+
 #[rocket::get("/new")]
 async fn new_event(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>) -> PageResult {
     let mut transaction = pool.begin().await?;
     let trez = User::from_id(&mut *transaction, Id::from(16287394041462225947_u64)).await?.ok_or(PageError::TrezUserData(2))?;
     page(transaction, &me, &uri, PageStyle::default(), "New Event — Hyrule Town Hall", html! {
         p {
-            : "If you are planning a tournament, community race, or other event for the Zelda Speedrunning or randomizer community, or if you would like Hyrule Town Hall to archive data about a past event you organized, please contact ";
+            : "If you are planning a tournament, community race, or other event for the Zelda Speedrunning or randomizer community, you can ";
+            a(href = uri!(crate::new_event::get)) : "propose it here";
+            : ".";
+        }
+        p {
+            : "If you would like Hyrule Town Hall to archive data about a past event you organized, or if your event has needs the proposal form doesn't cover, please contact ";
             : trez;
             : " to determine the specific needs of the event.";
         }
@@ -540,11 +624,18 @@ pub(crate) async fn rocket(pool: PgPool, discord_ctx: RwFuture<DiscordCtx>, http
     .mount("/", rocket::routes![
         index,
         archive,
+        series_page,
         new_event,
+        crate::new_event::approve,
+        crate::new_event::get,
+        crate::new_event::post,
+        crate::new_event::reject,
+        crate::new_event::review,
         robots_txt,
         api::graphql_request,
         api::graphql_query,
         api::graphql_playground,
+        api::graphql_subscription,
         api::entrants_csv,
         auth::racetime_callback,
         auth::discord_callback,
@@ -572,6 +663,8 @@ pub(crate) async fn rocket(pool: PgPool, discord_ctx: RwFuture<DiscordCtx>, http
         cal::edit_race_post,
         cal::add_file_hash,
         cal::add_file_hash_post,
+        cal::race_results,
+        cal::race_results_post,
         event::info,
         event::races,
         event::status,
@@ -589,11 +682,19 @@ pub(crate) async fn rocket(pool: PgPool, discord_ctx: RwFuture<DiscordCtx>, http
         event::enter::get,
         event::enter::post,
         event::teams::get,
+        event::standings::get,
+        event::swiss::get,
+        event::swiss::post,
         event::configure::get,
         event::configure::post,
         event::configure::restreamers_get,
         event::configure::add_restreamer,
         event::configure::remove_restreamer,
+        event::configure::webhooks_get,
+        event::configure::add_webhook,
+        event::configure::remove_webhook,
+        event::configure::test_webhook,
+        event::configure::regenerate_webhook_secret,
         event::configure::search_users,
         event::roles::get,
         event::roles::add_role_binding,
@@ -609,6 +710,9 @@ pub(crate) async fn rocket(pool: PgPool, discord_ctx: RwFuture<DiscordCtx>, http
         event::roles::revoke_signup,
         event::roles::revoke_role_request,
         event::roles::match_signup_page_get,
+        draft::web::get,
+        draft::web::post,
+        draft::web::undo,
         favicon::favicon_ico,
         favicon::favicon_png,
         legal::legal_disclaimer,
@@ -617,6 +721,7 @@ pub(crate) async fn rocket(pool: PgPool, discord_ctx: RwFuture<DiscordCtx>, http
         crate::mw::install_macos,
         notification::notifications,
         notification::dismiss,
+        scheduler::jobs,
         seed::get,
         user::profile,
     ])
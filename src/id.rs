@@ -87,6 +87,22 @@ impl Table for Signups {
     }
 }
 
+pub(crate) enum EventProposals {}
+
+impl Table for EventProposals {
+    fn query_exists(id: i64) -> sqlx::query::QueryScalar<'static, Postgres, bool, <Postgres as Database>::Arguments<'static>> {
+        sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM event_proposals WHERE id = $1) AS "exists!""#, id)
+    }
+}
+
+pub(crate) enum Webhooks {}
+
+impl Table for Webhooks {
+    fn query_exists(id: i64) -> sqlx::query::QueryScalar<'static, Postgres, bool, <Postgres as Database>::Arguments<'static>> {
+        sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM webhooks WHERE id = $1) AS "exists!""#, id)
+    }
+}
+
 #[derive(Derivative, Deserialize, Serialize)]
 #[cfg_attr(unix, derive(Protocol), async_proto(where(T: Sync)))]
 #[derivative(Debug(bound = ""), Clone(bound = ""), Copy(bound = ""), PartialEq(bound = ""), Eq(bound = ""), Hash(bound = ""), PartialOrd(bound = ""), Ord(bound = ""))]
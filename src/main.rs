@@ -50,18 +50,23 @@ mod lang;
 mod legal;
 #[macro_use] mod macros;
 mod mw;
+mod new_event;
 mod notification;
 mod ootr_web;
 mod prelude;
 mod racetime_bot;
+mod scheduler;
 mod seed;
 mod series;
 mod sheets;
 mod startgg;
 mod team;
+#[cfg(test)] mod testing;
 mod time;
 #[cfg(unix)] mod unix_socket;
 mod user;
+mod webhook;
+mod zpf;
 
 include!(concat!(env!("OUT_DIR"), "/version.rs"));
 
@@ -145,6 +150,7 @@ enum Error {
     #[cfg(unix)] #[error(transparent)] Read(#[from] async_proto::ReadError),
     #[error(transparent)] Reqwest(#[from] reqwest::Error),
     #[error(transparent)] Rocket(#[from] rocket::Error),
+    #[error(transparent)] Scheduler(#[from] scheduler::Error),
     #[error(transparent)] Serenity(#[from] serenity::Error),
     #[error(transparent)] Sql(#[from] sqlx::Error),
     #[error(transparent)] Task(#[from] tokio::task::JoinError),
@@ -158,6 +164,10 @@ async fn main(Args { port, subcommand }: Args) -> Result<(), Error> {
         #[cfg(unix)] let mut sock = UnixStream::connect(unix_socket::PATH).await?;
         #[cfg(unix)] subcommand.write(&mut sock).await?;
         match subcommand {
+            #[cfg(unix)] Subcommand::BackfillFileHashes => {
+                let num_updated = u64::read(&mut sock).await?;
+                println!("{} HTH: read file hashes for {num_updated} races", Utc::now().format("%Y-%m-%d %H:%M:%S"));
+            }
             #[cfg(unix)] Subcommand::CleanupRoles { .. } => {
                 u8::read(&mut sock).await?;
             }
@@ -232,7 +242,8 @@ async fn main(Args { port, subcommand }: Args) -> Result<(), Error> {
         let clean_shutdown = Arc::default();
         let racetime_config = if Environment::default().is_dev() { &config.racetime_bot_dev } else { &config.racetime_bot_production }.clone();
         let startgg_token = if Environment::default().is_dev() { &config.startgg_dev } else { &config.startgg_production };
-        let (seed_cache_tx, seed_cache_rx) = watch::channel(());
+        // allow organizers to test webhooks against local receivers in dev
+        let webhook_http_client = if Environment::default().is_dev() { insecure_http_client.clone() } else { webhook::http_client()? };
         let global_state = Arc::new(racetime_bot::GlobalState::new(
             Arc::clone(&new_room_lock),
             racetime_config,
@@ -242,20 +253,33 @@ async fn main(Args { port, subcommand }: Args) -> Result<(), Error> {
             insecure_http_client,
             config.league_api_key.clone(),
             startgg_token.clone(),
+            config.challonge_api_key.clone(),
             ootr_api_client,
             discord_builder.ctx_fut.clone(),
             Arc::clone(&clean_shutdown),
-            seed_cache_tx,
             seed_metadata,
+            racetime_bot::LocalRando::Managed,
+            config.seed_fallback.clone(),
+            config.seed_queue.clone(),
         ).await);
         let discord_builder = discord_bot::configure_builder(discord_builder, global_state.clone(), db_pool.clone(), http_client.clone(), config.clone(), Arc::clone(&new_room_lock), extra_room_tx.clone(), Arc::clone(&clean_shutdown), rocket.shutdown());
         #[cfg(unix)] let unix_listener = unix_socket::listen(rocket.shutdown(), clean_shutdown, global_state.clone());
-        let racetime_task = tokio::spawn(racetime_bot::main(config.clone(), rocket.shutdown(), global_state, seed_cache_rx)).map(|res| match res {
+        let racetime_task = tokio::spawn(racetime_bot::main(config.clone(), rocket.shutdown(), global_state.clone())).map(|res| match res {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(Error::from(e)),
+            Err(e) => Err(Error::from(e)),
+        });
+        let scheduler_task = tokio::spawn(scheduler::run(global_state, config, rocket.shutdown())).map(|res| match res {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(Error::from(e)),
+            Err(e) => Err(Error::from(e)),
+        });
+        let race_updates_task = tokio::spawn(api::forward_race_updates(db_pool.clone(), rocket.shutdown())).map(|res| match res {
             Ok(Ok(())) => Ok(()),
             Ok(Err(e)) => Err(Error::from(e)),
             Err(e) => Err(Error::from(e)),
         });
-        let import_task = tokio::spawn(cal::auto_import_races(db_pool, http_client, config, rocket.shutdown(), discord_builder.ctx_fut.clone(), new_room_lock)).map(|res| match res {
+        let webhook_task = tokio::spawn(webhook::run(db_pool, webhook_http_client, rocket.shutdown())).map(|res| match res {
             Ok(Ok(())) => Ok(()),
             Ok(Err(e)) => Err(Error::from(e)),
             Err(e) => Err(Error::from(e)),
@@ -276,7 +300,7 @@ async fn main(Args { port, subcommand }: Args) -> Result<(), Error> {
             Err(e) => Err(Error::from(e)),
         });
         #[cfg(not(unix))] let unix_socket_task = future::ok(());
-        let ((), (), (), (), ()) = tokio::try_join!(discord_task, import_task, racetime_task, rocket_task, unix_socket_task)?;
+        let ((), (), (), (), (), (), ()) = tokio::try_join!(discord_task, race_updates_task, racetime_task, rocket_task, scheduler_task, unix_socket_task, webhook_task)?;
     }
     Ok(())
 }
@@ -0,0 +1,550 @@
+//! The `/new-event` wizard, where users propose events which a site admin then approves.
+
+use {
+    sqlx::types::Json,
+    crate::{
+        discord_bot::ADMIN_USER,
+        id::EventProposals,
+        prelude::*,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "event_proposal_status", rename_all = "lowercase")]
+enum ProposalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ProposalStatus {
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending review",
+            Self::Approved => "Approved",
+            Self::Rejected => "Rejected",
+        }
+    }
+}
+
+/// Requirements to enter the event which can be chosen in the wizard, with their form field names. Other requirements can be set up by a site admin.
+const REQUIREMENTS: [(&str, &str); 5] = [
+    ("require_racetime", "Entrants must have a racetime.gg account connected"),
+    ("require_discord", "Entrants must have a Discord account connected"),
+    ("require_discord_guild", "Entrants must join the event's Discord server"),
+    ("require_twitch", "Entrants must have a Twitch account connected to their racetime.gg account"),
+    ("require_startgg", "Entrants must have a start.gg account connected"),
+];
+
+async fn proposal_form(mut transaction: Transaction<'_, Postgres>, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, ctx: Context<'_>) -> Result<RawHtml<String>, event::Error> {
+    let content = if let Some(ref me) = me {
+        let proposals = sqlx::query!(r#"SELECT series AS "series: Series", event, display_name, status AS "status: ProposalStatus" FROM event_proposals WHERE requester = $1 ORDER BY created_at DESC"#, me.id as _).fetch_all(&mut *transaction).await?;
+        let all_series = Series::all(&mut transaction).await?;
+        let mut errors = ctx.errors().collect_vec();
+        html! {
+            @if !proposals.is_empty() {
+                h2 : "Your proposals";
+                table {
+                    thead {
+                        tr {
+                            th : "Event";
+                            th : "Status";
+                        }
+                    }
+                    tbody {
+                        @for proposal in proposals {
+                            tr {
+                                td {
+                                    @if let ProposalStatus::Approved = proposal.status {
+                                        a(href = uri!(event::info(proposal.series, &*proposal.event))) : proposal.display_name;
+                                    } else {
+                                        : proposal.display_name;
+                                    }
+                                }
+                                td : proposal.status.description();
+                            }
+                        }
+                    }
+                }
+                h2 : "Propose another event";
+            }
+            : full_form(uri!(post), csrf, html! {
+                h3 : "Event";
+                : form_field("series", &mut errors, html! {
+                    label(for = "series") : "Series:";
+                    select(name = "series") {
+                        @for (series, display_name) in &all_series {
+                            option(value = series.slug(), selected? = ctx.field_value("series") == Some(series.slug())) : display_name;
+                        }
+                    }
+                    label(class = "help") : "(If your event doesn't fit any of these series, mention this in the notes below.)";
+                });
+                : form_field("event", &mut errors, html! {
+                    label(for = "event") : "Short identifier:";
+                    input(type = "text", name = "event", value? = ctx.field_value("event"));
+                    label(class = "help") : "(Used in the event's URL, e.g. “8” for season 8. Up to 8 lowercase letters and digits.)";
+                });
+                : form_field("display_name", &mut errors, html! {
+                    label(for = "display_name") : "Name:";
+                    input(type = "text", name = "display_name", value? = ctx.field_value("display_name"));
+                });
+                : form_field("start", &mut errors, html! {
+                    label(for = "start") : "Start date (UTC):";
+                    input(type = "date", name = "start", value? = ctx.field_value("start"));
+                });
+                : form_field("end", &mut errors, html! {
+                    label(for = "end") : "End date (UTC):";
+                    input(type = "date", name = "end", value? = ctx.field_value("end"));
+                    label(class = "help") : "(Leave empty if not known yet.)";
+                });
+                h3 : "Format";
+                : form_field("team_config", &mut errors, html! {
+                    label(for = "team_config") : "Teams:";
+                    select(name = "team_config") {
+                        @for team_config in all::<TeamConfig>() {
+                            @let value = format!("{team_config:?}");
+                            option(value = &value, selected? = ctx.field_value("team_config") == Some(&*value)) : team_config.description();
+                        }
+                    }
+                });
+                @for (name, description) in REQUIREMENTS {
+                    : form_field(name, &mut errors, html! {
+                        input(type = "checkbox", id = name, name = name, checked? = ctx.field_value(name) == Some("on"));
+                        label(for = name) : description;
+                    });
+                }
+                h3 : "Integrations";
+                : form_field("discord_guild", &mut errors, html! {
+                    label(for = "discord_guild") : "Discord server ID:";
+                    input(type = "text", name = "discord_guild", value? = ctx.field_value("discord_guild"));
+                    label(class = "help") : "(Optional. The bot will need to be invited to the server.)";
+                });
+                : form_field("racetime_goal", &mut errors, html! {
+                    label(for = "racetime_goal") : "racetime.gg goal:";
+                    input(type = "text", name = "racetime_goal", value? = ctx.field_value("racetime_goal"));
+                    label(class = "help") : "(Optional. The name of the goal race rooms should use, if race rooms should be opened automatically. A site admin sets this up by hand after approval.)";
+                });
+                h3 : "Settings";
+                : form_field("single_settings", &mut errors, html! {
+                    label(for = "single_settings") : "Settings:";
+                    textarea(name = "single_settings") : ctx.field_value("single_settings").unwrap_or_default();
+                    label(class = "help") : "(Optional. A JSON object of randomizer settings, if all races of the event use the same settings.)";
+                });
+                : form_field("draft_spec", &mut errors, html! {
+                    label(for = "draft_spec") : "Settings draft:";
+                    textarea(name = "draft_spec") : ctx.field_value("draft_spec").unwrap_or_default();
+                    label(class = "help") : "(Optional. A JSON description of the ban/pick steps and the settings that can be drafted, if settings are drafted for each match instead.)";
+                });
+                h3 : "Notes";
+                : form_field("notes", &mut errors, html! {
+                    label(for = "notes") : "Anything else the admins should know:";
+                    textarea(name = "notes") : ctx.field_value("notes").unwrap_or_default();
+                });
+            }, errors, "Submit for review")
+        }
+    } else {
+        html! {
+            p {
+                a(href = uri!(auth::login(Some(uri!(get))))) : "Sign in or create a Hyrule Town Hall account";
+                : " to propose an event.";
+            }
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle::default(), "New Event — Hyrule Town Hall", html! {
+        h1 : "Propose an event";
+        p : "If you are planning a tournament, community race, or other event for the Zelda speedrunning or randomizer community, you can propose it here. Once a site admin has approved your proposal, the event is created with you as its organizer and you can configure it further on its Configure page.";
+        : content;
+    }).await?)
+}
+
+#[rocket::get("/new-event")]
+pub(crate) async fn get(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>) -> Result<RawHtml<String>, event::Error> {
+    let transaction = pool.begin().await?;
+    proposal_form(transaction, me, uri, csrf.as_ref(), Context::default()).await
+}
+
+#[derive(FromForm, CsrfForm)]
+pub(crate) struct ProposalForm {
+    #[field(default = String::new())]
+    csrf: String,
+    series: String,
+    event: String,
+    display_name: String,
+    #[field(default = String::new())]
+    start: String,
+    #[field(default = String::new())]
+    end: String,
+    team_config: TeamConfig,
+    require_racetime: bool,
+    require_discord: bool,
+    require_discord_guild: bool,
+    require_twitch: bool,
+    require_startgg: bool,
+    #[field(default = String::new())]
+    discord_guild: String,
+    #[field(default = String::new())]
+    racetime_goal: String,
+    #[field(default = String::new())]
+    single_settings: String,
+    #[field(default = String::new())]
+    draft_spec: String,
+    #[field(default = String::new())]
+    notes: String,
+}
+
+#[rocket::post("/new-event", data = "<form>")]
+pub(crate) async fn post(pool: &State<PgPool>, discord_ctx: &State<RwFuture<DiscordCtx>>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, form: Form<Contextual<'_, ProposalForm>>) -> Result<RedirectOrContent, event::Error> {
+    let mut transaction = pool.begin().await?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if let Some(ref value) = form.value {
+        let series = if_chain! {
+            if let Ok(series) = value.series.parse::<Series>();
+            if series.display_name(&mut transaction).await?.is_some();
+            then {
+                Some(series)
+            } else {
+                form.context.push_error(form::Error::validation("There is no series with this identifier.").with_name("series"));
+                None
+            }
+        };
+        if !regex_is_match!("^[0-9a-z]{1,8}$", &value.event) {
+            form.context.push_error(form::Error::validation("The identifier must be 1 to 8 lowercase letters or digits.").with_name("event"));
+        } else if let Some(series) = series {
+            if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM events WHERE series = $1 AND event = $2) AS "exists!""#, series as _, &value.event).fetch_one(&mut *transaction).await? {
+                form.context.push_error(form::Error::validation("An event with this identifier already exists in this series.").with_name("event"));
+            } else if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM event_proposals WHERE series = $1 AND event = $2 AND status = 'pending') AS "exists!""#, series as _, &value.event).fetch_one(&mut *transaction).await? {
+                form.context.push_error(form::Error::validation("An event with this identifier has already been proposed and is waiting for review.").with_name("event"));
+            }
+        }
+        if value.display_name.trim().is_empty() {
+            form.context.push_error(form::Error::validation("This field is required.").with_name("display_name"));
+        }
+        let start = if value.start.is_empty() {
+            None
+        } else if let Some(start) = parse_date(&value.start) {
+            Some(start)
+        } else {
+            form.context.push_error(form::Error::validation("Dates must be formatted like 2025-12-31.").with_name("start"));
+            None
+        };
+        let end = if value.end.is_empty() {
+            None
+        } else if let Some(end) = parse_date(&value.end) {
+            if start.is_some_and(|start| end < start) {
+                form.context.push_error(form::Error::validation("The event can't end before it starts.").with_name("end"));
+            }
+            Some(end)
+        } else {
+            form.context.push_error(form::Error::validation("Dates must be formatted like 2025-12-31.").with_name("end"));
+            None
+        };
+        let discord_guild = if value.discord_guild.trim().is_empty() {
+            if value.require_discord_guild {
+                form.context.push_error(form::Error::validation("Enter the Discord server ID to require entrants to join it.").with_name("discord_guild"));
+            }
+            None
+        } else if let Some(guild_id) = value.discord_guild.trim().parse().ok().filter(|&id| id != 0) {
+            Some(GuildId::new(guild_id))
+        } else {
+            form.context.push_error(form::Error::validation("Discord server IDs are numbers. You can copy one by right-clicking the server with developer mode enabled.").with_name("discord_guild"));
+            None
+        };
+        let single_settings = if value.single_settings.trim().is_empty() {
+            None
+        } else {
+            match serde_json::from_str::<seed::Settings>(&value.single_settings) {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    form.context.push_error(form::Error::validation(format!("Failed to parse settings: {e}")).with_name("single_settings"));
+                    None
+                }
+            }
+        };
+        let draft_spec = if value.draft_spec.trim().is_empty() {
+            None
+        } else {
+            match serde_json::from_str::<draft::spec::Spec>(&value.draft_spec) {
+                Ok(spec) => match spec.validate() {
+                    Ok(()) => Some(spec),
+                    Err(e) => {
+                        form.context.push_error(form::Error::validation(format!("Invalid settings draft: {e}")).with_name("draft_spec"));
+                        None
+                    }
+                },
+                Err(e) => {
+                    form.context.push_error(form::Error::validation(format!("Failed to parse settings draft: {e}")).with_name("draft_spec"));
+                    None
+                }
+            }
+        };
+        if !value.single_settings.trim().is_empty() && !value.draft_spec.trim().is_empty() {
+            form.context.push_error(form::Error::validation("An event can have either fixed settings or a settings draft, not both.").with_name("draft_spec"));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(proposal_form(transaction, Some(me), uri, csrf.as_ref(), form.context).await?)
+        } else {
+            let series = series.expect("validated");
+            let mut requirements = Vec::default();
+            if value.require_racetime {
+                requirements.push(json!({"type": "raceTime"}));
+            }
+            if value.require_discord {
+                requirements.push(json!({"type": "discord"}));
+            }
+            if value.require_discord_guild {
+                requirements.push(json!({"type": "discordGuild", "name": value.display_name.trim()}));
+            }
+            if value.require_twitch {
+                requirements.push(json!({"type": "twitch"}));
+            }
+            if value.require_startgg {
+                requirements.push(json!({"type": "startGG"}));
+            }
+            let id = Id::<EventProposals>::new(&mut transaction).await?;
+            sqlx::query!("INSERT INTO event_proposals (
+                id, requester, series, event, display_name, start, end_time, team_config, enter_flow, discord_guild, racetime_goal, single_settings, draft_spec, notes
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+                id as _,
+                me.id as _,
+                series as _,
+                &value.event,
+                value.display_name.trim(),
+                start,
+                end,
+                value.team_config as _,
+                Json(json!({"requirements": requirements})) as _,
+                discord_guild.map(PgSnowflake) as _,
+                (!value.racetime_goal.trim().is_empty()).then(|| value.racetime_goal.trim()),
+                single_settings.map(Json) as _,
+                draft_spec.map(Json) as _,
+                (!value.notes.trim().is_empty()).then(|| value.notes.trim()),
+            ).execute(&mut *transaction).await?;
+            transaction.commit().await?;
+            let msg = MessageBuilder::default()
+                .mention_user(&me)
+                .push(" proposed a new event: ")
+                .push_safe(value.display_name.trim())
+                .push(" <")
+                .push(format!("{}{}", base_uri(), uri!(review)))
+                .push('>')
+                .build();
+            let discord_ctx = discord_ctx.read().await;
+            ADMIN_USER.create_dm_channel(&*discord_ctx).await?.say(&*discord_ctx, msg).await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(get)))
+        }
+    } else {
+        RedirectOrContent::Content(proposal_form(transaction, Some(me), uri, csrf.as_ref(), form.context).await?)
+    })
+}
+
+async fn review_page(mut transaction: Transaction<'_, Postgres>, me: Option<User>, uri: Origin<'_>, csrf: Option<&CsrfToken>, ctx: Option<(Id<EventProposals>, Context<'_>)>) -> Result<RawHtml<String>, event::Error> {
+    let content = if me.as_ref().is_some_and(|me| me.is_archivist) {
+        let proposals = sqlx::query!(r#"SELECT
+            id AS "id: Id<EventProposals>",
+            requester AS "requester: Id<Users>",
+            series AS "series: Series",
+            event,
+            display_name,
+            start,
+            end_time,
+            team_config AS "team_config: TeamConfig",
+            enter_flow,
+            discord_guild,
+            racetime_goal,
+            single_settings,
+            draft_spec,
+            notes,
+            created_at
+        FROM event_proposals WHERE status = 'pending' ORDER BY created_at"#).fetch_all(&mut *transaction).await?;
+        html! {
+            @if proposals.is_empty() {
+                p : "No event proposals are waiting for review.";
+            }
+            @for proposal in proposals {
+                article {
+                    h2 {
+                        : proposal.display_name;
+                        : " (";
+                        : proposal.series.slug();
+                        : "/";
+                        : proposal.event;
+                        : ")";
+                    }
+                    p {
+                        : "Proposed ";
+                        @if let Some(requester) = User::from_id(&mut *transaction, proposal.requester).await? {
+                            : "by ";
+                            : requester;
+                            : " ";
+                        }
+                        : format_datetime(proposal.created_at, DateTimeFormat { long: false, running_text: true });
+                    }
+                    p {
+                        : "Dates: ";
+                        @if let Some(start) = proposal.start {
+                            : start.format("%Y-%m-%d").to_string();
+                        } else {
+                            : "unknown";
+                        }
+                        : " to ";
+                        @if let Some(end) = proposal.end_time {
+                            : end.format("%Y-%m-%d").to_string();
+                        } else {
+                            : "unknown";
+                        }
+                    }
+                    p {
+                        : "Teams: ";
+                        : proposal.team_config.description();
+                    }
+                    p {
+                        : "Enter flow: ";
+                        code : proposal.enter_flow.to_string();
+                    }
+                    @if let Some(discord_guild) = proposal.discord_guild {
+                        p {
+                            : "Discord server ID: ";
+                            code : discord_guild;
+                        }
+                    }
+                    @if let Some(racetime_goal) = proposal.racetime_goal {
+                        p {
+                            : "racetime.gg goal: ";
+                            : racetime_goal;
+                            : " (not applied on approval, needs to be set up in the bot by hand before race rooms can be opened)";
+                        }
+                    }
+                    @if let Some(single_settings) = proposal.single_settings {
+                        p : "Settings:";
+                        pre : serde_json::to_string_pretty(&single_settings)?;
+                    }
+                    @if let Some(draft_spec) = proposal.draft_spec {
+                        p : "Settings draft:";
+                        pre : serde_json::to_string_pretty(&draft_spec)?;
+                    }
+                    @if let Some(notes) = proposal.notes {
+                        p : "Notes:";
+                        pre : notes;
+                    }
+                    @let errors = if let Some((id, ref ctx)) = ctx { if id == proposal.id { ctx.errors().collect() } else { Vec::default() } } else { Vec::default() };
+                    @let (errors, approve_button) = button_form(uri!(approve(proposal.id)), csrf, errors, "Approve");
+                    @let (_, reject_button) = button_form(uri!(reject(proposal.id)), csrf, Vec::default(), "Reject");
+                    : errors;
+                    div(class = "button-row") {
+                        : approve_button;
+                        : reject_button;
+                    }
+                }
+            }
+        }
+    } else {
+        html! {
+            p : "This page is only available to site admins.";
+        }
+    };
+    Ok(page(transaction, &me, &uri, PageStyle::default(), "Event Proposals — Hyrule Town Hall", html! {
+        h1 : "Event proposals";
+        : content;
+    }).await?)
+}
+
+#[rocket::get("/admin/event-proposals")]
+pub(crate) async fn review(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>, csrf: Option<CsrfToken>) -> Result<RawHtml<String>, event::Error> {
+    let transaction = pool.begin().await?;
+    review_page(transaction, me, uri, csrf.as_ref(), None).await
+}
+
+#[rocket::post("/admin/event-proposals/<id>/approve", data = "<form>")]
+pub(crate) async fn approve(pool: &State<PgPool>, discord_ctx: &State<RwFuture<DiscordCtx>>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, id: Id<EventProposals>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, StatusOrError<event::Error>> {
+    let mut transaction = pool.begin().await?;
+    let proposal = sqlx::query!(r#"SELECT
+        requester AS "requester: Id<Users>",
+        series AS "series: Series",
+        event,
+        display_name,
+        start,
+        end_time,
+        team_config AS "team_config: TeamConfig",
+        enter_flow,
+        discord_guild,
+        racetime_goal,
+        single_settings,
+        draft_spec,
+        status AS "status: ProposalStatus"
+    FROM event_proposals WHERE id = $1"#, id as _).fetch_optional(&mut *transaction).await?.ok_or(StatusOrError::Status(Status::NotFound))?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if form.value.is_some() {
+        if !me.is_archivist {
+            form.context.push_error(form::Error::validation("You must be a site admin to review event proposals."));
+        }
+        if proposal.status != ProposalStatus::Pending {
+            form.context.push_error(form::Error::validation("This proposal has already been reviewed."));
+        }
+        if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM events WHERE series = $1 AND event = $2) AS "exists!""#, proposal.series as _, &proposal.event).fetch_one(&mut *transaction).await? {
+            form.context.push_error(form::Error::validation("An event with this identifier has been created since this proposal was submitted."));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(review_page(transaction, Some(me), uri, csrf.as_ref(), Some((id, form.context))).await?)
+        } else {
+            sqlx::query!("INSERT INTO events (
+                series, event, display_name, start, end_time, listed, discord_guild, enter_flow, team_config, single_settings, draft_spec
+            ) VALUES ($1, $2, $3, $4, $5, FALSE, $6, $7, $8, $9, $10)",
+                proposal.series as _,
+                &proposal.event,
+                &proposal.display_name,
+                proposal.start,
+                proposal.end_time,
+                proposal.discord_guild,
+                proposal.enter_flow,
+                proposal.team_config as _,
+                proposal.single_settings,
+                proposal.draft_spec,
+            ).execute(&mut *transaction).await?;
+            sqlx::query!("INSERT INTO organizers (series, event, organizer) VALUES ($1, $2, $3)", proposal.series as _, &proposal.event, proposal.requester as _).execute(&mut *transaction).await?;
+            sqlx::query!("UPDATE event_proposals SET status = 'approved', reviewed_by = $1, reviewed_at = NOW() WHERE id = $2", me.id as _, id as _).execute(&mut *transaction).await?;
+            let requester = User::from_id(&mut *transaction, proposal.requester).await?;
+            transaction.commit().await?;
+            if let Some(discord) = requester.and_then(|requester| requester.discord) {
+                let mut msg = MessageBuilder::default();
+                msg.push("Your event ");
+                msg.push_safe(&proposal.display_name);
+                msg.push(" has been approved! You can now configure it at <");
+                msg.push(format!("{}{}", base_uri(), uri!(event::configure::get(proposal.series, &*proposal.event))));
+                msg.push(">. It won't show up in public listings until a site admin lists it, so let us know once it's ready.");
+                if let Some(racetime_goal) = proposal.racetime_goal {
+                    msg.push(" The racetime.gg goal ");
+                    msg.push_mono_safe(racetime_goal);
+                    msg.push(" still needs to be set up by a site admin before race rooms can be opened.");
+                }
+                let discord_ctx = discord_ctx.read().await;
+                discord.id.create_dm_channel(&*discord_ctx).await?.say(&*discord_ctx, msg.build()).await?;
+            }
+            RedirectOrContent::Redirect(Redirect::to(uri!(review)))
+        }
+    } else {
+        RedirectOrContent::Content(review_page(transaction, Some(me), uri, csrf.as_ref(), Some((id, form.context))).await?)
+    })
+}
+
+#[rocket::post("/admin/event-proposals/<id>/reject", data = "<form>")]
+pub(crate) async fn reject(pool: &State<PgPool>, me: User, uri: Origin<'_>, csrf: Option<CsrfToken>, id: Id<EventProposals>, form: Form<Contextual<'_, EmptyForm>>) -> Result<RedirectOrContent, event::Error> {
+    let mut transaction = pool.begin().await?;
+    let mut form = form.into_inner();
+    form.verify(&csrf);
+    Ok(if form.value.is_some() {
+        if !me.is_archivist {
+            form.context.push_error(form::Error::validation("You must be a site admin to review event proposals."));
+        }
+        if form.context.errors().next().is_some() {
+            RedirectOrContent::Content(review_page(transaction, Some(me), uri, csrf.as_ref(), Some((id, form.context))).await?)
+        } else {
+            sqlx::query!("UPDATE event_proposals SET status = 'rejected', reviewed_by = $1, reviewed_at = NOW() WHERE id = $2 AND status = 'pending'", me.id as _, id as _).execute(&mut *transaction).await?;
+            transaction.commit().await?;
+            RedirectOrContent::Redirect(Redirect::to(uri!(review)))
+        }
+    } else {
+        RedirectOrContent::Content(review_page(transaction, Some(me), uri, csrf.as_ref(), Some((id, form.context))).await?)
+    })
+}
@@ -154,7 +154,6 @@ pub(crate) use {
         sync::{
             broadcast,
             mpsc,
-            watch,
         },
         time::{
             Instant,
@@ -248,6 +247,7 @@ pub(crate) use {
             UnlockSpoilerLog,
         },
         racetime_host,
+        scheduler,
         seed,
         series::*,
         startgg,
@@ -260,6 +260,7 @@ pub(crate) use {
             self,
             User,
         },
+        zpf,
     },
 };
 #[cfg(unix)] pub(crate) use {
@@ -0,0 +1,233 @@
+//! Seed generation backends.
+//!
+//! Seeds are only rolled through [`GlobalState::roll`], which hands the [`SeedRequest`] to the [`SeedGenerator`] registered for its [`GeneratorKind`]. Supporting a new randomizer means adding a request variant and a generator here rather than changing [`Handler`].
+
+use crate::{
+    prelude::*,
+    racetime_bot::*,
+};
+
+/// A seed to be rolled, along with the generator-specific options.
+pub(crate) enum SeedRequest {
+    /// A regular Ocarina of Time randomizer seed, rolled on ootrandomizer.com if possible and locally otherwise.
+    Ootr {
+        preroll: PrerollMode,
+        allow_web: bool,
+        version: VersionedBranch,
+        settings: seed::Settings,
+    },
+    /// A seed with settings chosen by the random settings script.
+    Rsl {
+        preset: rsl::VersionedPreset,
+        world_count: u8,
+    },
+    /// A seed from triforceblitz.com.
+    TriforceBlitz {
+        version: &'static str,
+    },
+    /// A seed from the dev version of triforceblitz.com.
+    TriforceBlitzDev {
+        coop: bool,
+    },
+    /// A seed for the 2025 ALTTPR crosskeys tournament.
+    Crosskeys2025(CrosskeysRaceOptions),
+}
+
+impl SeedRequest {
+    pub(crate) fn kind(&self) -> GeneratorKind {
+        match self {
+            Self::Ootr { .. } => GeneratorKind::Ootr,
+            Self::Rsl { .. } => GeneratorKind::Rsl,
+            Self::TriforceBlitz { .. } | Self::TriforceBlitzDev { .. } => GeneratorKind::TriforceBlitz,
+            Self::Crosskeys2025(_) => GeneratorKind::AlttprDoorRando,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum GeneratorKind {
+    Ootr,
+    Rsl,
+    TriforceBlitz,
+    AlttprDoorRando,
+}
+
+/// What a [`SeedGenerator`] supports, for callers which need to adjust their requests accordingly.
+pub(crate) struct Capabilities {
+    /// Whether the spoiler log is unlocked by the generator's website on its own, rather than by [`SeedGenerator::unlock_spoiler_log`].
+    /// Such websites don't know about async parts, so the spoiler log has to stay locked for those.
+    pub(crate) auto_unlock_spoiler_log: bool,
+}
+
+#[async_trait]
+pub(crate) trait SeedGenerator: Send + Sync {
+    fn capabilities(&self) -> Capabilities;
+
+    /// Whether seeds with these files are managed by this generator.
+    ///
+    /// Generators whose output is another generator's seed format (like the random settings script producing regular randomizer seeds) return `false` and leave those seeds to the other generator.
+    fn handles(&self, files: &seed::Files) -> bool;
+
+    /// Starts rolling the requested seed. `room` is the race room the seed is for, if any.
+    ///
+    /// Generators which run a randomizer on this server should wait for a [worker](queue::SeedQueue::acquire_worker) with the given `priority` first.
+    /// This is only called with requests whose [`SeedRequest::kind`] this generator is registered for.
+    fn roll(&self, global_state: Arc<GlobalState>, delay_until: Option<DateTime<Utc>>, room: Option<String>, priority: Priority, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate>;
+
+    /// Reads the file hash, password, and spoiler log status of a seed rolled by this generator.
+    async fn extra(&self, seed: &seed::Data) -> Result<seed::ExtraData, seed::ExtraDataError> {
+        seed.extra(Utc::now()).await
+    }
+
+    /// Makes the spoiler log of a seed rolled by this generator available after the race.
+    async fn unlock_spoiler_log(&self, global_state: &GlobalState, files: &seed::Files) -> Result<(), Error>;
+}
+
+struct Ootr;
+
+#[async_trait]
+impl SeedGenerator for Ootr {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_unlock_spoiler_log: false,
+        }
+    }
+
+    fn handles(&self, files: &seed::Files) -> bool {
+        matches!(files, seed::Files::MidosHouse { .. } | seed::Files::OotrWeb { .. })
+    }
+
+    fn roll(&self, global_state: Arc<GlobalState>, delay_until: Option<DateTime<Utc>>, _: Option<String>, priority: Priority, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+        let SeedRequest::Ootr { preroll, allow_web, version, settings } = request else { unreachable!("seed request dispatched to wrong generator") };
+        global_state.roll_seed(preroll, allow_web, delay_until, priority, version, settings, unlock_spoiler_log)
+    }
+
+    async fn unlock_spoiler_log(&self, global_state: &GlobalState, files: &seed::Files) -> Result<(), Error> {
+        match files {
+            seed::Files::MidosHouse { file_stem, locked_spoiler_log_path } => if let Some(locked_spoiler_log_path) = locked_spoiler_log_path {
+                lock!(@write seed_metadata = global_state.seed_metadata; seed_metadata.remove(&**file_stem));
+                fs::rename(locked_spoiler_log_path, global_state.local_rando.seed_dir().join(format!("{file_stem}_Spoiler.json"))).await.to_racetime()?;
+            },
+            seed::Files::OotrWeb { id, file_stem, .. } => {
+                global_state.ootr_api_client.unlock_spoiler_log(*id).await.to_racetime()?;
+                let spoiler_log = global_state.ootr_api_client.seed_details(*id).await.to_racetime()?.spoiler_log;
+                fs::write(global_state.local_rando.seed_dir().join(format!("{file_stem}_Spoiler.json")), &spoiler_log).await.to_racetime()?;
+            }
+            seed::Files::AlttprDoorRando { .. } | seed::Files::TriforceBlitz { .. } | seed::Files::TfbSotd { .. } => unreachable!("seed files dispatched to wrong generator"),
+        }
+        Ok(())
+    }
+}
+
+struct Rsl;
+
+#[async_trait]
+impl SeedGenerator for Rsl {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_unlock_spoiler_log: false,
+        }
+    }
+
+    fn handles(&self, _: &seed::Files) -> bool {
+        false // random settings seeds are regular randomizer seeds
+    }
+
+    fn roll(&self, global_state: Arc<GlobalState>, delay_until: Option<DateTime<Utc>>, _: Option<String>, priority: Priority, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+        let SeedRequest::Rsl { preset, world_count } = request else { unreachable!("seed request dispatched to wrong generator") };
+        global_state.roll_rsl_seed(delay_until, priority, preset, world_count, unlock_spoiler_log)
+    }
+
+    async fn unlock_spoiler_log(&self, global_state: &GlobalState, files: &seed::Files) -> Result<(), Error> {
+        Ootr.unlock_spoiler_log(global_state, files).await
+    }
+}
+
+struct TriforceBlitz;
+
+#[async_trait]
+impl SeedGenerator for TriforceBlitz {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_unlock_spoiler_log: true,
+        }
+    }
+
+    fn handles(&self, files: &seed::Files) -> bool {
+        matches!(files, seed::Files::TriforceBlitz { .. } | seed::Files::TfbSotd { .. })
+    }
+
+    fn roll(&self, global_state: Arc<GlobalState>, delay_until: Option<DateTime<Utc>>, room: Option<String>, _: Priority, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+        match request {
+            SeedRequest::TriforceBlitz { version } => global_state.roll_tfb_seed(delay_until, version, room, unlock_spoiler_log),
+            SeedRequest::TriforceBlitzDev { coop } => global_state.roll_tfb_dev_seed(delay_until, coop, room, unlock_spoiler_log),
+            SeedRequest::Ootr { .. } | SeedRequest::Rsl { .. } | SeedRequest::Crosskeys2025(_) => unreachable!("seed request dispatched to wrong generator"),
+        }
+    }
+
+    async fn unlock_spoiler_log(&self, _: &GlobalState, _: &seed::Files) -> Result<(), Error> {
+        Ok(()) // automatically unlocked by triforceblitz.com
+    }
+}
+
+struct AlttprDoorRando;
+
+#[async_trait]
+impl SeedGenerator for AlttprDoorRando {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_unlock_spoiler_log: false,
+        }
+    }
+
+    fn handles(&self, files: &seed::Files) -> bool {
+        matches!(files, seed::Files::AlttprDoorRando { .. })
+    }
+
+    fn roll(&self, global_state: Arc<GlobalState>, _: Option<DateTime<Utc>>, _: Option<String>, priority: Priority, request: SeedRequest, _: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+        let SeedRequest::Crosskeys2025(crosskeys_options) = request else { unreachable!("seed request dispatched to wrong generator") };
+        global_state.roll_crosskeys2025_seed(priority, crosskeys_options)
+    }
+
+    async fn unlock_spoiler_log(&self, _: &GlobalState, _: &seed::Files) -> Result<(), Error> {
+        unreachable!("ALTTPR door rando spoiler logs are never unlocked")
+    }
+}
+
+/// The seed generators available on this server, by the kind of seed they roll.
+pub(crate) struct Registry(HashMap<GeneratorKind, Box<dyn SeedGenerator>>);
+
+impl Registry {
+    pub(crate) fn register(&mut self, kind: GeneratorKind, generator: impl SeedGenerator + 'static) {
+        self.0.insert(kind, Box::new(generator));
+    }
+
+    pub(crate) fn get(&self, kind: GeneratorKind) -> &dyn SeedGenerator {
+        self.0.get(&kind).unwrap_or_else(|| panic!("no seed generator registered for {kind:?}")).as_ref()
+    }
+
+    /// The generator managing seeds with these files, if any.
+    pub(crate) fn for_files(&self, files: &seed::Files) -> Option<&dyn SeedGenerator> {
+        self.0.values().find(|generator| generator.handles(files)).map(|generator| generator.as_ref())
+    }
+
+    /// Reads the file hash, password, and spoiler log status of a seed using the generator which rolled it.
+    pub(crate) async fn extra(&self, seed: &seed::Data) -> Result<seed::ExtraData, seed::ExtraDataError> {
+        if let Some(generator) = seed.files.as_ref().and_then(|files| self.for_files(files)) {
+            generator.extra(seed).await
+        } else {
+            seed.extra(Utc::now()).await
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self(HashMap::default());
+        registry.register(GeneratorKind::Ootr, Ootr);
+        registry.register(GeneratorKind::Rsl, Rsl);
+        registry.register(GeneratorKind::TriforceBlitz, TriforceBlitz);
+        registry.register(GeneratorKind::AlttprDoorRando, AlttprDoorRando);
+        registry
+    }
+}
@@ -41,7 +41,6 @@ use {
             AsyncWriteExt as _,
             BufReader,
         },
-        time::timeout,
     },
     wheel::{
         fs::File,
@@ -49,19 +48,35 @@ use {
     },
     crate::{
         cal::Entrant,
-        config::ConfigRaceTime,
+        config::{
+            ConfigRaceTime,
+            ConfigSeedFallback,
+            ConfigSeedQueue,
+        },
         discord_bot::ADMIN_USER,
         hash_icon::{
             HashIcon,
             OcarinaNote,
         },
         prelude::*,
+        racetime_bot::{
+            generator::SeedRequest,
+            queue::{
+                Priority,
+                SeedQueue,
+            },
+            room::RaceRoom,
+        },
     },
 };
 #[cfg(unix)] use async_proto::Protocol;
 #[cfg(windows)] use directories::UserDirs;
 
+pub(crate) mod generator;
+pub(crate) mod queue;
 mod report;
+mod room;
+#[cfg(all(test, unix))] mod tests;
 
 #[cfg(unix)] const PYTHON: &str = "python3";
 #[cfg(windows)] const PYTHON: &str = "py";
@@ -572,7 +587,7 @@ impl Goal {
         }
     }
 
-    async fn send_presets(&self, ctx: &RaceContext<GlobalState>) -> Result<(), Error> {
+    async fn send_presets(&self, ctx: &impl RaceRoom) -> Result<(), Error> {
         match self {
             | Self::Pic7
                 => ctx.say("!seed: The settings used for the race").await?,
@@ -765,7 +780,7 @@ impl Goal {
                         seed_password,
                         progression_spoiler
                     "#, self.as_str(), no_password).fetch_optional(&mut **transaction).await.to_racetime()? {
-                        let _ = global_state.seed_cache_tx.send(());
+                        scheduler::schedule(transaction, scheduler::JobKind::PrerollGoalSeeds, Utc::now()).await.to_racetime()?;
                         SeedCommandParseResult::QueueExisting {
                             data: seed::Data::from_db(
                                 None,
@@ -1325,9 +1340,68 @@ pub(crate) struct SeedMetadata {
     pub(crate) progression_spoiler: bool,
 }
 
+/// Where seeds rolled on this server come from.
+pub(crate) enum LocalRando {
+    /// The randomizer is cloned and built on demand and patch files are stored in [`seed::DIR`].
+    Managed,
+    /// Always run the randomizer at the given path and store patch files in the given directory. Web seed rolling is disabled.
+    #[cfg(test)]
+    Fixed {
+        rando_path: PathBuf,
+        seed_dir: PathBuf,
+    },
+}
+
+impl LocalRando {
+    pub(crate) fn seed_dir(&self) -> &Path {
+        match self {
+            Self::Managed => Path::new(seed::DIR),
+            #[cfg(test)] Self::Fixed { seed_dir, .. } => seed_dir,
+        }
+    }
+
+    fn allows_web(&self) -> bool {
+        match self {
+            Self::Managed => true,
+            #[cfg(test)] Self::Fixed { .. } => false,
+        }
+    }
+}
+
+/// Keeps track of ootrandomizer.com outages so seeds can be rolled locally in the meantime.
+struct WebFallback {
+    config: ConfigSeedFallback,
+    /// When ootrandomizer.com last failed with a network error.
+    last_outage: Mutex<Option<Instant>>,
+}
+
+impl WebFallback {
+    fn new(config: ConfigSeedFallback) -> Self {
+        Self {
+            last_outage: Mutex::default(),
+            config,
+        }
+    }
+
+    /// Whether seeds that could be rolled on ootrandomizer.com should currently be rolled locally instead.
+    async fn is_active(&self) -> bool {
+        self.config.enabled && lock!(last_outage = self.last_outage; last_outage.is_some_and(|last_outage| last_outage.elapsed() < Duration::from_secs(self.config.retry_after_secs)))
+    }
+
+    /// Whether to fall back to rolling locally after this error from ootrandomizer.com. If so, seeds will keep being rolled locally for a while.
+    async fn should_fall_back(&self, e: &ootr_web::Error) -> bool {
+        if self.config.enabled && e.is_network_error() {
+            lock!(last_outage = self.last_outage; *last_outage = Some(Instant::now()));
+            true
+        } else {
+            false
+        }
+    }
+}
+
 pub(crate) struct GlobalState {
     /// Locked while event rooms are being created. Wait with handling new rooms while it's held.
-    new_room_lock: Arc<Mutex<()>>,
+    pub(crate) new_room_lock: Arc<Mutex<()>>,
     host_info: racetime::HostInfo,
     racetime_config: ConfigRaceTime,
     extra_room_tx: Arc<RwLock<mpsc::Sender<String>>>,
@@ -1336,11 +1410,15 @@ pub(crate) struct GlobalState {
     insecure_http_client: reqwest::Client,
     league_api_key: String,
     startgg_token: String,
+    challonge_api_key: String,
     ootr_api_client: Arc<ootr_web::ApiClient>,
     pub(crate) discord_ctx: RwFuture<DiscordCtx>,
     clean_shutdown: Arc<Mutex<CleanShutdown>>,
-    seed_cache_tx: watch::Sender<()>,
     seed_metadata: Arc<RwLock<HashMap<String, SeedMetadata>>>,
+    local_rando: LocalRando,
+    web_fallback: WebFallback,
+    seed_generators: generator::Registry,
+    seed_queue: SeedQueue,
 }
 
 impl TypeMapKey for GlobalState {
@@ -1357,22 +1435,135 @@ impl GlobalState {
         insecure_http_client: reqwest::Client,
         league_api_key: String,
         startgg_token: String,
+        challonge_api_key: String,
         ootr_api_client: Arc<ootr_web::ApiClient>,
         discord_ctx: RwFuture<DiscordCtx>,
         clean_shutdown: Arc<Mutex<CleanShutdown>>,
-        seed_cache_tx: watch::Sender<()>,
         seed_metadata: Arc<RwLock<HashMap<String, SeedMetadata>>>,
+        local_rando: LocalRando,
+        seed_fallback: ConfigSeedFallback,
+        seed_queue: ConfigSeedQueue,
     ) -> Self {
         Self {
             host_info: racetime::HostInfo {
                 hostname: Cow::Borrowed(racetime_host()),
                 ..racetime::HostInfo::default()
             },
-            new_room_lock, racetime_config, extra_room_tx, db_pool, http_client, insecure_http_client, league_api_key, startgg_token, ootr_api_client, discord_ctx, clean_shutdown, seed_cache_tx, seed_metadata,
+            new_room_lock, racetime_config, extra_room_tx, db_pool, http_client, insecure_http_client, league_api_key, startgg_token, challonge_api_key, ootr_api_client, discord_ctx, clean_shutdown, seed_metadata, local_rando,
+            web_fallback: WebFallback::new(seed_fallback),
+            seed_generators: generator::Registry::default(),
+            seed_queue: SeedQueue::new(seed_queue),
+        }
+    }
+
+    /// Starts rolling a seed using the generator registered for the kind of seed requested.
+    ///
+    /// `room` is the race room the seed is for, if any. Only one seed is rolled for each room at a time, and the roll is abandoned if the room is closed.
+    pub(crate) async fn roll(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, room: Option<String>, priority: Priority, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+        let (update_tx, update_rx) = mpsc::channel(128);
+        let cancelled = if let Some(ref room) = room {
+            let Some(cancelled) = self.seed_queue.start_job(room).await else {
+                update_tx.send(SeedRollUpdate::Error(RollError::AlreadyRolling)).await.allow_unreceived();
+                return update_rx
+            };
+            Some(cancelled)
+        } else {
+            None
+        };
+        let mut updates = self.seed_generators.get(request.kind()).roll(self.clone(), delay_until, room.clone(), priority, request, unlock_spoiler_log);
+        tokio::spawn(async move {
+            let mut cancelled = pin!(async move {
+                if let Some(cancelled) = cancelled {
+                    let _ = cancelled.await;
+                } else {
+                    future::pending::<()>().await
+                }
+            });
+            loop {
+                select! {
+                    // dropping the generator's receiver stops it from continuing with the seed
+                    () = &mut cancelled => break,
+                    update = updates.recv() => if let Some(update) = update {
+                        if update_tx.send(update).await.is_err() { break }
+                    } else {
+                        break
+                    },
+                }
+            }
+            if let Some(room) = room {
+                self.seed_queue.finish_job(&room).await;
+            }
+        });
+        update_rx
+    }
+
+    /// Reads the file hashes of locally rolled race seeds which don't have one stored from their patch files. Returns the number of races updated.
+    pub(crate) async fn backfill_file_hashes(&self) -> Result<u64, BackfillFileHashesError> {
+        let mut transaction = self.db_pool.begin().await?;
+        let races = sqlx::query!(r#"SELECT id AS "id: Id<Races>", series AS "series: Series", event, file_stem AS "file_stem!" FROM races WHERE file_stem IS NOT NULL AND web_id IS NULL AND hash1 IS NULL"#).fetch_all(&mut *transaction).await?;
+        let mut rando_paths = HashMap::<(Series, String), Option<PathBuf>>::default();
+        let mut num_updated = 0;
+        for race in races {
+            let rando_path = match rando_paths.entry((race.series, race.event.clone())) {
+                hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                hash_map::Entry::Vacant(entry) => {
+                    let version = event::Data::new(&mut transaction, race.series, race.event).await?.and_then(|event| event.rando_version);
+                    entry.insert(if let Some(version) = version { Some(rando_path(&self.local_rando, version).await?) } else { None })
+                }
+            };
+            let Some(rando_path) = rando_path.as_deref() else { continue };
+            let seed_dir = self.local_rando.seed_dir();
+            let Some(patch_path) = [seed_dir.join(format!("{}.zpf", race.file_stem)), seed_dir.join(format!("{}.zpfz", race.file_stem))].into_iter().find(|path| path.exists()) else { continue };
+            match zpf::file_hash(rando_path, &patch_path).await {
+                Ok([hash1, hash2, hash3, hash4, hash5]) => {
+                    sqlx::query!("UPDATE races SET hash1 = $1, hash2 = $2, hash3 = $3, hash4 = $4, hash5 = $5 WHERE id = $6", hash1 as _, hash2 as _, hash3 as _, hash4 as _, hash5 as _, race.id as _).execute(&mut *transaction).await?;
+                    num_updated += 1;
+                }
+                Err(e) => eprintln!("failed to read file hash from patch file {}: {e} ({e:?})", patch_path.display()),
+            }
         }
+        transaction.commit().await?;
+        Ok(num_updated)
+    }
+
+    /// When the spoiler log of this race should be unlocked by the [scheduler](crate::scheduler), or `None` if it doesn't need to be.
+    ///
+    /// Race rooms unlock the spoiler log once the race is finished, but a race whose parts have all been played outside of a public room (e.g. async parts run in Discord) needs this as a fallback.
+    pub(crate) fn spoiler_log_unlock_at(&self, race: &Race) -> Option<DateTime<Utc>> {
+        let files = race.seed.files.as_ref()?;
+        if self.seed_generators.for_files(files)?.capabilities().auto_unlock_spoiler_log { return None }
+        let goal = Goal::for_event(race.series, &race.event)?;
+        if !matches!(goal.unlock_spoiler_log(true, false), UnlockSpoilerLog::Progression | UnlockSpoilerLog::After) { return None }
+        let is_locked = match files {
+            seed::Files::MidosHouse { locked_spoiler_log_path, .. } => locked_spoiler_log_path.as_ref().is_some_and(|path| Path::new(path).exists()),
+            seed::Files::OotrWeb { file_stem, .. } => !self.local_rando.seed_dir().join(format!("{file_stem}_Spoiler.json")).exists(),
+            seed::Files::AlttprDoorRando { .. } | seed::Files::TriforceBlitz { .. } | seed::Files::TfbSotd { .. } => false,
+        };
+        if !is_locked { return None }
+        // wait until every part of the race has ended
+        race.cal_events().map(|cal_event| cal_event.end()).try_fold(None, |latest, end| end.map(|end| latest.max(Some(end))))?
     }
 
-    pub(crate) fn roll_seed(self: Arc<Self>, preroll: PrerollMode, allow_web: bool, delay_until: Option<DateTime<Utc>>, version: VersionedBranch, mut settings: seed::Settings, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+    /// Unlocks the spoiler log of the given race if it's [due](Self::spoiler_log_unlock_at).
+    pub(crate) async fn unlock_race_spoiler_log(&self, id: Id<Races>) -> Result<(), UnlockSpoilerLogError> {
+        let mut transaction = self.db_pool.begin().await?;
+        let race = Race::from_id(&mut transaction, &self.http_client, id).await?;
+        transaction.commit().await?;
+        if self.spoiler_log_unlock_at(&race).is_some_and(|unlock_at| unlock_at <= Utc::now()) {
+            let files = race.seed.files.as_ref().expect("spoiler log to unlock without seed files");
+            if let Some(generator) = self.seed_generators.for_files(files) {
+                generator.unlock_spoiler_log(self, files).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the room for the given race right away instead of waiting for the scheduled room opening. The caller must hold `new_room_lock`.
+    pub(crate) async fn create_room(&self, transaction: &mut Transaction<'_, Postgres>, discord_ctx: &DiscordCtx, cal_event: &cal::Event, event: &event::Data<'static>) -> Result<Option<(bool, String)>, Error> {
+        create_room(transaction, discord_ctx, &self.host_info, &self.racetime_config.client_id, &self.racetime_config.client_secret, &self.extra_room_tx, &self.http_client, self.clean_shutdown.clone(), cal_event, event).await
+    }
+
+    fn roll_seed(self: Arc<Self>, preroll: PrerollMode, allow_web: bool, delay_until: Option<DateTime<Utc>>, priority: Priority, version: VersionedBranch, mut settings: seed::Settings, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
         let world_count = settings.get("world_count").map_or(1, |world_count| world_count.as_u64().expect("world_count setting wasn't valid u64").try_into().expect("too many worlds"));
         let password_lock = settings.get("password_lock").is_some_and(|password_lock| password_lock.as_bool().expect("password_lock setting wasn't a Boolean"));
         settings.insert(format!("create_spoiler"), json!(match unlock_spoiler_log {
@@ -1381,85 +1572,113 @@ impl GlobalState {
         }));
         let (update_tx, update_rx) = mpsc::channel(128);
         tokio::spawn(async move {
-            if_chain! {
+            let web_version = if_chain! {
                 if allow_web;
+                if self.local_rando.allows_web();
                 if let Some(web_version) = self.ootr_api_client.can_roll_on_web(None, &version, world_count, unlock_spoiler_log).await;
                 then {
-                    // ootrandomizer.com seed IDs are sequential, making it easy to find a seed if you know when it was rolled.
-                    // This is especially true for open races, whose rooms are opened an entire hour before start.
-                    // To make this a bit more difficult, we delay the start of seed rolling depending on the goal.
-                    match preroll {
-                        // The type of seed being rolled is unlikely to require a long time or multiple attempts to generate,
-                        // so we avoid the issue with sequential IDs by simply not rolling ahead of time.
-                        PrerollMode::None => if let Some(sleep_duration) = delay_until.and_then(|delay_until| (delay_until - Utc::now()).to_std().ok()) {
-                            sleep(sleep_duration).await;
-                        },
-                        // Middle-ground option. Start rolling the seed at a random point between 20 and 15 minutes before start.
-                        PrerollMode::Short => if let Some(max_sleep_duration) = delay_until.and_then(|delay_until| (delay_until - Utc::now()).to_std().ok()) {
-                            let min_sleep_duration = max_sleep_duration.saturating_sub(Duration::from_secs(5 * 60));
-                            let sleep_duration = rng().random_range(min_sleep_duration..max_sleep_duration);
-                            sleep(sleep_duration).await;
-                        },
-                        // The type of seed being rolled is fairly likely to require a long time and/or multiple attempts to generate.
-                        // Start rolling the seed at a random point between the room being opened and 30 minutes before start.
-                        PrerollMode::Medium => if let Some(max_sleep_duration) = delay_until.and_then(|delay_until| (delay_until - TimeDelta::minutes(15) - Utc::now()).to_std().ok()) {
-                            let sleep_duration = rng().random_range(Duration::default()..max_sleep_duration);
-                            sleep(sleep_duration).await;
-                        },
-                        // The type of seed being rolled is extremely likely to require a very long time and/or a large number of attempts to generate.
-                        // Start rolling the seed immediately upon the room being opened.
-                        PrerollMode::Long => {}
-                    }
-                    match self.ootr_api_client.roll_seed_with_retry(update_tx.clone(), delay_until, web_version, false, unlock_spoiler_log, settings).await {
-                        Ok(ootr_web::SeedInfo { id, gen_time, file_hash, file_stem, password }) => update_tx.send(SeedRollUpdate::Done {
-                            seed: seed::Data {
-                                file_hash: Some(file_hash),
-                                files: Some(seed::Files::OotrWeb {
-                                    file_stem: Cow::Owned(file_stem),
-                                    id, gen_time,
-                                }),
-                                progression_spoiler: unlock_spoiler_log == UnlockSpoilerLog::Progression,
-                                password,
-                            },
-                            rsl_preset: None,
-                            unlock_spoiler_log,
-                        }).await?,
-                        Err(e) => update_tx.send(SeedRollUpdate::Error(e.into())).await?, //TODO fall back to rolling locally for network errors
+                    if self.web_fallback.is_active().await {
+                        update_tx.send(SeedRollUpdate::WebUnavailable).await?;
+                        None
+                    } else {
+                        Some(web_version)
                     }
                 } else {
-                    update_tx.send(SeedRollUpdate::Started).await?;
-                    match roll_seed_locally(delay_until, version, match unlock_spoiler_log {
-                        UnlockSpoilerLog::Now | UnlockSpoilerLog::Progression | UnlockSpoilerLog::After => true,
-                        UnlockSpoilerLog::Never => password_lock, // spoiler log needs to be generated so the backend can read the password
-                    }, settings).await {
-                        Ok((patch_filename, spoiler_log_path)) => update_tx.send(match spoiler_log_path.map(|spoiler_log_path| spoiler_log_path.into_os_string().into_string()).transpose() {
-                            Ok(locked_spoiler_log_path) => match regex_captures!(r"^(.+)\.zpfz?$", &patch_filename) {
-                                Some((_, file_stem)) => SeedRollUpdate::Done {
-                                    seed: seed::Data {
-                                        file_hash: None, password: None, // will be read from spoiler log
-                                        files: Some(seed::Files::MidosHouse {
-                                            file_stem: Cow::Owned(file_stem.to_owned()),
-                                            locked_spoiler_log_path,
-                                        }),
-                                        progression_spoiler: unlock_spoiler_log == UnlockSpoilerLog::Progression,
-                                    },
-                                    rsl_preset: None,
-                                    unlock_spoiler_log,
-                                },
-                                None => SeedRollUpdate::Error(RollError::PatchPath),
-                            },
-                            Err(e) => SeedRollUpdate::Error(e.into())
-                        }).await?,
-                        Err(e) => update_tx.send(SeedRollUpdate::Error(e)).await?,
-                    }
+                    None
                 }
+            };
+            if let Some(web_version) = web_version {
+                // ootrandomizer.com seed IDs are sequential, making it easy to find a seed if you know when it was rolled.
+                // This is especially true for open races, whose rooms are opened an entire hour before start.
+                // To make this a bit more difficult, we delay the start of seed rolling depending on the goal.
+                match preroll {
+                    // The type of seed being rolled is unlikely to require a long time or multiple attempts to generate,
+                    // so we avoid the issue with sequential IDs by simply not rolling ahead of time.
+                    PrerollMode::None => if let Some(sleep_duration) = delay_until.and_then(|delay_until| (delay_until - Utc::now()).to_std().ok()) {
+                        sleep(sleep_duration).await;
+                    },
+                    // Middle-ground option. Start rolling the seed at a random point between 20 and 15 minutes before start.
+                    PrerollMode::Short => if let Some(max_sleep_duration) = delay_until.and_then(|delay_until| (delay_until - Utc::now()).to_std().ok()) {
+                        let min_sleep_duration = max_sleep_duration.saturating_sub(Duration::from_secs(5 * 60));
+                        let sleep_duration = rng().random_range(min_sleep_duration..max_sleep_duration);
+                        sleep(sleep_duration).await;
+                    },
+                    // The type of seed being rolled is fairly likely to require a long time and/or multiple attempts to generate.
+                    // Start rolling the seed at a random point between the room being opened and 30 minutes before start.
+                    PrerollMode::Medium => if let Some(max_sleep_duration) = delay_until.and_then(|delay_until| (delay_until - TimeDelta::minutes(15) - Utc::now()).to_std().ok()) {
+                        let sleep_duration = rng().random_range(Duration::default()..max_sleep_duration);
+                        sleep(sleep_duration).await;
+                    },
+                    // The type of seed being rolled is extremely likely to require a very long time and/or a large number of attempts to generate.
+                    // Start rolling the seed immediately upon the room being opened.
+                    PrerollMode::Long => {}
+                }
+                match self.ootr_api_client.roll_seed_with_retry(update_tx.clone(), delay_until, web_version, false, unlock_spoiler_log, settings.clone()).await {
+                    Ok(ootr_web::SeedInfo { id, gen_time, file_hash, file_stem, password }) => update_tx.send(SeedRollUpdate::Done {
+                        seed: seed::Data {
+                            file_hash: Some(file_hash),
+                            files: Some(seed::Files::OotrWeb {
+                                file_stem: Cow::Owned(file_stem),
+                                id, gen_time,
+                            }),
+                            progression_spoiler: unlock_spoiler_log == UnlockSpoilerLog::Progression,
+                            password,
+                        },
+                        rsl_preset: None,
+                        unlock_spoiler_log,
+                    }).await?,
+                    Err(e) => if self.web_fallback.should_fall_back(&e).await {
+                        update_tx.send(SeedRollUpdate::WebUnavailable).await?;
+                        let Some(_worker) = self.seed_queue.acquire_worker(priority, &update_tx).await else { return Ok(()) };
+                        self.roll_seed_locally_with_updates(&update_tx, delay_until, version, password_lock, settings, unlock_spoiler_log).await?;
+                    } else {
+                        update_tx.send(SeedRollUpdate::Error(e.into())).await?;
+                    },
+                }
+            } else {
+                let Some(_worker) = self.seed_queue.acquire_worker(priority, &update_tx).await else { return Ok(()) };
+                update_tx.send(SeedRollUpdate::Started).await?;
+                self.roll_seed_locally_with_updates(&update_tx, delay_until, version, password_lock, settings, unlock_spoiler_log).await?;
             }
             Ok::<_, mpsc::error::SendError<_>>(())
         });
         update_rx
     }
 
-    pub(crate) fn roll_crosskeys2025_seed(self: Arc<Self>, crosskeys_options: CrosskeysRaceOptions) -> mpsc::Receiver<SeedRollUpdate> {
+    async fn roll_seed_locally_with_updates(&self, update_tx: &mpsc::Sender<SeedRollUpdate>, delay_until: Option<DateTime<Utc>>, version: VersionedBranch, password_lock: bool, settings: seed::Settings, unlock_spoiler_log: UnlockSpoilerLog) -> Result<(), mpsc::error::SendError<SeedRollUpdate>> {
+        let res = select! {
+            res = roll_seed_locally(&self.local_rando, delay_until, version, match unlock_spoiler_log {
+                UnlockSpoilerLog::Now | UnlockSpoilerLog::Progression | UnlockSpoilerLog::After => true,
+                UnlockSpoilerLog::Never => password_lock, // spoiler log needs to be generated so the backend can read the password
+            }, settings) => res,
+            // nothing is waiting for this seed anymore, stop the randomizer
+            () = update_tx.closed() => return Ok(()),
+        };
+        match res {
+            Ok((patch_filename, spoiler_log_path, file_hash)) => update_tx.send(match spoiler_log_path.map(|spoiler_log_path| spoiler_log_path.into_os_string().into_string()).transpose() {
+                Ok(locked_spoiler_log_path) => match regex_captures!(r"^(.+)\.zpfz?$", &patch_filename) {
+                    Some((_, file_stem)) => SeedRollUpdate::Done {
+                        seed: seed::Data {
+                            file_hash, password: None, // otherwise read from spoiler log
+                            files: Some(seed::Files::MidosHouse {
+                                file_stem: Cow::Owned(file_stem.to_owned()),
+                                locked_spoiler_log_path,
+                            }),
+                            progression_spoiler: unlock_spoiler_log == UnlockSpoilerLog::Progression,
+                        },
+                        rsl_preset: None,
+                        unlock_spoiler_log,
+                    },
+                    None => SeedRollUpdate::Error(RollError::PatchPath),
+                },
+                Err(e) => SeedRollUpdate::Error(e.into())
+            }).await?,
+            Err(e) => update_tx.send(SeedRollUpdate::Error(e)).await?,
+        }
+        Ok(())
+    }
+
+    fn roll_crosskeys2025_seed(self: Arc<Self>, priority: Priority, crosskeys_options: CrosskeysRaceOptions) -> mpsc::Receiver<SeedRollUpdate> {
         let (update_tx, update_rx) = mpsc::channel(128);
         let update_tx2 = update_tx.clone();
         tokio::spawn(async move {
@@ -1528,6 +1747,7 @@ impl GlobalState {
             let yaml_path = yaml_file.path();
             tokio::fs::File::from_std(yaml_file.reopen().at(&yaml_file)?).write_all(serde_yml::to_string(&crosskeys_yaml)?.as_bytes()).await.at(&yaml_file)?;
             
+            let _worker = self.seed_queue.acquire_worker(priority, &update_tx).await.ok_or(RollError::ChannelClosed)?;
             // Add retry logic with 2 retries
             const MAX_RETRIES: u8 = 2;
             
@@ -1617,7 +1837,7 @@ impl GlobalState {
         Ok(hash)
     }
 
-    pub(crate) fn roll_rsl_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, preset: rsl::VersionedPreset, world_count: u8, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+    fn roll_rsl_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, priority: Priority, preset: rsl::VersionedPreset, world_count: u8, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
         let (update_tx, update_rx) = mpsc::channel(128);
         let update_tx2 = update_tx.clone();
         tokio::spawn(async move {
@@ -1643,8 +1863,13 @@ impl GlobalState {
                 .check(PYTHON).await?
                 .stdout;
             let randomizer_version = String::from_utf8(randomizer_version)?.trim().parse::<rando::Version>()?;
-            let web_version = self.ootr_api_client.can_roll_on_web(Some(&preset), &VersionedBranch::Pinned { version: randomizer_version.clone() }, world_count, unlock_spoiler_log).await;
+            let mut web_version = self.ootr_api_client.can_roll_on_web(Some(&preset), &VersionedBranch::Pinned { version: randomizer_version.clone() }, world_count, unlock_spoiler_log).await;
+            if web_version.is_some() && self.web_fallback.is_active().await {
+                update_tx.send(SeedRollUpdate::WebUnavailable).await.allow_unreceived();
+                web_version = None;
+            }
             // run the RSL script
+            let _worker = self.seed_queue.acquire_worker(priority, &update_tx).await.ok_or(RollError::ChannelClosed)?;
             update_tx.send(SeedRollUpdate::Started).await.allow_unreceived();
             let outer_tries = if web_version.is_some() { 5 } else { 1 }; // when generating locally, retries are already handled by the RSL script
             let mut last_error = None;
@@ -1723,7 +1948,14 @@ impl GlobalState {
                     let ootr_web::SeedInfo { id, gen_time, file_hash, file_stem, password } = match self.ootr_api_client.roll_seed_with_retry(update_tx.clone(), None /* always limit to 3 tries per settings */, web_version, true, unlock_spoiler_log, settings).await {
                         Ok(data) => data,
                         Err(ootr_web::Error::Retries { .. }) => continue,
-                        Err(e) => return Err(e.into()), //TODO fall back to rolling locally for network errors
+                        Err(e) => if self.web_fallback.should_fall_back(&e).await {
+                            // the settings were rolled with --no_seed, so run the RSL script again to also generate the seed
+                            update_tx.send(SeedRollUpdate::WebUnavailable).await.allow_unreceived();
+                            web_version = None;
+                            continue
+                        } else {
+                            return Err(e.into())
+                        },
                     };
                     update_tx.send(SeedRollUpdate::Done {
                         seed: seed::Data {
@@ -1781,7 +2013,7 @@ impl GlobalState {
         update_rx
     }
 
-    pub(crate) fn roll_tfb_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, version: &'static str, room: Option<String>, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+    fn roll_tfb_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, version: &'static str, room: Option<String>, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
         let (update_tx, update_rx) = mpsc::channel(128);
         let update_tx2 = update_tx.clone();
         tokio::spawn(async move {
@@ -1858,7 +2090,7 @@ impl GlobalState {
         update_rx
     }
 
-    pub(crate) fn roll_tfb_dev_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, coop: bool, room: Option<String>, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
+    fn roll_tfb_dev_seed(self: Arc<Self>, delay_until: Option<DateTime<Utc>>, coop: bool, room: Option<String>, unlock_spoiler_log: UnlockSpoilerLog) -> mpsc::Receiver<SeedRollUpdate> {
         let (update_tx, update_rx) = mpsc::channel(128);
         let update_tx2 = update_tx.clone();
         tokio::spawn(async move {
@@ -1907,18 +2139,7 @@ impl GlobalState {
             };
             let (is_dev, uuid) = tfb::parse_seed_url(response.url()).ok_or_else(|| RollError::TfbUrl(response.url().clone()))?;
             debug_assert!(is_dev);
-            /*
-            let patch = self.http_client
-                .get(format!("https://dev.triforceblitz.com/seeds/{uuid}/patch"))
-                .send().await?
-                .detailed_error_for_status().await?
-                .bytes().await?;
-            if coop {
-                //TODO decode patch as zip, extract file hash from P1.zpf
-            } else {
-                //TODO extract file hash from patch, which is a .zpf
-            }
-            */
+            // The patch could be read using zpf::file_hash, but dev.triforceblitz.com doesn't publish the symbol table of its randomizer fork, so the location of the file hash in the patched ROM is unknown.
             update_tx.send(SeedRollUpdate::Done {
                 seed: seed::Data {
                     file_hash: None,
@@ -1940,49 +2161,63 @@ impl GlobalState {
     }
 }
 
-async fn roll_seed_locally(delay_until: Option<DateTime<Utc>>, version: VersionedBranch, unlock_spoiler_log: bool, mut settings: seed::Settings) -> Result<(String, Option<PathBuf>), RollError> {
+/// The randomizer checkout to use for the given version, cloning or updating it as needed.
+async fn rando_path(local_rando: &LocalRando, version: VersionedBranch) -> Result<PathBuf, RollError> {
     let allow_riir = match version {
         VersionedBranch::Pinned { ref version } => version.branch() == rando::Branch::DevFenhl && (version.base(), version.supplementary()) >= (&Version::new(8, 3, 16), Some(1)), // some versions older than this generate corrupted patch files
         VersionedBranch::Latest { branch } => branch == rando::Branch::DevFenhl,
         VersionedBranch::Custom { .. } => false,
     };
-    let rando_path = match version {
-        VersionedBranch::Pinned { version } => {
-            version.clone_repo(allow_riir).await?;
-            version.dir(allow_riir)?
-        }
-        VersionedBranch::Latest { branch } => {
-            branch.clone_repo(allow_riir).await?;
-            branch.dir(allow_riir)?
-        }
-        VersionedBranch::Custom { github_username, branch } => {
-            let parent = {
-                #[cfg(unix)] { Path::new("/opt/git/github.com").join(&*github_username).join("OoT-Randomizer").join("branch") }
-                #[cfg(windows)] { UserDirs::new().ok_or(RollError::UserDirs)?.home_dir().join("git").join("github.com").join(&*github_username).join("OoT-Randomizer").join("branch") }
-            };
-            let dir = parent.join(&*branch);
-            if dir.exists() {
-                //TODO hard reset to remote instead?
-                //TODO use git2 or gix instead?
-                Command::new("git").arg("pull").current_dir(&dir).check("git").await?;
-            } else {
-                fs::create_dir_all(&parent).await?;
-                let mut command = Command::new("git"); //TODO use git2 or gix instead? (git2 doesn't support shallow clones, gix is very low level)
-                command.arg("clone");
-                command.arg(format!("https://github.com/{github_username}/OoT-Randomizer.git"));
-                command.arg(format!("--branch={branch}"));
-                command.arg(&*branch);
-                command.current_dir(parent);
-                command.check("git").await?;
+    Ok(match local_rando {
+        LocalRando::Managed => match version {
+            VersionedBranch::Pinned { version } => {
+                version.clone_repo(allow_riir).await?;
+                version.dir(allow_riir)?
+            }
+            VersionedBranch::Latest { branch } => {
+                branch.clone_repo(allow_riir).await?;
+                branch.dir(allow_riir)?
+            }
+            VersionedBranch::Custom { github_username, branch } => {
+                let parent = {
+                    #[cfg(unix)] { Path::new("/opt/git/github.com").join(&*github_username).join("OoT-Randomizer").join("branch") }
+                    #[cfg(windows)] { UserDirs::new().ok_or(RollError::UserDirs)?.home_dir().join("git").join("github.com").join(&*github_username).join("OoT-Randomizer").join("branch") }
+                };
+                let dir = parent.join(&*branch);
+                if dir.exists() {
+                    //TODO hard reset to remote instead?
+                    //TODO use git2 or gix instead?
+                    Command::new("git").arg("pull").current_dir(&dir).check("git").await?;
+                } else {
+                    fs::create_dir_all(&parent).await?;
+                    let mut command = Command::new("git"); //TODO use git2 or gix instead? (git2 doesn't support shallow clones, gix is very low level)
+                    command.arg("clone");
+                    command.arg(format!("https://github.com/{github_username}/OoT-Randomizer.git"));
+                    command.arg(format!("--branch={branch}"));
+                    command.arg(&*branch);
+                    command.current_dir(parent);
+                    command.check("git").await?;
+                }
+                dir
+            }
+        },
+        #[cfg(test)] LocalRando::Fixed { rando_path, .. } => rando_path.clone(),
+    })
+}
+
+/// Returns the patch filename, the path to the locked spoiler log if one was requested, and the file hash if it had to be read from the patch.
+async fn roll_seed_locally(local_rando: &LocalRando, delay_until: Option<DateTime<Utc>>, version: VersionedBranch, unlock_spoiler_log: bool, mut settings: seed::Settings) -> Result<(String, Option<PathBuf>, Option<[HashIcon; 5]>), RollError> {
+    let rando_path = rando_path(local_rando, version).await?;
+    match local_rando {
+        LocalRando::Managed => {
+            #[cfg(unix)] {
+                settings.insert(format!("rom"), json!(BaseDirectories::new().find_data_file(Path::new("midos-house").join("oot-ntscu-1.0.z64")).ok_or(RollError::RomPath)?));
+                if settings.get("language").and_then(|language| language.as_str()).is_some_and(|language| matches!(language, "french" | "german")) {
+                    settings.insert(format!("pal_rom"), json!(BaseDirectories::new().find_data_file(Path::new("midos-house").join("oot-pal-1.0.z64")).ok_or(RollError::RomPath)?));
+                }
             }
-            dir
-        }
-    };
-    #[cfg(unix)] {
-        settings.insert(format!("rom"), json!(BaseDirectories::new().find_data_file(Path::new("midos-house").join("oot-ntscu-1.0.z64")).ok_or(RollError::RomPath)?));
-        if settings.get("language").and_then(|language| language.as_str()).is_some_and(|language| matches!(language, "french" | "german")) {
-            settings.insert(format!("pal_rom"), json!(BaseDirectories::new().find_data_file(Path::new("midos-house").join("oot-pal-1.0.z64")).ok_or(RollError::RomPath)?));
         }
+        #[cfg(test)] LocalRando::Fixed { .. } => {}
     }
     settings.insert(format!("create_patch_file"), json!(true));
     settings.insert(format!("create_compressed_rom"), json!(false));
@@ -2011,6 +2246,7 @@ async fn roll_seed_locally(delay_until: Option<DateTime<Utc>>, version: Versione
         }
         let mut rando_process = rando_cmd.arg("--settings=-")
             .current_dir(&rando_path)
+            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -2034,15 +2270,43 @@ async fn roll_seed_locally(delay_until: Option<DateTime<Utc>>, version: Versione
             None
         };
         let patch_filename = patch_path.file_name().expect("patch file path with no file name");
-        fs::rename(&patch_path, Path::new(seed::DIR).join(patch_filename)).await?;
+        let seed_path = local_rando.seed_dir().join(patch_filename);
+        fs::rename(&patch_path, &seed_path).await?;
+        let file_hash = if spoiler_log_path.is_none() {
+            // no spoiler log to read the file hash from
+            match zpf::file_hash(&rando_path, &seed_path).await {
+                Ok(file_hash) => Some(file_hash),
+                Err(e) => {
+                    eprintln!("failed to read file hash from patch file {}: {e} ({e:?})", seed_path.display());
+                    None
+                }
+            }
+        } else {
+            None
+        };
         return Ok((
             patch_filename.to_str().expect("non-UTF-8 patch filename").to_owned(),
             spoiler_log_path,
+            file_hash,
         ))
     }
     unreachable!()
 }
 
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BackfillFileHashesError {
+    #[error(transparent)] Event(#[from] event::DataError),
+    #[error(transparent)] Roll(#[from] RollError),
+    #[error(transparent)] Sql(#[from] sqlx::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum UnlockSpoilerLogError {
+    #[error(transparent)] Cal(#[from] cal::Error),
+    #[error(transparent)] RaceTime(#[from] Error),
+    #[error(transparent)] Sql(#[from] sqlx::Error),
+}
+
 #[derive(Debug, thiserror::Error)]
 #[cfg_attr(unix, derive(Protocol))]
 #[cfg_attr(unix, async_proto(via = (String, String)))]
@@ -2063,6 +2327,8 @@ pub(crate) enum RollError {
     #[cfg(unix)] #[error(transparent)] Xdg(#[from] xdg::BaseDirectoriesError),
     #[error(transparent)] Yml(#[from] serde_yml::Error),
     #[error("no hash line found in spoiler log")] AlttprHashLineNotFound,
+    #[error("a seed is already being rolled for this room")]
+    AlreadyRolling,
     #[error("{display}")]
     Cloned {
         debug: String,
@@ -2147,6 +2413,8 @@ pub(crate) enum SeedRollUpdate {
         rsl_preset: Option<rsl::Preset>,
         unlock_spoiler_log: UnlockSpoilerLog,
     },
+    /// ootrandomizer.com is unreachable, so the seed is being rolled locally instead.
+    WebUnavailable,
     /// Seed rolling failed.
     Error(RollError),
     #[cfg(unix)]
@@ -2155,11 +2423,11 @@ pub(crate) enum SeedRollUpdate {
 }
 
 impl SeedRollUpdate {
-    async fn handle(self, db_pool: &PgPool, ctx: &RaceContext<GlobalState>, state: &ArcRwLock<RaceState>, official_data: Option<&OfficialRaceData>, language: Language, article: &'static str, description: &str) -> Result<(), Error> {
+    async fn handle(self, db_pool: &PgPool, ctx: &impl RaceRoom, state: &ArcRwLock<RaceState>, official_data: Option<&OfficialRaceData>, language: Language, article: &'static str, description: &str) -> Result<(), Error> {
         match self {
-            Self::Queued(0) => ctx.say("I'm already rolling other multiworld seeds so your seed has been queued. It is at the front of the queue so it will be rolled next.").await?,
-            Self::Queued(1) => ctx.say("I'm already rolling other multiworld seeds so your seed has been queued. There is 1 seed in front of it in the queue.").await?,
-            Self::Queued(pos) => ctx.say(format!("I'm already rolling other multiworld seeds so your seed has been queued. There are {pos} seeds in front of it in the queue.")).await?,
+            Self::Queued(0) => ctx.say("I'm already rolling other seeds so your seed has been queued. It is at the front of the queue so it will be rolled next.").await?,
+            Self::Queued(1) => ctx.say("I'm already rolling other seeds so your seed has been queued. There is 1 seed in front of it in the queue.").await?,
+            Self::Queued(pos) => ctx.say(format!("I'm already rolling other seeds so your seed has been queued. There are {pos} seeds in front of it in the queue.")).await?,
             Self::MovedForward(0) => ctx.say("The queue has moved and your seed is now at the front so it will be rolled next.").await?,
             Self::MovedForward(1) => ctx.say("The queue has moved and there is only 1 more seed in front of yours.").await?,
             Self::MovedForward(pos) => ctx.say(format!("The queue has moved and there are now {pos} seeds in front of yours.")).await?,
@@ -2168,18 +2436,23 @@ impl SeedRollUpdate {
             } else {
                 format!("Rolling {article} {description}…")
             }).await?,
+            Self::WebUnavailable => ctx.say(if let French = language {
+                "ootrandomizer.com est actuellement indisponible, la seed sera donc générée localement sur Hyrule Town Hall."
+            } else {
+                "ootrandomizer.com is currently unavailable, so the seed will be rolled locally on Hyrule Town Hall instead."
+            }).await?,
             Self::Done { mut seed, rsl_preset, unlock_spoiler_log } => {
                 if let Some(seed::Files::MidosHouse { ref file_stem, ref mut locked_spoiler_log_path }) = seed.files {
-                    lock!(@write seed_metadata = ctx.global_state.seed_metadata; seed_metadata.insert(file_stem.to_string(), SeedMetadata {
+                    lock!(@write seed_metadata = ctx.global_state().seed_metadata; seed_metadata.insert(file_stem.to_string(), SeedMetadata {
                         locked_spoiler_log_path: locked_spoiler_log_path.clone(),
                         progression_spoiler: unlock_spoiler_log == UnlockSpoilerLog::Progression,
                     }));
                     if unlock_spoiler_log == UnlockSpoilerLog::Now && locked_spoiler_log_path.is_some() {
-                        fs::rename(locked_spoiler_log_path.as_ref().unwrap(), Path::new(seed::DIR).join(format!("{file_stem}_Spoiler.json"))).await.to_racetime()?;
+                        fs::rename(locked_spoiler_log_path.as_ref().unwrap(), ctx.global_state().local_rando.seed_dir().join(format!("{file_stem}_Spoiler.json"))).await.to_racetime()?;
                         *locked_spoiler_log_path = None;
                     }
                 }
-                let extra = seed.extra(Utc::now()).await.to_racetime()?;
+                let extra = ctx.global_state().seed_generators.extra(&seed).await.to_racetime()?;
                 if let Some(OfficialRaceData { cal_event, .. }) = official_data {
                     match seed.files.as_ref().expect("received seed with no files") {
                         seed::Files::MidosHouse { file_stem, .. } => {
@@ -2283,7 +2556,7 @@ impl SeedRollUpdate {
                 set_bot_raceinfo(ctx, &seed, rsl_preset, false).await?;
                 if let Some(OfficialRaceData { cal_event, event, restreams, .. }) = official_data {
                     // send multiworld rooms
-                    let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
+                    let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
                     let mut mw_rooms_created = 0;
                     for team in cal_event.active_teams() {
                         if let Some(mw::Impl::MidosHouse) = team.mw_impl {
@@ -2358,7 +2631,7 @@ impl SeedRollUpdate {
                                         }
                                     }
                                     if !all_notified {
-                                        ADMIN_USER.create_dm_channel(&*ctx.global_state.discord_ctx.read().await).await.to_racetime()?.say(&*ctx.global_state.discord_ctx.read().await, format!("auto-tracker room for {reply_to}: `{tracker_room_name}`")).await.to_racetime()?;
+                                        ADMIN_USER.create_dm_channel(&*ctx.global_state().discord_ctx.read().await).await.to_racetime()?.say(&*ctx.global_state().discord_ctx.read().await, format!("auto-tracker room for {reply_to}: `{tracker_room_name}`")).await.to_racetime()?;
                                     }
                                 }
                                 mw_rooms_created += 1;
@@ -2387,6 +2660,12 @@ impl SeedRollUpdate {
                 }).await?; //TODO for official races, explain that retrying is done using !seed
                 lock!(@write state = state; *state = RaceState::Init);
             }
+            // the seed that's already being rolled will update the race state
+            Self::Error(RollError::AlreadyRolling) => ctx.say(if let French = language {
+                "Désolé, je suis déjà en train de générer une seed pour cette race. Veuillez patienter."
+            } else {
+                "Sorry, but I'm already rolling a seed for this room. Please wait."
+            }).await?,
             Self::Error(e) => {
                 eprintln!("seed roll error in https://{}{}: {e} ({e:?})", racetime_host(), ctx.data().await.url);
                 if let Environment::Production = Environment::default() {
@@ -2451,8 +2730,8 @@ async fn room_options(goal: Goal, event: &event::Data<'_>, cal_event: &cal::Even
     }
 }
 
-async fn set_bot_raceinfo(ctx: &RaceContext<GlobalState>, seed: &seed::Data, rsl_preset: Option<rsl::Preset>, show_password: bool) -> Result<(), Error> {
-    let extra = seed.extra(Utc::now()).await.to_racetime()?;
+async fn set_bot_raceinfo(ctx: &impl RaceRoom, seed: &seed::Data, rsl_preset: Option<rsl::Preset>, show_password: bool) -> Result<(), Error> {
+    let extra = ctx.global_state().seed_generators.extra(seed).await.to_racetime()?;
     ctx.set_bot_raceinfo(&format!(
         "{rsl_preset}{file_hash}{sep}{password}{newline}{seed_url}",
         rsl_preset = rsl_preset.map(|preset| format!("{}\n", preset.race_info())).unwrap_or_default(),
@@ -2713,29 +2992,42 @@ impl Handler {
 
     fn is_official(&self) -> bool { self.official_data.is_some() }
 
-    async fn goal(&self, ctx: &RaceContext<GlobalState>) -> Result<Goal, GoalFromStrError> {
-        if let Some(OfficialRaceData { goal, .. }) = self.official_data {
+    async fn goal(&self, ctx: &impl RaceRoom) -> Result<Goal, GoalFromStrError> {
+        Self::goal_for(ctx, self.official_data.as_ref()).await
+    }
+
+    async fn goal_for(ctx: &impl RaceRoom, official_data: Option<&OfficialRaceData>) -> Result<Goal, GoalFromStrError> {
+        if let Some(&OfficialRaceData { goal, .. }) = official_data {
             Ok(goal)
         } else {
             ctx.data().await.goal.name.parse()
         }
     }
 
-    async fn can_monitor(&self, ctx: &RaceContext<GlobalState>, is_monitor: bool, msg: &ChatMessage) -> sqlx::Result<bool> {
+    fn draft_kind(&self, goal: Goal) -> Option<draft::Kind> {
+        Self::draft_kind_for(self.official_data.as_ref(), goal)
+    }
+
+    /// Prefers the event's draft kind over the goal's so drafts defined in the database are used in official races.
+    fn draft_kind_for(official_data: Option<&OfficialRaceData>, goal: Goal) -> Option<draft::Kind> {
+        official_data.and_then(|OfficialRaceData { event, .. }| event.draft_kind()).or_else(|| goal.draft_kind())
+    }
+
+    async fn can_monitor(&self, ctx: &impl RaceRoom, is_monitor: bool, msg: &ChatMessage) -> sqlx::Result<bool> {
         if is_monitor { return Ok(true) }
         if let Some(OfficialRaceData { ref event, .. }) = self.official_data {
             if let Some(UserData { ref id, .. }) = msg.user {
-                if let Some(user) = User::from_racetime(&ctx.global_state.db_pool, id).await? {
-                    return sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM organizers WHERE series = $1 AND event = $2 AND organizer = $3) AS "exists!""#, event.series as _, &event.event, user.id as _).fetch_one(&ctx.global_state.db_pool).await
+                if let Some(user) = User::from_racetime(&ctx.global_state().db_pool, id).await? {
+                    return sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM organizers WHERE series = $1 AND event = $2 AND organizer = $3) AS "exists!""#, event.series as _, &event.event, user.id as _).fetch_one(&ctx.global_state().db_pool).await
                 }
             }
         }
         Ok(false)
     }
 
-    async fn send_settings(&self, ctx: &RaceContext<GlobalState>, preface: &str, reply_to: &str) -> Result<(), Error> {
+    async fn send_settings(&self, ctx: &impl RaceRoom, preface: &str, reply_to: &str) -> Result<(), Error> {
         let goal = self.goal(ctx).await.to_racetime()?;
-        if let Some(draft_kind) = goal.draft_kind() {
+        if let Some(draft_kind) = self.draft_kind(goal) {
             let available_settings = lock!(@read state = self.race_state; if let RaceState::Draft { state: ref draft, .. } = *state {
                 match draft.next_step(draft_kind, self.official_data.as_ref().and_then(|OfficialRaceData { cal_event, .. }| cal_event.race.game), &mut draft::MessageContext::RaceTime { high_seed_name: &self.high_seed_name, low_seed_name: &self.low_seed_name, reply_to }).await.to_racetime()?.kind {
                     draft::StepKind::GoFirst => None,
@@ -2757,6 +3049,7 @@ impl Handler {
                 draft::Kind::TournoiFrancoS3 => fr::S3_SETTINGS.into_iter().map(|fr::Setting { description, .. }| Cow::Borrowed(description)).collect(),
                 draft::Kind::TournoiFrancoS4 => fr::S4_SETTINGS.into_iter().map(|fr::Setting { description, .. }| Cow::Borrowed(description)).collect(),
                 draft::Kind::TournoiFrancoS5 => fr::S5_SETTINGS.into_iter().map(|fr::Setting { description, .. }| Cow::Borrowed(description)).collect(),
+                draft::Kind::Custom(spec) => spec.settings.iter().map(|setting| Cow::Owned(setting.description())).collect(),
             });
             if available_settings.is_empty() {
                 ctx.say(if let French = goal.language() {
@@ -2776,11 +3069,16 @@ impl Handler {
         Ok(())
     }
 
-    async fn advance_draft(&self, ctx: &RaceContext<GlobalState>, state: &RaceState) -> Result<(), Error> {
-        let goal = self.goal(ctx).await.to_racetime()?;
-        let Some(draft_kind) = goal.draft_kind() else { unreachable!() };
+    async fn advance_draft(&self, ctx: &impl RaceRoom, state: &RaceState) -> Result<(), Error> {
+        Self::advance_draft_with(ctx, &self.race_state, self.official_data.as_ref(), &self.high_seed_name, &self.low_seed_name, state).await
+    }
+
+    /// Like [`Self::advance_draft`], but usable from tasks that outlive the borrow of the handler, such as the draft timer.
+    async fn advance_draft_with(ctx: &impl RaceRoom, race_state: &ArcRwLock<RaceState>, official_data: Option<&OfficialRaceData>, high_seed_name: &str, low_seed_name: &str, state: &RaceState) -> Result<(), Error> {
+        let goal = Self::goal_for(ctx, official_data).await.to_racetime()?;
+        let Some(draft_kind) = Self::draft_kind_for(official_data, goal) else { unreachable!() };
         let RaceState::Draft { state: ref draft, unlock_spoiler_log } = *state else { unreachable!() };
-        let step = draft.next_step(draft_kind, self.official_data.as_ref().and_then(|OfficialRaceData { cal_event, .. }| cal_event.race.game), &mut draft::MessageContext::RaceTime { high_seed_name: &self.high_seed_name, low_seed_name: &self.low_seed_name, reply_to: "friend" }).await.to_racetime()?;
+        let step = draft.next_step(draft_kind, official_data.and_then(|OfficialRaceData { cal_event, .. }| cal_event.race.game), &mut draft::MessageContext::RaceTime { high_seed_name, low_seed_name, reply_to: "friend" }).await.to_racetime()?;
         match step.kind {
             draft::StepKind::Done(settings) => {
                 let (article, description) = if let French = goal.language() {
@@ -2788,8 +3086,13 @@ impl Handler {
                 } else {
                     ("a", format!("seed with {}", step.message))
                 };
-                let event = self.official_data.as_ref().map(|OfficialRaceData { event, .. }| event);
-                self.roll_seed(ctx, goal.preroll_seeds(event.map(|event| (event.series, &*event.event))), goal.rando_version(event), settings, unlock_spoiler_log, goal.language(), article, description).await;
+                let event = official_data.map(|OfficialRaceData { event, .. }| event);
+                Self::roll_with(ctx, race_state, official_data, SeedRequest::Ootr {
+                    preroll: goal.preroll_seeds(event.map(|event| (event.series, &*event.event))),
+                    allow_web: true,
+                    version: goal.rando_version(event),
+                    settings,
+                }, unlock_spoiler_log, goal.language(), article, description).await;
             }
             draft::StepKind::DoneRsl { preset, world_count } => {
                 let (article, description) = if let French = goal.language() {
@@ -2797,46 +3100,104 @@ impl Handler {
                 } else {
                     ("a", format!("seed with {}", step.message))
                 };
-                self.roll_rsl_seed(ctx, preset, world_count, unlock_spoiler_log, goal.language(), article, description).await;
+                Self::roll_with(ctx, race_state, official_data, SeedRequest::Rsl { preset, world_count }, unlock_spoiler_log, goal.language(), article, description).await;
+            }
+            draft::StepKind::GoFirst | draft::StepKind::Ban { .. } | draft::StepKind::Pick { .. } | draft::StepKind::BooleanChoice { .. } => {
+                ctx.say(step.message).await?;
+                if let Some(official_data) = official_data {
+                    Self::start_draft_timer(ctx, race_state, official_data, high_seed_name, low_seed_name, draft);
+                }
             }
-            draft::StepKind::GoFirst | draft::StepKind::Ban { .. } | draft::StepKind::Pick { .. } | draft::StepKind::BooleanChoice { .. } => ctx.say(step.message).await?,
         }
         Ok(())
     }
 
-    async fn draft_action(&self, ctx: &RaceContext<GlobalState>, sender: Option<&UserData>, action: draft::Action) -> Result<(), Error> {
+    /// Whether the draft step that started with the given state is still waiting for a choice in this race room.
+    async fn draft_step_pending(ctx: &impl RaceRoom, race_state: &ArcRwLock<RaceState>, draft_before: &Draft) -> bool {
+        let data = ctx.data().await;
+        if !Self::should_handle_inner(&*data, ctx.global_state().clone(), Some(None)).await { return false }
+        if !matches!(data.status.value, RaceStatusValue::Open | RaceStatusValue::Invitational) { return false }
+        lock!(@read state = race_state; matches!(*state, RaceState::Draft { state: ref draft, .. } if draft == draft_before))
+    }
+
+    /// Enforces the event's draft step time limit for the current step of a draft in this race room. The timer stops once the draft state changes.
+    fn start_draft_timer(ctx: &impl RaceRoom, race_state: &ArcRwLock<RaceState>, official_data: &OfficialRaceData, high_seed_name: &str, low_seed_name: &str, draft: &Draft) {
+        let Some(time_limit) = draft::timer::race_room_time_limit(&official_data.event) else { return };
+        let ctx = ctx.clone();
+        let race_state = race_state.clone();
+        let official_data = official_data.clone();
+        let high_seed_name = high_seed_name.to_owned();
+        let low_seed_name = low_seed_name.to_owned();
+        let draft_before = draft.clone();
+        tokio::spawn(async move {
+            sleep(time_limit - time_limit / 4).await;
+            if !Self::draft_step_pending(&ctx, &race_state, &draft_before).await { return Ok(()) }
+            ctx.say(format!("Reminder: {} left for this step of the settings draft. If no choice is made in time, the step will be skipped if possible, or otherwise decided randomly.", English.format_duration(time_limit / 4, true))).await?;
+            sleep(time_limit / 4).await;
+            if !Self::draft_step_pending(&ctx, &race_state, &draft_before).await { return Ok(()) }
+            lock!(@write state = race_state; if let RaceState::Draft { state: ref mut draft, .. } = *state {
+                // the draft may have advanced while waiting for the lock
+                if *draft == draft_before {
+                    let goal = Self::goal_for(&ctx, Some(&official_data)).await.to_racetime()?;
+                    let Some(draft_kind) = Self::draft_kind_for(Some(&official_data), goal) else { unreachable!() };
+                    let game = official_data.cal_event.race.game;
+                    if let (Some(active_team), Some(action)) = (draft.active_team(draft_kind, game).await.to_racetime()?, draft.timeout_action(draft_kind, game).await.to_racetime()?) {
+                        let team_name = active_team.choose(&*high_seed_name, &*low_seed_name);
+                        let response = draft.apply(draft_kind, game, &mut draft::MessageContext::RaceTime { high_seed_name: &high_seed_name, low_seed_name: &low_seed_name, reply_to: team_name }, action.clone()).await.to_racetime()?.expect("draft timeout made illegal action");
+                        let team = official_data.cal_event.race.teams().find(|team| (team.id == draft_before.high_seed) == matches!(active_team, draft::Team::HighSeed)).map(|team| team.id);
+                        let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+                        draft::history::record(&mut transaction, official_data.cal_event.race.id, None, team, draft::history::Frontend::Timer, &action, &draft_before).await.to_racetime()?;
+                        transaction.commit().await.to_racetime()?;
+                        ctx.say(format!("Time's up for {team_name}. {response}")).await?;
+                        Self::advance_draft_with(&ctx, &race_state, Some(&official_data), &high_seed_name, &low_seed_name, &state).await?;
+                    }
+                }
+            });
+            Ok::<_, Error>(())
+        });
+    }
+
+    async fn draft_action(&self, ctx: &impl RaceRoom, sender: Option<&UserData>, action: draft::Action) -> Result<(), Error> {
         let goal = self.goal(ctx).await.to_racetime()?;
         let reply_to = sender.map_or("friend", |user| &user.name);
         if let RaceStatusValue::Open | RaceStatusValue::Invitational = ctx.data().await.status.value {
-            lock!(@write state = self.race_state; if let Some(draft_kind) = goal.draft_kind() {
+            lock!(@write state = self.race_state; if let Some(draft_kind) = self.draft_kind(goal) {
                 match *state {
                     RaceState::Init => match draft_kind {
-                        draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => ctx.say(format!("Sorry {reply_to}, no draft has been started. Use “!seed draft” to start one.")).await?,
+                        draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => ctx.say(format!("Sorry {reply_to}, no draft has been started. Use “!seed draft” to start one.")).await?,
                         draft::Kind::RslS7 => ctx.say(format!("Sorry {reply_to}, no draft has been started. Use “!seed draft” to start one. For more info about these options, use !presets")).await?,
                         draft::Kind::TournoiFrancoS3 => ctx.say(format!("Désolé {reply_to}, le draft n'a pas débuté. Utilisez “!seed draft” pour en commencer un. Pour plus d'infos, utilisez !presets")).await?,
                         draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 => ctx.say(format!("Sorry {reply_to}, no draft has been started. Use “!seed draft” to start one. For more info about these options, use !presets / le draft n'a pas débuté. Utilisez “!seed draft” pour en commencer un. Pour plus d'infos, utilisez !presets")).await?,
                     },
                     RaceState::Draft { state: ref mut draft, .. } => {
-                        let is_active_team = if let Some(OfficialRaceData { ref cal_event, ref event, .. }) = self.official_data {
-                            let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
-                            let is_active_team = if_chain! {
+                        let (is_active_team, actor, team) = if let Some(OfficialRaceData { ref cal_event, ref event, .. }) = self.official_data {
+                            let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+                            let (is_active_team, actor, team) = if_chain! {
                                 if let Some(sender) = sender;
                                 if let Some(user) = User::from_racetime(&mut *transaction, &sender.id).await.to_racetime()?;
                                 if let Some(team) = Team::from_event_and_member(&mut transaction, event.series, &event.event, user.id).await.to_racetime()?;
                                 then {
-                                    draft.is_active_team(draft_kind, cal_event.race.game, team.id).await.to_racetime()?
+                                    (draft.is_active_team(draft_kind, cal_event.race.game, team.id).await.to_racetime()?, Some(user.id), Some(team.id))
                                 } else {
-                                    false
+                                    (false, None, None)
                                 }
                             };
                             transaction.commit().await.to_racetime()?;
-                            is_active_team
+                            (is_active_team, actor, team)
                         } else {
-                            true
+                            (true, None, None)
                         };
                         if is_active_team {
-                            match draft.apply(draft_kind, self.official_data.as_ref().and_then(|OfficialRaceData { cal_event, .. }| cal_event.race.game), &mut draft::MessageContext::RaceTime { high_seed_name: &self.high_seed_name, low_seed_name: &self.low_seed_name, reply_to }, action).await.to_racetime()? {
-                                Ok(_) => self.advance_draft(ctx, &state).await?,
+                            let state_before = draft.clone();
+                            match draft.apply(draft_kind, self.official_data.as_ref().and_then(|OfficialRaceData { cal_event, .. }| cal_event.race.game), &mut draft::MessageContext::RaceTime { high_seed_name: &self.high_seed_name, low_seed_name: &self.low_seed_name, reply_to }, action.clone()).await.to_racetime()? {
+                                Ok(_) => {
+                                    if let Some(OfficialRaceData { ref cal_event, .. }) = self.official_data {
+                                        let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+                                        draft::history::record(&mut transaction, cal_event.race.id, actor, team, draft::history::Frontend::RaceTime, &action, &state_before).await.to_racetime()?;
+                                        transaction.commit().await.to_racetime()?;
+                                    }
+                                    self.advance_draft(ctx, &state).await?;
+                                }
                                 Err(mut error_msg) => {
                                     unlock!();
                                     // can't send messages longer than 1000 characters
@@ -2852,7 +3213,7 @@ impl Handler {
                             }
                         } else {
                             match draft_kind {
-                                draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 => ctx.say(format!("Sorry {reply_to}, it's not your turn in the settings draft.")).await?,
+                                draft::Kind::S7 | draft::Kind::MultiworldS3 | draft::Kind::MultiworldS4 | draft::Kind::MultiworldS5 | draft::Kind::Custom(_) => ctx.say(format!("Sorry {reply_to}, it's not your turn in the settings draft.")).await?,
                                 draft::Kind::RslS7 => ctx.say(format!("Sorry {reply_to}, it's not your turn in the weights draft.")).await?,
                                 draft::Kind::TournoiFrancoS3 => ctx.say(format!("Désolé {reply_to}, mais ce n'est pas votre tour.")).await?,
                                 draft::Kind::TournoiFrancoS4 | draft::Kind::TournoiFrancoS5 => ctx.say(format!("Sorry {reply_to}, it's not your turn in the settings draft. / mais ce n'est pas votre tour.")).await?,
@@ -2876,13 +3237,11 @@ impl Handler {
         Ok(())
     }
 
-    async fn roll_seed_inner(&self, ctx: &RaceContext<GlobalState>, delay_until: Option<DateTime<Utc>>, mut updates: mpsc::Receiver<SeedRollUpdate>, language: Language, article: &'static str, description: String) {
-        let db_pool = ctx.global_state.db_pool.clone();
+    async fn roll_seed_inner(ctx: &impl RaceRoom, state: ArcRwLock<RaceState>, official_data: Option<OfficialRaceData>, delay_until: Option<DateTime<Utc>>, mut updates: mpsc::Receiver<SeedRollUpdate>, language: Language, article: &'static str, description: String) {
+        let db_pool = ctx.global_state().db_pool.clone();
         let ctx = ctx.clone();
-        let state = self.race_state.clone();
-        let official_data = self.official_data.clone();
         tokio::spawn(async move {
-            lock!(@write state = state; *state = RaceState::Rolling); //TODO ensure only one seed is rolled at a time
+            lock!(@write state = state; *state = RaceState::Rolling); // GlobalState::roll ensures only one seed is rolled at a time
             let mut seed_state = None::<SeedRollUpdate>;
             if let Some(delay) = delay_until.and_then(|delay_until| (delay_until - Utc::now()).to_std().ok()) {
                 // don't want to give an unnecessarily exact estimate if the room was opened automatically 30 or 60 minutes ahead of start
@@ -2924,71 +3283,57 @@ impl Handler {
         });
     }
 
-    async fn roll_seed(&self, ctx: &RaceContext<GlobalState>, preroll: PrerollMode, version: VersionedBranch, settings: seed::Settings, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
-        let official_start = self.official_data.as_ref().map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
+    async fn roll(&self, ctx: &impl RaceRoom, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
+        Self::roll_with(ctx, &self.race_state, self.official_data.as_ref(), request, unlock_spoiler_log, language, article, description).await
+    }
+
+    async fn roll_with(ctx: &impl RaceRoom, race_state: &ArcRwLock<RaceState>, official_data: Option<&OfficialRaceData>, request: SeedRequest, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
+        let official_start = official_data.map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
         let delay_until = official_start.map(|start| start - TimeDelta::minutes(15));
-        self.roll_seed_inner(ctx, delay_until, ctx.global_state.clone().roll_seed(preroll, true, delay_until, version, settings, unlock_spoiler_log), language, article, description).await;
+        let unlock_spoiler_log = if_chain! {
+            if unlock_spoiler_log == UnlockSpoilerLog::After;
+            if ctx.global_state().seed_generators.get(request.kind()).capabilities().auto_unlock_spoiler_log;
+            if official_data.is_some_and(|official_data| official_data.cal_event.is_private_async_part());
+            then {
+                // websites which unlock spoiler logs automatically don't know about async parts so this has to be disabled for asyncs
+                UnlockSpoilerLog::Never
+            } else {
+                unlock_spoiler_log
+            }
+        };
+        let room = format!("https://{}{}", racetime_host(), ctx.data().await.url);
+        let priority = if official_data.is_some() { Priority::Official } else { Priority::Practice };
+        Self::roll_seed_inner(ctx, race_state.clone(), official_data.cloned(), delay_until, ctx.global_state().clone().roll(delay_until, Some(room), priority, request, unlock_spoiler_log).await, language, article, description).await;
     }
 
-    async fn roll_crosskeys2025_seed(&self, ctx: &RaceContext<GlobalState>, cal_event: cal::Event, language: Language, article: &'static str) {
+    async fn roll_crosskeys2025_seed(&self, ctx: &impl RaceRoom, cal_event: cal::Event, language: Language, article: &'static str) {
         let official_start = cal_event.start().expect("handling room for official race without start time");
         let delay_until = official_start - TimeDelta::minutes(10);
 
-        let crosskeys_options = CrosskeysRaceOptions::for_race(&ctx.global_state.db_pool, &cal_event.race).await;
-        self.roll_seed_inner(ctx, Some(delay_until), ctx.global_state.clone().roll_crosskeys2025_seed(crosskeys_options), language, article, format!("seed with {}", crosskeys_options.as_seed_options_str())).await;
+        let crosskeys_options = CrosskeysRaceOptions::for_race(&ctx.global_state().db_pool, &cal_event.race).await;
+        let room = format!("https://{}{}", racetime_host(), ctx.data().await.url);
+        Self::roll_seed_inner(ctx, self.race_state.clone(), self.official_data.clone(), Some(delay_until), ctx.global_state().clone().roll(Some(delay_until), Some(room), Priority::Official, SeedRequest::Crosskeys2025(crosskeys_options), UnlockSpoilerLog::Never).await, language, article, format!("seed with {}", crosskeys_options.as_seed_options_str())).await;
         ctx.send_message(format!("@entrants Remember: this race will be played with {}!",
                                     crosskeys_options.as_race_options_str()
                                 ), true, Vec::default()).await.expect("failed to send race options");
     }
 
-    async fn roll_rsl_seed(&self, ctx: &RaceContext<GlobalState>, preset: rsl::VersionedPreset, world_count: u8, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
-        let official_start = self.official_data.as_ref().map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
-        let delay_until = official_start.map(|start| start - TimeDelta::minutes(15));
-        self.roll_seed_inner(ctx, delay_until, ctx.global_state.clone().roll_rsl_seed(delay_until, preset, world_count, unlock_spoiler_log), language, article, description).await;
-    }
-
-    async fn roll_tfb_seed(&self, ctx: &RaceContext<GlobalState>, version: &'static str, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
-        let official_start = self.official_data.as_ref().map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
-        let delay_until = official_start.map(|start| start - TimeDelta::minutes(15));
-        // Triforce Blitz website's auto unlock doesn't know about async parts so has to be disabled for asyncs
-        let unlock_spoiler_log = if unlock_spoiler_log == UnlockSpoilerLog::After && self.official_data.as_ref().is_some_and(|official_data| official_data.cal_event.is_private_async_part()) { UnlockSpoilerLog::Never } else { unlock_spoiler_log };
-        self.roll_seed_inner(ctx, delay_until, ctx.global_state.clone().roll_tfb_seed(delay_until, version, Some(format!("https://{}{}", racetime_host(), ctx.data().await.url)), unlock_spoiler_log), language, article, description).await;
-    }
-
-    async fn roll_tfb_dev_seed(&self, ctx: &RaceContext<GlobalState>, coop: bool, unlock_spoiler_log: UnlockSpoilerLog, language: Language, article: &'static str, description: String) {
-        let official_start = self.official_data.as_ref().map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
-        let delay_until = official_start.map(|start| start - TimeDelta::minutes(15));
-        // Triforce Blitz website's auto unlock doesn't know about async parts so has to be disabled for asyncs
-        let unlock_spoiler_log = if unlock_spoiler_log == UnlockSpoilerLog::After && self.official_data.as_ref().is_some_and(|official_data| official_data.cal_event.is_private_async_part()) { UnlockSpoilerLog::Never } else { unlock_spoiler_log };
-        self.roll_seed_inner(ctx, delay_until, ctx.global_state.clone().roll_tfb_dev_seed(delay_until, coop, Some(format!("https://{}{}", racetime_host(), ctx.data().await.url)), unlock_spoiler_log), language, article, description).await;
-    }
-
-    async fn queue_existing_seed(&self, ctx: &RaceContext<GlobalState>, seed: seed::Data, language: Language, article: &'static str, description: String) {
+    async fn queue_existing_seed(&self, ctx: &impl RaceRoom, seed: seed::Data, language: Language, article: &'static str, description: String) {
         let official_start = self.official_data.as_ref().map(|official_data| official_data.cal_event.start().expect("handling room for official race without start time"));
         let delay_until = official_start.map(|start| start - TimeDelta::minutes(15));
         let (tx, rx) = mpsc::channel(1);
         tx.send(SeedRollUpdate::Done { rsl_preset: None, unlock_spoiler_log: UnlockSpoilerLog::After, seed }).await.unwrap();
-        self.roll_seed_inner(ctx, delay_until, rx, language, article, description).await;
+        Self::roll_seed_inner(ctx, self.race_state.clone(), self.official_data.clone(), delay_until, rx, language, article, description).await;
     }
 
     /// Returns `false` if this race was already finished/cancelled.
-    async fn unlock_spoiler_log(&self, ctx: &RaceContext<GlobalState>, goal: Goal) -> Result<bool, Error> {
+    async fn unlock_spoiler_log(&self, ctx: &impl RaceRoom, goal: Goal) -> Result<bool, Error> {
         lock!(@write state = self.race_state; {
             match *state {
                 RaceState::Rolled(seed::Data { files: Some(ref files), .. }) => if self.official_data.as_ref().is_none_or(|official_data| !official_data.cal_event.is_private_async_part()) {
                     if let UnlockSpoilerLog::Progression | UnlockSpoilerLog::After = goal.unlock_spoiler_log(self.is_official(), false /* we may try to unlock a log that's already unlocked, but other than that, this assumption doesn't break anything */) {
-                        match files {
-                            seed::Files::AlttprDoorRando { .. } => unreachable!(),
-                            seed::Files::MidosHouse { file_stem, locked_spoiler_log_path } => if let Some(locked_spoiler_log_path) = locked_spoiler_log_path {
-                                lock!(@write seed_metadata = ctx.global_state.seed_metadata; seed_metadata.remove(&**file_stem));
-                                fs::rename(locked_spoiler_log_path, Path::new(seed::DIR).join(format!("{file_stem}_Spoiler.json"))).await.to_racetime()?;
-                            },
-                            seed::Files::OotrWeb { id, file_stem, .. } => {
-                                ctx.global_state.ootr_api_client.unlock_spoiler_log(*id).await.to_racetime()?;
-                                let spoiler_log = ctx.global_state.ootr_api_client.seed_details(*id).await.to_racetime()?.spoiler_log;
-                                fs::write(Path::new(seed::DIR).join(format!("{file_stem}_Spoiler.json")), &spoiler_log).await.to_racetime()?;
-                            }
-                            seed::Files::TriforceBlitz { .. } | seed::Files::TfbSotd { .. } => {} // automatically unlocked by triforceblitz.com
+                        if let Some(generator) = ctx.global_state().seed_generators.for_files(files) {
+                            generator.unlock_spoiler_log(ctx.global_state(), files).await?;
                         }
                     }
                 },
@@ -3002,57 +3347,16 @@ impl Handler {
         });
         Ok(true)
     }
-}
-
-#[async_trait]
-impl RaceHandler<GlobalState> for Handler {
-    async fn should_handle(race_data: &RaceData, global_state: Arc<GlobalState>) -> Result<bool, Error> {
-        Ok(Self::should_handle_inner(race_data, global_state, None).await)
-    }
-
-    async fn should_stop(&mut self, ctx: &RaceContext<GlobalState>) -> Result<bool, Error> {
-        Ok(!Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(Some(self))).await)
-    }
 
-    async fn task(global_state: Arc<GlobalState>, race_data: Arc<tokio::sync::RwLock<RaceData>>, join_handle: tokio::task::JoinHandle<()>) -> Result<(), Error> {
-        let race_data = ArcRwLock::from(race_data);
-        tokio::spawn(async move {
-            lock!(@read data = race_data; println!("race handler for https://{}{} started", racetime_host(), data.url));
-            let res = join_handle.await;
-            lock!(@read data = race_data; {
-                lock!(clean_shutdown = global_state.clean_shutdown; {
-                    let room = OpenRoom::RaceTime {
-                        room_url: data.url.clone(),
-                        public: !data.unlisted,
-                    };
-                    assert!(clean_shutdown.open_rooms.remove(&room));
-                    clean_shutdown.updates.send(CleanShutdownUpdate::RoomClosed(room)).allow_unreceived();
-                    if clean_shutdown.open_rooms.is_empty() {
-                        clean_shutdown.updates.send(CleanShutdownUpdate::Empty).allow_unreceived();
-                    }
-                });
-                if let Ok(()) = res {
-                    println!("race handler for https://{}{} stopped", racetime_host(), data.url);
-                } else {
-                    eprintln!("race handler for https://{}{} panicked", racetime_host(), data.url);
-                    if let Environment::Production = Environment::default() {
-                        let _ = wheel::night_report(&format!("{}/error", night_path()), Some(&format!("race handler for https://{}{} panicked", racetime_host(), data.url))).await;
-                    }
-                }
-            });
-        });
-        Ok(())
-    }
-
-    async fn new(ctx: &RaceContext<GlobalState>) -> Result<Self, Error> {
+    async fn new_inner(ctx: &impl RaceRoom) -> Result<Self, Error> {
         let data = ctx.data().await;
         let goal = data.goal.name.parse::<Goal>().to_racetime()?;
-        let (existing_seed, official_data, race_state, high_seed_name, low_seed_name, fpa_enabled) = lock!(new_room_lock = ctx.global_state.new_room_lock; { // make sure a new room isn't handled before it's added to the database
-            let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
-            let new_data = if let Some(cal_event) = cal::Event::from_room(&mut transaction, &ctx.global_state.http_client, format!("https://{}{}", racetime_host(), ctx.data().await.url).parse()?).await.to_racetime()? {
+        let (existing_seed, official_data, race_state, high_seed_name, low_seed_name, fpa_enabled) = lock!(new_room_lock = ctx.global_state().new_room_lock; { // make sure a new room isn't handled before it's added to the database
+            let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+            let new_data = if let Some(cal_event) = cal::Event::from_room(&mut transaction, &ctx.global_state().http_client, format!("https://{}{}", racetime_host(), ctx.data().await.url).parse()?).await.to_racetime()? {
                 let event = cal_event.race.event(&mut transaction).await.to_racetime()?;
                 let mut entrants = Vec::default();
-                for member in cal_event.racetime_users_to_invite(&mut transaction, &*ctx.global_state.discord_ctx.read().await, &event).await.to_racetime()? {
+                for member in cal_event.racetime_users_to_invite(&mut transaction, &ctx.global_state().discord_ctx, &event).await.to_racetime()? {
                     match member {
                         Ok(member) => {
                             if let Some(entrant) = data.entrants.iter().find(|entrant| entrant.user.id == member) {
@@ -3165,8 +3469,8 @@ impl RaceHandler<GlobalState> for Handler {
                     Entrants::Open | Entrants::Count { .. } => event.open_stream_delay,
                     Entrants::Two(_) | Entrants::Three(_) | Entrants::Named(_) => event.invitational_stream_delay,
                 };
-                let emulator_settings_reminder = event.series == Series::Standard && event.event != "w" || event.series == Series::League; //TODO move to database
-                let prevent_late_joins = event.series == Series::SpeedGaming || event.series == Series::Standard && event.event == "8"; //TODO move to database
+                let emulator_settings_reminder = event.emulator_settings_reminder;
+                let prevent_late_joins = event.prevent_late_joins;
                 if !stream_delay.is_zero() || emulator_settings_reminder || prevent_late_joins {
                     let delay_until = cal_event.start().expect("handling room for official race without start time") - stream_delay - TimeDelta::minutes(5);
                     if let Ok(delay) = (delay_until - Utc::now()).to_std() {
@@ -3174,7 +3478,7 @@ impl RaceHandler<GlobalState> for Handler {
                         let requires_emote_only = event.series == Series::SpeedGaming && cal_event.race.phase.as_ref().is_some_and(|phase| phase == "Bracket");
                         tokio::spawn(async move {
                             sleep_until(Instant::now() + delay).await;
-                            if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await { return }
+                            if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await { return }
                             if !stream_delay.is_zero() {
                                 ctx.say(format!("@entrants Remember to go live with a delay of {} ({} seconds){}!",
                                     English.format_duration(stream_delay, true),
@@ -3185,7 +3489,7 @@ impl RaceHandler<GlobalState> for Handler {
                             if emulator_settings_reminder || prevent_late_joins {
                                 sleep(stream_delay).await;
                                 let data = ctx.data().await;
-                                if !Self::should_handle_inner(&*data, ctx.global_state.clone(), Some(None)).await { return }
+                                if !Self::should_handle_inner(&*data, ctx.global_state().clone(), Some(None)).await { return }
                                 if prevent_late_joins && data.status.value == RaceStatusValue::Open {
                                     ctx.set_invitational().await.expect("failed to make the room invitational");
                                 }
@@ -3248,7 +3552,7 @@ impl RaceHandler<GlobalState> for Handler {
                                 password: None, //TODO get from API
                                 files: Some(seed::Files::OotrWeb {
                                     gen_time: Utc::now(),
-                                    file_stem: Cow::Owned(ctx.global_state.ootr_api_client.patch_file_stem(id).await.to_racetime()?),
+                                    file_stem: Cow::Owned(ctx.global_state().ootr_api_client.patch_file_stem(id).await.to_racetime()?),
                                     id,
                                 }),
                                 progression_spoiler: false, //TODO
@@ -3259,7 +3563,7 @@ impl RaceHandler<GlobalState> for Handler {
                 }
                 if let RaceStatusValue::Pending | RaceStatusValue::InProgress = data.status.value { //TODO also check this in official races
                     if_chain! {
-                        if let Ok(log) = ctx.global_state.http_client.get(format!("https://{}{}/log", racetime_host(), data.url)).send().await;
+                        if let Ok(log) = ctx.global_state().http_client.get(format!("https://{}{}/log", racetime_host(), data.url)).send().await;
                         if let Ok(log) = log.detailed_error_for_status().await;
                         if let Ok(log) = log.text().await; //TODO stream response
                         if !log.to_ascii_lowercase().contains("break"); //TODO parse chatlog and recover breaks config instead of sending this
@@ -4070,10 +4374,20 @@ impl RaceHandler<GlobalState> for Handler {
                             | Goal::Sgl2024
                             | Goal::SongsOfHope
                             | Goal::TriforceBlitzProgressionSpoiler
-                                => this.roll_seed(ctx, goal.preroll_seeds(event_id), goal.rando_version(Some(event)), goal.single_settings().expect("goal has no single settings"), goal.unlock_spoiler_log(true, false), English, "a", format!("seed")).await,
+                                => this.roll(ctx, SeedRequest::Ootr {
+                                    preroll: goal.preroll_seeds(event_id),
+                                    allow_web: true,
+                                    version: goal.rando_version(Some(event)),
+                                    settings: goal.single_settings().expect("goal has no single settings"),
+                                }, goal.unlock_spoiler_log(true, false), English, "a", format!("seed")).await,
                             | Goal::WeTryToBeBetterS1
                             | Goal::WeTryToBeBetterS2
-                                => this.roll_seed(ctx, goal.preroll_seeds(event_id), goal.rando_version(Some(event)), goal.single_settings().expect("goal has no single settings"), goal.unlock_spoiler_log(true, false), French, "une", format!("seed")).await,
+                                => this.roll(ctx, SeedRequest::Ootr {
+                                    preroll: goal.preroll_seeds(event_id),
+                                    allow_web: true,
+                                    version: goal.rando_version(Some(event)),
+                                    settings: goal.single_settings().expect("goal has no single settings"),
+                                }, goal.unlock_spoiler_log(true, false), French, "une", format!("seed")).await,
                             | Goal::Cc7
                             | Goal::MultiworldS3
                             | Goal::MultiworldS4
@@ -4086,20 +4400,33 @@ impl RaceHandler<GlobalState> for Handler {
                             | Goal::Crosskeys2025
                                 => this.roll_crosskeys2025_seed(ctx, cal_event.clone(), English, "a").await,
                             Goal::NineDaysOfSaws => unreachable!("9dos series has concluded"),
-                            Goal::PicRs2 => this.roll_rsl_seed(ctx, rsl::VersionedPreset::Fenhl {
-                                version: Some((Version::new(2, 3, 8), 10)),
-                                preset: rsl::DevFenhlPreset::Pictionary,
-                            }, 1, goal.unlock_spoiler_log(true, false), English, "a", format!("seed")).await,
+                            Goal::PicRs2 => this.roll(ctx, SeedRequest::Rsl {
+                                preset: rsl::VersionedPreset::Fenhl {
+                                    version: Some((Version::new(2, 3, 8), 10)),
+                                    preset: rsl::DevFenhlPreset::Pictionary,
+                                },
+                                world_count: 1,
+                            }, goal.unlock_spoiler_log(true, false), English, "a", format!("seed")).await,
                             Goal::StandardRuleset => if let (Series::Standard, "8" | "8cc") = (event.series, &*event.event) {
-                                this.roll_seed(ctx, goal.preroll_seeds(event_id), goal.rando_version(Some(event)), s::s8_settings(), goal.unlock_spoiler_log(true, false), English, "an", format!("S8 seed")).await
+                                this.roll(ctx, SeedRequest::Ootr {
+                                    preroll: goal.preroll_seeds(event_id),
+                                    allow_web: true,
+                                    version: goal.rando_version(Some(event)),
+                                    settings: s::s8_settings(),
+                                }, goal.unlock_spoiler_log(true, false), English, "an", format!("S8 seed")).await
                             } else {
-                                let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
+                                let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
                                 let mut settings = event::Data::new(&mut transaction, Series::Standard, "w").await.to_racetime()?.expect("missing weeklies event").single_settings.expect("no settings configured for weeklies");
                                 transaction.commit().await.to_racetime()?;
                                 settings.insert(format!("password_lock"), json!(true));
-                                this.roll_seed(ctx, goal.preroll_seeds(event_id), goal.rando_version(Some(event)), settings, goal.unlock_spoiler_log(true, false), English, "a", format!("weekly seed")).await
+                                this.roll(ctx, SeedRequest::Ootr {
+                                    preroll: goal.preroll_seeds(event_id),
+                                    allow_web: true,
+                                    version: goal.rando_version(Some(event)),
+                                    settings,
+                                }, goal.unlock_spoiler_log(true, false), English, "a", format!("weekly seed")).await
                             },
-                            Goal::TriforceBlitz => this.roll_tfb_dev_seed(ctx, true, goal.unlock_spoiler_log(true, false), English, "a", format!("Triforce Blitz S4 co-op seed")).await,
+                            Goal::TriforceBlitz => this.roll(ctx, SeedRequest::TriforceBlitzDev { coop: true }, goal.unlock_spoiler_log(true, false), English, "a", format!("Triforce Blitz S4 co-op seed")).await,
                         },
                         RaceState::Draft { .. } => this.advance_draft(ctx, &state).await?,
                         RaceState::Rolling | RaceState::Rolled(_) | RaceState::SpoilerSent => {}
@@ -4110,7 +4437,7 @@ impl RaceHandler<GlobalState> for Handler {
         Ok(this)
     }
 
-    async fn command(&mut self, ctx: &RaceContext<GlobalState>, cmd_name: String, args: Vec<String>, _is_moderator: bool, is_monitor: bool, msg: &ChatMessage) -> Result<(), Error> {
+    async fn command_inner(&mut self, ctx: &impl RaceRoom, cmd_name: String, args: Vec<String>, _is_moderator: bool, is_monitor: bool, msg: &ChatMessage) -> Result<(), Error> {
         let goal = self.goal(ctx).await.to_racetime()?;
         let reply_to = msg.user.as_ref().map_or("friend", |user| &user.name);
         match &*cmd_name.to_ascii_lowercase() {
@@ -4390,13 +4717,13 @@ impl RaceHandler<GlobalState> for Handler {
                             "All restreams ready, unlocking auto-start…"
                         }
                     }).await?;
-                    let (access_token, _) = racetime::authorize_with_host(&ctx.global_state.host_info, &ctx.global_state.racetime_config.client_id, &ctx.global_state.racetime_config.client_secret, &ctx.global_state.http_client).await?;
+                    let (access_token, _) = racetime::authorize_with_host(&ctx.global_state().host_info, &ctx.global_state().racetime_config.client_id, &ctx.global_state().racetime_config.client_secret, &ctx.global_state().http_client).await?;
                     room_options(
                         goal, event, cal_event,
                         ctx.data().await.info_user.clone().unwrap_or_default(),
                         ctx.data().await.info_bot.clone().unwrap_or_default(),
                         true,
-                    ).await.edit_with_host(&ctx.global_state.host_info, &access_token, &ctx.global_state.http_client, CATEGORY, &ctx.data().await.slug).await?;
+                    ).await.edit_with_host(&ctx.global_state().host_info, &access_token, &ctx.global_state().http_client, CATEGORY, &ctx.data().await.slug).await?;
                 } else {
                     ctx.say(format!("Restream ready, still waiting for other restreams.")).await?;
                 }
@@ -4416,17 +4743,17 @@ impl RaceHandler<GlobalState> for Handler {
                             Url::parse(&format!("https://twitch.tv/{restream_url}"))
                         };
                         if let Ok(restream_url) = restream_url {
-                            let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
-                            match parse_user(&mut transaction, &ctx.global_state.http_client, restreamer).await {
+                            let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+                            match parse_user(&mut transaction, &ctx.global_state().http_client, restreamer).await {
                                 Ok(restreamer_racetime_id) => {
                                     if restreams.is_empty() {
-                                        let (access_token, _) = racetime::authorize_with_host(&ctx.global_state.host_info, &ctx.global_state.racetime_config.client_id, &ctx.global_state.racetime_config.client_secret, &ctx.global_state.http_client).await?;
+                                        let (access_token, _) = racetime::authorize_with_host(&ctx.global_state().host_info, &ctx.global_state().racetime_config.client_id, &ctx.global_state().racetime_config.client_secret, &ctx.global_state().http_client).await?;
                                         room_options(
                                             goal, event, cal_event,
                                             ctx.data().await.info_user.clone().unwrap_or_default(),
                                             ctx.data().await.info_bot.clone().unwrap_or_default(),
                                             false,
-                                        ).await.edit_with_host(&ctx.global_state.host_info, &access_token, &ctx.global_state.http_client, CATEGORY, &ctx.data().await.slug).await?;
+                                        ).await.edit_with_host(&ctx.global_state().host_info, &access_token, &ctx.global_state().http_client, CATEGORY, &ctx.data().await.slug).await?;
                                     }
                                     restreams.entry(restream_url).or_default().restreamer_racetime_id = Some(restreamer_racetime_id.clone());
                                     ctx.say("Restreamer assigned. Use “!ready” once the restream is ready. Auto-start will be unlocked once all restreams are ready.").await?; //TODO mention restreamer
@@ -4524,8 +4851,8 @@ impl RaceHandler<GlobalState> for Handler {
                             format!("Sorry {reply_to}, seed rolling is locked. Only {} may roll a seed for this race.", if self.is_official() { "race monitors or tournament organizers" } else { "race monitors" })
                         }).await?;
                     } else {
-                        let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
-                        match goal.parse_seed_command(&mut transaction, &ctx.global_state, self.is_official(), cmd_name.to_ascii_lowercase() == "spoilerseed", false, &args).await.to_racetime()? {
+                        let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
+                        match goal.parse_seed_command(&mut transaction, &ctx.global_state(), self.is_official(), cmd_name.to_ascii_lowercase() == "spoilerseed", false, &args).await.to_racetime()? {
                             SeedCommandParseResult::Alttpr => {
                                 // TODO THIS NEEDS TO BE IMPLEMENTED -- call door rando .py and roll seed with arguments
                                 Command::new("echo").args(["hello", "world"]).check("echo").await.to_racetime()?;
@@ -4533,11 +4860,16 @@ impl RaceHandler<GlobalState> for Handler {
                             }
                             SeedCommandParseResult::Regular { settings, unlock_spoiler_log, language, article, description } => {
                                 let event = self.official_data.as_ref().map(|OfficialRaceData { event, .. }| event);
-                                self.roll_seed(ctx, goal.preroll_seeds(event.map(|event| (event.series, &*event.event))), goal.rando_version(event), settings, unlock_spoiler_log, language, article, description).await
+                                self.roll(ctx, SeedRequest::Ootr {
+                                    preroll: goal.preroll_seeds(event.map(|event| (event.series, &*event.event))),
+                                    allow_web: true,
+                                    version: goal.rando_version(event),
+                                    settings,
+                                }, unlock_spoiler_log, language, article, description).await
                             },
-                            SeedCommandParseResult::Rsl { preset, world_count, unlock_spoiler_log, language, article, description } => self.roll_rsl_seed(ctx, preset, world_count, unlock_spoiler_log, language, article, description).await,
-                            SeedCommandParseResult::Tfb { version, unlock_spoiler_log, language, article, description } => self.roll_tfb_seed(ctx, version, unlock_spoiler_log, language, article, description).await,
-                            SeedCommandParseResult::TfbDev { coop, unlock_spoiler_log, language, article, description } => self.roll_tfb_dev_seed(ctx, coop, unlock_spoiler_log, language, article, description).await,
+                            SeedCommandParseResult::Rsl { preset, world_count, unlock_spoiler_log, language, article, description } => self.roll(ctx, SeedRequest::Rsl { preset, world_count }, unlock_spoiler_log, language, article, description).await,
+                            SeedCommandParseResult::Tfb { version, unlock_spoiler_log, language, article, description } => self.roll(ctx, SeedRequest::TriforceBlitz { version }, unlock_spoiler_log, language, article, description).await,
+                            SeedCommandParseResult::TfbDev { coop, unlock_spoiler_log, language, article, description } => self.roll(ctx, SeedRequest::TriforceBlitzDev { coop }, unlock_spoiler_log, language, article, description).await,
                             SeedCommandParseResult::QueueExisting { data, language, article, description } => self.queue_existing_seed(ctx, data, language, article, description).await,
                             SeedCommandParseResult::SendPresets { language, msg } => {
                                 ctx.say(if let French = language {
@@ -4618,7 +4950,7 @@ impl RaceHandler<GlobalState> for Handler {
         Ok(())
     }
 
-    async fn race_data(&mut self, ctx: &RaceContext<GlobalState>, _old_race_data: RaceData) -> Result<(), Error> {
+    async fn race_data_inner(&mut self, ctx: &impl RaceRoom, _old_race_data: RaceData) -> Result<(), Error> {
         let data = ctx.data().await;
         let goal = self.goal(ctx).await.to_racetime()?;
         if let Some(OfficialRaceData { ref event, ref entrants, ref mut scores, .. }) = self.official_data {
@@ -4648,14 +4980,14 @@ impl RaceHandler<GlobalState> for Handler {
         }
         if !self.start_saved {
             if let (Goal::Rsl, Some(start)) = (goal, data.started_at) {
-                sqlx::query!("UPDATE rsl_seeds SET start = $1 WHERE room = $2", start, format!("https://{}{}", racetime_host(), ctx.data().await.url)).execute(&ctx.global_state.db_pool).await.to_racetime()?;
+                sqlx::query!("UPDATE rsl_seeds SET start = $1 WHERE room = $2", start, format!("https://{}{}", racetime_host(), ctx.data().await.url)).execute(&ctx.global_state().db_pool).await.to_racetime()?;
                 self.start_saved = true;
             }
         }
         match data.status.value {
             RaceStatusValue::Pending => if !self.password_sent {
                 lock!(@read state = self.race_state; if let RaceState::Rolled(ref seed) = *state {
-                    let extra = seed.extra(Utc::now()).await.to_racetime()?;
+                    let extra = ctx.global_state().seed_generators.extra(seed).await.to_racetime()?;
                     if let Some(password) = extra.password {
                         ctx.say(format!("This seed is password protected. To start a file, enter this password on the file select screen:\n{}\nYou are allowed to enter the password before the race starts.", format_password(password))).await?;
                         set_bot_raceinfo(ctx, seed, None /*TODO support RSL seeds with password lock? */, true).await?;
@@ -4668,7 +5000,7 @@ impl RaceHandler<GlobalState> for Handler {
                                 for note in password {
                                     msg.push_emoji(&ocarina_note_to_ootr_discord_emoji(note));
                                 }
-                                ChannelId::new(1306254442298998884).say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await.to_racetime()?; //TODO move channel ID to database
+                                ctx.discord().say(ChannelId::new(1306254442298998884), msg.build()).await.to_racetime()?; //TODO move channel ID to database
                             }
                         }
                     }
@@ -4681,7 +5013,7 @@ impl RaceHandler<GlobalState> for Handler {
                         let ctx = ctx.clone();
                         tokio::spawn(async move {
                             sleep(breaks.interval - Duration::from_secs(5 * 60)).await;
-                            while Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await {
+                            while Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await {
                                 let (_, ()) = tokio::join!(
                                     ctx.say(if let French = goal.language() {
                                         "@entrants Rappel : pause dans 5 minutes."
@@ -4690,7 +5022,7 @@ impl RaceHandler<GlobalState> for Handler {
                                     }),
                                     sleep(Duration::from_secs(5 * 60)),
                                 );
-                                if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await { break }
+                                if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await { break }
                                 let msg = if let French = goal.language() {
                                     format!("@entrants C'est l'heure de la pause ! Elle durera {}.", French.format_duration(breaks.duration, true))
                                 } else {
@@ -4700,7 +5032,7 @@ impl RaceHandler<GlobalState> for Handler {
                                     ctx.say(msg),
                                     sleep(breaks.duration),
                                 );
-                                if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await { break }
+                                if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await { break }
                                 let (_, ()) = tokio::join!(
                                     ctx.say(if let French = goal.language() {
                                         "@entrants Fin de la pause. Vous pouvez recommencer à jouer."
@@ -4725,7 +5057,7 @@ impl RaceHandler<GlobalState> for Handler {
                                 }) - Utc::now();
                                 if let Ok(initial_wait) = initial_wait.to_std() {
                                     sleep(initial_wait).await;
-                                    if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await { return }
+                                    if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await { return }
                                     let (_, ()) = tokio::join!(
                                         ctx.say("@entrants Reminder: 5 minutes until you can start drawing/playing."),
                                         sleep(Duration::from_secs(5 * 60)),
@@ -4744,7 +5076,7 @@ impl RaceHandler<GlobalState> for Handler {
                                     sleep(initial_wait).await;
                                     let is_1v1 = {
                                         let data = ctx.data().await;
-                                        if !Self::should_handle_inner(&*data, ctx.global_state.clone(), Some(None)).await { return }
+                                        if !Self::should_handle_inner(&*data, ctx.global_state().clone(), Some(None)).await { return }
                                         data.entrants_count == 2
                                     };
                                     let _ = ctx.say(if is_1v1 {
@@ -4763,7 +5095,7 @@ impl RaceHandler<GlobalState> for Handler {
                                 let initial_wait = ctx.data().await.started_at.expect("in-progress race with no start time") + TimeDelta::minutes(10) - Utc::now();
                                 if let Ok(initial_wait) = initial_wait.to_std() {
                                     sleep(initial_wait).await;
-                                    if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state.clone(), Some(None)).await { return }
+                                    if !Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(None)).await { return }
                                     let (_, ()) = tokio::join!(
                                         ctx.say("@entrants Reminder: 5 minutes until you can start playing."),
                                         sleep(Duration::from_secs(5 * 60)),
@@ -4774,7 +5106,7 @@ impl RaceHandler<GlobalState> for Handler {
                                     );
                                     let is_1v1 = {
                                         let data = ctx.data().await;
-                                        if !Self::should_handle_inner(&*data, ctx.global_state.clone(), Some(None)).await { return }
+                                        if !Self::should_handle_inner(&*data, ctx.global_state().clone(), Some(None)).await { return }
                                         data.entrants_count == 2
                                     };
                                     let _ = ctx.say(if is_1v1 {
@@ -4830,30 +5162,8 @@ impl RaceHandler<GlobalState> for Handler {
                                         msg.push(racetime_host());
                                         msg.push(&ctx.data().await.url);
                                         msg.push('>');
-                                        if event.discord_race_results_channel.is_some() || matches!(cal_event.race.source, cal::Source::StartGG { .. }) {
-                                            msg.push(" — please manually ");
-                                            if let Some(results_channel) = event.discord_race_results_channel {
-                                                msg.push("post the announcement in ");
-                                                msg.mention(&results_channel);
-                                            }
-                                            match cal_event.race.startgg_set_url() {
-                                                Ok(Some(startgg_set_url)) => {
-                                                    if event.discord_race_results_channel.is_some() {
-                                                        msg.push(" and ");
-                                                    }
-                                                    msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
-                                                }
-                                                Ok(None) => {}
-                                                Err(_) => {
-                                                    if event.discord_race_results_channel.is_some() {
-                                                        msg.push(" and ");
-                                                    }
-                                                    msg.push("report the result on start.gg");
-                                                }
-                                            }
-                                            msg.push(" after adjusting the times");
-                                        }
-                                        let _ = organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await;
+                                        report::push_manual_report_request(&mut msg, &cal_event, &event);
+                                        let _ = ctx.discord().say(organizer_channel, msg.build()).await;
                                     }
                                 }
                             }
@@ -4869,7 +5179,7 @@ impl RaceHandler<GlobalState> for Handler {
             RaceStatusValue::Cancelled => {
                 if !self.password_sent {
                     lock!(@read state = self.race_state; if let RaceState::Rolled(ref seed) = *state {
-                        let extra = seed.extra(Utc::now()).await.to_racetime()?;
+                        let extra = ctx.global_state().seed_generators.extra(seed).await.to_racetime()?;
                         if let Some(password) = extra.password {
                             ctx.say(format!("This seed is password protected. To start a file, enter this password on the file select screen:\n{}", format_password(password))).await?;
                             set_bot_raceinfo(ctx, seed, None /*TODO support RSL seeds with password lock? */, true).await?;
@@ -4882,14 +5192,14 @@ impl RaceHandler<GlobalState> for Handler {
                         let form = collect![as HashMap<_, _>:
                             "id" => id.to_string(),
                         ];
-                        let request = ctx.global_state.http_client.post("https://league.ootrandomizer.com/reportCancelFromMidoHouse")
-                            .bearer_auth(&ctx.global_state.league_api_key)
+                        let request = ctx.global_state().http_client.post("https://league.ootrandomizer.com/reportCancelFromMidoHouse")
+                            .bearer_auth(&ctx.global_state().league_api_key)
                             .form(&form);
                         println!("reporting cancel to League website: {:?}", serde_urlencoded::to_string(&form));
                         request.send().await?.detailed_error_for_status().await.to_racetime()?;
                     } else {
                         if let Some(organizer_channel) = event.discord_organizer_channel {
-                            organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, MessageBuilder::default()
+                            ctx.discord().say(organizer_channel, MessageBuilder::default()
                                 .push("race cancelled: <https://")
                                 .push(racetime_host())
                                 .push(&ctx.data().await.url)
@@ -4901,7 +5211,7 @@ impl RaceHandler<GlobalState> for Handler {
                 }
                 self.unlock_spoiler_log(ctx, goal).await?;
                 if let Goal::Rsl = goal {
-                    sqlx::query!("DELETE FROM rsl_seeds WHERE room = $1", format!("https://{}{}", racetime_host(), ctx.data().await.url)).execute(&ctx.global_state.db_pool).await.to_racetime()?;
+                    sqlx::query!("DELETE FROM rsl_seeds WHERE room = $1", format!("https://{}{}", racetime_host(), ctx.data().await.url)).execute(&ctx.global_state().db_pool).await.to_racetime()?;
                 }
                 self.cleaned_up.store(true, atomic::Ordering::SeqCst);
             }
@@ -4909,6 +5219,60 @@ impl RaceHandler<GlobalState> for Handler {
         }
         Ok(())
     }
+}
+
+#[async_trait]
+impl RaceHandler<GlobalState> for Handler {
+    async fn should_handle(race_data: &RaceData, global_state: Arc<GlobalState>) -> Result<bool, Error> {
+        Ok(Self::should_handle_inner(race_data, global_state, None).await)
+    }
+
+    async fn should_stop(&mut self, ctx: &RaceContext<GlobalState>) -> Result<bool, Error> {
+        Ok(!Self::should_handle_inner(&*ctx.data().await, ctx.global_state().clone(), Some(Some(self))).await)
+    }
+
+    async fn task(global_state: Arc<GlobalState>, race_data: Arc<tokio::sync::RwLock<RaceData>>, join_handle: tokio::task::JoinHandle<()>) -> Result<(), Error> {
+        let race_data = ArcRwLock::from(race_data);
+        tokio::spawn(async move {
+            lock!(@read data = race_data; println!("race handler for https://{}{} started", racetime_host(), data.url));
+            let res = join_handle.await;
+            lock!(@read data = race_data; {
+                global_state.seed_queue.cancel(&format!("https://{}{}", racetime_host(), data.url)).await;
+                lock!(clean_shutdown = global_state.clean_shutdown; {
+                    let room = OpenRoom::RaceTime {
+                        room_url: data.url.clone(),
+                        public: !data.unlisted,
+                    };
+                    assert!(clean_shutdown.open_rooms.remove(&room));
+                    clean_shutdown.updates.send(CleanShutdownUpdate::RoomClosed(room)).allow_unreceived();
+                    if clean_shutdown.open_rooms.is_empty() {
+                        clean_shutdown.updates.send(CleanShutdownUpdate::Empty).allow_unreceived();
+                    }
+                });
+                if let Ok(()) = res {
+                    println!("race handler for https://{}{} stopped", racetime_host(), data.url);
+                } else {
+                    eprintln!("race handler for https://{}{} panicked", racetime_host(), data.url);
+                    if let Environment::Production = Environment::default() {
+                        let _ = wheel::night_report(&format!("{}/error", night_path()), Some(&format!("race handler for https://{}{} panicked", racetime_host(), data.url))).await;
+                    }
+                }
+            });
+        });
+        Ok(())
+    }
+
+    async fn new(ctx: &RaceContext<GlobalState>) -> Result<Self, Error> {
+        Self::new_inner(ctx).await
+    }
+
+    async fn command(&mut self, ctx: &RaceContext<GlobalState>, cmd_name: String, args: Vec<String>, is_moderator: bool, is_monitor: bool, msg: &ChatMessage) -> Result<(), Error> {
+        self.command_inner(ctx, cmd_name, args, is_moderator, is_monitor, msg).await
+    }
+
+    async fn race_data(&mut self, ctx: &RaceContext<GlobalState>, old_race_data: RaceData) -> Result<(), Error> {
+        self.race_data_inner(ctx, old_race_data).await
+    }
 
     async fn error(&mut self, _: &RaceContext<GlobalState>, mut errors: Vec<String>) -> Result<(), Error> {
         errors.retain(|error|
@@ -5093,7 +5457,7 @@ pub(crate) async fn create_room(transaction: &mut Transaction<'_, Postgres>, dis
                     });
                 }
             });
-            Err("remember to send your video to an organizer once you're done") //TODO “please check your direct messages” for private async parts, “will be handled here in the match thread” for public async parts
+            Err("please check your direct messages to start your async part")
         }
     };
     let is_room_url = room_url.is_ok();
@@ -5283,145 +5647,142 @@ pub(crate) enum PrepareSeedsError {
     #[error(transparent)] Sql(#[from] sqlx::Error),
 }
 
-async fn prepare_seeds(global_state: Arc<GlobalState>, mut seed_cache_rx: watch::Receiver<()>, mut shutdown: rocket::Shutdown) -> Result<(), PrepareSeedsError> {
-    'outer: loop {
-        for id in sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE NOT ignored AND room IS NULL AND async_room1 IS NULL AND async_room2 IS NULL AND async_room3 IS NULL AND file_stem IS NULL AND tfb_uuid IS NULL"#).fetch_all(&global_state.db_pool).await? {
-            let mut transaction = global_state.db_pool.begin().await?;
-            let race = Race::from_id(&mut transaction, &global_state.http_client, id).await?;
-            let event = race.event(&mut transaction).await?;
-            if let Some(goal) = Goal::for_event(event.series, &*event.event) {
-                if let PrerollMode::Long = goal.preroll_seeds(Some((event.series, &*event.event))) {
-                    if let Some(settings) = race.single_settings(&mut transaction).await? {
-                        transaction.commit().await?;
-                        if race.seed.files.is_none()
-                        && race
-                            .cal_events()
-                            .filter_map(|cal_event| cal_event.start())
-                            .min()
-                            .is_some_and(|start| start > Utc::now())
-                        {
-                            'seed: loop {
-                                let mut seed_rx = global_state.clone().roll_seed(
-                                    PrerollMode::Long,
-                                    false,
-                                    None,
-                                    goal.rando_version(Some(&event)),
-                                    settings.clone(),
-                                    goal.unlock_spoiler_log(true, false),
-                                );
-                                loop {
-                                    select! {
-                                        () = &mut shutdown => break 'outer,
-                                        Some(update) = seed_rx.recv() => match update {
-                                            SeedRollUpdate::Queued(_) |
-                                            SeedRollUpdate::MovedForward(_) |
-                                            SeedRollUpdate::Started => {}
-                                            SeedRollUpdate::Done { mut seed, rsl_preset: _, unlock_spoiler_log: _ } => {
-                                                let extra = seed.extra(Utc::now()).await?;
-                                                seed.file_hash = extra.file_hash;
-                                                seed.password = extra.password;
-                                                // reload race data in case anything changed during seed rolling
-                                                let mut transaction = global_state.db_pool.begin().await?;
-                                                let mut race = Race::from_id(&mut transaction, &global_state.http_client, race.id).await?;
-                                                if !race.has_any_room() {
-                                                    race.seed = seed;
-                                                    race.save(&mut transaction).await?;
-                                                }
-                                                transaction.commit().await?;
-                                                break 'seed
-                                            }
-                                            SeedRollUpdate::Error(RollError::Retries { num_retries, last_error }) => {
-                                                if let Some(last_error) = last_error {
-                                                    eprintln!("seed rolling failed {num_retries} times, sample error:\n{last_error}");
-                                                } else {
-                                                    eprintln!("seed rolling failed {num_retries} times, no sample error recorded");
-                                                }
-                                                continue 'seed
-                                            }
-                                            SeedRollUpdate::Error(e) => return Err(e.into()),
-                                            #[cfg(unix)] SeedRollUpdate::Message(_) => {}
-                                        },
-                                    }
-                                }
-                            }
-                        }
+/// Whether a seed should be rolled ahead of time for this race, see [`preroll_race_seed`].
+pub(crate) async fn needs_preroll(transaction: &mut Transaction<'_, Postgres>, race: &Race) -> Result<bool, PrepareSeedsError> {
+    if race.ignored || race.has_any_room() || race.seed.files.is_some() { return Ok(false) }
+    if !race.cal_events().filter_map(|cal_event| cal_event.start()).min().is_some_and(|start| start > Utc::now()) { return Ok(false) }
+    let event = race.event(&mut *transaction).await?;
+    let Some(goal) = Goal::for_event(event.series, &*event.event) else { return Ok(false) };
+    Ok(goal.preroll_seeds(Some((event.series, &*event.event))) == PrerollMode::Long && race.single_settings(&mut *transaction).await?.is_some())
+}
+
+/// Rolls the seed for an upcoming race so it's ready when the room opens. Run as a [scheduled job](crate::scheduler) for races which [need it](needs_preroll).
+pub(crate) async fn preroll_race_seed(global_state: Arc<GlobalState>, id: Id<Races>) -> Result<(), PrepareSeedsError> {
+    let mut transaction = global_state.db_pool.begin().await?;
+    let race = Race::from_id(&mut transaction, &global_state.http_client, id).await?;
+    if !needs_preroll(&mut transaction, &race).await? { return Ok(()) }
+    let event = race.event(&mut transaction).await?;
+    let Some(goal) = Goal::for_event(event.series, &*event.event) else { return Ok(()) };
+    let Some(settings) = race.single_settings(&mut transaction).await? else { return Ok(()) };
+    transaction.commit().await?;
+    loop {
+        let mut seed_rx = global_state.clone().roll(
+            None,
+            None,
+            Priority::Official,
+            SeedRequest::Ootr {
+                preroll: PrerollMode::Long,
+                allow_web: false,
+                version: goal.rando_version(Some(&event)),
+                settings: settings.clone(),
+            },
+            goal.unlock_spoiler_log(true, false),
+        ).await;
+        loop {
+            match seed_rx.recv().await.ok_or(RollError::ChannelClosed)? {
+                SeedRollUpdate::Queued(_) |
+                SeedRollUpdate::MovedForward(_) |
+                SeedRollUpdate::Started |
+                SeedRollUpdate::WebUnavailable => {}
+                SeedRollUpdate::Done { mut seed, rsl_preset: _, unlock_spoiler_log: _ } => {
+                    let extra = global_state.seed_generators.extra(&seed).await?;
+                    seed.file_hash = extra.file_hash;
+                    seed.password = extra.password;
+                    // reload race data in case anything changed during seed rolling
+                    let mut transaction = global_state.db_pool.begin().await?;
+                    let mut race = Race::from_id(&mut transaction, &global_state.http_client, race.id).await?;
+                    if !race.has_any_room() {
+                        race.seed = seed;
+                        race.save(&mut transaction).await?;
                     }
+                    transaction.commit().await?;
+                    return Ok(())
+                }
+                SeedRollUpdate::Error(RollError::Retries { num_retries, last_error }) => {
+                    if let Some(last_error) = last_error {
+                        eprintln!("seed rolling failed {num_retries} times, sample error:\n{last_error}");
+                    } else {
+                        eprintln!("seed rolling failed {num_retries} times, no sample error recorded");
+                    }
+                    break
                 }
+                SeedRollUpdate::Error(e) => return Err(e.into()),
+                #[cfg(unix)] SeedRollUpdate::Message(_) => {}
             }
         }
-        let event_rows = sqlx::query!(r#"SELECT series AS "series: Series", event FROM events WHERE end_time IS NULL OR end_time > NOW()"#).fetch_all(&global_state.db_pool).await?;
-        for goal in all::<Goal>() {
-            if let Some(settings) = goal.single_settings() { //TODO preroll for official races with single settings even if their goals don't have single settings
-                if goal.preroll_seeds(None) == PrerollMode::Long && event_rows.iter().any(|row| goal.matches_event(row.series, &row.event)) {
-                    if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM prerolled_seeds WHERE goal_name = $1) AS "exists!""#, goal.as_str()).fetch_one(&global_state.db_pool).await? { break }
-                    'seed: loop {
-                        let mut seed_rx = global_state.clone().roll_seed(
-                            PrerollMode::Long,
-                            false,
-                            None,
-                            goal.rando_version(None),
-                            settings.clone(),
-                            goal.unlock_spoiler_log(false, false),
-                        );
-                        loop {
-                            select! {
-                                () = &mut shutdown => break 'outer,
-                                Some(update) = seed_rx.recv() => match update {
-                                    SeedRollUpdate::Queued(_) |
-                                    SeedRollUpdate::MovedForward(_) |
-                                    SeedRollUpdate::Started => {}
-                                    SeedRollUpdate::Done { seed, rsl_preset: _, unlock_spoiler_log: _ } => {
-                                        let extra = seed.extra(Utc::now()).await?;
-                                        let [hash1, hash2, hash3, hash4, hash5] = match extra.file_hash {
-                                            Some(hash) => hash.map(Some),
-                                            None => [None; 5],
-                                        };
-                                        match seed.files {
-                                            Some(seed::Files::MidosHouse { file_stem, locked_spoiler_log_path }) => {
-                                                sqlx::query!("INSERT INTO prerolled_seeds
-                                                    (goal_name, file_stem, locked_spoiler_log_path, hash1, hash2, hash3, hash4, hash5, seed_password, progression_spoiler)
-                                                VALUES
-                                                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                                                ",
-                                                    goal.as_str(),
-                                                    &file_stem,
-                                                    locked_spoiler_log_path,
-                                                    hash1 as _,
-                                                    hash2 as _,
-                                                    hash3 as _,
-                                                    hash4 as _,
-                                                    hash5 as _,
-                                                    extra.password.map(|password| password.into_iter().map(char::from).collect::<String>()),
-                                                    goal.unlock_spoiler_log(false, false) == UnlockSpoilerLog::Progression,
-                                                ).execute(&global_state.db_pool).await?;
-                                            }
-                                            _ => unimplemented!("unexpected seed files in prerolled seed"),
-                                        }
-                                        break 'seed
-                                    }
-                                    SeedRollUpdate::Error(RollError::Retries { num_retries, last_error }) => {
-                                        if let Some(last_error) = last_error {
-                                            eprintln!("seed rolling failed {num_retries} times, sample error:\n{last_error}");
-                                        } else {
-                                            eprintln!("seed rolling failed {num_retries} times, no sample error recorded");
-                                        }
-                                        continue 'seed
+    }
+}
+
+/// Fills the cache of prerolled seeds for goals with single settings that are used by an ongoing event. Run as a [scheduled job](crate::scheduler) every hour and whenever a cached seed is used.
+pub(crate) async fn preroll_goal_seeds(global_state: Arc<GlobalState>) -> Result<(), PrepareSeedsError> {
+    let event_rows = sqlx::query!(r#"SELECT series AS "series: Series", event FROM events WHERE end_time IS NULL OR end_time > NOW()"#).fetch_all(&global_state.db_pool).await?;
+    for goal in all::<Goal>() {
+        if let Some(settings) = goal.single_settings() { //TODO preroll for official races with single settings even if their goals don't have single settings
+            if goal.preroll_seeds(None) == PrerollMode::Long && event_rows.iter().any(|row| goal.matches_event(row.series, &row.event)) {
+                if sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM prerolled_seeds WHERE goal_name = $1) AS "exists!""#, goal.as_str()).fetch_one(&global_state.db_pool).await? { break }
+                'seed: loop {
+                    let mut seed_rx = global_state.clone().roll(
+                        None,
+                        None,
+                        Priority::Practice,
+                        SeedRequest::Ootr {
+                            preroll: PrerollMode::Long,
+                            allow_web: false,
+                            version: goal.rando_version(None),
+                            settings: settings.clone(),
+                        },
+                        goal.unlock_spoiler_log(false, false),
+                    ).await;
+                    loop {
+                        match seed_rx.recv().await.ok_or(RollError::ChannelClosed)? {
+                            SeedRollUpdate::Queued(_) |
+                            SeedRollUpdate::MovedForward(_) |
+                            SeedRollUpdate::Started |
+                            SeedRollUpdate::WebUnavailable => {}
+                            SeedRollUpdate::Done { seed, rsl_preset: _, unlock_spoiler_log: _ } => {
+                                let extra = global_state.seed_generators.extra(&seed).await?;
+                                let [hash1, hash2, hash3, hash4, hash5] = match extra.file_hash {
+                                    Some(hash) => hash.map(Some),
+                                    None => [None; 5],
+                                };
+                                match seed.files {
+                                    Some(seed::Files::MidosHouse { file_stem, locked_spoiler_log_path }) => {
+                                        sqlx::query!("INSERT INTO prerolled_seeds
+                                            (goal_name, file_stem, locked_spoiler_log_path, hash1, hash2, hash3, hash4, hash5, seed_password, progression_spoiler)
+                                        VALUES
+                                            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                                        ",
+                                            goal.as_str(),
+                                            &file_stem,
+                                            locked_spoiler_log_path,
+                                            hash1 as _,
+                                            hash2 as _,
+                                            hash3 as _,
+                                            hash4 as _,
+                                            hash5 as _,
+                                            extra.password.map(|password| password.into_iter().map(char::from).collect::<String>()),
+                                            goal.unlock_spoiler_log(false, false) == UnlockSpoilerLog::Progression,
+                                        ).execute(&global_state.db_pool).await?;
                                     }
-                                    SeedRollUpdate::Error(e) => return Err(e.into()),
-                                    #[cfg(unix)] SeedRollUpdate::Message(_) => {}
-                                },
+                                    _ => unimplemented!("unexpected seed files in prerolled seed"),
+                                }
+                                break 'seed
+                            }
+                            SeedRollUpdate::Error(RollError::Retries { num_retries, last_error }) => {
+                                if let Some(last_error) = last_error {
+                                    eprintln!("seed rolling failed {num_retries} times, sample error:\n{last_error}");
+                                } else {
+                                    eprintln!("seed rolling failed {num_retries} times, no sample error recorded");
+                                }
+                                continue 'seed
                             }
+                            SeedRollUpdate::Error(e) => return Err(e.into()),
+                            #[cfg(unix)] SeedRollUpdate::Message(_) => {}
                         }
                     }
                 }
             }
         }
-        select! {
-            () = &mut shutdown => break,
-            res = timeout(Duration::from_secs(60 * 60), seed_cache_rx.changed().then(|res| if let Ok(()) = res { Either::Left(future::ready(())) } else { Either::Right(future::pending()) })) => {
-                let (Ok(()) | Err(_)) = res;
-            }
-        }
     }
     Ok(())
 }
@@ -5435,66 +5796,60 @@ pub(crate) enum CreateRoomsError {
     #[error(transparent)] Sql(#[from] sqlx::Error),
 }
 
-async fn create_rooms(global_state: Arc<GlobalState>, mut shutdown: rocket::Shutdown) -> Result<(), CreateRoomsError> {
-    loop {
-        select! {
-            () = &mut shutdown => break,
-            _ = sleep(Duration::from_secs(30)) => { //TODO exact timing (coordinate with everything that can change the schedule)
-                lock!(new_room_lock = global_state.new_room_lock; { // make sure a new room isn't handled before it's added to the database
-                    let mut transaction = global_state.db_pool.begin().await?;
-                    for cal_event in cal::Event::rooms_to_open(&mut transaction, &global_state.http_client).await? {
-                        let event = cal_event.race.event(&mut transaction).await?;
-                        if let Some((is_room_url, msg)) = create_room(&mut transaction, &*global_state.discord_ctx.read().await, &global_state.host_info, &global_state.racetime_config.client_id, &global_state.racetime_config.client_secret, &global_state.extra_room_tx, &global_state.http_client, global_state.clean_shutdown.clone(), &cal_event, &event).await? {
-                            let ctx = global_state.discord_ctx.read().await;
-                            if is_room_url && cal_event.is_private_async_part() {
-                                let msg = match cal_event.race.entrants {
-                                    Entrants::Two(_) => format!("unlisted room for first async half: {msg}"),
-                                    Entrants::Three(_) => format!("unlisted room for first/second async part: {msg}"),
-                                    _ => format!("unlisted room for async part: {msg}"),
-                                };
-                                if let Some(channel) = event.discord_organizer_channel {
-                                    channel.say(&*ctx, &msg).await?;
-                                } else {
-                                    // DM Admin
-                                    ADMIN_USER.create_dm_channel(&*ctx).await?.say(&*ctx, &msg).await?;
-                                }
-                                for team in cal_event.active_teams() {
-                                    for member in team.members(&mut transaction).await? {
-                                        if let Some(discord) = member.discord {
-                                            discord.id.create_dm_channel(&*ctx).await?.say(&*ctx, &msg).await?;
-                                        }
-                                    }
-                                }
+/// Opens the rooms for all races which are about to start. Run as a [scheduled job](crate::scheduler) at the [time the next room opens](cal::Event::room_opens_at).
+pub(crate) async fn open_rooms(global_state: &GlobalState) -> Result<(), CreateRoomsError> {
+    lock!(new_room_lock = global_state.new_room_lock; { // make sure a new room isn't handled before it's added to the database
+        let mut transaction = global_state.db_pool.begin().await?;
+        for cal_event in cal::Event::rooms_to_open(&mut transaction, &global_state.http_client).await? {
+            let event = cal_event.race.event(&mut transaction).await?;
+            if let Some((is_room_url, msg)) = create_room(&mut transaction, &*global_state.discord_ctx.read().await, &global_state.host_info, &global_state.racetime_config.client_id, &global_state.racetime_config.client_secret, &global_state.extra_room_tx, &global_state.http_client, global_state.clean_shutdown.clone(), &cal_event, &event).await? {
+                let ctx = global_state.discord_ctx.read().await;
+                if is_room_url && cal_event.is_private_async_part() {
+                    let msg = match cal_event.race.entrants {
+                        Entrants::Two(_) => format!("unlisted room for first async half: {msg}"),
+                        Entrants::Three(_) => format!("unlisted room for first/second async part: {msg}"),
+                        _ => format!("unlisted room for async part: {msg}"),
+                    };
+                    if let Some(channel) = event.discord_organizer_channel {
+                        channel.say(&*ctx, &msg).await?;
+                    } else {
+                        // DM Admin
+                        ADMIN_USER.create_dm_channel(&*ctx).await?.say(&*ctx, &msg).await?;
+                    }
+                    for team in cal_event.active_teams() {
+                        for member in team.members(&mut transaction).await? {
+                            if let Some(discord) = member.discord {
+                                discord.id.create_dm_channel(&*ctx).await?.say(&*ctx, &msg).await?;
+                            }
+                        }
+                    }
+                } else {
+                    if_chain! {
+                        if !cal_event.is_private_async_part();
+                        if let Some(channel) = event.discord_race_room_channel;
+                        then {
+                            if let Some(thread) = cal_event.race.scheduling_thread {
+                                thread.say(&*ctx, &msg).await?;
+                                channel.send_message(&*ctx, CreateMessage::default().content(msg).allowed_mentions(CreateAllowedMentions::default())).await?;
                             } else {
-                                if_chain! {
-                                    if !cal_event.is_private_async_part();
-                                    if let Some(channel) = event.discord_race_room_channel;
-                                    then {
-                                        if let Some(thread) = cal_event.race.scheduling_thread {
-                                            thread.say(&*ctx, &msg).await?;
-                                            channel.send_message(&*ctx, CreateMessage::default().content(msg).allowed_mentions(CreateAllowedMentions::default())).await?;
-                                        } else {
-                                            channel.say(&*ctx, msg).await?;
-                                        }
-                                    } else {
-                                        if let Some(thread) = cal_event.race.scheduling_thread {
-                                            thread.say(&*ctx, msg).await?;
-                                        } else if let Some(channel) = event.discord_organizer_channel {
-                                            channel.say(&*ctx, msg).await?;
-                                        } else {
-                                            // DM Admin
-                                            ADMIN_USER.create_dm_channel(&*ctx).await?.say(&*ctx, msg).await?;
-                                        }
-                                    }
-                                }
+                                channel.say(&*ctx, msg).await?;
+                            }
+                        } else {
+                            if let Some(thread) = cal_event.race.scheduling_thread {
+                                thread.say(&*ctx, msg).await?;
+                            } else if let Some(channel) = event.discord_organizer_channel {
+                                channel.say(&*ctx, msg).await?;
+                            } else {
+                                // DM Admin
+                                ADMIN_USER.create_dm_channel(&*ctx).await?.say(&*ctx, msg).await?;
                             }
                         }
                     }
-                    transaction.commit().await?;
-                });
+                }
             }
         }
-    }
+        transaction.commit().await?;
+    });
     Ok(())
 }
 
@@ -5543,16 +5898,10 @@ async fn handle_rooms(global_state: Arc<GlobalState>, racetime_config: &ConfigRa
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum MainError {
-    #[error(transparent)] CreateRooms(#[from] CreateRoomsError),
     #[error(transparent)] HandleRooms(#[from] HandleRoomsError),
-    #[error(transparent)] PrepareSeeds(#[from] PrepareSeedsError),
-} 
-
-pub(crate) async fn main(config: Config, shutdown: rocket::Shutdown, global_state: Arc<GlobalState>, seed_cache_rx: watch::Receiver<()>) -> Result<(), MainError> {
-    let ((), (), ()) = tokio::try_join!(
-        prepare_seeds(global_state.clone(), seed_cache_rx, shutdown.clone()).err_into::<MainError>(),
-        create_rooms(global_state.clone(), shutdown.clone()).err_into(),
-        handle_rooms(global_state, if Environment::default().is_dev() { &config.racetime_bot_dev } else { &config.racetime_bot_production }, shutdown).err_into(),
-    )?;
+}
+
+pub(crate) async fn main(config: Config, shutdown: rocket::Shutdown, global_state: Arc<GlobalState>) -> Result<(), MainError> {
+    handle_rooms(global_state, if Environment::default().is_dev() { &config.racetime_bot_dev } else { &config.racetime_bot_production }, shutdown).await?;
     Ok(())
 }
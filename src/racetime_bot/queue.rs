@@ -0,0 +1,128 @@
+//! Scheduling of seed rolls.
+//!
+//! Only one seed is rolled for each race room at a time, and generators which run the randomizer on this server wait for one of a limited number of workers before doing so.
+//! Seeds for official races are moved ahead of practice seeds while waiting for a worker.
+
+use {
+    tokio::sync::{
+        OwnedSemaphorePermit,
+        Semaphore,
+        oneshot,
+    },
+    crate::{
+        config::ConfigSeedQueue,
+        prelude::*,
+        racetime_bot::SeedRollUpdate,
+    },
+};
+
+#[cfg(test)] mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Priority {
+    /// A seed requested outside of an official race, e.g. using `!seed` in a practice room or `/seed` on Discord.
+    Practice,
+    /// A seed for an official race, which has a start time to meet.
+    Official,
+}
+
+struct Waiting {
+    priority: Priority,
+    position_tx: mpsc::UnboundedSender<usize>,
+}
+
+pub(crate) struct SeedQueue {
+    workers: Arc<Semaphore>,
+    waiting: Mutex<Vec<Waiting>>,
+    rooms: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl SeedQueue {
+    pub(crate) fn new(config: ConfigSeedQueue) -> Self {
+        Self {
+            workers: Arc::new(Semaphore::new(config.workers)),
+            waiting: Mutex::default(),
+            rooms: Mutex::default(),
+        }
+    }
+
+    /// Registers a seed roll for the given race room.
+    ///
+    /// Returns `None` if a seed is already being rolled for this room. Otherwise, the returned receiver resolves once the roll should be abandoned or has been [finished](Self::finish_job).
+    pub(crate) async fn start_job(&self, room: &str) -> Option<oneshot::Receiver<()>> {
+        lock!(rooms = self.rooms; match rooms.entry(room.to_owned()) {
+            hash_map::Entry::Occupied(_) => None,
+            hash_map::Entry::Vacant(entry) => {
+                let (cancel_tx, cancel_rx) = oneshot::channel();
+                entry.insert(cancel_tx);
+                Some(cancel_rx)
+            }
+        })
+    }
+
+    pub(crate) async fn finish_job(&self, room: &str) {
+        lock!(rooms = self.rooms; { rooms.remove(room); });
+    }
+
+    /// Abandons the seed roll for the given race room, if any. Called when the room is closed.
+    pub(crate) async fn cancel(&self, room: &str) {
+        if let Some(cancel_tx) = lock!(rooms = self.rooms; rooms.remove(room)) {
+            let _ = cancel_tx.send(());
+        }
+    }
+
+    /// Waits for a worker to become available for running a generator on this server, informing the requester of the seed about its position in the queue.
+    ///
+    /// Returns `None` if nothing is waiting for the seed anymore.
+    pub(crate) async fn acquire_worker(&self, priority: Priority, update_tx: &mpsc::Sender<SeedRollUpdate>) -> Option<OwnedSemaphorePermit> {
+        let (position_tx, mut position_rx) = mpsc::unbounded_channel();
+        let mut position = lock!(waiting = self.waiting; {
+            if_chain! {
+                if waiting.is_empty();
+                if let Ok(permit) = self.workers.clone().try_acquire_owned();
+                then {
+                    unlock!();
+                    return Some(permit)
+                }
+            }
+            // the seed at the front of the queue is already waiting for the next free worker, so it keeps its place even if it has a lower priority
+            let position = if waiting.is_empty() { 0 } else { 1 + waiting[1..].iter().take_while(|waiting| waiting.priority >= priority).count() };
+            waiting.insert(position, Waiting { priority, position_tx: position_tx.clone() });
+            for (new_position, waiting) in waiting.iter().enumerate().skip(position + 1) {
+                waiting.position_tx.send(new_position).allow_unreceived();
+            }
+            position
+        });
+        let permit = async {
+            update_tx.send(SeedRollUpdate::Queued(position.try_into().unwrap())).await.ok()?;
+            let mut announced_position = position;
+            loop {
+                if position == 0 {
+                    select! {
+                        permit = self.workers.clone().acquire_owned() => break Some(permit.expect("seed queue semaphore closed")),
+                        () = update_tx.closed() => break None,
+                    }
+                } else {
+                    select! {
+                        Some(new_position) = position_rx.recv() => {
+                            position = new_position;
+                            // only announce progress, a seed being moved back by an official race is reported once it moves forward again
+                            if position < announced_position {
+                                announced_position = position;
+                                update_tx.send(SeedRollUpdate::MovedForward(position.try_into().unwrap())).await.ok()?;
+                            }
+                        }
+                        () = update_tx.closed() => break None,
+                    }
+                }
+            }
+        }.await;
+        lock!(waiting = self.waiting; if let Some(position) = waiting.iter().position(|waiting| waiting.position_tx.same_channel(&position_tx)) {
+            waiting.remove(position);
+            for (new_position, waiting) in waiting.iter().enumerate().skip(position) {
+                waiting.position_tx.send(new_position).allow_unreceived();
+            }
+        });
+        permit
+    }
+}
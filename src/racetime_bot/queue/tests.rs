@@ -0,0 +1,57 @@
+use super::*;
+
+fn queue(workers: usize) -> Arc<SeedQueue> {
+    Arc::new(SeedQueue::new(ConfigSeedQueue { workers }))
+}
+
+/// Starts waiting for a worker in the background and returns the receiver for the updates sent in the process.
+async fn enqueue(queue: &Arc<SeedQueue>, priority: Priority) -> (mpsc::Receiver<SeedRollUpdate>, tokio::task::JoinHandle<Option<OwnedSemaphorePermit>>) {
+    let (update_tx, mut update_rx) = mpsc::channel(8);
+    let queue = queue.clone();
+    let handle = tokio::spawn(async move { queue.acquire_worker(priority, &update_tx).await });
+    assert!(matches!(update_rx.recv().await, Some(SeedRollUpdate::Queued(_))));
+    (update_rx, handle)
+}
+
+#[tokio::test]
+async fn official_seeds_skip_ahead() {
+    let queue = queue(1);
+    let (update_tx, _update_rx) = mpsc::channel(8);
+    let running = queue.acquire_worker(Priority::Practice, &update_tx).await.expect("worker should be available");
+    let (_front_rx, front) = enqueue(&queue, Priority::Practice).await;
+    let (mut practice_rx, practice) = enqueue(&queue, Priority::Practice).await;
+    let (mut official_rx, official) = enqueue(&queue, Priority::Official).await;
+    drop(running);
+    let front = front.await.unwrap().expect("seed at the front of the queue should get the next worker");
+    assert!(matches!(official_rx.recv().await, Some(SeedRollUpdate::MovedForward(0))));
+    drop(front);
+    let official = official.await.unwrap().expect("official seed should get a worker before practice seeds");
+    assert!(matches!(practice_rx.recv().await, Some(SeedRollUpdate::MovedForward(0))));
+    assert!(!practice.is_finished());
+    drop(official);
+    assert!(practice.await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn abandoned_seeds_leave_the_queue() {
+    let queue = queue(1);
+    let (update_tx, _update_rx) = mpsc::channel(8);
+    let running = queue.acquire_worker(Priority::Practice, &update_tx).await.expect("worker should be available");
+    let (abandoned_rx, abandoned) = enqueue(&queue, Priority::Practice).await;
+    let (mut waiting_rx, waiting) = enqueue(&queue, Priority::Practice).await;
+    drop(abandoned_rx);
+    assert!(abandoned.await.unwrap().is_none());
+    assert!(matches!(waiting_rx.recv().await, Some(SeedRollUpdate::MovedForward(0))));
+    drop(running);
+    assert!(waiting.await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn one_job_per_room() {
+    let queue = queue(1);
+    let cancelled = queue.start_job("https://racetime.gg/ootr/example-room-1234").await.expect("room should be free");
+    assert!(queue.start_job("https://racetime.gg/ootr/example-room-1234").await.is_none());
+    assert!(queue.start_job("https://racetime.gg/ootr/other-room-5678").await.is_some());
+    queue.cancel("https://racetime.gg/ootr/example-room-1234").await;
+    assert!(cancelled.await.is_ok());
+}
@@ -7,6 +7,8 @@ use {
     },
 };
 
+#[cfg(test)] mod tests;
+
 trait Score {
     type SortKey: Ord;
 
@@ -15,6 +17,8 @@ trait Score {
     fn time_window(&self, other: &Self) -> Option<Duration>;
     fn format(&self, language: Language) -> Cow<'_, str>;
     fn as_duration(&self) -> Option<Option<Duration>>;
+    fn finish_time(&self) -> Option<Duration>;
+    fn tfb_pieces(&self) -> Option<u8>;
 }
 
 impl Score for Option<Duration> {
@@ -45,6 +49,14 @@ impl Score for Option<Duration> {
     fn as_duration(&self) -> Option<Option<Duration>> {
         Some(*self)
     }
+
+    fn finish_time(&self) -> Option<Duration> {
+        *self
+    }
+
+    fn tfb_pieces(&self) -> Option<u8> {
+        None
+    }
 }
 
 impl Score for tfb::Score {
@@ -72,9 +84,75 @@ impl Score for tfb::Score {
     fn as_duration(&self) -> Option<Option<Duration>> {
         None
     }
+
+    fn finish_time(&self) -> Option<Duration> {
+        (self.pieces > 0).then_some(self.last_collection_time)
+    }
+
+    fn tfb_pieces(&self) -> Option<u8> {
+        Some(self.pieces)
+    }
 }
 
-async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ctx: &RaceContext<GlobalState>, cal_event: &cal::Event, event: &event::Data<'_>, mut entrants: [(Entrant, S, Url); 2]) -> Result<Transaction<'a, Postgres>, Error> {
+/// The placement of each entrant, counting from 1, with entrants who have the same score sharing a placement.
+///
+/// `scores` must already be sorted by placement.
+fn placements<'a, S: Score + 'a>(scores: impl IntoIterator<Item = &'a S>) -> Vec<u16> {
+    let mut placements = Vec::<u16>::default();
+    let mut prev_key = None;
+    for (idx, score) in scores.into_iter().enumerate() {
+        let key = score.sort_key();
+        let placement = match (prev_key.as_ref(), placements.last()) {
+            (Some(prev_key), Some(&prev_placement)) if *prev_key == key => prev_placement,
+            (_, _) => u16::try_from(idx + 1).unwrap_or(u16::MAX),
+        };
+        placements.push(placement);
+        prev_key = Some(key);
+    }
+    placements
+}
+
+/// Stores each entrant's finish time and placement so they can be shown on the website and in the API.
+///
+/// `entrants` must already be sorted by placement.
+async fn record_results<S: Score>(transaction: &mut Transaction<'_, Postgres>, ctx: &impl RaceRoom, cal_event: &cal::Event, breaks_used: bool, entrants: &[(Entrant, S, Url)]) -> Result<(), Error> {
+    let room = Url::parse(&format!("https://{}{}", racetime_host(), ctx.data().await.url)).to_racetime()?;
+    let results = entrants.iter().zip_eq(placements(entrants.iter().map(|(_, score, _)| score))).map(|((entrant, score, entrant_room), placement)| {
+        // results are only reported automatically if FPA wasn't called in this room, but it may have been called in an earlier async part
+        let (fpa_invoked, breaks_used) = if *entrant_room == room { (false, breaks_used) } else { (cal_event.race.fpa_invoked, cal_event.race.breaks_used) };
+        cal::RaceResult {
+            entrant: entrant.clone(),
+            finish_time: score.finish_time(),
+            tfb_pieces: score.tfb_pieces(),
+            dnf: score.is_dnf(),
+            placement, fpa_invoked, breaks_used,
+        }
+    }).collect_vec();
+    cal_event.race.record_results(transaction, &ctx.global_state().http_client, &results).await.to_racetime()?;
+    Ok(())
+}
+
+/// Records the results of a race and announces them in the format appropriate for the number of entrants.
+async fn report_entrants<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ctx: &impl RaceRoom, cal_event: &cal::Event, event: &event::Data<'_>, breaks_used: bool, mut entrants: Vec<(Entrant, S, Url)>) -> Result<Transaction<'a, Postgres>, Error> {
+    entrants.sort_by_key(|(_, score, _)| score.sort_key());
+    let is_ffa = matches!(cal_event.race.entrants, Entrants::Open | Entrants::Count { .. });
+    // close finishes in invitational races are confirmed by organizers before the result counts
+    if is_ffa || !has_close_finish(entrants.iter().map(|(_, score, _)| score), event.retime_window) {
+        record_results(&mut transaction, ctx, cal_event, breaks_used, &entrants).await?;
+    }
+    if !is_ffa {
+        match <[_; 2]>::try_from(entrants) {
+            Ok(entrants) => return report_1v1(transaction, ctx, cal_event, event, entrants).await,
+            Err(entrants) if entrants.len() > 2 => return report_placements(transaction, ctx, cal_event, event, entrants).await,
+            Err(_) => {}
+        }
+    }
+    let room = Url::parse(&format!("https://{}{}", racetime_host(), ctx.data().await.url)).to_racetime()?;
+    report_ffa(ctx, cal_event, event, room).await?;
+    Ok(transaction)
+}
+
+async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ctx: &impl RaceRoom, cal_event: &cal::Event, event: &event::Data<'_>, mut entrants: [(Entrant, S, Url); 2]) -> Result<Transaction<'a, Postgres>, Error> {
     entrants.sort_unstable_by_key(|(_, time, _)| time.sort_key());
     let [(winner, winning_time, winning_room), (loser, losing_time, losing_room)] = entrants;
     if winning_time.is_dnf() && losing_time.is_dnf() {
@@ -166,7 +244,7 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
                     builder.build()
                 }
             };
-            results_channel.say(&*ctx.global_state.discord_ctx.read().await, msg).await.to_racetime()?;
+            ctx.discord().say(results_channel, msg).await.to_racetime()?;
         }
     } else if losing_time.time_window(&winning_time).is_some_and(|time_window| time_window <= event.retime_window) {
         if let Some(organizer_channel) = event.discord_organizer_channel {
@@ -178,22 +256,9 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
                 msg.push(losing_room);
             }
             msg.push('>');
-            if event.discord_race_results_channel.is_some() || matches!(cal_event.race.source, cal::Source::StartGG { .. }) {
-                msg.push(" — please manually ");
-                if let Some(results_channel) = event.discord_race_results_channel {
-                    msg.push("post the announcement in ");
-                    msg.mention(&results_channel);
-                }
-                if let Some(startgg_set_url) = cal_event.race.startgg_set_url().to_racetime()? {
-                    if event.discord_race_results_channel.is_some() {
-                        msg.push(" and ");
-                    }
-                    msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
-                }
-                msg.push(" after adjusting the times");
-            }
+            push_manual_report_request(&mut msg, cal_event, event);
             //TODO note to manually initialize high seed for next game's draft (if any) and use `/post-status`
-            organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await.to_racetime()?;
+            ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
         }
     } else {
         if let Some(results_channel) = event.discord_race_results_channel.or(event.discord_organizer_channel) {
@@ -277,11 +342,53 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
                     builder.build()
                 }
             };
-            results_channel.say(&*ctx.global_state.discord_ctx.read().await, msg).await.to_racetime()?;
+            ctx.discord().say(results_channel, msg).await.to_racetime()?;
         }
+        let (game_winners, match_decided) = if let (Some(_), Entrant::MidosHouseTeam(winner_team)) = (cal_event.race.game, &winner) {
+            let game_winners = cal_event.race.game_winners(&mut transaction).await.to_racetime()?;
+            let game_count = cal_event.race.game_count(&mut transaction).await.to_racetime()?;
+            let match_decided = cal::has_clinched(&game_winners, winner_team.id, game_count);
+            (game_winners, match_decided)
+        } else {
+            (Vec::default(), cal_event.race.game.is_none())
+        };
         match cal_event.race.source {
             cal::Source::Manual | cal::Source::Sheet { .. } => {}
-            cal::Source::Challonge { .. } => {} //TODO
+            cal::Source::Challonge { ref id } => if let (Entrant::MidosHouseTeam(winner_team), Entrant::MidosHouseTeam(loser_team)) = (&winner, &loser) {
+                let [winner_score, loser_score] = if cal_event.race.game.is_some() {
+                    [winner_team, loser_team].map(|team| game_winners.iter().filter(|&&(_, winning_team)| winning_team == team.id).count())
+                } else {
+                    [1, 0]
+                };
+                let result = if let (MatchSource::Challonge { community, tournament }, Some(winner_id), Some(loser_id)) = (
+                    event.match_source(),
+                    winner_team.challonge_id(&mut transaction).await.to_racetime()?,
+                    loser_team.challonge_id(&mut transaction).await.to_racetime()?,
+                ) {
+                    challonge::report_match(
+                        &ctx.global_state().http_client, &ctx.global_state().challonge_api_key, community, tournament, id,
+                        [(&winner_id, winner_score), (&loser_id, loser_score)],
+                        match_decided.then_some(&*winner_id),
+                    ).await.map_err(|e| e.to_string())
+                } else {
+                    Err(format!("missing Challonge participant ID"))
+                };
+                if let Err(e) = result {
+                    if let Some(organizer_channel) = event.discord_organizer_channel {
+                        let mut msg = MessageBuilder::default();
+                        msg.push("failed to report race result to Challonge: <https://");
+                        msg.push(racetime_host());
+                        msg.push(&ctx.data().await.url);
+                        msg.push("> (");
+                        msg.push_safe(e);
+                        msg.push(')');
+                        ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
+                    } else {
+                        // no one would see the error otherwise
+                        wheel::night_report(&format!("{}/error", night_path()), Some(&format!("failed to report race result to Challonge for https://{}{}: {e}", racetime_host(), ctx.data().await.url))).await.to_racetime()?;
+                    }
+                }
+            },
             cal::Source::League { id } => if let (Some(winner), Some(loser), Some(winning_time), Some(losing_time)) = (
                 match &winner {
                     Entrant::MidosHouseTeam(team) => team.members(&mut transaction).await.to_racetime()?.into_iter().exactly_one().ok().and_then(|member| member.racetime).map(|racetime| racetime.id),
@@ -307,47 +414,56 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
                 if let Some(losing_time) = losing_time {
                     form.insert("losingTime", losing_time.as_secs().to_string());
                 }
-                let request = ctx.global_state.http_client.post("https://league.ootrandomizer.com/reportResultFromMidoHouse")
-                    .bearer_auth(&ctx.global_state.league_api_key)
+                let request = ctx.global_state().http_client.post("https://league.ootrandomizer.com/reportResultFromMidoHouse")
+                    .bearer_auth(&ctx.global_state().league_api_key)
                     .form(&form);
                 println!("reporting result to League website: {:?}", serde_urlencoded::to_string(&form));
                 request.send().await?.detailed_error_for_status().await.to_racetime()?;
             },
-            cal::Source::StartGG { ref set, .. } => if cal_event.race.game.is_none() { //TODO also auto-report multi-game matches (report all games but the last as match progress)
-                if let Entrant::MidosHouseTeam(Team { startgg_id: Some(winner_entrant_id), .. }) = &winner {
-                    startgg::query_uncached::<startgg::ReportOneGameResultMutation>(&ctx.global_state.http_client, &ctx.global_state.startgg_token, startgg::report_one_game_result_mutation::Variables {
-                        set_id: set.clone(),
-                        winner_entrant_id: winner_entrant_id.clone(),
-                    }).await.to_racetime()?;
-                } else {
-                    if let Some(organizer_channel) = event.discord_organizer_channel {
-                        let mut msg = MessageBuilder::default();
-                        msg.push("failed to report race result to start.gg: <https://");
-                        msg.push(racetime_host());
-                        msg.push(&ctx.data().await.url);
-                        msg.push("> (winner has no start.gg entrant ID)");
-                        organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await.to_racetime()?;
-                    }
+            cal::Source::StartGG { ref set, .. } => if let Entrant::MidosHouseTeam(Team { startgg_id: Some(winner_entrant_id), .. }) = &winner {
+                // start.gg replaces the set's game data on each report, so all games played so far are included
+                let game_data = cal_event.race.game.is_some().then(|| game_winners.iter().map(|&(game, winning_team)| Some(startgg::report_one_game_result_mutation::BracketSetGameDataInput {
+                    winner_id: [&winner, &loser].into_iter().find_map(|entrant| if let Entrant::MidosHouseTeam(team) = entrant {
+                        team.startgg_id.clone().filter(|_| team.id == winning_team)
+                    } else {
+                        None
+                    }),
+                    game_num: game.into(),
+                    entrant1_score: None,
+                    entrant2_score: None,
+                    stage_id: None,
+                    selections: None,
+                })).collect());
+                startgg::query_uncached::<startgg::ReportOneGameResultMutation>(&ctx.global_state().http_client, &ctx.global_state().startgg_token, startgg::report_one_game_result_mutation::Variables {
+                    set_id: set.clone(),
+                    winner_entrant_id: match_decided.then(|| winner_entrant_id.clone()),
+                    game_data,
+                }).await.to_racetime()?;
+            } else {
+                if let Some(organizer_channel) = event.discord_organizer_channel {
+                    let mut msg = MessageBuilder::default();
+                    msg.push("failed to report race result to start.gg: <https://");
+                    msg.push(racetime_host());
+                    msg.push(&ctx.data().await.url);
+                    msg.push("> (winner has no start.gg entrant ID)");
+                    ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
                 }
             },
-            cal::Source::SpeedGaming { .. } => {} //TODO
+            cal::Source::SpeedGaming { .. } => {} // SpeedGaming's API is read-only, so results can't be reported back
         }
         if_chain! {
+            if !match_decided;
             if let Entrant::MidosHouseTeam(winner) = winner;
             if let Entrant::MidosHouseTeam(loser) = loser;
             if let Some(draft_kind) = event.draft_kind();
-            if let Some(next_game) = cal_event.race.next_game(&mut transaction, &ctx.global_state.http_client).await.to_racetime()?;
+            if let Some(next_game) = cal_event.race.next_game(&mut transaction, &ctx.global_state().http_client).await.to_racetime()?;
             then {
-                //TODO if this game decides the match, delete next game instead of initializing draft
                 let draft = Draft::for_next_game(&mut transaction, draft_kind, loser.id, winner.id).await.to_racetime()?;
                 sqlx::query!("UPDATE races SET draft_state = $1 WHERE id = $2", sqlx::types::Json(&draft) as _, next_game.id as _).execute(&mut *transaction).await.to_racetime()?;
                 if_chain! {
                     if let Some(guild_id) = event.discord_guild;
                     if let Some(scheduling_thread) = next_game.scheduling_thread;
-                    // not automatically posting if the match might already be decided
-                    //TODO remove this condition after implementing handling for decided matches (see TODO comment above)
-                    if cal_event.race.game.expect("found next game for race without game number") <= cal_event.race.game_count(&mut transaction).await.to_racetime()? / 2;
-                    let discord_ctx = ctx.global_state.discord_ctx.read().await;
+                    let discord_ctx = ctx.global_state().discord_ctx.read().await;
                     let data = discord_ctx.data.read().await;
                     if let Some(Some(command_ids)) = data.get::<CommandIds>().and_then(|command_ids| command_ids.get(&guild_id).copied());
                     then {
@@ -358,6 +474,7 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
                         };
                         scheduling_thread.say(&*discord_ctx, draft.next_step(draft_kind, next_game.game, &mut msg_ctx).await.to_racetime()?.message).await.to_racetime()?;
                         transaction = msg_ctx.into_transaction();
+                        draft::timer::reset(&mut transaction, event, next_game.id).await.to_racetime()?;
                     }
                 }
             }
@@ -366,7 +483,192 @@ async fn report_1v1<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ct
     Ok(transaction)
 }
 
-async fn report_ffa(ctx: &RaceContext<GlobalState>, cal_event: &cal::Event, event: &event::Data<'_>, room: Url) -> Result<(), Error> {
+/// Whether any two entrants with adjacent placements finished within the retime window of each other, so the placements need to be confirmed by an organizer.
+///
+/// `scores` must already be sorted by placement.
+fn has_close_finish<'a, S: Score + 'a>(scores: impl IntoIterator<Item = &'a S>, retime_window: Duration) -> bool {
+    scores.into_iter().tuple_windows().any(|(better, worse)| worse.time_window(better).is_some_and(|time_window| time_window <= retime_window))
+}
+
+/// Asks organizers to enter the result of a race which wasn't recorded automatically, along with any announcement or bracket report that depends on it.
+pub(super) fn push_manual_report_request(msg: &mut MessageBuilder, cal_event: &cal::Event, event: &event::Data<'_>) {
+    msg.push(" — please ");
+    msg.push_named_link_no_preview("enter the result", uri!(base_uri(), cal::race_results(event.series, &*event.event, cal_event.race.id)).to_string());
+    if let Some(results_channel) = event.discord_race_results_channel {
+        msg.push(" and post the announcement in ");
+        msg.mention(&results_channel);
+    }
+    match cal_event.race.startgg_set_url() {
+        Ok(Some(startgg_set_url)) => {
+            msg.push(" and ");
+            msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
+        }
+        Ok(None) => {}
+        Err(_) => { msg.push(" and report the result on start.gg"); }
+    }
+    if let cal::Source::Challonge { .. } = cal_event.race.source {
+        msg.push(" and report the result on Challonge");
+    }
+    msg.push(" after adjusting the times");
+}
+
+/// The text to put before the entrant with the given index in a list of `len` entrants.
+fn list_separator(language: Language, idx: usize, len: usize) -> &'static str {
+    match (language, idx) {
+        (_, 0) => "",
+        (French, idx) if idx == len - 1 => " et ",
+        (_, 1) if len == 2 => " and ",
+        (_, idx) if idx == len - 1 => ", and ",
+        (_, _) => ", ",
+    }
+}
+
+async fn report_placements<'a, S: Score>(mut transaction: Transaction<'a, Postgres>, ctx: &impl RaceRoom, cal_event: &cal::Event, event: &event::Data<'_>, mut entrants: Vec<(Entrant, S, Url)>) -> Result<Transaction<'a, Postgres>, Error> {
+    entrants.sort_by_key(|(_, score, _)| score.sort_key()); // stable sort to keep ties in the order given by racetime.gg
+    let single_room = entrants.iter().map(|(_, _, room)| room).all_equal_value().ok().cloned();
+    if has_close_finish(entrants.iter().map(|(_, score, _)| score), event.retime_window) {
+        if let Some(organizer_channel) = event.discord_organizer_channel {
+            let mut msg = MessageBuilder::default();
+            msg.push("race finished with close placements: ");
+            for (idx, room) in entrants.iter().map(|(_, _, room)| room).unique().enumerate() {
+                if idx > 0 {
+                    msg.push(" and ");
+                }
+                msg.push('<');
+                msg.push(room.to_string());
+                msg.push('>');
+            }
+            push_manual_report_request(&mut msg, cal_event, event);
+            ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
+        }
+        return Ok(transaction)
+    }
+    if let Some(results_channel) = event.discord_race_results_channel.or(event.discord_organizer_channel) {
+        let mut builder = MessageBuilder::default();
+        let language = if_chain! {
+            if let French = event.language;
+            if let Some(phase_round) = match (&cal_event.race.phase, &cal_event.race.round) {
+                (Some(phase), Some(round)) => if let Some(Some(phase_round)) = sqlx::query_scalar!("SELECT display_fr FROM phase_round_options WHERE series = $1 AND event = $2 AND phase = $3 AND round = $4", event.series as _, &event.event, phase, round).fetch_optional(&mut *transaction).await.to_racetime()? {
+                    Some(Some(phase_round))
+                } else {
+                    None // no translation
+                },
+                (Some(_), None) | (None, Some(_)) => None, // no translation
+                (None, None) => Some(None), // no phase/round
+            };
+            if cal_event.race.game.is_none();
+            then {
+                if let Some(phase_round) = phase_round {
+                    builder.push_safe(phase_round);
+                    builder.push(" : ");
+                }
+                French
+            } else {
+                let info_prefix = match (&cal_event.race.phase, &cal_event.race.round) {
+                    (Some(phase), Some(round)) => Some(format!("{phase} {round}")),
+                    (Some(phase), None) => Some(phase.clone()),
+                    (None, Some(round)) => Some(round.clone()),
+                    (None, None) => None,
+                };
+                match (info_prefix, cal_event.race.game) {
+                    (Some(prefix), Some(game)) => {
+                        builder.push_safe(prefix);
+                        builder.push(", game ");
+                        builder.push(game.to_string());
+                        builder.push(": ");
+                    }
+                    (Some(prefix), None) => {
+                        builder.push_safe(prefix);
+                        builder.push(": ");
+                    }
+                    (None, Some(game)) => {
+                        builder.push("game ");
+                        builder.push(game.to_string());
+                        builder.push(": ");
+                    }
+                    (None, None) => {}
+                }
+                English
+            }
+        };
+        if entrants.iter().all(|(_, score, _)| score.is_dnf()) {
+            if let French = language {
+                for (idx, (entrant, _, room)) in entrants.iter().enumerate() {
+                    builder.push(if idx == 0 { "Ni " } else if entrants.len() == 2 { " ni " } else { ", ni " });
+                    builder.mention_entrant(&mut transaction, event.discord_guild, entrant).await.to_racetime()?;
+                    if single_room.is_none() {
+                        builder.push(" [<");
+                        builder.push(room.to_string());
+                        builder.push(">]");
+                    }
+                }
+                builder.push(" n'ont fini");
+            } else {
+                for (idx, (entrant, _, room)) in entrants.iter().enumerate() {
+                    builder.push(list_separator(English, idx, entrants.len()));
+                    builder.mention_entrant(&mut transaction, event.discord_guild, entrant).await.to_racetime()?;
+                    if single_room.is_none() {
+                        builder.push(" [<");
+                        builder.push(room.to_string());
+                        builder.push(">]");
+                    }
+                }
+                builder.push(" all did not finish");
+            }
+        } else {
+            for (idx, (entrant, score, room)) in entrants.iter().enumerate() {
+                if idx == 1 {
+                    builder.push(match (language, entrants[0].0.name_is_plural()) {
+                        (French, false) => " remporte la course devant ",
+                        (French, true) => " remportent la course devant ",
+                        (_, false) => " wins, followed by ",
+                        (_, true) => " win, followed by ",
+                    });
+                } else if idx > 1 {
+                    builder.push(list_separator(language, idx - 1, entrants.len() - 1));
+                }
+                builder.mention_entrant(&mut transaction, event.discord_guild, entrant).await.to_racetime()?;
+                builder.push(" (");
+                builder.push(score.format(language));
+                builder.push(')');
+                if single_room.is_none() {
+                    builder.push(" [<");
+                    builder.push(room.to_string());
+                    builder.push(">]");
+                }
+            }
+        }
+        if let Some(ref room) = single_room {
+            builder.push(" <");
+            builder.push(room.to_string());
+            builder.push('>');
+        }
+        ctx.discord().say(results_channel, builder.build()).await.to_racetime()?;
+    }
+    match cal_event.race.source {
+        cal::Source::Manual | cal::Source::Sheet { .. } | cal::Source::SpeedGaming { .. } => {}
+        // these sources only support 1v1 results
+        cal::Source::Challonge { .. } | cal::Source::League { .. } | cal::Source::StartGG { .. } => if let Some(organizer_channel) = event.discord_organizer_channel {
+            let mut msg = MessageBuilder::default();
+            msg.push("race with more than 2 entrants finished: <https://");
+            msg.push(racetime_host());
+            msg.push(&ctx.data().await.url);
+            msg.push("> — please manually ");
+            if let Some(startgg_set_url) = cal_event.race.startgg_set_url().to_racetime()? {
+                msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
+            } else {
+                msg.push(match cal_event.race.source {
+                    cal::Source::Challonge { .. } => "report the result on Challonge",
+                    _ => "report the result on the League website",
+                });
+            }
+            ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
+        },
+    }
+    Ok(transaction)
+}
+
+async fn report_ffa(ctx: &impl RaceRoom, cal_event: &cal::Event, event: &event::Data<'_>, room: Url) -> Result<(), Error> {
     if let Some(results_channel) = event.discord_race_results_channel.or(event.discord_organizer_channel) {
         let mut builder = MessageBuilder::default();
         let info_prefix = match (&cal_event.race.phase, &cal_event.race.round) {
@@ -396,14 +698,14 @@ async fn report_ffa(ctx: &RaceContext<GlobalState>, cal_event: &cal::Event, even
         builder.push("race finished: <");
         builder.push(room.to_string());
         builder.push('>');
-        results_channel.say(&*ctx.global_state.discord_ctx.read().await, builder.build()).await.to_racetime()?;
+        ctx.discord().say(results_channel, builder.build()).await.to_racetime()?;
     }
     Ok(())
 }
 
 impl Handler {
     #[must_use = "should set cleaned_up if this returns true"]
-    pub(super) async fn check_tfb_finish(&self, ctx: &RaceContext<GlobalState>) -> Result<bool, Error> {
+    pub(super) async fn check_tfb_finish(&self, ctx: &impl RaceRoom) -> Result<bool, Error> {
         let data = ctx.data().await;
         let Some(OfficialRaceData { ref cal_event, ref event, fpa_invoked, breaks_used, ref scores, .. }) = self.official_data else { return Ok(true) };
         Ok(if let Some(scores) = data.entrants.iter().map(|entrant| {
@@ -422,13 +724,13 @@ impl Handler {
         })
     }
 
-    pub(super) async fn official_race_finished(&self, ctx: &RaceContext<GlobalState>, data: RwLockReadGuard<'_, RaceData>, cal_event: &cal::Event, event: &event::Data<'_>, fpa_invoked: bool, breaks_used: bool, tfb_scores: Option<HashMap<String, tfb::Score>>) -> Result<(), Error> {
+    pub(super) async fn official_race_finished(&self, ctx: &impl RaceRoom, data: RwLockReadGuard<'_, RaceData>, cal_event: &cal::Event, event: &event::Data<'_>, fpa_invoked: bool, breaks_used: bool, tfb_scores: Option<HashMap<String, tfb::Score>>) -> Result<(), Error> {
         let stream_delay = match cal_event.race.entrants {
             Entrants::Open | Entrants::Count { .. } => event.open_stream_delay,
             Entrants::Two(_) | Entrants::Three(_) | Entrants::Named(_) => event.invitational_stream_delay,
         };
         sleep(stream_delay).await;
-        let mut transaction = ctx.global_state.db_pool.begin().await.to_racetime()?;
+        let mut transaction = ctx.global_state().db_pool.begin().await.to_racetime()?;
         if cal_event.is_private_async_part() {
             ctx.say("@entrants Please remember to send the videos of your run to a tournament organizer.").await?;
             if fpa_invoked {
@@ -438,7 +740,7 @@ impl Handler {
                 sqlx::query!("UPDATE races SET breaks_used = TRUE WHERE id = $1", cal_event.race.id as _).execute(&mut *transaction).await.to_racetime()?;
             }
             if let Some(organizer_channel) = event.discord_organizer_channel {
-                organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, MessageBuilder::default()
+                ctx.discord().say(organizer_channel, MessageBuilder::default()
                     .push("first half of async finished")
                     .push(if fpa_invoked { " with FPA call" } else if event.manual_reporting_with_breaks && breaks_used { " with breaks" } else { "" })
                     .push(": <https://")
@@ -449,206 +751,161 @@ impl Handler {
                 ).await.to_racetime()?;
             }
         } else if fpa_invoked {
+            // remembered so the results page can fill it in for the organizers
+            sqlx::query!("UPDATE races SET fpa_invoked = TRUE WHERE id = $1", cal_event.race.id as _).execute(&mut *transaction).await.to_racetime()?;
             if let Some(organizer_channel) = event.discord_organizer_channel {
                 let mut msg = MessageBuilder::default();
                 msg.push("race finished with FPA call: <https://");
                 msg.push(racetime_host());
                 msg.push(&ctx.data().await.url);
                 msg.push('>');
-                if event.discord_race_results_channel.is_some() || matches!(cal_event.race.source, cal::Source::StartGG { .. }) {
-                    msg.push(" — please manually ");
-                    if let Some(results_channel) = event.discord_race_results_channel {
-                        msg.push("post the announcement in ");
-                        msg.mention(&results_channel);
-                    }
-                    if let Some(startgg_set_url) = cal_event.race.startgg_set_url().to_racetime()? {
-                        if event.discord_race_results_channel.is_some() {
-                            msg.push(" and ");
-                        }
-                        msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
-                    }
-                    msg.push(" after adjusting the times");
-                }
+                push_manual_report_request(&mut msg, cal_event, event);
                 //TODO note to manually initialize high seed for next game's draft (if any) and use `/post-status`
-                organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await.to_racetime()?;
+                ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
             }
         } else if event.manual_reporting_with_breaks && breaks_used {
+            sqlx::query!("UPDATE races SET breaks_used = TRUE WHERE id = $1", cal_event.race.id as _).execute(&mut *transaction).await.to_racetime()?;
             if let Some(organizer_channel) = event.discord_organizer_channel {
                 let mut msg = MessageBuilder::default();
                 msg.push("race finished with breaks: <https://");
                 msg.push(racetime_host());
                 msg.push(&ctx.data().await.url);
                 msg.push('>');
-                if event.discord_race_results_channel.is_some() || matches!(cal_event.race.source, cal::Source::StartGG { .. }) {
-                    msg.push(" — please manually ");
-                    if let Some(results_channel) = event.discord_race_results_channel {
-                        msg.push("post the announcement in ");
-                        msg.mention(&results_channel);
-                    }
-                    if let Some(startgg_set_url) = cal_event.race.startgg_set_url().to_racetime()? {
-                        if event.discord_race_results_channel.is_some() {
-                            msg.push(" and ");
-                        }
-                        msg.push_named_link_no_preview("report the result on start.gg", startgg_set_url);
-                    }
-                    msg.push(" after adjusting the times");
-                }
+                push_manual_report_request(&mut msg, cal_event, event);
                 //TODO note to manually initialize high seed for next game's draft (if any) and use `/post-status`
-                organizer_channel.say(&*ctx.global_state.discord_ctx.read().await, msg.build()).await.to_racetime()?;
+                ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
             }
         } else {
             match event.team_config {
-                TeamConfig::Solo => match cal_event.race.entrants {
-                    Entrants::Open | Entrants::Count { .. } => {
-                        let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
-                        report_ffa(ctx, cal_event, event, room).await?;
-                    }
-                    Entrants::Named(_) => unimplemented!(),
-                    Entrants::Two(_) | Entrants::Three(_) => {
-                        let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
-                        if let Some(mut tfb_scores) = tfb_scores {
-                            let mut teams = Vec::with_capacity(data.entrants.len());
-                            for entrant in &data.entrants {
-                                teams.push((if_chain! {
-                                    if let Some(user) = User::from_racetime(&mut *transaction, &entrant.user.id).await.to_racetime()?;
-                                    if let Some(team) = Team::from_event_and_member(&mut transaction, event.series, &event.event, user.id).await.to_racetime()?;
-                                    then {
-                                        Entrant::MidosHouseTeam(team)
-                                    } else {
-                                        Entrant::Named {
-                                            name: entrant.user.full_name.clone(),
-                                            racetime_id: Some(entrant.user.id.clone()),
-                                            twitch_username: entrant.user.twitch_name.clone(),
-                                        }
+                // only Pictionary runners join the race room, so each room entrant stands for their team
+                TeamConfig::Solo | TeamConfig::Pictionary => {
+                    let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
+                    if let Some(mut tfb_scores) = tfb_scores {
+                        let mut teams = Vec::with_capacity(data.entrants.len());
+                        for entrant in &data.entrants {
+                            teams.push((if_chain! {
+                                if let Some(user) = User::from_racetime(&mut *transaction, &entrant.user.id).await.to_racetime()?;
+                                if let Some(team) = Team::from_event_and_member(&mut transaction, event.series, &event.event, user.id).await.to_racetime()?;
+                                then {
+                                    Entrant::MidosHouseTeam(team)
+                                } else {
+                                    Entrant::Named {
+                                        name: entrant.user.full_name.clone(),
+                                        racetime_id: Some(entrant.user.id.clone()),
+                                        twitch_username: entrant.user.twitch_name.clone(),
                                     }
-                                }, tfb_scores.remove(&entrant.user.id).expect("missing TFB score"), room.clone()));
-                            }
-                            if let Ok(teams) = teams.try_into() {
-                                transaction = report_1v1(transaction, ctx, cal_event, event, teams).await?;
-                            } else { //TODO separate function for reporting 3-entrant results
-                                report_ffa(ctx, cal_event, event, room).await?;
-                            }
-                        } else {
-                            let mut teams = Vec::with_capacity(data.entrants.len());
-                            for entrant in &data.entrants {
-                                teams.push((if_chain! {
-                                    if let Some(user) = User::from_racetime(&mut *transaction, &entrant.user.id).await.to_racetime()?;
-                                    if let Some(team) = Team::from_event_and_member(&mut transaction, event.series, &event.event, user.id).await.to_racetime()?;
-                                    then {
-                                        Entrant::MidosHouseTeam(team)
-                                    } else {
-                                        Entrant::Named {
-                                            name: entrant.user.full_name.clone(),
-                                            racetime_id: Some(entrant.user.id.clone()),
-                                            twitch_username: entrant.user.twitch_name.clone(),
-                                        }
+                                }
+                            }, tfb_scores.remove(&entrant.user.id).expect("missing TFB score"), room.clone()));
+                        }
+                        transaction = report_entrants(transaction, ctx, cal_event, event, breaks_used, teams).await?;
+                    } else {
+                        let mut teams = Vec::with_capacity(data.entrants.len());
+                        for entrant in &data.entrants {
+                            teams.push((if_chain! {
+                                if let Some(user) = User::from_racetime(&mut *transaction, &entrant.user.id).await.to_racetime()?;
+                                if let Some(team) = Team::from_event_and_member(&mut transaction, event.series, &event.event, user.id).await.to_racetime()?;
+                                then {
+                                    Entrant::MidosHouseTeam(team)
+                                } else {
+                                    Entrant::Named {
+                                        name: entrant.user.full_name.clone(),
+                                        racetime_id: Some(entrant.user.id.clone()),
+                                        twitch_username: entrant.user.twitch_name.clone(),
                                     }
-                                }, entrant.finish_time, room.clone()));
-                            }
-                            if let Ok(teams) = teams.try_into() {
-                                transaction = report_1v1(transaction, ctx, cal_event, event, teams).await?;
-                            } else { //TODO separate function for reporting 3-entrant results
-                                report_ffa(ctx, cal_event, event, room).await?;
-                            }
+                                }
+                            }, entrant.finish_time, room.clone()));
                         }
+                        transaction = report_entrants(transaction, ctx, cal_event, event, breaks_used, teams).await?;
                     }
-                },
-                TeamConfig::Pictionary => unimplemented!(), //TODO calculate like solo but report as teams
-                _ => match cal_event.race.entrants {
-                    Entrants::Open | Entrants::Count { .. } => {
-                        let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
-                        report_ffa(ctx, cal_event, event, room).await?;
-                    }
-                    Entrants::Named(_) => unimplemented!(),
-                    Entrants::Two(_) | Entrants::Three(_) => {
-                        let mut team_times = HashMap::<_, Vec<_>>::default();
-                        let mut team_rooms = HashMap::new();
-                        if cal_event.is_public_async_part() {
-                            #[derive(Debug, thiserror::Error)]
-                            #[error("ExactlyOneError while formatting result of last async half")]
-                            struct ExactlyOneError;
+                }
+                TeamConfig::CoOp | TeamConfig::TfbCoOp | TeamConfig::Multiworld => {
+                    let mut team_times = HashMap::<_, Vec<_>>::default();
+                    let mut team_rooms = HashMap::new();
+                    let mut solo_runner_found = false;
+                    if cal_event.is_public_async_part() {
+                        #[derive(Debug, thiserror::Error)]
+                        #[error("ExactlyOneError while formatting result of last async half")]
+                        struct ExactlyOneError;
 
-                            for private_async_part in cal_event.race.cal_events().filter(|cal_event| cal_event.is_private_async_part()) {
-                                if let Some(ref room) = private_async_part.room() {
-                                    let nonactive_team = private_async_part.active_teams().exactly_one().map_err(|_| Error::Custom(Box::new(ExactlyOneError)))?;
-                                    let data = ctx.global_state.http_client.get(format!("{}/data", room.to_string()))
-                                        .send().await?
-                                        .detailed_error_for_status().await.to_racetime()?
-                                        .json_with_text_in_error::<RaceData>().await.to_racetime()?;
-                                    team_rooms.insert(nonactive_team.racetime_slug.clone().expect("non-racetime.gg team"), Url::clone(room));
-                                    for entrant in &data.entrants {
-                                        team_times.entry(nonactive_team.racetime_slug.clone().expect("non-racetime.gg team")).or_default().push(entrant.finish_time);
-                                    }
-                                }
-                            }
-                            let active_team = cal_event.active_teams().exactly_one().map_err(|_| Error::Custom(Box::new(ExactlyOneError)))?;
-                            team_rooms.insert(active_team.racetime_slug.clone().expect("non-racetime.gg team"), Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?);
-                            for entrant in &data.entrants {
-                                team_times.entry(active_team.racetime_slug.clone().expect("non-racetime.gg team")).or_default().push(entrant.finish_time);
-                            }
-                        } else {
-                            for entrant in &data.entrants {
-                                if let Some(ref team) = entrant.team {
-                                    if let hash_map::Entry::Vacant(entry) = team_rooms.entry(team.slug.clone()) {
-                                        entry.insert(Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?);
-                                    }
-                                    team_times.entry(team.slug.clone()).or_default().push(entrant.finish_time);
-                                } else {
-                                    unimplemented!("solo runner in team race") //TODO report error in organizer channel
+                        for private_async_part in cal_event.race.cal_events().filter(|cal_event| cal_event.is_private_async_part()) {
+                            if let Some(ref room) = private_async_part.room() {
+                                let nonactive_team = private_async_part.active_teams().exactly_one().map_err(|_| Error::Custom(Box::new(ExactlyOneError)))?;
+                                let data = ctx.global_state().http_client.get(format!("{}/data", room.to_string()))
+                                    .send().await?
+                                    .detailed_error_for_status().await.to_racetime()?
+                                    .json_with_text_in_error::<RaceData>().await.to_racetime()?;
+                                team_rooms.insert(nonactive_team.racetime_slug.clone().expect("non-racetime.gg team"), Url::clone(room));
+                                for entrant in &data.entrants {
+                                    team_times.entry(nonactive_team.racetime_slug.clone().expect("non-racetime.gg team")).or_default().push(entrant.finish_time);
                                 }
                             }
                         }
-                        if let Some(mut tfb_scores) = tfb_scores {
-                            let mut all_teams_found = true;
-                            let mut teams = Vec::with_capacity(team_times.len());
-                            for team_slug in team_times.keys() {
-                                if let Some(team) = Team::from_racetime(&mut transaction, event.series, &event.event, &team_slug).await.to_racetime()? {
-                                    teams.push((
-                                        Entrant::MidosHouseTeam(team),
-                                        tfb_scores.remove(team_slug).expect("missing TFB score"),
-                                        team_rooms.remove(team_slug).expect("each team should have a room"),
-                                    ));
-                                } else {
-                                    all_teams_found = false;
-                                }
-                            }
-                            if_chain! {
-                                if all_teams_found;
-                                if let Ok(teams) = teams.try_into();
-                                then {
-                                    transaction = report_1v1(transaction, ctx, cal_event, event, teams).await?;
-                                } else { //TODO separate function for reporting 3-entrant results
-                                    let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
-                                    report_ffa(ctx, cal_event, event, room).await?;
+                        let active_team = cal_event.active_teams().exactly_one().map_err(|_| Error::Custom(Box::new(ExactlyOneError)))?;
+                        team_rooms.insert(active_team.racetime_slug.clone().expect("non-racetime.gg team"), Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?);
+                        for entrant in &data.entrants {
+                            team_times.entry(active_team.racetime_slug.clone().expect("non-racetime.gg team")).or_default().push(entrant.finish_time);
+                        }
+                    } else {
+                        for entrant in &data.entrants {
+                            if let Some(ref team) = entrant.team {
+                                if let hash_map::Entry::Vacant(entry) = team_rooms.entry(team.slug.clone()) {
+                                    entry.insert(Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?);
                                 }
+                                team_times.entry(team.slug.clone()).or_default().push(entrant.finish_time);
+                            } else {
+                                solo_runner_found = true;
                             }
-                        } else {
-                            let mut all_teams_found = true;
-                            let mut teams = Vec::with_capacity(team_times.len());
-                            for (team_slug, times) in team_times {
-                                if let Some(team) = Team::from_racetime(&mut transaction, event.series, &event.event, &team_slug).await.to_racetime()? {
-                                    teams.push((
-                                        Entrant::MidosHouseTeam(team),
-                                        times.iter().try_fold(Duration::default(), |acc, &time| Some(acc + time?)).map(|total| total / u32::try_from(times.len()).expect("too many team members")),
-                                        team_rooms.remove(&team_slug).expect("each team should have a room"),
-                                    ));
-                                } else {
-                                    all_teams_found = false;
-                                }
+                        }
+                    }
+                    if solo_runner_found {
+                        if let Some(organizer_channel) = event.discord_organizer_channel {
+                            let mut msg = MessageBuilder::default();
+                            msg.push("could not record race result: <https://");
+                            msg.push(racetime_host());
+                            msg.push(&ctx.data().await.url);
+                            msg.push("> (an entrant joined the team race without a team)");
+                            ctx.discord().say(organizer_channel, msg.build()).await.to_racetime()?;
+                        }
+                    }
+                    let mut all_teams_found = !solo_runner_found;
+                    if let Some(mut tfb_scores) = tfb_scores {
+                        let mut teams = Vec::with_capacity(team_times.len());
+                        for team_slug in team_times.keys() {
+                            if let Some(team) = Team::from_racetime(&mut transaction, event.series, &event.event, &team_slug).await.to_racetime()? {
+                                teams.push((
+                                    Entrant::MidosHouseTeam(team),
+                                    tfb_scores.remove(team_slug).expect("missing TFB score"),
+                                    team_rooms.remove(team_slug).expect("each team should have a room"),
+                                ));
+                            } else {
+                                all_teams_found = false;
                             }
-                            if_chain! {
-                                if all_teams_found;
-                                if let Ok(teams) = teams.try_into();
-                                then {
-                                    transaction = report_1v1(transaction, ctx, cal_event, event, teams).await?;
-                                } else { //TODO separate function for reporting 3-entrant results
-                                    let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
-                                    report_ffa(ctx, cal_event, event, room).await?;
-                                }
+                        }
+                        if all_teams_found {
+                            transaction = report_entrants(transaction, ctx, cal_event, event, breaks_used, teams).await?;
+                        }
+                    } else {
+                        let mut teams = Vec::with_capacity(team_times.len());
+                        for (team_slug, times) in team_times {
+                            if let Some(team) = Team::from_racetime(&mut transaction, event.series, &event.event, &team_slug).await.to_racetime()? {
+                                teams.push((
+                                    Entrant::MidosHouseTeam(team),
+                                    times.iter().try_fold(Duration::default(), |acc, &time| Some(acc + time?)).map(|total| total / u32::try_from(times.len()).expect("too many team members")),
+                                    team_rooms.remove(&team_slug).expect("each team should have a room"),
+                                ));
+                            } else {
+                                all_teams_found = false;
                             }
                         }
+                        if all_teams_found {
+                            transaction = report_entrants(transaction, ctx, cal_event, event, breaks_used, teams).await?;
+                        }
                     }
-                },
+                    if !all_teams_found {
+                        let room = Url::parse(&format!("https://{}{}", racetime_host(), data.url)).to_racetime()?;
+                        report_ffa(ctx, cal_event, event, room).await?;
+                    }
+                }
             }
         }
         transaction.commit().await.to_racetime()?;
@@ -0,0 +1,79 @@
+use super::*;
+
+fn mins(mins: u64) -> Duration {
+    Duration::from_secs(mins * 60)
+}
+
+fn tfb(pieces: u8, last_collection_time: Duration) -> tfb::Score {
+    tfb::Score { team_config: TeamConfig::Solo, pieces, last_collection_time }
+}
+
+#[test]
+fn finish_times_sort_before_dnf() {
+    let mut scores = [None, Some(mins(95)), Some(mins(90)), None];
+    scores.sort_unstable_by_key(Score::sort_key);
+    assert_eq!(scores, [Some(mins(90)), Some(mins(95)), None, None]);
+    assert!(!Some(mins(90)).is_dnf());
+    assert!(None::<Duration>.is_dnf());
+}
+
+#[test]
+fn finish_time_window() {
+    assert_eq!(Some(mins(95)).time_window(&Some(mins(90))), Some(mins(5)));
+    assert_eq!(None::<Duration>.time_window(&Some(mins(90))), None, "no time window if either entrant didn't finish");
+    assert_eq!(Some(mins(95)).time_window(&None), None);
+    assert_eq!(Some(mins(95)).as_duration(), Some(Some(mins(95))));
+}
+
+#[test]
+fn format_finish_time() {
+    assert_eq!(Some(mins(95) + Duration::from_secs(7)).format(English), "1:35:07");
+    assert_eq!(Some(mins(95) + Duration::from_secs(7)).format(French), "1:35:07");
+    assert_eq!(None::<Duration>.format(English), "DNF");
+    assert_eq!(None::<Duration>.format(French), "forfait");
+}
+
+#[test]
+fn tfb_scores() {
+    let mut scores = [tfb(2, mins(80)), tfb(0, Duration::default()), tfb(3, mins(100)), tfb(2, mins(70))];
+    scores.sort_unstable_by_key(Score::sort_key);
+    // more pieces first, then earlier last collection time
+    assert_eq!(scores.map(|score| (score.pieces, score.last_collection_time)), [(3, mins(100)), (2, mins(70)), (2, mins(80)), (0, Duration::default())]);
+    assert!(scores[3].is_dnf());
+    assert!(!scores[2].is_dnf());
+    assert_eq!(scores[2].time_window(&scores[1]), Some(mins(10)));
+    assert_eq!(scores[1].time_window(&scores[0]), None, "no time window between different piece counts");
+    assert_eq!(scores[0].format(English), "3/3 in 1:40:00");
+    assert_eq!(scores[3].format(French), "0/3");
+    assert_eq!(scores[0].as_duration(), None);
+}
+
+#[test]
+fn close_finishes() {
+    let retime_window = mins(1);
+    assert!(!has_close_finish(&[Some(mins(90)), Some(mins(95)), None], retime_window));
+    assert!(has_close_finish(&[Some(mins(90)), Some(mins(95)), Some(mins(95) + Duration::from_secs(30))], retime_window), "close finish between 2nd and 3rd place");
+    assert!(!has_close_finish(&[Some(mins(90)), None, None], retime_window), "entrants who didn't finish are tied but don't need confirmation");
+    assert!(has_close_finish(&[tfb(3, mins(100)), tfb(3, mins(100)), tfb(1, mins(50))], retime_window));
+    assert!(!has_close_finish(&[tfb(3, mins(100)), tfb(2, mins(100))], retime_window));
+}
+
+#[test]
+fn entrant_lists() {
+    let list = |language, len| (0..len).map(|idx| format!("{}{idx}", list_separator(language, idx, len))).collect::<String>();
+    assert_eq!(list(English, 1), "0");
+    assert_eq!(list(English, 2), "0 and 1");
+    assert_eq!(list(English, 3), "0, 1, and 2");
+    assert_eq!(list(English, 4), "0, 1, 2, and 3");
+    assert_eq!(list(French, 2), "0 et 1");
+    assert_eq!(list(French, 3), "0, 1 et 2");
+}
+
+#[test]
+fn tied_placements() {
+    assert_eq!(placements(&[Some(mins(90)), Some(mins(95)), None]), [1, 2, 3]);
+    assert_eq!(placements(&[Some(mins(90)), Some(mins(90)), Some(mins(95))]), [1, 1, 3], "entrants with the same finish time share a placement");
+    assert_eq!(placements(&[Some(mins(90)), None, None]), [1, 2, 2]);
+    assert_eq!(placements(&[tfb(3, mins(100)), tfb(2, mins(80)), tfb(2, mins(80)), tfb(0, Duration::default())]), [1, 2, 2, 4]);
+    assert!(placements::<Option<Duration>>(&[]).is_empty());
+}
@@ -0,0 +1,240 @@
+//! The connections a race room handler uses to talk to racetime.gg and Discord.
+//!
+//! [`Handler`] only uses these through the [`RaceRoom`] trait so that room handling, seed rolling, and result reporting can be exercised in tests using [`FakeRaceRoom`].
+
+use {
+    tokio::sync::RwLockReadGuard,
+    crate::{
+        prelude::*,
+        racetime_bot::*,
+    },
+};
+
+/// The Discord operations a race room handler performs, such as posting race results.
+#[async_trait]
+pub(crate) trait Discord: Send + Sync {
+    async fn say(&self, channel: ChannelId, content: String) -> serenity::Result<()>;
+}
+
+#[async_trait]
+impl Discord for RwFuture<DiscordCtx> {
+    async fn say(&self, channel: ChannelId, content: String) -> serenity::Result<()> {
+        channel.say(&*self.read().await, content).await?;
+        Ok(())
+    }
+}
+
+/// A race room as seen by [`Handler`]. Implemented for [`RaceContext`] when connected to racetime.gg.
+#[async_trait]
+pub(crate) trait RaceRoom: Clone + Send + Sync + 'static {
+    fn global_state(&self) -> &Arc<GlobalState>;
+    fn discord(&self) -> &dyn Discord;
+    async fn data(&self) -> RwLockReadGuard<'_, RaceData>;
+    async fn say<M: fmt::Display + Send>(&self, message: M) -> Result<(), Error>;
+    async fn send_message<M: fmt::Display + Send>(&self, message: M, pinned: bool, actions: Vec<(&'static str, ActionButton)>) -> Result<(), Error>;
+    async fn send_direct_message(&self, message: &str, user: &str) -> Result<(), Error>;
+    async fn set_bot_raceinfo(&self, info: &str) -> Result<(), Error>;
+    async fn set_invitational(&self) -> Result<(), Error>;
+    async fn invite_user(&self, user: &str) -> Result<(), Error>;
+    async fn accept_request(&self, user: &str) -> Result<(), Error>;
+    async fn remove_entrant(&self, user: &str) -> Result<(), Error>;
+    async fn add_monitor(&self, user: &str) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl RaceRoom for RaceContext<GlobalState> {
+    fn global_state(&self) -> &Arc<GlobalState> {
+        &self.global_state
+    }
+
+    fn discord(&self) -> &dyn Discord {
+        &self.global_state.discord_ctx
+    }
+
+    async fn data(&self) -> RwLockReadGuard<'_, RaceData> {
+        RaceContext::data(self).await
+    }
+
+    async fn say<M: fmt::Display + Send>(&self, message: M) -> Result<(), Error> {
+        RaceContext::say(self, message.to_string()).await
+    }
+
+    async fn send_message<M: fmt::Display + Send>(&self, message: M, pinned: bool, actions: Vec<(&'static str, ActionButton)>) -> Result<(), Error> {
+        RaceContext::send_message(self, message.to_string(), pinned, actions).await
+    }
+
+    async fn send_direct_message(&self, message: &str, user: &str) -> Result<(), Error> {
+        RaceContext::send_direct_message(self, message, user).await
+    }
+
+    async fn set_bot_raceinfo(&self, info: &str) -> Result<(), Error> {
+        RaceContext::set_bot_raceinfo(self, info).await
+    }
+
+    async fn set_invitational(&self) -> Result<(), Error> {
+        RaceContext::set_invitational(self).await
+    }
+
+    async fn invite_user(&self, user: &str) -> Result<(), Error> {
+        RaceContext::invite_user(self, user).await
+    }
+
+    async fn accept_request(&self, user: &str) -> Result<(), Error> {
+        RaceContext::accept_request(self, user).await
+    }
+
+    async fn remove_entrant(&self, user: &str) -> Result<(), Error> {
+        RaceContext::remove_entrant(self, user).await
+    }
+
+    async fn add_monitor(&self, user: &str) -> Result<(), Error> {
+        RaceContext::add_monitor(self, user).await
+    }
+}
+
+/// Something the handler did in a [`FakeRaceRoom`].
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RoomAction {
+    Message {
+        text: String,
+        pinned: bool,
+    },
+    DirectMessage {
+        text: String,
+        user: String,
+    },
+    BotRaceInfo(String),
+    SetInvitational,
+    Invite(String),
+    AcceptRequest(String),
+    RemoveEntrant(String),
+    AddMonitor(String),
+}
+
+/// Records Discord messages instead of sending them.
+#[cfg(test)]
+#[derive(Default, Clone)]
+pub(crate) struct FakeDiscord {
+    messages: Arc<std::sync::Mutex<Vec<(ChannelId, String)>>>,
+}
+
+#[cfg(test)]
+impl FakeDiscord {
+    pub(crate) fn messages(&self, channel: ChannelId) -> Vec<String> {
+        self.messages.lock().expect("fake Discord poisoned").iter()
+            .filter(|(iter_channel, _)| *iter_channel == channel)
+            .map(|(_, content)| content.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Discord for FakeDiscord {
+    async fn say(&self, channel: ChannelId, content: String) -> serenity::Result<()> {
+        self.messages.lock().expect("fake Discord poisoned").push((channel, content));
+        Ok(())
+    }
+}
+
+/// An in-process race room. Race data only changes when the test calls [`FakeRaceRoom::set_data`], except that [`RaceRoom::set_invitational`] updates the room status.
+#[cfg(test)]
+#[derive(Clone)]
+pub(crate) struct FakeRaceRoom {
+    global_state: Arc<GlobalState>,
+    pub(crate) discord: FakeDiscord,
+    data: Arc<tokio::sync::RwLock<RaceData>>,
+    actions: Arc<std::sync::Mutex<Vec<RoomAction>>>,
+}
+
+#[cfg(test)]
+impl FakeRaceRoom {
+    pub(crate) fn new(global_state: Arc<GlobalState>, data: RaceData) -> Self {
+        Self {
+            discord: FakeDiscord::default(),
+            data: Arc::new(tokio::sync::RwLock::new(data)),
+            actions: Arc::default(),
+            global_state,
+        }
+    }
+
+    /// Replaces the race data as if racetime.gg had sent an update, and returns the previous data.
+    pub(crate) async fn set_data(&self, data: RaceData) -> RaceData {
+        mem::replace(&mut *self.data.write().await, data)
+    }
+
+    pub(crate) fn actions(&self) -> Vec<RoomAction> {
+        self.actions.lock().expect("fake race room poisoned").clone()
+    }
+
+    /// The text of all chat messages sent so far, including pinned ones.
+    pub(crate) fn messages(&self) -> Vec<String> {
+        self.actions().into_iter().filter_map(|action| if let RoomAction::Message { text, .. } = action { Some(text) } else { None }).collect()
+    }
+
+    fn record(&self, action: RoomAction) {
+        self.actions.lock().expect("fake race room poisoned").push(action);
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl RaceRoom for FakeRaceRoom {
+    fn global_state(&self) -> &Arc<GlobalState> {
+        &self.global_state
+    }
+
+    fn discord(&self) -> &dyn Discord {
+        &self.discord
+    }
+
+    async fn data(&self) -> RwLockReadGuard<'_, RaceData> {
+        self.data.read().await
+    }
+
+    async fn say<M: fmt::Display + Send>(&self, message: M) -> Result<(), Error> {
+        self.send_message(message, false, Vec::default()).await
+    }
+
+    async fn send_message<M: fmt::Display + Send>(&self, message: M, pinned: bool, _: Vec<(&'static str, ActionButton)>) -> Result<(), Error> {
+        self.record(RoomAction::Message { text: message.to_string(), pinned });
+        Ok(())
+    }
+
+    async fn send_direct_message(&self, message: &str, user: &str) -> Result<(), Error> {
+        self.record(RoomAction::DirectMessage { text: message.to_owned(), user: user.to_owned() });
+        Ok(())
+    }
+
+    async fn set_bot_raceinfo(&self, info: &str) -> Result<(), Error> {
+        self.record(RoomAction::BotRaceInfo(info.to_owned()));
+        Ok(())
+    }
+
+    async fn set_invitational(&self) -> Result<(), Error> {
+        self.data.write().await.status.value = RaceStatusValue::Invitational;
+        self.record(RoomAction::SetInvitational);
+        Ok(())
+    }
+
+    async fn invite_user(&self, user: &str) -> Result<(), Error> {
+        self.record(RoomAction::Invite(user.to_owned()));
+        Ok(())
+    }
+
+    async fn accept_request(&self, user: &str) -> Result<(), Error> {
+        self.record(RoomAction::AcceptRequest(user.to_owned()));
+        Ok(())
+    }
+
+    async fn remove_entrant(&self, user: &str) -> Result<(), Error> {
+        self.record(RoomAction::RemoveEntrant(user.to_owned()));
+        Ok(())
+    }
+
+    async fn add_monitor(&self, user: &str) -> Result<(), Error> {
+        self.record(RoomAction::AddMonitor(user.to_owned()));
+        Ok(())
+    }
+}
@@ -0,0 +1,324 @@
+//! End-to-end scenarios for official race rooms, run against [`FakeRaceRoom`] and a stub randomizer instead of racetime.gg, Discord, and ootrandomizer.com.
+
+use {
+    std::os::unix::fs::PermissionsExt as _,
+    tempfile::TempDir,
+    crate::{
+        racetime_bot::room::{
+            FakeRaceRoom,
+            RoomAction,
+        },
+        testing::{
+            self,
+            TestDb,
+        },
+    },
+    super::*,
+};
+
+const RESULTS_CHANNEL: ChannelId = ChannelId::new(1001);
+const ORGANIZER_CHANNEL: ChannelId = ChannelId::new(1002);
+
+#[derive(Debug, thiserror::Error)]
+enum TestError {
+    #[error(transparent)] Event(#[from] event::DataError),
+    #[error(transparent)] Io(#[from] io::Error),
+    #[error(transparent)] Json(#[from] serde_json::Error),
+    #[error(transparent)] RaceTime(#[from] Error),
+    #[error(transparent)] Sql(#[from] sqlx::Error),
+    #[error(transparent)] Url(#[from] url::ParseError),
+}
+
+/// A randomizer checkout whose `ootr-cli` writes a fixed patch file and spoiler log.
+fn stub_rando() -> io::Result<TempDir> {
+    let rando_dir = tempfile::tempdir()?;
+    let bin_dir = rando_dir.path().join("target").join("release");
+    std::fs::create_dir_all(&bin_dir)?;
+    let cli_path = bin_dir.join("ootr-cli");
+    std::fs::write(&cli_path, r#"#!/bin/sh
+cat > /dev/null
+mkdir -p Output
+printf 'patch' > Output/TEST_Stub.zpf
+printf '{"file_hash":["Rod","Compass","Key","Map","Heart"]}' > Output/TEST_Stub_Spoiler.json
+echo 'Creating Patch File: TEST_Stub.zpf' >&2
+echo 'Created spoiler log at: TEST_Stub_Spoiler.json' >&2
+"#)?;
+    std::fs::set_permissions(&cli_path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(rando_dir)
+}
+
+async fn global_state(db_pool: PgPool, rando_dir: &TempDir, seed_dir: &TempDir) -> Arc<GlobalState> {
+    let http_client = reqwest::Client::new();
+    Arc::new(GlobalState::new(
+        Arc::default(),
+        ConfigRaceTime { client_id: format!("test"), client_secret: format!("test") },
+        Arc::new(RwLock::new(mpsc::channel(1).0)),
+        db_pool,
+        http_client.clone(),
+        http_client.clone(),
+        String::default(),
+        String::default(),
+        String::default(),
+        Arc::new(ootr_web::ApiClient::new(http_client, String::default(), String::default())),
+        RwFuture::new(future::pending()),
+        Arc::default(),
+        Arc::default(),
+        LocalRando::Fixed {
+            rando_path: rando_dir.path().to_owned(),
+            seed_dir: seed_dir.path().to_owned(),
+        },
+        ConfigSeedFallback::default(),
+        ConfigSeedQueue::default(),
+    ).await)
+}
+
+fn room_path() -> String {
+    format!("/{CATEGORY}/fake-room-0001")
+}
+
+fn user_data(user: &User) -> serde_json::Value {
+    let racetime = user.racetime.as_ref().expect("test user without racetime.gg account");
+    json!({
+        "id": racetime.id,
+        "full_name": racetime.display_name,
+        "name": racetime.display_name,
+        "discriminator": null,
+        "url": format!("/user/{}", racetime.id),
+        "avatar": null,
+        "pronouns": null,
+        "flair": "",
+        "twitch_name": null,
+        "twitch_display_name": null,
+        "twitch_channel": null,
+        "can_moderate": false,
+    })
+}
+
+fn entrant(user: &User, status: &str, finish_time: Option<&str>) -> serde_json::Value {
+    json!({
+        "user": user_data(user),
+        "team": null,
+        "status": { "value": status, "verbose_value": status, "help_text": "" },
+        "finish_time": finish_time,
+        "finished_at": finish_time.map(|_| Utc::now()),
+        "place": null,
+        "place_ordinal": null,
+        "score": null,
+        "score_change": null,
+        "comment": null,
+        "has_comment": false,
+        "stream_live": false,
+        "stream_override": false,
+        "actions": [],
+    })
+}
+
+fn race_data(status: &str, entrants: Vec<serde_json::Value>) -> serde_json::Result<RaceData> {
+    let finished = status == "finished";
+    serde_json::from_value(json!({
+        "version": 1,
+        "name": room_path().trim_start_matches('/'),
+        "slug": "fake-room-0001",
+        "status": { "value": status, "verbose_value": status, "help_text": "" },
+        "url": room_path(),
+        "data_url": format!("{}/data", room_path()),
+        "websocket_url": format!("/ws/race/fake-room-0001"),
+        "websocket_bot_url": format!("/ws/o/bot/fake-room-0001"),
+        "websocket_oauth_url": format!("/ws/o/race/fake-room-0001"),
+        "category": {
+            "name": "Test Category",
+            "short_name": "TEST",
+            "slug": CATEGORY,
+            "url": format!("/{CATEGORY}"),
+            "data_url": format!("/{CATEGORY}/data"),
+            "image": null,
+        },
+        "goal": { "name": Goal::Cc7.as_str(), "custom": false },
+        "info": "",
+        "info_bot": null,
+        "info_user": null,
+        "team_race": false,
+        "entrants_count": entrants.len(),
+        "entrants_count_finished": if finished { entrants.len() } else { 0 },
+        "entrants_count_inactive": 0,
+        "entrants": entrants,
+        "opened_at": Utc::now() - TimeDelta::minutes(30),
+        "start_delay": "P0DT00H00M15.000000S",
+        "started_at": if matches!(status, "in_progress" | "finished") { Some(Utc::now() - TimeDelta::hours(2)) } else { None },
+        "ended_at": if finished { Some(Utc::now()) } else { None },
+        "cancelled_at": null,
+        "unlisted": false,
+        "time_limit": "P1DT00H00M00.000000S",
+        "time_limit_auto_complete": false,
+        "require_even_teams": false,
+        "streaming_required": false,
+        "auto_start": true,
+        "opened_by": null,
+        "opened_by_bot": "Hyrule Town Hall",
+        "monitors": [],
+        "recordable": true,
+        "recorded": false,
+        "recorded_by": null,
+        "disqualify_unready": false,
+        "allow_comments": true,
+        "hide_comments": false,
+        "hide_entrants": false,
+        "chat_restricted": false,
+        "allow_prerace_chat": true,
+        "allow_midrace_chat": true,
+        "allow_non_entrant_chat": true,
+        "chat_message_delay": "P0DT00H00M00.000000S",
+        "bot_meta": {},
+    }))
+}
+
+fn chat_message(user: &User, text: &str) -> serde_json::Result<ChatMessage> {
+    serde_json::from_value(json!({
+        "id": Uuid::new_v4().to_string(),
+        "user": user_data(user),
+        "bot": null,
+        "direct_to": null,
+        "posted_at": Utc::now(),
+        "message": text,
+        "message_plain": text,
+        "highlight": false,
+        "is_dm": false,
+        "is_bot": false,
+        "is_monitor": false,
+        "is_pinned": false,
+        "is_system": false,
+        "delay": "P0DT00H00M00.000000S",
+    }))
+}
+
+/// Splits a chat command the way racetime.gg does before it reaches [`Handler::command_inner`].
+async fn command(handler: &mut Handler, room: &FakeRaceRoom, user: &User, text: &str) -> Result<(), TestError> {
+    let msg = chat_message(user, text)?;
+    let mut words = text.trim_start_matches('!').split_whitespace().map(str::to_owned);
+    let cmd_name = words.next().expect("empty command");
+    handler.command_inner(room, cmd_name, words.collect(), false, false, &msg).await?;
+    Ok(())
+}
+
+struct Scenario {
+    alice: User,
+    bob: User,
+    race_id: Id<Races>,
+}
+
+/// A Standard 7cc race between Alice (high seed) and Bob, starting in 2 minutes so no reminders are scheduled and the seed isn't delayed.
+async fn scenario(db_pool: &PgPool) -> Result<Scenario, TestError> {
+    let mut transaction = db_pool.begin().await?;
+    let event = testing::event(&mut transaction, Series::Standard, "7cc", TeamConfig::Solo).await?;
+    sqlx::query("UPDATE events SET discord_race_results_channel = $1, discord_organizer_channel = $2 WHERE series = $3 AND event = $4")
+        .bind(RESULTS_CHANNEL.get() as i64)
+        .bind(ORGANIZER_CHANNEL.get() as i64)
+        .bind(event.series)
+        .bind(&*event.event)
+        .execute(&mut *transaction).await?;
+    let alice = testing::user(&mut transaction, "Alice").await?;
+    let bob = testing::user(&mut transaction, "Bob").await?;
+    let alice_team = testing::team(&mut transaction, &event, None, Some(1), &[(&alice, event::Role::None)]).await?;
+    let bob_team = testing::team(&mut transaction, &event, None, Some(2), &[(&bob, event::Role::None)]).await?;
+    let mut race = testing::race(&mut transaction, &event, [&alice_team, &bob_team]).await?;
+    race.schedule = RaceSchedule::Live {
+        start: Utc::now() + TimeDelta::minutes(2),
+        end: None,
+        room: Some(format!("https://{}{}", racetime_host(), room_path()).parse()?),
+    };
+    race.draft = Some(Draft {
+        high_seed: alice_team.id,
+        went_first: None,
+        skipped_bans: 0,
+        settings: draft::Picks::default(),
+    });
+    race.save(&mut transaction).await?;
+    transaction.commit().await?;
+    Ok(Scenario { alice, bob, race_id: race.id })
+}
+
+fn racetime_id(user: &User) -> String {
+    user.racetime.as_ref().expect("test user without racetime.gg account").id.clone()
+}
+
+#[tokio::test]
+async fn draft_seed_and_result() -> Result<(), TestError> {
+    let db = TestDb::new().await;
+    let rando_dir = stub_rando()?;
+    let seed_dir = tempfile::tempdir()?;
+    let Scenario { alice, bob, race_id } = scenario(&db.pool).await?;
+    let room = FakeRaceRoom::new(
+        global_state(db.pool.clone(), &rando_dir, &seed_dir).await,
+        race_data("invitational", vec![entrant(&alice, "requested", None)])?,
+    );
+    let mut handler = Handler::new_inner(&room).await?;
+    let actions = room.actions();
+    assert!(actions.contains(&RoomAction::AcceptRequest(racetime_id(&alice))), "entrants who requested to join are accepted");
+    assert!(actions.contains(&RoomAction::Invite(racetime_id(&bob))), "missing entrants are invited");
+    assert!(room.messages().iter().any(|msg| msg.starts_with("Welcome to")));
+    assert_eq!(room.messages().last().map(String::as_str), Some("Alice, you have the higher seed. Choose whether you want to go !first or !second"));
+    command(&mut handler, &room, &bob, "!first").await?;
+    assert_eq!(room.messages().last().map(String::as_str), Some("Sorry Bob, it's not your turn in the settings draft."));
+    command(&mut handler, &room, &alice, "!second").await?;
+    command(&mut handler, &room, &bob, "!ban trials").await?;
+    command(&mut handler, &room, &alice, "!skip").await?;
+    command(&mut handler, &room, &bob, "!pick bridge open").await?;
+    command(&mut handler, &room, &alice, "!pick deku open").await?;
+    command(&mut handler, &room, &alice, "!pick camc off").await?;
+    command(&mut handler, &room, &bob, "!pick cuccos 1").await?;
+    timeout(Duration::from_secs(60), async {
+        while !lock!(@read state = handler.race_state; matches!(*state, RaceState::Rolled(_))) {
+            sleep(Duration::from_millis(100)).await;
+        }
+    }).await.expect("seed wasn't rolled in time");
+    let messages = room.messages();
+    assert!(messages.iter().any(|msg| msg.starts_with("Rolling a seed with ")));
+    assert!(messages.contains(&format!("@entrants Here is your seed: {}/seed/TEST_Stub", base_uri())));
+    assert!(messages.contains(&format!("The spoiler log will be available on the seed page after the race.")));
+    assert!(room.actions().iter().any(|action| matches!(action, RoomAction::BotRaceInfo(_))));
+    let file_stem = sqlx::query_scalar::<_, Option<String>>("SELECT file_stem FROM races WHERE id = $1").bind(race_id).fetch_one(&db.pool).await?;
+    assert_eq!(file_stem.as_deref(), Some("TEST_Stub"));
+    assert!(seed_dir.path().join("TEST_Stub.zpf").exists(), "patch file is moved to the seed directory");
+    assert!(!seed_dir.path().join("TEST_Stub_Spoiler.json").exists(), "spoiler log stays locked until the race is finished");
+    let old_data = room.set_data(race_data("finished", vec![
+        entrant(&alice, "done", Some("P0DT01H30M00.000000S")),
+        entrant(&bob, "done", Some("P0DT01H35M00.000000S")),
+    ])?).await;
+    handler.race_data_inner(&room, old_data).await?;
+    assert!(seed_dir.path().join("TEST_Stub_Spoiler.json").exists(), "spoiler log is unlocked after the race");
+    let [result] = <[_; 1]>::try_from(room.discord.messages(RESULTS_CHANNEL)).expect("expected exactly one result message");
+    assert!(result.contains(" (1:30:00) defeats "), "unexpected result message: {result}");
+    assert!(result.contains(" (1:35:00) <"), "unexpected result message: {result}");
+    assert!(room.discord.messages(ORGANIZER_CHANNEL).is_empty());
+    db.cleanup().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn fpa_call_is_reported_to_organizers() -> Result<(), TestError> {
+    let db = TestDb::new().await;
+    let rando_dir = stub_rando()?;
+    let seed_dir = tempfile::tempdir()?;
+    let Scenario { alice, bob, .. } = scenario(&db.pool).await?;
+    let room = FakeRaceRoom::new(
+        global_state(db.pool.clone(), &rando_dir, &seed_dir).await,
+        race_data("invitational", vec![entrant(&alice, "not_ready", None), entrant(&bob, "not_ready", None)])?,
+    );
+    let mut handler = Handler::new_inner(&room).await?;
+    assert!(room.messages().iter().any(|msg| msg.starts_with("Fair play agreement is active")));
+    command(&mut handler, &room, &alice, "!fpa").await?;
+    assert_eq!(room.messages().last().map(String::as_str), Some("FPA cannot be invoked before the race starts."));
+    room.set_data(race_data("in_progress", vec![entrant(&alice, "in_progress", None), entrant(&bob, "in_progress", None)])?).await;
+    command(&mut handler, &room, &alice, "!fpa").await?;
+    assert!(room.messages().last().is_some_and(|msg| msg.starts_with("@everyone FPA has been invoked by Alice.")));
+    let old_data = room.set_data(race_data("finished", vec![
+        entrant(&alice, "done", Some("P0DT01H30M00.000000S")),
+        entrant(&bob, "done", Some("P0DT01H35M00.000000S")),
+    ])?).await;
+    handler.race_data_inner(&room, old_data).await?;
+    let [report] = <[_; 1]>::try_from(room.discord.messages(ORGANIZER_CHANNEL)).expect("expected exactly one organizer message");
+    assert!(report.starts_with(&format!("race finished with FPA call: <https://{}{}>", racetime_host(), room_path())), "unexpected organizer message: {report}");
+    assert!(room.discord.messages(RESULTS_CHANNEL).is_empty(), "results of races with FPA calls are posted manually");
+    db.cleanup().await;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! Timed background jobs like opening race rooms, prerolling seeds, and importing races.
+//!
+//! Jobs are stored in the database so they survive restarts and can be inspected on the [admin page](jobs).
+//! Jobs for a race are recomputed whenever the race is updated, so changes to its schedule take effect right away. Failed jobs are retried with exponential backoff.
+
+use {
+    sqlx::postgres::PgListener,
+    crate::{
+        api,
+        discord_bot,
+        prelude::*,
+        racetime_bot::{
+            CreateRoomsError,
+            GlobalState,
+            PrepareSeedsError,
+            UnlockSpoilerLogError,
+        },
+    },
+};
+
+/// How often races are imported from external schedules.
+const IMPORT_INTERVAL: TimeDelta = TimeDelta::minutes(1);
+/// How often the prerolled seed cache is checked for missing seeds, in addition to whenever a prerolled seed is used.
+const GOAL_PREROLL_INTERVAL: TimeDelta = TimeDelta::hours(1);
+/// A job which ran successfully but is still due afterwards is retried after this long rather than immediately.
+const RECHECK_INTERVAL: TimeDelta = TimeDelta::seconds(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Sequence, sqlx::Type)]
+#[sqlx(type_name = "job_kind", rename_all = "snake_case")]
+pub(crate) enum JobKind {
+    /// Opens the rooms for all races which are about to start, scheduled for the earliest room opening of each race.
+    OpenRoom,
+    /// Rolls the seed for a race ahead of time.
+    PrerollSeed,
+    /// Fills the cache of prerolled seeds for goals with single settings.
+    PrerollGoalSeeds,
+    /// Sends a reminder or resolves the current step when a race's settings draft is timed.
+    DraftTimer,
+    /// Unlocks the spoiler log of a race whose parts were played outside of a public race room.
+    UnlockSpoilerLog,
+    /// Imports races for events with automatic imports enabled.
+    ImportRaces,
+    /// Reminds the entrants of an upcoming race in its scheduling thread.
+    RaceReminder,
+}
+
+impl JobKind {
+    fn is_per_race(&self) -> bool {
+        match self {
+            Self::OpenRoom | Self::PrerollSeed | Self::DraftTimer | Self::UnlockSpoilerLog | Self::RaceReminder => true,
+            Self::PrerollGoalSeeds | Self::ImportRaces => false,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::OpenRoom => "Open race room",
+            Self::PrerollSeed => "Preroll seed",
+            Self::PrerollGoalSeeds => "Preroll practice seeds",
+            Self::DraftTimer => "Draft time limit",
+            Self::UnlockSpoilerLog => "Unlock spoiler log",
+            Self::ImportRaces => "Import races",
+            Self::RaceReminder => "Race reminder",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)] Cal(#[from] cal::Error),
+    #[error(transparent)] CreateRooms(#[from] CreateRoomsError),
+    #[error(transparent)] Event(#[from] event::Error),
+    #[error(transparent)] EventData(#[from] event::DataError),
+    #[error(transparent)] PrepareSeeds(#[from] PrepareSeedsError),
+    #[error(transparent)] Reminder(#[from] discord_bot::reminder::Error),
+    #[error(transparent)] Sql(#[from] sqlx::Error),
+    #[error(transparent)] UnlockSpoilerLog(#[from] UnlockSpoilerLogError),
+    #[error(transparent)] Wheel(#[from] wheel::Error),
+}
+
+/// Schedules a job which isn't about a specific race, or moves it to the given time if it's already scheduled. Jobs for races are scheduled automatically whenever the race is updated.
+pub(crate) async fn schedule(transaction: &mut Transaction<'_, Postgres>, kind: JobKind, run_at: DateTime<Utc>) -> sqlx::Result<()> {
+    debug_assert!(!kind.is_per_race());
+    sqlx::query!("INSERT INTO scheduled_jobs (kind, run_at) VALUES ($1, $2) ON CONFLICT (kind) WHERE race IS NULL DO UPDATE SET run_at = EXCLUDED.run_at", kind as _, run_at).execute(&mut **transaction).await?;
+    Ok(())
+}
+
+/// Recomputes the jobs for the given race based on its current state.
+///
+/// If `just_ran` is given, that job has just completed, so if it's still due, it's postponed by [`RECHECK_INTERVAL`] instead of running again right away.
+async fn reschedule_race(global_state: &GlobalState, race_id: Id<Races>, just_ran: Option<JobKind>) -> Result<(), Error> {
+    let mut transaction = global_state.db_pool.begin().await?;
+    if !sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM races WHERE id = $1) AS "exists!""#, race_id as _).fetch_one(&mut *transaction).await? {
+        return Ok(()) // jobs are deleted along with the race
+    }
+    let race = Race::from_id(&mut transaction, &global_state.http_client, race_id).await?;
+    let event = race.event(&mut transaction).await?;
+    let now = Utc::now();
+    for kind in all::<JobKind>().filter(JobKind::is_per_race) {
+        let run_at = match kind {
+            JobKind::OpenRoom => race.cal_events().filter_map(|cal_event| cal_event.room_opens_at()).min(),
+            JobKind::PrerollSeed => racetime_bot::needs_preroll(&mut transaction, &race).await?.then_some(now),
+            JobKind::DraftTimer => draft::timer::next_check(&mut transaction, &event, race.id).await?,
+            JobKind::UnlockSpoilerLog => global_state.spoiler_log_unlock_at(&race),
+            JobKind::RaceReminder => discord_bot::reminder::due_at(&mut transaction, &race).await?,
+            JobKind::PrerollGoalSeeds | JobKind::ImportRaces => unreachable!(),
+        };
+        if let Some(mut run_at) = run_at {
+            if just_ran == Some(kind) && run_at <= now {
+                run_at = now + RECHECK_INTERVAL;
+            }
+            // failing jobs keep their backoff unless the race now needs the job sooner
+            sqlx::query!("INSERT INTO scheduled_jobs (kind, race, run_at) VALUES ($1, $2, $3) ON CONFLICT (kind, race) WHERE race IS NOT NULL DO UPDATE SET run_at = CASE WHEN scheduled_jobs.attempts = 0 THEN EXCLUDED.run_at ELSE LEAST(scheduled_jobs.run_at, EXCLUDED.run_at) END", kind as _, race.id as _, run_at).execute(&mut *transaction).await?;
+        } else {
+            sqlx::query!("DELETE FROM scheduled_jobs WHERE kind = $1 AND race = $2 AND started_at IS NULL", kind as _, race.id as _).execute(&mut *transaction).await?;
+        }
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Keeps the scheduler running if the jobs for a race can't be recomputed, e.g. because of a network error while loading the race.
+async fn report_reschedule_error(race: Id<Races>, e: Error) -> wheel::Result<()> {
+    eprintln!("failed to reschedule jobs for race {race}: {e} ({e:?})");
+    wheel::night_report(&format!("{}/error", night_path()), Some(&format!("failed to reschedule jobs for race {race}: {e} ({e:?})"))).await
+}
+
+/// Recomputes the jobs for all races which might need any, e.g. after missing race updates.
+async fn reschedule_all(global_state: &GlobalState) -> Result<(), Error> {
+    let races = sqlx::query_scalar!(r#"SELECT id AS "id: Id<Races>" FROM races WHERE GREATEST(start, async_start1, async_start2, async_start3) > NOW() - INTERVAL '1 day' OR id IN (SELECT race FROM draft_timers)"#).fetch_all(&global_state.db_pool).await?;
+    for race in races {
+        if let Err(e) = reschedule_race(global_state, race, None).await {
+            report_reschedule_error(race, e).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn run_job(global_state: Arc<GlobalState>, config: &Config, kind: JobKind, race: Option<Id<Races>>) -> Result<(), Error> {
+    match kind {
+        JobKind::OpenRoom => racetime_bot::open_rooms(&global_state).await?,
+        JobKind::PrerollSeed => racetime_bot::preroll_race_seed(global_state, race.expect("per-race job without race")).await?,
+        JobKind::PrerollGoalSeeds => racetime_bot::preroll_goal_seeds(global_state).await?,
+        JobKind::DraftTimer => draft::timer::check_race(&global_state.db_pool, &global_state.http_client, &*global_state.discord_ctx.read().await, race.expect("per-race job without race")).await?,
+        JobKind::UnlockSpoilerLog => global_state.unlock_race_spoiler_log(race.expect("per-race job without race")).await?,
+        JobKind::RaceReminder => discord_bot::reminder::send(&global_state.db_pool, &global_state.http_client, &*global_state.discord_ctx.read().await, race.expect("per-race job without race")).await?,
+        JobKind::ImportRaces => cal::auto_import_races(&global_state.db_pool, &global_state.http_client, config, &global_state.discord_ctx, &global_state.new_room_lock).await?,
+    }
+    Ok(())
+}
+
+struct Job {
+    id: i32,
+    kind: JobKind,
+    race: Option<Id<Races>>,
+    run_at: DateTime<Utc>,
+    attempts: i16,
+}
+
+async fn finish_job(global_state: &GlobalState, job: Job, res: Result<(), Error>) -> Result<(), Error> {
+    match res {
+        Ok(()) => if let Some(race) = job.race {
+            sqlx::query!("DELETE FROM scheduled_jobs WHERE id = $1", job.id).execute(&global_state.db_pool).await?;
+            if let Err(e) = reschedule_race(global_state, race, Some(job.kind)).await {
+                report_reschedule_error(race, e).await?;
+            }
+        } else {
+            let next_run = Utc::now() + match job.kind {
+                JobKind::PrerollGoalSeeds => GOAL_PREROLL_INTERVAL,
+                JobKind::ImportRaces => IMPORT_INTERVAL,
+                JobKind::OpenRoom | JobKind::PrerollSeed | JobKind::DraftTimer | JobKind::UnlockSpoilerLog | JobKind::RaceReminder => unreachable!(),
+            };
+            // keep the new time if the job was rescheduled while it was running
+            sqlx::query!("UPDATE scheduled_jobs SET started_at = NULL, attempts = 0, last_error = NULL, run_at = CASE WHEN run_at = $2 THEN $3 ELSE run_at END WHERE id = $1", job.id, job.run_at, next_run).execute(&global_state.db_pool).await?;
+        },
+        Err(e) => {
+            let attempts = job.attempts.saturating_add(1);
+            let retry_at = Utc::now() + TimeDelta::minutes(1 << (attempts - 1).min(6));
+            sqlx::query!("UPDATE scheduled_jobs SET started_at = NULL, attempts = $2, last_error = $3, run_at = $4 WHERE id = $1", job.id, attempts, e.to_string(), retry_at).execute(&global_state.db_pool).await?;
+            let context = if let Some(race) = job.race { format!(" for race {race}") } else { String::default() };
+            eprintln!("scheduled job {:?}{context} failed (attempt {attempts}): {e} ({e:?})", job.kind);
+            if attempts >= 3 {
+                wheel::night_report(&format!("{}/error", night_path()), Some(&format!("scheduled job {:?}{context} failed {attempts} times in a row: {e} ({e:?})", job.kind))).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs jobs when they're due until shutdown.
+pub(crate) async fn run(global_state: Arc<GlobalState>, config: Config, mut shutdown: rocket::Shutdown) -> Result<(), Error> {
+    let mut race_updates = api::subscribe_race_updates();
+    let mut listener = PgListener::connect_with(&global_state.db_pool).await?;
+    listener.listen("scheduled_jobs").await?;
+    // jobs which were running when the server stopped are started over
+    sqlx::query!("UPDATE scheduled_jobs SET started_at = NULL WHERE started_at IS NOT NULL").execute(&global_state.db_pool).await?;
+    reschedule_all(&global_state).await?;
+    let mut transaction = global_state.db_pool.begin().await?;
+    schedule(&mut transaction, JobKind::PrerollGoalSeeds, Utc::now()).await?;
+    schedule(&mut transaction, JobKind::ImportRaces, Utc::now()).await?;
+    transaction.commit().await?;
+    let config = Arc::new(config);
+    let (done_tx, mut done_rx) = mpsc::channel(16);
+    loop {
+        for job in sqlx::query_as!(Job, r#"UPDATE scheduled_jobs SET started_at = NOW() WHERE started_at IS NULL AND run_at <= NOW() RETURNING
+            id,
+            kind AS "kind: JobKind",
+            race AS "race: Id<Races>",
+            run_at,
+            attempts
+        "#).fetch_all(&global_state.db_pool).await? {
+            let global_state = global_state.clone();
+            let config = config.clone();
+            let done_tx = done_tx.clone();
+            // jobs run in the background so e.g. a long seed roll doesn't delay room openings
+            tokio::spawn(async move {
+                let res = run_job(global_state, &config, job.kind, job.race).await;
+                done_tx.send((job, res)).await.allow_unreceived();
+            });
+        }
+        let next_run_at = sqlx::query_scalar!("SELECT MIN(run_at) FROM scheduled_jobs WHERE started_at IS NULL").fetch_one(&global_state.db_pool).await?;
+        let wait = next_run_at.map(|next_run_at| (next_run_at - Utc::now()).to_std().unwrap_or_default());
+        select! {
+            () = &mut shutdown => break,
+            res = listener.recv() => { res?; }
+            res = race_updates.recv() => match res {
+                Ok(notification) => {
+                    let race = Id::from(notification.race);
+                    if let Err(e) = reschedule_race(&global_state, race, None).await {
+                        report_reschedule_error(race, e).await?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("scheduler missed {skipped} race updates, rescheduling all races");
+                    reschedule_all(&global_state).await?;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            Some((job, res)) = done_rx.recv() => finish_job(&global_state, job, res).await?,
+            () = sleep(wait.unwrap_or_default()), if wait.is_some() => {}
+        }
+    }
+    Ok(())
+}
+
+#[rocket::get("/admin/jobs")]
+pub(crate) async fn jobs(pool: &State<PgPool>, me: Option<User>, uri: Origin<'_>) -> PageResult {
+    let mut transaction = pool.begin().await?;
+    let content = if let Some(ref me) = me {
+        if me.is_archivist {
+            let jobs = sqlx::query!(r#"SELECT
+                scheduled_jobs.kind AS "kind: JobKind",
+                scheduled_jobs.race AS "race: Id<Races>",
+                races.series AS "series?: Series",
+                races.event AS "event?",
+                scheduled_jobs.run_at,
+                scheduled_jobs.started_at,
+                scheduled_jobs.attempts,
+                scheduled_jobs.last_error
+            FROM scheduled_jobs LEFT JOIN races ON races.id = scheduled_jobs.race ORDER BY scheduled_jobs.started_at IS NULL, scheduled_jobs.run_at"#).fetch_all(&mut *transaction).await?;
+            html! {
+                p : "Background jobs are scheduled automatically and rescheduled whenever a race is edited. Failed jobs are retried with exponential backoff.";
+                @if jobs.is_empty() {
+                    p : "No jobs scheduled.";
+                } else {
+                    table {
+                        thead {
+                            tr {
+                                th : "Job";
+                                th : "Race";
+                                th : "Status";
+                                th : "Failed attempts";
+                                th : "Last error";
+                            }
+                        }
+                        tbody {
+                            @for job in jobs {
+                                tr {
+                                    td : job.kind.description();
+                                    td {
+                                        @if let (Some(race), Some(series), Some(event)) = (job.race, job.series, job.event) {
+                                            a(href = uri!(cal::edit_race(series, event, race, _))) : race.to_string();
+                                        }
+                                    }
+                                    td {
+                                        @if let Some(started_at) = job.started_at {
+                                            : "Running since ";
+                                            : format_datetime(started_at, DateTimeFormat { long: false, running_text: true });
+                                        } else {
+                                            : "Scheduled for ";
+                                            : format_datetime(job.run_at, DateTimeFormat { long: false, running_text: true });
+                                        }
+                                    }
+                                    td : job.attempts;
+                                    td {
+                                        @if let Some(last_error) = job.last_error {
+                                            code : last_error;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            html! {
+                p : "This page is only available to archivists.";
+            }
+        }
+    } else {
+        html! {
+            p {
+                a(href = uri!(auth::login(Some(uri!(jobs))))) : "Sign in or create a Hyrule Town Hall account";
+                : " to view this page.";
+            }
+        }
+    };
+    page(transaction, &me, &uri, PageStyle::default(), "Scheduled Jobs — Hyrule Town Hall", html! {
+        h1 : "Scheduled jobs";
+        : content;
+    }).await
+}
@@ -205,7 +205,6 @@ impl Data {
                 } else {
                     (self.file_hash, self.password, None, ChestAppearances::random())
                 };
-                //TODO if file_hash.is_none() and a patch file is available, read the file hash from the patched rom?
                 return Ok(ExtraData {
                     spoiler_status: if spoiler_path_exists {
                         if let Some(spoiler_file_name) = spoiler_file_name {
@@ -222,7 +221,6 @@ impl Data {
                 })
             }
         }
-        //TODO if file_hash.is_none() and a patch file is available, read the file hash from the patched rom?
         Ok(ExtraData {
             spoiler_status: SpoilerStatus::NotFound,
             file_hash: self.file_hash,
@@ -233,6 +231,24 @@ impl Data {
     }
 }
 
+impl Data {
+    /// A page where this seed can be viewed or downloaded, if any.
+    pub(crate) fn url(&self, now: DateTime<Utc>) -> Option<Url> {
+        match self.files.as_ref()? {
+            Files::AlttprDoorRando { uuid } => {
+                let mut patcher_url = Url::parse("https://alttprpatch.synack.live/patcher.html").expect("wrong hardcoded URL");
+                patcher_url.query_pairs_mut().append_pair("patch", &format!("https://hth.zeldaspeedruns.com/seed/DR_{uuid}.bps"));
+                Some(patcher_url)
+            }
+            Files::OotrWeb { id, gen_time, .. } if *gen_time > now - WEB_TIMEOUT => Some(format!("https://ootrandomizer.com/seed/get?id={id}").parse().expect("wrong hardcoded URL")),
+            Files::OotrWeb { file_stem, .. } | Files::MidosHouse { file_stem, .. } => Some(format!("{}/seed/{file_stem}", base_uri()).parse().expect("wrong hardcoded URL")),
+            Files::TriforceBlitz { is_dev: true, uuid } => Some(format!("https://dev.triforceblitz.com/seeds/{uuid}").parse().expect("wrong hardcoded URL")),
+            Files::TriforceBlitz { is_dev: false, uuid } => Some(format!("https://www.triforceblitz.com/seed/{uuid}").parse().expect("wrong hardcoded URL")),
+            Files::TfbSotd { ordinal, .. } => Some(format!("https://www.triforceblitz.com/seed/daily/{ordinal}").parse().expect("wrong hardcoded URL")),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum ExtraDataError {
     #[error(transparent)] Json(#[from] serde_json::Error),
@@ -256,6 +272,17 @@ pub(crate) struct ExtraData {
     chests: ChestAppearances,
 }
 
+impl ExtraData {
+    /// The URL of the full spoiler log, if it has been unlocked.
+    pub(crate) fn spoiler_log_url(&self) -> Option<Url> {
+        if let SpoilerStatus::Unlocked(ref spoiler_file_name) = self.spoiler_status {
+            Some(format!("{}/seed/{spoiler_file_name}", base_uri()).parse().expect("wrong hardcoded URL"))
+        } else {
+            None
+        }
+    }
+}
+
 enum SpoilerStatus {
     Unlocked(String),
     Progression,
@@ -1128,3 +1128,23 @@ pub(crate) fn resolve_s5_draft_settings(picks: &draft::Picks) -> seed::Settings
         format!("ice_trap_appearance") => json!("anything"),
     ]
 }
+
+/// The season 3 draft as a [`draft::spec::Spec`].
+///
+/// A team's choice between banning and picking becomes a pick which may be the default option. The `hard_settings_ok`, `mixed-dungeons`, and `mq_dungeons_count` picks are settings outside of every step's pool.
+pub(crate) fn s3_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/fr/draft-3.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
+/// The season 4 draft as a [`draft::spec::Spec`], with the same differences as [`s3_draft_spec`]. Additionally, enemy souls aren't renamed to boss souls if hard settings aren't allowed.
+pub(crate) fn s4_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/fr/draft-4.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
+/// The season 5 draft as a [`draft::spec::Spec`], with the same differences as [`s4_draft_spec`].
+pub(crate) fn s5_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/fr/draft-5.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
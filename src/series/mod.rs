@@ -20,7 +20,6 @@ use {
         },
     },
     crate::prelude::*,
-    chrono::TimeDelta,
 };
 
 pub(crate) mod br;
@@ -39,10 +38,14 @@ pub(crate) mod scrubs;
 pub(crate) mod sgl;
 pub(crate) mod soh;
 pub(crate) mod tfb;
+#[cfg(test)] mod tests;
 pub(crate) mod wttbb;
 pub(crate) mod xkeys;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Sequence)]
+/// A group of events. Metadata like the display name is stored in the `series` table.
+///
+/// Series with custom behavior in code have their own variant, all others are represented as [`Series::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum Series {
     BattleRoyale,
     CoOp,
@@ -62,10 +65,11 @@ pub(crate) enum Series {
     TournoiFrancophone,
     TriforceBlitz,
     WeTryToBeBetter,
+    Custom(CustomSlug),
 }
 
 impl Series {
-    pub(crate) fn slug(&self) -> &'static str {
+    pub(crate) fn slug(&self) -> &str {
         match self {
             Self::BattleRoyale => "ohko",
             Self::CoOp => "coop",
@@ -85,41 +89,40 @@ impl Series {
             Self::TournoiFrancophone => "fr",
             Self::TriforceBlitz => "tfb",
             Self::WeTryToBeBetter => "wttbb",
+            Self::Custom(slug) => slug.as_str(),
         }
     }
 
-    pub(crate) fn display_name(&self) -> &'static str {
-        match self {
-            Self::BattleRoyale => "Battle Royale",
-            Self::CoOp => "Co-op Tournaments",
-            Self::CopaDoBrasil => "Copa do Brasil",
-            Self::Crosskeys => "Crosskeys Tournaments",
-            Self::League => "League",
-            Self::MixedPools => "Mixed Pools Tournaments",
-            Self::Mq => "12 MQ Tournaments",
-            Self::Multiworld => "Multiworld Tournaments",
-            Self::NineDaysOfSaws => "9 Days of SAWS",
-            Self::Pictionary => "Pictionary Spoiler Log Races",
-            Self::Rsl => "Random Settings League",
-            Self::Scrubs => "Scrubs Tournaments",
-            Self::SongsOfHope => "Songs of Hope",
-            Self::SpeedGaming => "SpeedGaming Live",
-            Self::Standard => "Standard Tournaments",
-            Self::TournoiFrancophone => "Tournois Francophones",
-            Self::TriforceBlitz => "Triforce Blitz",
-            Self::WeTryToBeBetter => "WeTryToBeBetter",
-        }
+    /// Returns `None` if this series doesn't exist in the database.
+    pub(crate) async fn display_name(&self, transaction: &mut Transaction<'_, Postgres>) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!("SELECT display_name FROM series WHERE series = $1", *self as _).fetch_optional(&mut **transaction).await
     }
 
-    pub(crate) fn default_race_duration(&self) -> TimeDelta {
-        match self {
-            Self::TriforceBlitz => TimeDelta::hours(2),
-            Self::BattleRoyale | Self::Crosskeys => TimeDelta::hours(2) + TimeDelta::minutes(30),
-            Self::CoOp | Self::MixedPools | Self::Scrubs | Self::SpeedGaming | Self::WeTryToBeBetter => TimeDelta::hours(3),
-            Self::CopaDoBrasil | Self::League | Self::NineDaysOfSaws | Self::SongsOfHope | Self::Standard | Self::TournoiFrancophone => TimeDelta::hours(3) + TimeDelta::minutes(30),
-            Self::Mq | Self::Multiworld | Self::Pictionary => TimeDelta::hours(4),
-            Self::Rsl => TimeDelta::hours(4) + TimeDelta::minutes(30),
-        }
+    /// All series in the database with their display names, sorted by display name.
+    pub(crate) async fn all(transaction: &mut Transaction<'_, Postgres>) -> sqlx::Result<Vec<(Self, String)>> {
+        sqlx::query!(r#"SELECT series AS "series: Self", display_name FROM series ORDER BY display_name"#).fetch(&mut **transaction)
+            .map_ok(|row| (row.series, row.display_name))
+            .try_collect().await
+    }
+}
+
+/// The URL part of a series without a dedicated [`Series`] variant, stored inline so [`Series`] can be [`Copy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct CustomSlug {
+    len: u8,
+    bytes: [u8; 8],
+}
+
+impl CustomSlug {
+    fn new(slug: &str) -> Option<Self> {
+        if slug.is_empty() || slug.len() > 8 || !slug.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) { return None }
+        let mut bytes = [0; 8];
+        bytes[..slug.len()].copy_from_slice(slug.as_bytes());
+        Some(Self { len: slug.len() as u8, bytes })
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..usize::from(self.len)]).expect("series slugs are ASCII")
     }
 }
 
@@ -127,7 +130,27 @@ impl FromStr for Series {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, ()> {
-        all::<Self>().find(|series| series.slug() == s).ok_or(())
+        Ok(match s {
+            "ohko" => Self::BattleRoyale,
+            "coop" => Self::CoOp,
+            "br" => Self::CopaDoBrasil,
+            "xkeys" => Self::Crosskeys,
+            "league" => Self::League,
+            "mp" => Self::MixedPools,
+            "mq" => Self::Mq,
+            "mw" => Self::Multiworld,
+            "9dos" => Self::NineDaysOfSaws,
+            "pic" => Self::Pictionary,
+            "rsl" => Self::Rsl,
+            "scrubs" => Self::Scrubs,
+            "soh" => Self::SongsOfHope,
+            "sgl" => Self::SpeedGaming,
+            "s" => Self::Standard,
+            "fr" => Self::TournoiFrancophone,
+            "tfb" => Self::TriforceBlitz,
+            "wttbb" => Self::WeTryToBeBetter,
+            _ => Self::Custom(CustomSlug::new(s).ok_or(())?),
+        })
     }
 }
 
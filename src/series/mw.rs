@@ -567,6 +567,26 @@ pub(crate) fn resolve_s5_draft_settings(picks: &draft::Picks) -> seed::Settings
     ]
 }
 
+/// The season 3 draft as a [`draft::spec::Spec`]. Top 8 seeding and seeding by qualifier times are approximated by qualifier rank.
+pub(crate) fn s3_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/mw/draft-3.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
+/// The season 4 draft as a [`draft::spec::Spec`].
+///
+/// The `special_csmc` pick is a setting outside of every step's pool.
+pub(crate) fn s4_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/mw/draft-4.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
+/// The season 5 draft as a [`draft::spec::Spec`].
+pub(crate) fn s5_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/mw/draft-5.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
 pub(crate) fn s3_chests(picks: &draft::Picks) -> ChestAppearances {
     static WEIGHTS: LazyLock<HashMap<String, Vec<(ChestAppearances, usize)>>> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/mw/chests-3-6.2.181.json")).expect("failed to parse chest weights")); //TODO update to 6.2.205
 
@@ -155,6 +155,12 @@ pub(crate) fn resolve_s7_draft_settings(picks: &draft::Picks) -> seed::Settings
     settings
 }
 
+/// The season 7 draft as a [`draft::spec::Spec`]. Unlike [`draft::Kind::S7`], it also allows picking a setting's default option.
+pub(crate) fn s7_draft_spec() -> &'static draft::spec::Spec {
+    static SPEC: LazyLock<draft::spec::Spec> = LazyLock::new(|| serde_json::from_str(include_str!("../../assets/event/s/draft-7.json")).expect("failed to parse draft spec"));
+    &SPEC
+}
+
 #[derive(FromStr, Display, PartialEq, Eq, Hash, Sequence)]
 pub(crate) enum WeeklyKind {
     Kokiri,
@@ -98,6 +98,13 @@ impl Player {
     }
 }
 
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CrewMember {
+    discord_id: Option<String>,
+    approved: bool,
+}
+
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Restream {
@@ -105,6 +112,16 @@ pub(crate) struct Restream {
     pub(crate) match2: Option<RestreamMatch>,
     pub(crate) channels: Vec<RestreamChannel>,
     pub(crate) when_countdown: DateTime<Utc>,
+    #[serde(default)]
+    broadcasters: Vec<CrewMember>,
+}
+
+/// How [`Restream::update_race`] changed a race's starting time.
+pub(crate) enum ScheduleChange {
+    Unchanged,
+    /// The race had no starting time before.
+    Scheduled,
+    Rescheduled,
 }
 
 impl Restream {
@@ -112,11 +129,16 @@ impl Restream {
         self.match1.iter().chain(&self.match2)
     }
 
-    pub(crate) fn update_race(&self, race: &mut Race, id: i64) -> Result<(), url::ParseError> {
+    pub(crate) async fn update_race(&self, transaction: &mut Transaction<'_, Postgres>, race: &mut Race, id: i64) -> Result<ScheduleChange, cal::Error> {
+        let mut change = ScheduleChange::Unchanged;
         if !race.cal_events().any(|cal_event| cal_event.room().is_some()) { // don't mess with starting time if room already open
             assert!(matches!(mem::replace(&mut race.source, cal::Source::SpeedGaming { id }), cal::Source::Manual | cal::Source::SpeedGaming { id: _ }));
+            change = match race.schedule {
+                RaceSchedule::Live { start, .. } if start == self.when_countdown => ScheduleChange::Unchanged,
+                RaceSchedule::Unscheduled => ScheduleChange::Scheduled,
+                RaceSchedule::Live { .. } | RaceSchedule::Async { .. } => ScheduleChange::Rescheduled,
+            };
             race.schedule.set_live_start(self.when_countdown);
-            //TODO if schedule changed, post notice in scheduling thread, open room if short notice
         }
         if !race.schedule_locked {
             for channel in &self.channels {
@@ -124,10 +146,20 @@ impl Restream {
                     let video_url = Url::parse(&format!("https://twitch.tv/{}", channel.slug))?;
                     entry.insert(video_url);
                 }
-                //TODO register restreamer, if any
+            }
+            if_chain! {
+                // SpeedGaming doesn't say which broadcaster handles which channel, so only register unambiguous restreamers
+                if let Ok(channel) = self.channels.iter().exactly_one();
+                if let Ok(broadcaster) = self.broadcasters.iter().filter(|broadcaster| broadcaster.approved).exactly_one();
+                if let Some(discord_id) = broadcaster.discord_id.as_deref().and_then(|discord_id| discord_id.parse().ok());
+                if let Some(user) = User::from_discord(&mut **transaction, UserId::new(discord_id)).await?;
+                if let Some(racetime) = user.racetime;
+                then {
+                    race.restreamers.entry(channel.language).or_insert(racetime.id);
+                }
             }
         }
-        Ok(())
+        Ok(change)
     }
 }
 
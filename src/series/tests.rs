@@ -0,0 +1,211 @@
+use crate::prelude::*;
+
+fn picks(picks: &[(&'static str, &'static str)]) -> draft::Picks {
+    picks.iter().map(|&(setting, value)| (Cow::Borrowed(setting), Cow::Borrowed(value))).collect()
+}
+
+#[test]
+fn s7_base_settings() {
+    let settings = s::resolve_s7_draft_settings(&draft::Picks::default());
+    assert_eq!(settings["user_message"], json!("S7 Tournament"));
+    assert_eq!(settings["trials"], json!(0));
+    assert_eq!(settings["open_forest"], json!("closed_deku"));
+    assert!(!settings.contains_key("tokensanity"));
+    assert!(!settings["allowed_tricks"].as_array().unwrap().contains(&json!("logic_dc_scarecrow_gs")));
+    assert_eq!(s::display_s7_draft_picks(&draft::Picks::default()), "base settings");
+}
+
+#[test]
+fn s7_bans_keep_base_settings() {
+    let bans = picks(&[("trials", "default"), ("deku", "default")]);
+    assert_eq!(s::resolve_s7_draft_settings(&bans), s::resolve_s7_draft_settings(&draft::Picks::default()));
+    assert_eq!(s::display_s7_draft_picks(&bans), "base settings");
+}
+
+#[test]
+fn s7_picks() {
+    let settings = s::resolve_s7_draft_settings(&picks(&[("trials", "on"), ("keys", "anywhere"), ("starting_age", "child")]));
+    assert_eq!(settings["trials"], json!(3));
+    assert_eq!(settings["shuffle_smallkeys"], json!("keysanity"));
+    assert_eq!(settings["key_rings_choice"], json!("all"));
+    assert_eq!(settings["keyring_give_bk"], json!(true));
+    assert_eq!(settings["starting_age"], json!("child"));
+}
+
+#[test]
+fn s7_dungeon_er_allows_scarecrow_trick() {
+    let settings = s::resolve_s7_draft_settings(&picks(&[("dungeons", "on")]));
+    assert_eq!(settings["shuffle_dungeon_entrances"], json!("simple"));
+    assert!(settings["allowed_tricks"].as_array().unwrap().contains(&json!("logic_dc_scarecrow_gs")));
+}
+
+#[test]
+fn s7_tokensanity_combines() {
+    assert_eq!(s::resolve_s7_draft_settings(&picks(&[("ow_tokens", "on")]))["tokensanity"], json!("overworld"));
+    assert_eq!(s::resolve_s7_draft_settings(&picks(&[("dungeon_tokens", "on")]))["tokensanity"], json!("dungeons"));
+    assert_eq!(s::resolve_s7_draft_settings(&picks(&[("ow_tokens", "on"), ("dungeon_tokens", "on")]))["tokensanity"], json!("all"));
+}
+
+#[test]
+#[should_panic = "unknown setting in draft picks"]
+fn s7_unknown_setting() {
+    s::resolve_s7_draft_settings(&picks(&[("not_a_setting", "on")]));
+}
+
+#[test]
+fn mw_s5_defaults() {
+    let settings = mw::resolve_s5_draft_settings(&draft::Picks::default());
+    assert_eq!(settings["world_count"], json!(3));
+    assert_eq!(settings["triforce_hunt"], json!(false));
+    assert_eq!(settings["bridge"], json!("medallions"));
+    assert_eq!(settings["shuffle_ganon_bosskey"], json!("medallions"));
+    assert_eq!(settings["tokensanity"], json!("dungeons"));
+    assert_eq!(settings["key_rings_choice"], json!("off"));
+    assert_eq!(settings["hint_dist"], json!("mw_path"));
+}
+
+#[test]
+fn mw_s5_picks() {
+    let settings = mw::resolve_s5_draft_settings(&picks(&[("gbk", "th"), ("smallkeys", "regional"), ("spawn", "random"), ("hints", "woth")]));
+    assert_eq!(settings["triforce_hunt"], json!(true));
+    assert_eq!(settings["shuffle_smallkeys"], json!("regional"));
+    assert_eq!(settings["key_rings_choice"], json!("all"));
+    assert_eq!(settings["starting_age"], json!("random"));
+    assert_eq!(settings["spawn_positions"], json!(["child", "adult"]));
+    assert_eq!(settings["hint_dist"], json!("mw_woth"));
+    let settings = mw::resolve_s5_draft_settings(&picks(&[("gbk", "stones")]));
+    assert_eq!(settings["triforce_hunt"], json!(false));
+    assert_eq!(settings["shuffle_ganon_bosskey"], json!("stones"));
+}
+
+#[test]
+fn mw_every_setting_resolves() {
+    // picking any non-default option on its own must change the resulting settings
+    for (resolve, settings) in [
+        (mw::resolve_s3_draft_settings as fn(&draft::Picks) -> seed::Settings, mw::S3_SETTINGS),
+        (mw::resolve_s4_draft_settings, mw::S4_SETTINGS),
+        (mw::resolve_s5_draft_settings, mw::S5_SETTINGS),
+    ] {
+        let base = resolve(&draft::Picks::default());
+        assert_eq!(base, resolve(&settings.iter().map(|setting| (Cow::Borrowed(setting.name), Cow::Borrowed(setting.default))).collect()), "explicit defaults must match base settings");
+        for setting in settings {
+            for &(value, _) in setting.other {
+                assert_ne!(resolve(&picks(&[(setting.name, value)])), base, "{}={value} has no effect", setting.name);
+            }
+        }
+    }
+}
+
+#[test]
+fn fr_s5_defaults() {
+    let settings = fr::resolve_s5_draft_settings(&draft::Picks::default());
+    assert_eq!(settings["user_message"], json!("Tournoi Francophone Saison 5"));
+    assert_eq!(settings["triforce_hunt"], json!(false));
+    assert_eq!(settings["bridge"], json!("medallions"));
+    assert_eq!(settings["bridge_medallions"], json!(6));
+    assert_eq!(settings["trials_random"], json!(false));
+    assert_eq!(settings["mq_dungeons_count"], json!(0));
+}
+
+#[test]
+fn fr_s5_bridge_and_triforce_count() {
+    let settings = fr::resolve_s5_draft_settings(&picks(&[("bridge", "2stones")]));
+    assert_eq!(settings["bridge"], json!("stones"));
+    assert_eq!(settings["bridge_stones"], json!(2));
+    assert_eq!(settings["shuffle_ganon_bosskey"], json!("dungeons"));
+    let settings = fr::resolve_s5_draft_settings(&picks(&[("th", "on"), ("shops", "4")]));
+    assert_eq!(settings["triforce_hunt"], json!(true));
+    assert_eq!(settings["triforce_count_per_world"], json!(40));
+    assert_eq!(settings["triforce_goal_per_world"], json!(27));
+    let settings = fr::resolve_s5_draft_settings(&picks(&[("th", "on"), ("pots", "all"), ("crates", "all")]));
+    assert_eq!(settings["triforce_count_per_world"], json!(45), "triforce count is clamped");
+    let settings = fr::resolve_s5_draft_settings(&picks(&[("th", "on"), ("souls", "all-anywhere"), ("1major", "on")]));
+    assert_eq!(settings["triforce_count_per_world"], json!(1), "triforce count is clamped");
+    assert_eq!(settings["triforce_goal_per_world"], json!(1));
+}
+
+#[test]
+fn fr_every_setting_resolves() {
+    for (resolve, settings) in [
+        (fr::resolve_s3_draft_settings as fn(&draft::Picks) -> seed::Settings, &fr::S3_SETTINGS[..]),
+        (fr::resolve_s4_draft_settings, &fr::S4_SETTINGS[..]),
+        (fr::resolve_s5_draft_settings, &fr::S5_SETTINGS[..]),
+    ] {
+        let base = resolve(&draft::Picks::default());
+        assert_eq!(base, resolve(&settings.iter().map(|setting| (Cow::Borrowed(setting.name), Cow::Borrowed(setting.default))).collect()), "explicit defaults must match base settings");
+        for setting in settings {
+            for &(value, _, _) in setting.other {
+                resolve(&picks(&[(setting.name, value)]));
+            }
+        }
+    }
+}
+
+/// Checks that a draft spec resolves to the same settings as the legacy draft for no picks and for every pick or pair of picks, including bans.
+fn assert_spec_matches(spec: &'static draft::spec::Spec, legacy: fn(&draft::Picks) -> seed::Settings) {
+    spec.validate().expect("invalid draft spec");
+    let options = spec.settings.iter()
+        .flat_map(|setting| iter::once(&setting.default).chain(&setting.other).map(move |option| (&*setting.name, &*option.name)))
+        .collect_vec();
+    let combinations = iter::once(Vec::default())
+        .chain(options.iter().map(|&option| vec![option]))
+        .chain(options.iter().tuple_combinations().filter(|((setting1, _), (setting2, _))| setting1 != setting2).map(|(&option1, &option2)| vec![option1, option2]));
+    for combination in combinations {
+        let picks = combination.into_iter().map(|(setting, option)| (Cow::Borrowed(setting), Cow::Borrowed(option))).collect::<draft::Picks>();
+        assert_eq!(spec.resolve(&picks), legacy(&picks), "picks: {picks:?}");
+    }
+}
+
+#[test]
+fn s7_spec_matches_legacy_draft() {
+    assert_spec_matches(s::s7_draft_spec(), s::resolve_s7_draft_settings);
+}
+
+#[test]
+fn mw_specs_match_legacy_drafts() {
+    assert_spec_matches(mw::s3_draft_spec(), mw::resolve_s3_draft_settings);
+    assert_spec_matches(mw::s4_draft_spec(), mw::resolve_s4_draft_settings);
+    assert_spec_matches(mw::s5_draft_spec(), mw::resolve_s5_draft_settings);
+}
+
+#[test]
+fn fr_specs_match_legacy_drafts() {
+    assert_spec_matches(fr::s3_draft_spec(), fr::resolve_s3_draft_settings);
+    assert_spec_matches(fr::s4_draft_spec(), fr::resolve_s4_draft_settings);
+    assert_spec_matches(fr::s5_draft_spec(), fr::resolve_s5_draft_settings);
+}
+
+#[test]
+fn fr_spec_draft_restrictions() {
+    let spec = fr::s5_draft_spec();
+    let (ban, pick) = (&spec.steps[0], &spec.steps[2]);
+    let no_hard = picks(&[("hard_settings_ok", "no")]);
+    assert!(spec.ban_settings(ban, &no_hard).get("keysanity").is_none(), "settings with only hard options can't be banned without hard settings");
+    let skulls = spec.draft_settings(pick, &no_hard).get("skulls").expect("skulls has options which aren't hard");
+    assert_eq!(skulls.options.iter().map(|option| option.name).collect_vec(), ["off", "dungeons"]);
+    let hard = picks(&[("hard_settings_ok", "ok")]);
+    assert!(spec.ban_settings(ban, &hard).get("keysanity").is_some());
+    assert_eq!(spec.draft_settings(pick, &hard).get("skulls").expect("skulls is available").options.len(), 4);
+    assert!(spec.ban_settings(ban, &picks(&[("hard_settings_ok", "ok"), ("keysanity", "on")])).get("keysy").is_none(), "keysy and keysanity are exclusive");
+    assert!(spec.ban_settings(ban, &picks(&[("hard_settings_ok", "ok"), ("keysanity", "off")])).get("keysy").is_some(), "a ban doesn't exclude other settings");
+    assert!(spec.ban_settings(ban, &picks(&[("1major", "on")])).get("th").is_none());
+    assert_eq!(spec.pick_count(&hard), 0, "hard_settings_ok isn't part of the draft");
+}
+
+#[test]
+fn series_slugs_round_trip() {
+    for series in [Series::Standard, Series::NineDaysOfSaws, Series::WeTryToBeBetter] {
+        assert_eq!(series.slug().parse(), Ok(series));
+    }
+    let custom = "newcomm".parse::<Series>().expect("valid custom series slug");
+    assert!(matches!(custom, Series::Custom(_)));
+    assert_eq!(custom.slug(), "newcomm");
+}
+
+#[test]
+fn invalid_series_slugs() {
+    assert_eq!("".parse::<Series>(), Err(()));
+    assert_eq!("toolongslug".parse::<Series>(), Err(()));
+    assert_eq!("Upper".parse::<Series>(), Err(()));
+    assert_eq!("a/b".parse::<Series>(), Err(()));
+}
@@ -133,6 +133,11 @@ impl Team {
         Ok(members)
     }
 
+    /// The tournament-specific participant ID of this team, if it's in a Challonge bracket.
+    pub(crate) async fn challonge_id(&self, transaction: &mut Transaction<'_, Postgres>) -> sqlx::Result<Option<String>> {
+        sqlx::query_scalar!("SELECT challonge_id FROM teams WHERE id = $1", self.id as _).fetch_one(&mut **transaction).await
+    }
+
     pub(crate) async fn members_roles(&self, transaction: &mut Transaction<'_, Postgres>) -> sqlx::Result<Vec<(User, Role)>> {
         let rows = self.member_ids_roles(&mut *transaction).await?;
         let mut members = Vec::with_capacity(rows.len());
@@ -0,0 +1,140 @@
+//! Helpers for tests which need a database.
+//!
+//! Each [`TestDb`] is a new PostgreSQL database built from the files in `migrations/`, so tests can run in parallel without affecting each other or a development database.
+//! Connection parameters are read from the standard `PGHOST`, `PGPORT`, `PGUSER` and `PGPASSWORD` environment variables, and the user must be allowed to create databases.
+
+use {
+    sqlx::{
+        ConnectOptions as _,
+        postgres::{
+            PgConnectOptions,
+            PgPoolOptions,
+        },
+    },
+    crate::{
+        cal::Source,
+        event::Role,
+        prelude::*,
+    },
+};
+
+/// Migrations whose changes were already included when `000_init.sql` was dumped, so applying them again to a new database would fail.
+const ALREADY_IN_INIT_DUMP: &[&str] = &["001_add_role_management.sql"];
+
+pub(crate) struct TestDb {
+    name: String,
+    admin_options: PgConnectOptions,
+    pub(crate) pool: PgPool,
+}
+
+impl TestDb {
+    pub(crate) async fn new() -> Self {
+        let admin_options = PgConnectOptions::new().database("postgres");
+        let name = format!("hth_test_{}", Uuid::new_v4().simple());
+        let mut admin = admin_options.connect().await.expect("failed to connect to PostgreSQL, check the PGHOST, PGPORT, PGUSER, and PGPASSWORD environment variables");
+        sqlx::raw_sql(&format!("CREATE DATABASE {name}")).execute(&mut admin).await.expect("failed to create test database");
+        let options = admin_options.clone().database(&name);
+        let mut conn = options.connect().await.expect("failed to connect to test database");
+        let mut migrations = std::fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations")).expect("failed to list migrations")
+            .map(|entry| entry.expect("failed to list migrations").path())
+            .collect_vec();
+        migrations.sort_unstable();
+        for path in migrations {
+            if ALREADY_IN_INIT_DUMP.iter().any(|&name| path.file_name().is_some_and(|file_name| file_name == name)) { continue }
+            let sql = std::fs::read_to_string(&path).expect("failed to read migration");
+            // 000_init.sql is a database dump which clears the search path for the remainder of the session
+            sqlx::raw_sql("RESET ALL").execute(&mut conn).await.expect("failed to reset session settings");
+            sqlx::raw_sql(&strip_ownership(&sql)).execute(&mut conn).await.unwrap_or_else(|e| panic!("failed to apply migration {}: {e}", path.display()));
+        }
+        let pool = PgPoolOptions::default().max_connections(4).connect_with(options).await.expect("failed to connect to test database");
+        Self { name, admin_options, pool }
+    }
+
+    /// Drops the database. Databases of tests which panic are left behind for inspection.
+    pub(crate) async fn cleanup(self) {
+        self.pool.close().await;
+        let mut admin = self.admin_options.connect().await.expect("failed to connect to PostgreSQL");
+        sqlx::raw_sql(&format!("DROP DATABASE {} WITH (FORCE)", self.name)).execute(&mut admin).await.expect("failed to drop test database");
+    }
+}
+
+/// The migrations assign ownership to the production database roles, which don't necessarily exist on the test server.
+fn strip_ownership(sql: &str) -> String {
+    sql.lines()
+        .filter(|line| !(line.starts_with("ALTER ") && line.contains(" OWNER TO ")) && !line.starts_with("GRANT "))
+        .join("\n")
+}
+
+pub(crate) async fn event(transaction: &mut Transaction<'_, Postgres>, series: Series, event: &str, team_config: TeamConfig) -> Result<event::Data<'static>, event::DataError> {
+    sqlx::query("INSERT INTO events (series, event, display_name, team_config, listed) VALUES ($1, $2, $3, $4, TRUE)")
+        .bind(series)
+        .bind(event)
+        .bind(format!("Test Event {event}"))
+        .bind(team_config)
+        .execute(&mut **transaction).await?;
+    Ok(event::Data::new(transaction, series, event.to_owned()).await?.expect("just inserted"))
+}
+
+pub(crate) async fn user(transaction: &mut Transaction<'_, Postgres>, racetime_display_name: &str) -> sqlx::Result<User> {
+    let id = Id::<Users>::new(transaction).await?;
+    sqlx::query("INSERT INTO users (id, racetime_id, racetime_display_name) VALUES ($1, $2, $3)")
+        .bind(id)
+        .bind(format!("test{id}"))
+        .bind(racetime_display_name)
+        .execute(&mut **transaction).await?;
+    Ok(User::from_id(&mut **transaction, id).await?.expect("just inserted"))
+}
+
+/// A team with confirmed members, optionally named and seeded.
+pub(crate) async fn team(transaction: &mut Transaction<'_, Postgres>, event: &event::Data<'_>, name: Option<&str>, qualifier_rank: Option<i16>, members: &[(&User, Role)]) -> sqlx::Result<Team> {
+    let id = Id::<Teams>::new(transaction).await?;
+    sqlx::query("INSERT INTO teams (id, series, event, name, qualifier_rank) VALUES ($1, $2, $3, $4, $5)")
+        .bind(id)
+        .bind(event.series)
+        .bind(&*event.event)
+        .bind(name)
+        .bind(qualifier_rank)
+        .execute(&mut **transaction).await?;
+    for &(member, role) in members {
+        sqlx::query("INSERT INTO team_members (team, member, status, role) VALUES ($1, $2, 'confirmed', $3)")
+            .bind(id)
+            .bind(member.id)
+            .bind(role)
+            .execute(&mut **transaction).await?;
+    }
+    Ok(Team::from_id(transaction, id).await?.expect("just inserted"))
+}
+
+/// An unscheduled race between two teams which hasn't been saved yet, so tests can adjust it first.
+pub(crate) async fn race(transaction: &mut Transaction<'_, Postgres>, event: &event::Data<'_>, [team1, team2]: [&Team; 2]) -> sqlx::Result<Race> {
+    Ok(Race {
+        id: Id::new(transaction).await?,
+        series: event.series,
+        event: event.event.to_string(),
+        source: Source::Manual,
+        entrants: Entrants::Two([
+            Entrant::MidosHouseTeam(team1.clone()),
+            Entrant::MidosHouseTeam(team2.clone()),
+        ]),
+        phase: None,
+        round: None,
+        game: None,
+        scheduling_thread: None,
+        schedule: RaceSchedule::Unscheduled,
+        schedule_updated_at: None,
+        fpa_invoked: false,
+        breaks_used: false,
+        draft: None,
+        seed: seed::Data::default(),
+        video_urls: HashMap::default(),
+        restreamers: HashMap::default(),
+        last_edited_by: None,
+        last_edited_at: None,
+        ignored: false,
+        schedule_locked: false,
+        notified: false,
+        async_notified_1: false,
+        async_notified_2: false,
+        async_notified_3: false,
+    })
+}
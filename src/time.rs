@@ -121,6 +121,11 @@ pub(crate) fn parse_duration(mut s: &str, default_unit: DurationUnit) -> Option<
     Some(duration)
 }
 
+/// Parses the value of a `date` input field as midnight UTC.
+pub(crate) fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok().map(|date| date.and_time(NaiveTime::MIN).and_utc())
+}
+
 pub(crate) fn unparse_duration(duration: Duration) -> String {
     let mut buf = String::default();
     let secs = duration.as_secs();
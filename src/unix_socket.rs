@@ -15,7 +15,9 @@ use {
         racetime_bot::{
             CleanShutdownUpdate,
             Goal,
+            generator::SeedRequest,
             PrerollMode,
+            queue::Priority,
             RollError,
             SeedCommandParseResult,
             SeedRollUpdate,
@@ -32,6 +34,8 @@ fn json_arg(arg: &str) -> serde_json::Result<Json> {
 
 #[derive(clap::Subcommand, Protocol)]
 pub(crate) enum ClientMessage {
+    /// Read missing file hashes of locally rolled race seeds from their patch files
+    BackfillFileHashes,
     CleanupRoles {
         guild_id: GuildId,
     },
@@ -91,6 +95,10 @@ pub(crate) async fn listen(mut shutdown: rocket::Shutdown, clean_shutdown: Arc<M
                 tokio::spawn(async move {
                     loop {
                         match ClientMessage::read(&mut sock).await {
+                            Ok(ClientMessage::BackfillFileHashes) => {
+                                let num_updated = global_state.backfill_file_hashes().await.expect("error backfilling file hashes");
+                                num_updated.write(&mut sock).await.expect("error writing to UNIX socket");
+                            }
                             Ok(ClientMessage::CleanupRoles { guild_id }) => {
                                 let discord_ctx = global_state.discord_ctx.read().await;
                                 let mut transaction = global_state.db_pool.begin().await.expect("error cleaning up Discord roles");
@@ -147,7 +155,12 @@ pub(crate) async fn listen(mut shutdown: rocket::Shutdown, clean_shutdown: Arc<M
                                 break
                             }
                             Ok(ClientMessage::Roll { version, settings, spoiler_log }) => if let Json::Object(settings) = settings {
-                                let mut rx = global_state.clone().roll_seed(PrerollMode::Medium, true, None, VersionedBranch::Pinned { version }, settings, if spoiler_log { UnlockSpoilerLog::Now } else { UnlockSpoilerLog::Never });
+                                let mut rx = global_state.clone().roll(None, None, Priority::Practice, SeedRequest::Ootr {
+                                    preroll: PrerollMode::Medium,
+                                    allow_web: true,
+                                    version: VersionedBranch::Pinned { version },
+                                    settings,
+                                }, if spoiler_log { UnlockSpoilerLog::Now } else { UnlockSpoilerLog::Never }).await;
                                 loop {
                                     let update = rx.recv().await;
                                     update.write(&mut sock).await.expect("error writing to UNIX socket");
@@ -165,7 +178,7 @@ pub(crate) async fn listen(mut shutdown: rocket::Shutdown, clean_shutdown: Arc<M
                                     rsl::VersionedPreset::new_unversioned(&branch, preset.as_deref())
                                 };
                                 if let Ok(preset) = preset {
-                                    let mut rx = global_state.clone().roll_rsl_seed(None, preset, worlds, if spoiler_log { UnlockSpoilerLog::Now } else { UnlockSpoilerLog::Never });
+                                    let mut rx = global_state.clone().roll(None, None, Priority::Practice, SeedRequest::Rsl { preset, world_count: worlds }, if spoiler_log { UnlockSpoilerLog::Now } else { UnlockSpoilerLog::Never }).await;
                                     loop {
                                         let update = rx.recv().await;
                                         update.write(&mut sock).await.expect("error writing to UNIX socket");
@@ -186,26 +199,32 @@ pub(crate) async fn listen(mut shutdown: rocket::Shutdown, clean_shutdown: Arc<M
                                         break
                                     }
                                 };
+                                let priority = if is_official { Priority::Official } else { Priority::Practice };
                                 let mut rx = match goal.parse_seed_command(&mut transaction, &global_state, is_official, spoiler_seed, no_password, &args).await {
                                     Ok(SeedCommandParseResult::Regular { mut settings, unlock_spoiler_log, description, .. }) => {
                                         if no_password {
                                             settings.remove("password_lock");
                                         }
                                         Some(SeedRollUpdate::Message(description)).write(&mut sock).await.expect("error writing to UNIX socket");
-                                        global_state.clone().roll_seed(goal.preroll_seeds(None /*TODO replace is_official parameter with optional series and event */), !no_web, None, goal.rando_version(None /*TODO replace is_official parameter with optional series and event */), settings, unlock_spoiler_log)
+                                        global_state.clone().roll(None, None, priority, SeedRequest::Ootr {
+                                            preroll: goal.preroll_seeds(None /*TODO replace is_official parameter with optional series and event */),
+                                            allow_web: !no_web,
+                                            version: goal.rando_version(None /*TODO replace is_official parameter with optional series and event */),
+                                            settings,
+                                        }, unlock_spoiler_log).await
                                     }
                                     Ok(SeedCommandParseResult::Alttpr) => unimplemented!(),
                                     Ok(SeedCommandParseResult::Rsl { preset, world_count, unlock_spoiler_log, description, .. }) => {
                                         Some(SeedRollUpdate::Message(description)).write(&mut sock).await.expect("error writing to UNIX socket");
-                                        global_state.clone().roll_rsl_seed(None, preset, world_count, unlock_spoiler_log)
+                                        global_state.clone().roll(None, None, priority, SeedRequest::Rsl { preset, world_count }, unlock_spoiler_log).await
                                     }
                                     Ok(SeedCommandParseResult::Tfb { version, unlock_spoiler_log, description, .. }) => {
                                         Some(SeedRollUpdate::Message(description)).write(&mut sock).await.expect("error writing to UNIX socket");
-                                        global_state.clone().roll_tfb_seed(None, version, None, unlock_spoiler_log)
+                                        global_state.clone().roll(None, None, priority, SeedRequest::TriforceBlitz { version }, unlock_spoiler_log).await
                                     }
                                     Ok(SeedCommandParseResult::TfbDev { coop, unlock_spoiler_log, description, .. }) => {
                                         Some(SeedRollUpdate::Message(description)).write(&mut sock).await.expect("error writing to UNIX socket");
-                                        global_state.clone().roll_tfb_dev_seed(None, coop, None, unlock_spoiler_log)
+                                        global_state.clone().roll(None, None, priority, SeedRequest::TriforceBlitzDev { coop }, unlock_spoiler_log).await
                                     }
                                     Ok(SeedCommandParseResult::QueueExisting { data, description, .. }) => {
                                         Some(SeedRollUpdate::Message(description)).write(&mut sock).await.expect("error writing to UNIX socket");
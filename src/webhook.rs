@@ -0,0 +1,368 @@
+//! Outgoing webhooks that notify external services (e.g. a restream team's bot) about an event's lifecycle.
+//!
+//! Deliveries are stored in the database before being sent, so they survive restarts and can be retried.
+//! Each request body is signed with HMAC-SHA256 using the webhook's shared secret,
+//! and the signature is sent as `sha256=<hex>` in the `X-MidosHouse-Signature-256` header.
+//!
+//! Since webhook URLs are chosen by organizers, notifications are only sent to public addresses, so they can't be used to reach services on the server's own network.
+
+use {
+    std::net::{
+        IpAddr,
+        Ipv4Addr,
+        Ipv6Addr,
+        SocketAddr,
+    },
+    hmac::{
+        Hmac,
+        Mac as _,
+    },
+    sha2::Sha256,
+    sqlx::types::Json,
+    crate::{
+        api::{
+            self,
+            RaceUpdateKind,
+            RaceUpdateNotification,
+        },
+        id::Webhooks,
+        prelude::*,
+    },
+};
+
+#[cfg(test)] mod tests;
+
+/// Failed deliveries are retried with exponential backoff (1 minute, 2 minutes, 4 minutes, …) until this many attempts have been made.
+const MAX_ATTEMPTS: i16 = 8;
+/// How long an organizer's endpoint has to respond before the delivery counts as failed.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Sequence, sqlx::Type, FromFormField)]
+#[sqlx(type_name = "webhook_event_type", rename_all = "snake_case")]
+pub(crate) enum EventType {
+    #[field(value = "race_scheduled")]
+    RaceScheduled,
+    #[field(value = "room_opened")]
+    RoomOpened,
+    #[field(value = "seed_rolled")]
+    SeedRolled,
+    #[field(value = "race_finished")]
+    RaceFinished,
+    #[field(value = "signup_confirmed")]
+    SignupConfirmed,
+    #[field(value = "team_resigned")]
+    TeamResigned,
+    /// Sent when an organizer clicks “Send test payload”. Always delivered regardless of the webhook's selected event types.
+    #[field(value = "test")]
+    Test,
+}
+
+impl EventType {
+    /// The value of the `type` field in the payload and of the `X-MidosHouse-Event` header.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::RaceScheduled => "race_scheduled",
+            Self::RoomOpened => "room_opened",
+            Self::SeedRolled => "seed_rolled",
+            Self::RaceFinished => "race_finished",
+            Self::SignupConfirmed => "signup_confirmed",
+            Self::TeamResigned => "team_resigned",
+            Self::Test => "test",
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            Self::RaceScheduled => "Race scheduled or rescheduled",
+            Self::RoomOpened => "Race room opened",
+            Self::SeedRolled => "Seed rolled",
+            Self::RaceFinished => "Race finished",
+            Self::SignupConfirmed => "Signup confirmed",
+            Self::TeamResigned => "Team resigned",
+            Self::Test => "Test payload",
+        }
+    }
+}
+
+fn envelope(series: Series, event: &str, event_type: EventType, data: serde_json::Value) -> serde_json::Value {
+    json!({
+        "type": event_type.as_str(),
+        "series": series.slug(),
+        "event": event,
+        "createdAt": Utc::now(),
+        "data": data,
+    })
+}
+
+pub(crate) async fn team_json(transaction: &mut Transaction<'_, Postgres>, team: &Team) -> sqlx::Result<serde_json::Value> {
+    let members = team.members(&mut *transaction).await?.into_iter().map(|member| json!({
+        "id": member.id.to_string(),
+        "displayName": member.display_name(),
+        "racetimeId": member.racetime.as_ref().map(|racetime| &racetime.id),
+        "discordId": member.discord.as_ref().map(|discord| discord.id.to_string()),
+    })).collect_vec();
+    Ok(json!({
+        "id": team.id.to_string(),
+        "name": team.name,
+        "racetimeSlug": team.racetime_slug,
+        "members": members,
+    }))
+}
+
+async fn race_json(transaction: &mut Transaction<'_, Postgres>, race: &Race) -> sqlx::Result<serde_json::Value> {
+    let mut teams = Vec::default();
+    for team in race.teams() {
+        teams.push(team_json(&mut *transaction, team).await?);
+    }
+    Ok(json!({
+        "id": race.id.to_string(),
+        "phase": race.phase,
+        "round": race.round,
+        "game": race.game,
+        "teams": teams,
+        "schedule": race.cal_events().map(|event| json!({
+            "start": event.start(),
+            "end": event.end(),
+            "room": event.room(),
+        })).collect_vec(),
+    }))
+}
+
+/// Queues a notification for every webhook of the given event that is subscribed to the given event type.
+///
+/// Nothing is sent until the transaction is committed.
+pub(crate) async fn enqueue(transaction: &mut Transaction<'_, Postgres>, series: Series, event: &str, event_type: EventType, data: serde_json::Value) -> sqlx::Result<()> {
+    let payload = envelope(series, event, event_type, data);
+    sqlx::query!("INSERT INTO webhook_deliveries (webhook, event_type, payload) SELECT id, $3, $4 FROM webhooks WHERE series = $1 AND event = $2 AND $3 = ANY(event_types)", series as _, event, event_type as _, Json(&payload) as _).execute(&mut **transaction).await?;
+    Ok(())
+}
+
+/// Queues a test notification for a single webhook.
+pub(crate) async fn enqueue_test(transaction: &mut Transaction<'_, Postgres>, webhook: Id<Webhooks>, series: Series, event: &str) -> sqlx::Result<()> {
+    let payload = envelope(series, event, EventType::Test, json!({
+        "message": "This is a test payload sent from Mido's House.",
+    }));
+    sqlx::query!("INSERT INTO webhook_deliveries (webhook, event_type, payload) VALUES ($1, 'test', $2)", webhook as _, Json(&payload) as _).execute(&mut **transaction).await?;
+    Ok(())
+}
+
+/// Returns the value of the `X-MidosHouse-Signature-256` header for the given request body.
+pub(crate) fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether notifications may be sent to this address, i.e. it's not in a private, loopback, link-local, or otherwise reserved range.
+pub(crate) fn is_public(ip: IpAddr) -> bool {
+    fn is_public_v4(ip: Ipv4Addr) -> bool {
+        let [a, b, ..] = ip.octets();
+        !(
+            a == 0 // “this network”
+            || ip.is_private()
+            || ip.is_loopback()
+            || ip.is_link_local()
+            || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+            || ip.is_broadcast()
+            || ip.is_documentation()
+            || ip.is_multicast()
+        )
+    }
+
+    fn is_public_v6(ip: Ipv6Addr) -> bool {
+        if let Some(ip) = ip.to_ipv4_mapped() { return is_public_v4(ip) }
+        let first = ip.segments()[0];
+        !(
+            ip.is_unspecified()
+            || ip.is_loopback()
+            || ip.is_multicast()
+            || first & 0xfe00 == 0xfc00 // unique local
+            || first & 0xffc0 == 0xfe80 // link-local
+        )
+    }
+
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+/// Checks whether notifications may be sent to the given URL. The error is a message for the organizer.
+///
+/// On development instances, http URLs and local addresses are allowed for testing with a local receiver.
+pub(crate) async fn check_url(url: &Url) -> Result<(), String> {
+    let is_dev = Environment::default().is_dev();
+    match url.scheme() {
+        "https" => {}
+        "http" if is_dev => {}
+        _ => return Err(format!("Webhook URLs must use https.")),
+    }
+    if is_dev { return Ok(()) }
+    let addrs = match url.host() {
+        None => return Err(format!("Webhook URLs must include a host.")),
+        Some(url::Host::Ipv4(ip)) => vec![IpAddr::V4(ip)],
+        Some(url::Host::Ipv6(ip)) => vec![IpAddr::V6(ip)],
+        Some(url::Host::Domain(domain)) => match tokio::net::lookup_host((domain, url.port_or_known_default().unwrap_or(443))).await {
+            Ok(addrs) => addrs.map(|addr| addr.ip()).collect(),
+            Err(e) => return Err(format!("Failed to look up the webhook's host: {e}")),
+        },
+    };
+    if addrs.is_empty() {
+        Err(format!("The webhook's host doesn't have any addresses."))
+    } else if addrs.into_iter().all(is_public) {
+        Ok(())
+    } else {
+        Err(format!("Webhook URLs must not point to private, loopback, or link-local addresses."))
+    }
+}
+
+/// Resolves host names to public addresses only, so a webhook's host can't be pointed at a private address after its URL has been checked.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?.filter(|addr| is_public(addr.ip())).collect_vec();
+            if addrs.is_empty() {
+                return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("{} has no public addresses", name.as_str())).into())
+            }
+            Ok(Box::new(addrs.into_iter()) as Box<dyn Iterator<Item = SocketAddr> + Send>)
+        })
+    }
+}
+
+/// The HTTP client used to deliver notifications in production. It only connects to public addresses and doesn't follow redirects, since those could point anywhere.
+pub(crate) fn http_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(concat!("MidosHouse/", env!("CARGO_PKG_VERSION"), " (https://github.com/midoshouse/midos.house)"))
+        .timeout(Duration::from_secs(30))
+        .use_rustls_tls()
+        .https_only(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+}
+
+/// Generates a random shared secret for organizers who don't specify one.
+pub(crate) fn generate_secret() -> String {
+    hex::encode(rng().random::<[u8; 32]>())
+}
+
+async fn handle_race_update(db_pool: &PgPool, http_client: &reqwest::Client, notification: RaceUpdateNotification) -> Result<(), event::Error> {
+    let mut transaction = db_pool.begin().await?;
+    if !sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM webhooks WHERE series = $1 AND event = $2) AS "exists!""#, notification.series, notification.event).fetch_one(&mut *transaction).await? {
+        return Ok(())
+    }
+    if !sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM races WHERE id = $1) AS "exists!""#, notification.race).fetch_one(&mut *transaction).await? {
+        return Ok(()) // the race was deleted before its update was processed
+    }
+    let race = Race::from_id(&mut transaction, http_client, Id::from(notification.race)).await?;
+    for kind in notification.kinds {
+        let event_type = match kind {
+            RaceUpdateKind::Schedule if !matches!(race.schedule, RaceSchedule::Unscheduled) => EventType::RaceScheduled,
+            RaceUpdateKind::Room if race.has_any_room() => EventType::RoomOpened,
+            RaceUpdateKind::Seed if race.seed.files.is_some() || race.seed.file_hash.is_some() => EventType::SeedRolled,
+            RaceUpdateKind::Results if race.is_ended() => EventType::RaceFinished,
+            RaceUpdateKind::Schedule | RaceUpdateKind::Room | RaceUpdateKind::Seed | RaceUpdateKind::Draft | RaceUpdateKind::Results => continue,
+        };
+        let data = race_json(&mut transaction, &race).await?;
+        enqueue(&mut transaction, race.series, &race.event, event_type, data).await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+struct Delivery {
+    id: i32,
+    event_type: EventType,
+    payload: String,
+    attempts: i16,
+    url: String,
+    secret: String,
+}
+
+/// Sends the deliveries which are due. Each one is sent in the background so a slow endpoint doesn't hold up the others or the processing of race updates.
+async fn deliver(db_pool: &PgPool, http_client: &reqwest::Client) -> sqlx::Result<()> {
+    // the deliveries are claimed by postponing them until after they've timed out, so they're picked up again if the server stops while they're in flight
+    let due = sqlx::query_as!(Delivery, r#"UPDATE webhook_deliveries SET next_attempt_at = NOW() + INTERVAL '1 minute' FROM webhooks WHERE webhooks.id = webhook_deliveries.webhook AND webhook_deliveries.id IN (
+        SELECT id FROM webhook_deliveries WHERE next_attempt_at <= NOW() ORDER BY next_attempt_at LIMIT 64
+    ) RETURNING
+        webhook_deliveries.id,
+        webhook_deliveries.event_type AS "event_type: EventType",
+        webhook_deliveries.payload::text AS "payload!",
+        webhook_deliveries.attempts,
+        webhooks.url,
+        webhooks.secret
+    "#).fetch_all(db_pool).await?;
+    for delivery in due {
+        let db_pool = db_pool.clone();
+        let http_client = http_client.clone();
+        tokio::spawn(async move {
+            let id = delivery.id;
+            if let Err(e) = deliver_one(&db_pool, &http_client, delivery).await {
+                eprintln!("failed to record result of webhook delivery {id}: {e} ({e:?})");
+            }
+        });
+    }
+    Ok(())
+}
+
+async fn deliver_one(db_pool: &PgPool, http_client: &reqwest::Client, delivery: Delivery) -> sqlx::Result<()> {
+    let attempts = delivery.attempts + 1;
+    // webhooks added before URLs were checked, or whose host has moved to a private address, are given up on right away
+    let url_check = match Url::parse(&delivery.url) {
+        Ok(url) => check_url(&url).await,
+        Err(e) => Err(format!("Failed to parse URL: {e}")),
+    };
+    if let Err(message) = url_check {
+        sqlx::query!("UPDATE webhook_deliveries SET attempts = $1, next_attempt_at = NULL, last_status = NULL, last_error = $2 WHERE id = $3", attempts, message, delivery.id).execute(db_pool).await?;
+        return Ok(())
+    }
+    let result = http_client.post(&delivery.url)
+        .timeout(DELIVERY_TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-MidosHouse-Event", delivery.event_type.as_str())
+        .header("X-MidosHouse-Delivery", delivery.id.to_string())
+        .header("X-MidosHouse-Signature-256", signature(&delivery.secret, delivery.payload.as_bytes()))
+        .body(delivery.payload)
+        .send().await
+        .and_then(|response| response.error_for_status());
+    match result {
+        Ok(response) => {
+            sqlx::query!("UPDATE webhook_deliveries SET attempts = $1, next_attempt_at = NULL, delivered_at = NOW(), last_status = $2, last_error = NULL WHERE id = $3", attempts, response.status().as_u16() as i16, delivery.id).execute(db_pool).await?;
+        }
+        Err(e) => {
+            let next_attempt_at = (attempts < MAX_ATTEMPTS).then(|| Utc::now() + TimeDelta::minutes(1 << (attempts - 1)));
+            sqlx::query!("UPDATE webhook_deliveries SET attempts = $1, next_attempt_at = $2, last_status = $3, last_error = $4 WHERE id = $5", attempts, next_attempt_at, e.status().map(|status| status.as_u16() as i16), e.to_string(), delivery.id).execute(db_pool).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Turns race updates into webhook notifications and sends pending deliveries, checking every 10 seconds until shutdown.
+///
+/// Failures to handle an individual race update are logged rather than returned, so they don't take down the server.
+pub(crate) async fn run(db_pool: PgPool, http_client: reqwest::Client, mut shutdown: rocket::Shutdown) -> Result<(), event::Error> {
+    let mut race_updates = api::subscribe_race_updates();
+    let mut interval = tokio::time::interval(Duration::from_secs(10));
+    loop {
+        select! {
+            () = &mut shutdown => break,
+            res = race_updates.recv() => match res {
+                Ok(notification) => {
+                    let race = notification.race;
+                    if let Err(e) = handle_race_update(&db_pool, &http_client, notification).await {
+                        eprintln!("failed to queue webhook notifications for race {race}: {e} ({e:?})");
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("webhook worker missed {skipped} race updates");
+                    wheel::night_report(&format!("{}/error", night_path()), Some(&format!("webhook worker missed {skipped} race updates, some webhook notifications were not sent"))).await?;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = interval.tick() => deliver(&db_pool, &http_client).await?,
+        }
+    }
+    Ok(())
+}
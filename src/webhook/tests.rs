@@ -0,0 +1,11 @@
+use super::*;
+
+#[test]
+fn public_addresses() {
+    for ip in ["1.1.1.1", "2606:4700:4700::1111"] {
+        assert!(is_public(ip.parse().unwrap()), "{ip} should be public");
+    }
+    for ip in ["127.0.0.1", "10.0.0.1", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "::", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+        assert!(!is_public(ip.parse().unwrap()), "{ip} should not be public");
+    }
+}
@@ -0,0 +1,182 @@
+//! Reading randomizer patch files, e.g. to recover the file hash of a seed rolled without a spoiler log.
+//!
+//! A `.zpf` file is a zlib-compressed Zelda Patch Format patch, and a `.zpfz` file is a zip archive of such patches, one per world.
+//! Patches are applied to the decompressed base ROM and their data is XORed with a key stream taken from that same ROM, so reading anything from a patch requires the base ROM.
+
+use {
+    std::io::{
+        Cursor,
+        Read as _,
+    },
+    flate2::read::ZlibDecoder,
+    zip::{
+        ZipArchive,
+        result::ZipError,
+    },
+    crate::{
+        hash_icon::HashIconDecodeError,
+        prelude::*,
+    },
+};
+
+#[cfg(test)] mod tests;
+
+/// The symbol for the location of the file hash in the randomizer payload.
+const FILE_HASH_SYMBOL: &str = "CFG_FILE_SELECT_HASH";
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)] HashIcon(#[from] HashIconDecodeError),
+    #[error(transparent)] Io(#[from] io::Error),
+    #[error(transparent)] Json(#[from] serde_json::Error),
+    #[error(transparent)] ParseInt(#[from] std::num::ParseIntError),
+    #[error(transparent)] Wheel(#[from] wheel::Error),
+    #[error(transparent)] Zip(#[from] ZipError),
+    #[error("patch refers to data outside of the base ROM")]
+    BaseRom,
+    #[error("file is not in Zelda Patch Format")]
+    Header,
+    #[error("patch archive has no patch for world {0}")]
+    MissingWorld(u8),
+    #[error("randomizer symbol table has no {0} symbol")]
+    Symbol(&'static str),
+    #[error("patch file ended unexpectedly")]
+    UnexpectedEof,
+    #[error("unsupported Zelda Patch Format version {0}")]
+    Version(u32),
+}
+
+/// Reads the file hash of a seed from its patch file or multiworld patch archive.
+///
+/// `rando_path` must be a randomizer checkout of the version the seed was rolled with that has been used to roll a seed before, since it provides the decompressed base ROM as well as the location of the file hash.
+pub(crate) async fn file_hash(rando_path: &Path, patch_path: &Path) -> Result<[HashIcon; 5], Error> {
+    let base_rom = fs::read(rando_path.join("ZOOTDEC.z64")).await?;
+    let address = symbol_address(rando_path, FILE_HASH_SYMBOL).await?;
+    let patch = fs::read(patch_path).await?;
+    let patch = if patch.starts_with(b"PK\x03\x04") {
+        // all worlds of a multiworld seed have the same file hash
+        world_patch(&patch, 1)?
+    } else {
+        patch
+    };
+    let [hash1, hash2, hash3, hash4, hash5] = read(&base_rom, &patch, address, 5)?[..] else { unreachable!("read returns the requested number of bytes") };
+    Ok([hash1.try_into()?, hash2.try_into()?, hash3.try_into()?, hash4.try_into()?, hash5.try_into()?])
+}
+
+async fn symbol_address(rando_path: &Path, name: &'static str) -> Result<usize, Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Symbol {
+        Address(String),
+        Detailed {
+            address: String,
+        },
+    }
+
+    let symbols_path = rando_path.join("data").join("generated").join("symbols.json");
+    let symbols = serde_json::from_str::<HashMap<String, Symbol>>(&fs::read_to_string(&symbols_path).await?)?;
+    let (Symbol::Address(address) | Symbol::Detailed { address }) = symbols.get(name).ok_or(Error::Symbol(name))?;
+    let address = u32::from_str_radix(address.trim_start_matches("0x"), 16)?;
+    // symbols may be listed as RAM addresses of the randomizer payload, which is loaded from ROM address 0x0348_0000 to RAM address 0x8040_0000
+    Ok(if address >= 0x8040_0000 { address - 0x8040_0000 + 0x0348_0000 } else { address } as usize)
+}
+
+/// Extracts the patch for the given world (starting at 1) from a multiworld patch archive.
+fn world_patch(archive: &[u8], world: u8) -> Result<Vec<u8>, Error> {
+    let mut archive = ZipArchive::new(Cursor::new(archive))?;
+    for name in [format!("P{world}.zpf"), format!("P{world}.zpfz")] {
+        match archive.by_name(&name) {
+            Ok(mut file) => {
+                let mut patch = Vec::default();
+                file.read_to_end(&mut patch)?;
+                return Ok(patch)
+            }
+            Err(ZipError::FileNotFound) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(Error::MissingWorld(world))
+}
+
+/// Decompresses a patch unless it's already uncompressed.
+fn decompress(patch: &[u8]) -> Result<Vec<u8>, Error> {
+    if patch.starts_with(b"ZPFv") {
+        Ok(patch.to_owned())
+    } else {
+        let mut buf = Vec::default();
+        ZlibDecoder::new(patch).read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len { return Err(Error::UnexpectedEof) }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u24(&mut self) -> Result<u32, Error> {
+        let [b0, b1, b2] = *self.bytes(3)? else { unreachable!() };
+        Ok(u32::from_be_bytes([0, b0, b1, b2]))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+}
+
+/// Reads `len` bytes at `address` of the ROM that results from applying `patch` to `base_rom`, without patching the entire ROM.
+fn read(base_rom: &[u8], patch: &[u8], address: usize, len: usize) -> Result<Vec<u8>, Error> {
+    let mut output = base_rom.get(address..address + len).ok_or(Error::BaseRom)?.to_owned();
+    let patch = decompress(patch)?;
+    let mut patch = Reader { data: &patch };
+    if patch.bytes(4)? != b"ZPFv" { return Err(Error::Header) }
+    let version = patch.u32()?;
+    if version != 1 { return Err(Error::Version(version)) }
+    patch.u32()?; // location of the DMA table, which isn't needed since the base ROM is known
+    let xor_range_start = patch.u32()? as usize;
+    let xor_range_end = patch.u32()? as usize;
+    let mut xor_address = patch.u32()? as usize;
+    // DMA table updates, which move files around in the ROM
+    loop {
+        let dma_index = patch.u16()?;
+        if dma_index == 0xffff { break }
+        let from_file = patch.u32()?;
+        let start = patch.u32()? as usize;
+        let size = patch.u24()? as usize;
+        for rom_address in start.max(address)..(start + size).min(address + len) {
+            output[rom_address - address] = if from_file == 0xffff_ffff {
+                0 // new file
+            } else {
+                *base_rom.get(from_file as usize + rom_address - start).ok_or(Error::BaseRom)?
+            };
+        }
+    }
+    // XOR data blocks, which go up to the end of the patch
+    while !patch.data.is_empty() {
+        let block_start = patch.u32()? as usize;
+        let block_size = patch.u16()? as usize;
+        let block = patch.bytes(block_size)?;
+        for (rom_address, byte) in (block_start..).zip(block) {
+            let key = *base_rom.get(xor_address).ok_or(Error::BaseRom)?;
+            xor_address += 1;
+            if xor_address > xor_range_end {
+                xor_address = xor_range_start;
+            }
+            if (address..address + len).contains(&rom_address) {
+                output[rom_address - address] = byte ^ key;
+            }
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,78 @@
+use {
+    std::io::Write as _,
+    flate2::{
+        Compression,
+        write::ZlibEncoder,
+    },
+    super::*,
+};
+
+fn base_rom() -> Vec<u8> {
+    (0..64).collect()
+}
+
+/// A patch which copies bytes 0..4 to 40..44 and writes `[0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5]` to 8..14, using bytes 16..=19 as the XOR key.
+fn patch() -> Vec<u8> {
+    let mut patch = b"ZPFv".to_vec();
+    patch.extend_from_slice(&1u32.to_be_bytes()); // version
+    patch.extend_from_slice(&0u32.to_be_bytes()); // DMA table
+    patch.extend_from_slice(&16u32.to_be_bytes()); // XOR range start
+    patch.extend_from_slice(&19u32.to_be_bytes()); // XOR range end
+    patch.extend_from_slice(&16u32.to_be_bytes()); // XOR address
+    patch.extend_from_slice(&0u16.to_be_bytes()); // DMA index
+    patch.extend_from_slice(&0u32.to_be_bytes()); // from file
+    patch.extend_from_slice(&40u32.to_be_bytes()); // start
+    patch.extend_from_slice(&4u32.to_be_bytes()[1..]); // size
+    patch.extend_from_slice(&0xffffu16.to_be_bytes()); // end of DMA table
+    patch.extend_from_slice(&8u32.to_be_bytes()); // block start
+    patch.extend_from_slice(&6u16.to_be_bytes()); // block size
+    patch.extend([0xa0 ^ 16, 0xa1 ^ 17, 0xa2 ^ 18, 0xa3 ^ 19, 0xa4 ^ 16, 0xa5 ^ 17]);
+    patch
+}
+
+fn compress(patch: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::default(), Compression::best());
+    encoder.write_all(patch).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn read_xor_blocks() -> Result<(), Error> {
+    assert_eq!(read(&base_rom(), &patch(), 6, 6)?, [6, 7, 0xa0, 0xa1, 0xa2, 0xa3]);
+    assert_eq!(read(&base_rom(), &patch(), 12, 4)?, [0xa4, 0xa5, 14, 15], "key stream should wrap around at the end of the XOR range");
+    Ok(())
+}
+
+#[test]
+fn read_moved_files() -> Result<(), Error> {
+    assert_eq!(read(&base_rom(), &patch(), 38, 4)?, [38, 39, 0, 1]);
+    Ok(())
+}
+
+#[test]
+fn read_compressed() -> Result<(), Error> {
+    assert_eq!(read(&base_rom(), &compress(&patch()), 8, 6)?, [0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5]);
+    Ok(())
+}
+
+#[test]
+fn reject_other_formats() {
+    assert!(matches!(read(&base_rom(), b"ZPFv\0\0\0\x02", 0, 1), Err(Error::Version(2))));
+    assert!(matches!(read(&base_rom(), &compress(b"PATCH"), 0, 1), Err(Error::Header)));
+    assert!(matches!(read(&base_rom(), &patch()[..40], 0, 1), Err(Error::UnexpectedEof)));
+}
+
+#[tokio::test]
+async fn file_hash_from_patch() -> Result<(), Error> {
+    let rando_dir = tempfile::tempdir()?;
+    std::fs::write(rando_dir.path().join("ZOOTDEC.z64"), base_rom())?;
+    std::fs::create_dir_all(rando_dir.path().join("data").join("generated"))?;
+    std::fs::write(rando_dir.path().join("data").join("generated").join("symbols.json"), r#"{"CFG_FILE_SELECT_HASH": "00000008"}"#)?;
+    let mut patch = patch();
+    patch.truncate(patch.len() - 6);
+    patch.extend([0x07 ^ 16, 0x08 ^ 17, 0x1f ^ 18, 0x03 ^ 19, 0x04 ^ 16, 0x05 ^ 17]);
+    let patch_path = rando_dir.path().join("seed.zpf");
+    std::fs::write(&patch_path, compress(&patch))?;
+    assert_eq!(file_hash(rando_dir.path(), &patch_path).await?, [HashIcon::Powder, HashIcon::Rod, HashIcon::Key, HashIcon::Bow, HashIcon::Hookshot]);
+    Ok(())
+}